@@ -4,12 +4,14 @@ extern crate core;
 
 use crate::types::FileFormat;
 use clap::Parser;
+use fluentbase_softfloat::lower_floats;
 use fluentbase_types::{
     create_sovereign_import_linker,
     SysFuncIdx::STATE,
     STATE_DEPLOY,
     STATE_MAIN,
 };
+use fluentbase_wasi::shim_wasi_imports;
 use log::debug;
 use rwasm::{
     engine::{bytecode::Instruction, RwasmConfig, StateRouterConfig},
@@ -73,6 +75,14 @@ struct Args {
 
     #[arg(long, default_value_t = false)]
     retranslate_main: bool,
+
+    /// Don't lower float instructions into soft-float routines
+    #[arg(long, default_value_t = false)]
+    no_soft_float: bool,
+
+    /// Don't replace WASI preview1 imports with fluentbase syscalls
+    #[arg(long, default_value_t = false)]
+    no_wasi_shim: bool,
 }
 
 fn main() {
@@ -97,6 +107,18 @@ fn main() {
         }
     }
 
+    let wasm_binary = if args.no_wasi_shim {
+        wasm_binary
+    } else {
+        shim_wasi_imports(&wasm_binary).unwrap_or_else(|err| panic!("{}", err))
+    };
+
+    let wasm_binary = if args.no_soft_float {
+        wasm_binary
+    } else {
+        lower_floats(&wasm_binary).unwrap()
+    };
+
     let mut config = RwasmModule::default_config(None);
     config
         .rwasm_config(RwasmConfig {
@@ -1,7 +1,7 @@
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::{__private::Span, format_ident, quote};
-use syn::{self, Data, Fields, Ident};
+use syn::{self, Data, DataEnum, Expr, ExprLit, Fields, Ident, Lit};
 
 #[proc_macro]
 pub fn path_to_test_name(token: TokenStream) -> TokenStream {
@@ -17,21 +17,47 @@ pub fn path_to_test_name(token: TokenStream) -> TokenStream {
     })
 }
 
-fn impl_derive_codec(ast: &syn::DeriveInput) -> TokenStream {
+fn crate_name() -> proc_macro2::TokenStream {
     let crate_name = std::env::var("CARGO_PKG_NAME").unwrap();
-    let crate_name = if crate_name == "fluentbase-codec" {
+    if crate_name == "fluentbase-codec" {
         quote! { crate }
     } else {
         quote! { fluentbase_codec }
-    };
+    }
+}
+
+fn impl_derive_codec(ast: &syn::DeriveInput, attrs: &CodecAttrs) -> TokenStream {
+    let crate_name = crate_name();
     let data_struct = match &ast.data {
         Data::Struct(data_struct) => data_struct,
-        _ => panic!("only structs are supported"),
+        Data::Enum(_) if attrs.extensible => panic!("only structs can be extensible"),
+        Data::Enum(data_enum) => {
+            if data_enum.variants.iter().any(|variant| {
+                variant
+                    .fields
+                    .iter()
+                    .any(|field| field_since(field).is_some())
+            }) {
+                panic!("`since` fields are allowed only in `#[codec(extensible)]` structs");
+            }
+            return impl_derive_codec_enum(ast, data_enum, crate_name);
+        }
+        _ => panic!("only structs and enums are supported"),
     };
     let named_fields = match &data_struct.fields {
         Fields::Named(named_fields) => named_fields,
         _ => panic!("only named fields are supported"),
     };
+    if attrs.extensible {
+        return impl_derive_codec_extensible(ast, named_fields, crate_name);
+    }
+    if named_fields
+        .named
+        .iter()
+        .any(|field| field_since(field).is_some())
+    {
+        panic!("`since` fields are allowed only in `#[codec(extensible)]` structs");
+    }
     let header_sizes = named_fields.named.iter().map(|field| {
         let ty = &field.ty;
         quote! {
@@ -54,6 +80,14 @@ fn impl_derive_codec(ast: &syn::DeriveInput) -> TokenStream {
             field_offset += <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE;
         }
     });
+    let try_decode_types = named_fields.named.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        quote! {
+            <#ty as #crate_name::Encoder<#ty>>::try_decode_body(decoder, field_offset, &mut result.#ident)?;
+            field_offset += <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE;
+        }
+    });
     let impl_types = named_fields.named.iter().map(|field| {
         let ident = field.ident.as_ref().unwrap();
         let span = ident.span();
@@ -94,6 +128,10 @@ fn impl_derive_codec(ast: &syn::DeriveInput) -> TokenStream {
                 #( #decode_types; )*
                 (0, 0)
             }
+            fn try_decode_body(decoder: &mut #crate_name::BufferDecoder, mut field_offset: usize, result: &mut #struct_name #type_generics) -> Result<(), #crate_name::CodecError> {
+                #( #try_decode_types; )*
+                Ok(())
+            }
         }
         pub trait #i_struct_name {
             #( #impl_types )*
@@ -105,8 +143,631 @@ fn impl_derive_codec(ast: &syn::DeriveInput) -> TokenStream {
     TokenStream::from(output)
 }
 
-#[proc_macro_derive(Codec)]
+fn impl_derive_codec_enum(
+    ast: &syn::DeriveInput,
+    data_enum: &DataEnum,
+    crate_name: proc_macro2::TokenStream,
+) -> TokenStream {
+    let enum_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    // discriminants follow the Rust rules: explicit values or the previous one plus one
+    let mut next_discriminant = 0u32;
+    let discriminants = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let discriminant = match &variant.discriminant {
+                Some((
+                    _,
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(lit_int),
+                        ..
+                    }),
+                )) => lit_int
+                    .base10_parse::<u32>()
+                    .expect("enum discriminant must fit into u32"),
+                Some(_) => panic!("only integer literals are supported as enum discriminants"),
+                None => next_discriminant,
+            };
+            next_discriminant = discriminant.wrapping_add(1);
+            discriminant
+        })
+        .collect::<Vec<_>>();
+    let variant_sizes = data_enum.variants.iter().map(|variant| {
+        let field_sizes = variant.fields.iter().map(|field| {
+            let ty = &field.ty;
+            quote! {
+                <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE
+            }
+        });
+        quote! {
+            let variant_size = 0 #( + #field_sizes)*;
+            if variant_size > size {
+                size = variant_size;
+            }
+        }
+    });
+    let bindings = |fields: &Fields| {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => ident.clone(),
+                None => format_ident!("field_{}", i),
+            })
+            .collect::<Vec<_>>()
+    };
+    let pattern = |variant: &syn::Variant, bindings: &[Ident]| {
+        let ident = &variant.ident;
+        match &variant.fields {
+            Fields::Named(_) => quote! { #enum_name::#ident { #( #bindings ),* } },
+            Fields::Unnamed(_) => quote! { #enum_name::#ident ( #( #bindings ),* ) },
+            Fields::Unit => quote! { #enum_name::#ident },
+        }
+    };
+    let encode_variants =
+        data_enum
+            .variants
+            .iter()
+            .zip(discriminants.iter())
+            .map(|(variant, discriminant)| {
+                let bindings = bindings(&variant.fields);
+                let pattern = pattern(variant, &bindings);
+                let encode_fields =
+                    variant
+                        .fields
+                        .iter()
+                        .zip(bindings.iter())
+                        .map(|(field, binding)| {
+                            let ty = &field.ty;
+                            quote! {
+                                #binding.encode(encoder, field_offset);
+                                field_offset += <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE;
+                            }
+                        });
+                quote! {
+                    #pattern => {
+                        encoder.write_u32(field_offset, #discriminant);
+                        field_offset += 4;
+                        #( #encode_fields )*
+                    }
+                }
+            });
+    let decode_variants = |fallible: bool| {
+        data_enum
+            .variants
+            .iter()
+            .zip(discriminants.iter())
+            .map(|(variant, discriminant)| {
+                let bindings = bindings(&variant.fields);
+                let pattern = pattern(variant, &bindings);
+                let decode_fields = variant.fields.iter().zip(bindings.iter()).map(|(field, binding)| {
+                    let ty = &field.ty;
+                    let decode_field = if fallible {
+                        quote! { <#ty as #crate_name::Encoder<#ty>>::try_decode_body(decoder, field_offset, &mut #binding)?; }
+                    } else {
+                        quote! { <#ty as #crate_name::Encoder<#ty>>::decode_body(decoder, field_offset, &mut #binding); }
+                    };
+                    quote! {
+                        let mut #binding: #ty = Default::default();
+                        #decode_field
+                        field_offset += <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE;
+                    }
+                });
+                quote! {
+                    #discriminant => {
+                        #( #decode_fields )*
+                        *result = #pattern;
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let try_decode_variants = decode_variants(true);
+    let decode_variants = decode_variants(false);
+    let output = quote! {
+        impl #impl_generics #crate_name::Encoder<#enum_name #type_generics> for #enum_name #type_generics #where_clause {
+            const HEADER_SIZE: usize = {
+                let mut size = 0;
+                #( #variant_sizes )*
+                4 + size
+            };
+            #[allow(unused_mut, unused_assignments)]
+            fn encode<W: #crate_name::WritableBuffer>(&self, encoder: &mut W, mut field_offset: usize) {
+                match self {
+                    #( #encode_variants )*
+                }
+            }
+            #[allow(unused_mut, unused_assignments)]
+            fn decode_header(decoder: &mut #crate_name::BufferDecoder, mut field_offset: usize, result: &mut #enum_name #type_generics) -> (usize, usize) {
+                let discriminant = decoder.read_u32(field_offset);
+                field_offset += 4;
+                match discriminant {
+                    #( #decode_variants )*
+                    _ => panic!("unknown enum discriminant"),
+                }
+                (0, 0)
+            }
+            #[allow(unused_mut, unused_assignments)]
+            fn try_decode_body(decoder: &mut #crate_name::BufferDecoder, mut field_offset: usize, result: &mut #enum_name #type_generics) -> Result<(), #crate_name::CodecError> {
+                let discriminant = decoder.try_read_u32(field_offset)?;
+                field_offset += 4;
+                match discriminant {
+                    #( #try_decode_variants )*
+                    _ => return Err(#crate_name::CodecError::InvalidTag(discriminant)),
+                }
+                Ok(())
+            }
+        }
+    };
+    TokenStream::from(output)
+}
+
+/// Extensible structs are encoded as bytes: a `u32` version followed by headers of fields and
+/// their dynamic data. Fields missing in older versions are decoded as defaults and trailing data
+/// of newer versions is skipped, so the header size of the struct doesn't depend on its fields.
+fn impl_derive_codec_extensible(
+    ast: &syn::DeriveInput,
+    named_fields: &syn::FieldsNamed,
+    crate_name: proc_macro2::TokenStream,
+) -> TokenStream {
+    let versions = extensible_versions(named_fields);
+    let version = versions.iter().copied().max().unwrap_or(0);
+    let types = named_fields
+        .named
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+    let idents = named_fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let decode_fields = |fallible: bool| {
+        idents
+            .iter()
+            .zip(types.iter())
+            .zip(versions.iter())
+            .map(|((ident, ty), since)| {
+                let decode_field = if fallible {
+                    quote! { <#ty as #crate_name::Encoder<#ty>>::try_decode_body(&mut body_decoder, field_offset, &mut result.#ident)?; }
+                } else {
+                    quote! { <#ty as #crate_name::Encoder<#ty>>::decode_body(&mut body_decoder, field_offset, &mut result.#ident); }
+                };
+                quote! {
+                    if #since <= version {
+                        #decode_field
+                    } else {
+                        result.#ident = Default::default();
+                    }
+                    field_offset += <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE;
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let try_decode_fields = decode_fields(true);
+    let decode_fields = decode_fields(false);
+    let struct_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let output = quote! {
+        impl #impl_generics #crate_name::Encoder<#struct_name #type_generics> for #struct_name #type_generics #where_clause {
+            const HEADER_SIZE: usize = 8;
+            fn encode<W: #crate_name::WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+                let mut body_encoder = #crate_name::BufferEncoder::new(
+                    4 #( + <#types as #crate_name::Encoder<#types>>::HEADER_SIZE )*,
+                    None,
+                );
+                #crate_name::WritableBuffer::write_u32(&mut body_encoder, 0, #version);
+                let mut body_offset = 4;
+                #(
+                    self.#idents.encode(&mut body_encoder, body_offset);
+                    body_offset += <#types as #crate_name::Encoder<#types>>::HEADER_SIZE;
+                )*
+                encoder.write_bytes(field_offset, body_encoder.finalize().as_slice());
+            }
+            fn decode_header(decoder: &mut #crate_name::BufferDecoder, field_offset: usize, _result: &mut #struct_name #type_generics) -> (usize, usize) {
+                decoder.read_bytes_header(field_offset)
+            }
+            #[allow(unused_mut, unused_assignments)]
+            fn decode_body(decoder: &mut #crate_name::BufferDecoder, field_offset: usize, result: &mut #struct_name #type_generics) {
+                let mut body_decoder = #crate_name::BufferDecoder::new(decoder.read_bytes(field_offset));
+                let version = body_decoder.read_u32(0);
+                let mut field_offset = 4;
+                #( #decode_fields )*
+            }
+            #[allow(unused_mut, unused_assignments)]
+            fn try_decode_body(decoder: &mut #crate_name::BufferDecoder, field_offset: usize, result: &mut #struct_name #type_generics) -> Result<(), #crate_name::CodecError> {
+                let mut body_decoder = #crate_name::BufferDecoder::new(decoder.try_read_bytes(field_offset)?);
+                let version = body_decoder.try_read_u32(0)?;
+                let mut field_offset = 4;
+                #( #try_decode_fields )*
+                Ok(())
+            }
+        }
+    };
+    TokenStream::from(output)
+}
+
+/// Returns the version of the `#[codec(since = N)]` attribute of the field.
+fn field_since(field: &syn::Field) -> Option<u32> {
+    let mut since = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("codec"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("since") {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                since = Some(value.base10_parse::<u32>()?);
+            } else {
+                return Err(meta.error("unknown codec field attribute"));
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+    since
+}
+
+/// Versions of fields of an extensible struct, fields without `since` are there from version 0.
+fn extensible_versions(named_fields: &syn::FieldsNamed) -> Vec<u32> {
+    let versions = named_fields
+        .named
+        .iter()
+        .map(|field| field_since(field).unwrap_or(0))
+        .collect::<Vec<_>>();
+    if versions.windows(2).any(|pair| pair[0] > pair[1]) {
+        panic!("fields of extensible structs must be ordered by their `since` versions");
+    }
+    versions
+}
+
+/// Options of the type set with `#[codec(...)]`.
+#[derive(Default)]
+struct CodecAttrs {
+    solidity: bool,
+    view: bool,
+    extensible: bool,
+    schema: bool,
+}
+
+fn parse_codec_attrs(ast: &syn::DeriveInput) -> CodecAttrs {
+    let mut attrs = CodecAttrs::default();
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("codec"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("solidity") {
+                attrs.solidity = true;
+            } else if meta.path.is_ident("view") {
+                attrs.view = true;
+            } else if meta.path.is_ident("extensible") {
+                attrs.extensible = true;
+            } else if meta.path.is_ident("schema") {
+                attrs.schema = true;
+            } else {
+                return Err(meta.error("unknown codec attribute"));
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+    attrs
+}
+
+fn impl_derive_solidity(ast: &syn::DeriveInput) -> TokenStream {
+    let crate_name = crate_name();
+    let name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let output = match &ast.data {
+        Data::Struct(data_struct) => {
+            let fields = match &data_struct.fields {
+                Fields::Named(named_fields) => &named_fields.named,
+                _ => panic!("only named fields are supported"),
+            };
+            let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+            let idents = fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
+            quote! {
+                impl #impl_generics #crate_name::SolidityABI for #name #type_generics #where_clause {
+                    const DYNAMIC: bool = false #( || <#types as #crate_name::SolidityABI>::DYNAMIC )*;
+                    const HEAD_SIZE: usize = if Self::DYNAMIC {
+                        32
+                    } else {
+                        0 #( + <#types as #crate_name::SolidityABI>::HEAD_SIZE )*
+                    };
+                    fn encode_solidity(&self, encoder: &mut #crate_name::SolidityEncoder) {
+                        let mut tuple = encoder.tuple(0 #( + <#types as #crate_name::SolidityABI>::HEAD_SIZE )*);
+                        #( tuple.encode_field(&self.#idents); )*
+                        tuple.finish();
+                    }
+                    fn decode_solidity(decoder: &#crate_name::SolidityDecoder, offset: usize) -> Result<Self, #crate_name::CodecError> {
+                        let mut tuple = decoder.tuple(offset);
+                        Ok(Self {
+                            #( #idents: tuple.decode_field()?, )*
+                        })
+                    }
+                }
+            }
+        }
+        Data::Enum(data_enum) => {
+            // Solidity enums are `uint8` values of variants in the declaration order
+            let variants = data_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    if !matches!(variant.fields, Fields::Unit) || variant.discriminant.is_some() {
+                        panic!("only enums with unit variants and implicit discriminants can be encoded as Solidity ABI");
+                    }
+                    &variant.ident
+                })
+                .collect::<Vec<_>>();
+            if variants.len() > 256 {
+                panic!("Solidity enums can't have more than 256 variants");
+            }
+            let indices = (0..variants.len()).map(|i| i as u8).collect::<Vec<_>>();
+            quote! {
+                impl #impl_generics #crate_name::SolidityABI for #name #type_generics #where_clause {
+                    const DYNAMIC: bool = false;
+                    const HEAD_SIZE: usize = 32;
+                    fn encode_solidity(&self, encoder: &mut #crate_name::SolidityEncoder) {
+                        let index: u8 = match self {
+                            #( Self::#variants => #indices, )*
+                        };
+                        <u8 as #crate_name::SolidityABI>::encode_solidity(&index, encoder);
+                    }
+                    fn decode_solidity(decoder: &#crate_name::SolidityDecoder, offset: usize) -> Result<Self, #crate_name::CodecError> {
+                        match <u8 as #crate_name::SolidityABI>::decode_solidity(decoder, offset)? {
+                            #( #indices => Ok(Self::#variants), )*
+                            index => Err(#crate_name::CodecError::InvalidTag(index as u32)),
+                        }
+                    }
+                }
+            }
+        }
+        _ => panic!("only structs and enums are supported"),
+    };
+    TokenStream::from(output)
+}
+
+fn impl_derive_view(ast: &syn::DeriveInput) -> TokenStream {
+    let crate_name = crate_name();
+    let name = &ast.ident;
+    if !ast.generics.params.is_empty() {
+        panic!("views of generic types are not supported");
+    }
+    let output = match &ast.data {
+        Data::Struct(data_struct) => {
+            let fields = match &data_struct.fields {
+                Fields::Named(named_fields) => &named_fields.named,
+                _ => panic!("only named fields are supported"),
+            };
+            let vis = &ast.vis;
+            let view_name = format_ident!("{}View", name);
+            let accessors = fields.iter().enumerate().map(|(i, field)| {
+                let ident = field.ident.as_ref().unwrap();
+                let ty = &field.ty;
+                let field_offsets = fields.iter().take(i).map(|field| {
+                    let ty = &field.ty;
+                    quote! { <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE }
+                });
+                quote! {
+                    #vis fn #ident(&self) -> <#ty as #crate_name::CodecView<'a>>::View {
+                        <#ty as #crate_name::CodecView<'a>>::view(self.buffer, self.field_offset #( + #field_offsets )*)
+                    }
+                }
+            });
+            let doc = format!(
+                "Lazily decoded view of [`{}`], fields are read from the buffer on access.",
+                name
+            );
+            quote! {
+                #[doc = #doc]
+                #[derive(Clone, Copy)]
+                #vis struct #view_name<'a> {
+                    buffer: &'a [u8],
+                    field_offset: usize,
+                }
+                impl<'a> #view_name<'a> {
+                    #vis fn new(buffer: &'a [u8]) -> Self {
+                        Self { buffer, field_offset: 0 }
+                    }
+                    #( #accessors )*
+                }
+                impl<'a> #crate_name::CodecView<'a> for #name {
+                    type View = #view_name<'a>;
+                    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+                        #view_name { buffer, field_offset }
+                    }
+                }
+            }
+        }
+        // variants can't be accessed without matching on them, so enums are decoded eagerly
+        Data::Enum(_) => quote! {
+            impl<'a> #crate_name::CodecView<'a> for #name {
+                type View = #name;
+                fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+                    let mut decoder = #crate_name::BufferDecoder::new(buffer);
+                    let mut result = #name::default();
+                    <#name as #crate_name::Encoder<#name>>::decode_body(&mut decoder, field_offset, &mut result);
+                    result
+                }
+            }
+        },
+        _ => panic!("only structs and enums are supported"),
+    };
+    TokenStream::from(output)
+}
+
+fn impl_derive_schema(ast: &syn::DeriveInput, attrs: &CodecAttrs) -> TokenStream {
+    let crate_name = crate_name();
+    let name = &ast.ident;
+    let mut generics = ast.generics.clone();
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    {
+        let where_clause = generics.make_where_clause();
+        for param in params.iter() {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #param: #crate_name::CodecSchema });
+        }
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    // fields are described with their offsets from the start of the header, `base` bytes are
+    // taken by the enum discriminant or by the version of extensible structs
+    let field_schemas = |fields: &Fields, base: usize| {
+        let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+        let schemas = fields.iter().enumerate().map(|(i, field)| {
+            let since = field_since(field).map(|since| quote! { .since(#since) });
+            let field_name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string(),
+            };
+            let ty = &field.ty;
+            let field_offsets = types.iter().take(i);
+            quote! {
+                #crate_name::FieldSchema::new(
+                    #field_name,
+                    <#ty as #crate_name::CodecSchema>::type_schema(),
+                    #base #( + <#field_offsets as #crate_name::Encoder<#field_offsets>>::HEADER_SIZE )*,
+                    <#ty as #crate_name::Encoder<#ty>>::HEADER_SIZE,
+                ) #since
+            }
+        });
+        quote! { &[ #( #schemas ),* ] }
+    };
+    let (add_definition, types) = match &ast.data {
+        Data::Struct(data_struct) => {
+            if !matches!(data_struct.fields, Fields::Named(_)) {
+                panic!("only named fields are supported");
+            }
+            let add_definition = if attrs.extensible {
+                let fields = field_schemas(&data_struct.fields, 4);
+                quote! {
+                    schema.add_extensible_struct(name, #fields)
+                }
+            } else {
+                let fields = field_schemas(&data_struct.fields, 0);
+                quote! {
+                    schema.add_struct(name, <Self as #crate_name::Encoder<Self>>::HEADER_SIZE, #fields)
+                }
+            };
+            (
+                add_definition,
+                data_struct
+                    .fields
+                    .iter()
+                    .map(|field| &field.ty)
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Data::Enum(data_enum) => {
+            let mut next_discriminant = 0u32;
+            let variants = data_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    let discriminant = match &variant.discriminant {
+                        Some((
+                            _,
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Int(lit_int),
+                                ..
+                            }),
+                        )) => lit_int.base10_parse::<u32>().unwrap(),
+                        _ => next_discriminant,
+                    };
+                    next_discriminant = discriminant.wrapping_add(1);
+                    let variant_name = variant.ident.to_string();
+                    let fields = field_schemas(&variant.fields, 4);
+                    quote! {
+                        #crate_name::VariantSchema::new(#variant_name, #discriminant, #fields)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let add_definition = quote! {
+                schema.add_enum(name, <Self as #crate_name::Encoder<Self>>::HEADER_SIZE, &[ #( #variants ),* ])
+            };
+            let types = data_enum
+                .variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter().map(|field| &field.ty))
+                .collect::<Vec<_>>();
+            (add_definition, types)
+        }
+        _ => panic!("only structs and enums are supported"),
+    };
+    let name_str = name.to_string();
+    let output = quote! {
+        impl #impl_generics #crate_name::CodecSchema for #name #type_generics #where_clause {
+            fn type_schema() -> #crate_name::TypeSchema {
+                #crate_name::TypeSchema::Defined(#crate_name::Schema::type_name(
+                    #name_str,
+                    &[ #( <#params as #crate_name::CodecSchema>::type_schema() ),* ],
+                ))
+            }
+            fn add_definitions(schema: &mut #crate_name::Schema) {
+                let name = #crate_name::Schema::type_name(
+                    #name_str,
+                    &[ #( <#params as #crate_name::CodecSchema>::type_schema() ),* ],
+                );
+                // referred types are added once, when the first type referring to them is added
+                if #add_definition {
+                    #( <#types as #crate_name::CodecSchema>::add_definitions(schema); )*
+                }
+            }
+        }
+    };
+    TokenStream::from(output)
+}
+
+/// Derives `Encoder` for structs with named fields and for enums.
+///
+/// Struct fields are encoded one after another in the header. Enums have a header of
+/// `4 + max(variant header size)` bytes: a little-endian `u32` discriminant followed by fields
+/// of the variant encoded like struct fields, the unused tail of the header is zeroed. The
+/// discriminant is the explicit one of the variant (`Variant = 7`) or the previous one plus one,
+/// starting from zero, so variants have to be appended or pinned with explicit discriminants to
+/// keep the encoding stable. Fields of enum variants must implement `Default`.
+///
+/// With `#[codec(solidity)]` the type also implements `SolidityABI`: structs are encoded as
+/// Solidity tuples and enums, which may have unit variants only, as `uint8`.
+///
+/// With `#[codec(extensible)]` a struct is encoded as bytes that start with a `u32` version, so
+/// its header is always 8 bytes. Fields can be appended with `#[codec(since = N)]`, they're
+/// decoded as defaults from values of older versions and older decoders skip them. Fields must
+/// be ordered by versions and the `I{Name}` trait with field offsets isn't generated.
+///
+/// With `#[codec(view)]` the type implements `CodecView`. For a struct `Name` it generates
+/// `NameView<'a>` with an accessor per field that reads the field from the buffer on every call,
+/// `Bytes` and vectors are borrowed and structs marked with `#[codec(view)]` are viewed lazily as
+/// well. Enums are decoded eagerly and must implement `Default`.
+///
+/// With `#[codec(schema)]` the type implements `CodecSchema`, which describes names, types,
+/// offsets and header sizes of its fields, so `Schema::of::<Name>()` can be exported as JSON or
+/// as TypeScript codecs. Derived types of its fields must be marked with `#[codec(schema)]` as
+/// well and type parameters of generic types must implement `CodecSchema`.
+#[proc_macro_derive(Codec, attributes(codec))]
 pub fn codec_macro_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
-    impl_derive_codec(&ast)
+    let attrs = parse_codec_attrs(&ast);
+    let mut output = impl_derive_codec(&ast, &attrs);
+    if attrs.schema {
+        output.extend(impl_derive_schema(&ast, &attrs));
+    }
+    if attrs.solidity {
+        output.extend(impl_derive_solidity(&ast));
+    }
+    if attrs.view {
+        if attrs.extensible {
+            panic!("views of extensible structs are not supported");
+        }
+        output.extend(impl_derive_view(&ast));
+    }
+    output
 }
@@ -0,0 +1,136 @@
+use crate::{
+    buffer::WritableBuffer,
+    vec::check_length,
+    BufferDecoder,
+    BufferEncoder,
+    CodecError,
+    Encoder,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+
+/// B-tree collections have the same layout as hash ones, entries are already sorted.
+impl<K: Default + Sized + Encoder<K> + Ord, V: Default + Sized + Encoder<V>> Encoder<BTreeMap<K, V>>
+    for BTreeMap<K, V>
+{
+    // length + keys (bytes) + values (bytes)
+    const HEADER_SIZE: usize = 4 + 8 + 8;
+
+    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+        encoder.write_u32(field_offset, self.len() as u32);
+        // encode keys
+        let mut key_encoder = BufferEncoder::new(K::HEADER_SIZE * self.len(), None);
+        for (i, key) in self.keys().enumerate() {
+            key.encode(&mut key_encoder, K::HEADER_SIZE * i);
+        }
+        encoder.write_bytes(field_offset + 4, key_encoder.finalize().as_slice());
+        // encode values
+        let mut value_encoder = BufferEncoder::new(V::HEADER_SIZE * self.len(), None);
+        for (i, value) in self.values().enumerate() {
+            value.encode(&mut value_encoder, V::HEADER_SIZE * i);
+        }
+        encoder.write_bytes(field_offset + 12, value_encoder.finalize().as_slice());
+    }
+
+    fn decode_header(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        _result: &mut BTreeMap<K, V>,
+    ) -> (usize, usize) {
+        let (keys_offset, keys_length) = decoder.read_bytes_header(field_offset + 4);
+        let (_, values_length) = decoder.read_bytes_header(field_offset + 12);
+        (keys_offset, keys_length + values_length)
+    }
+
+    fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut BTreeMap<K, V>) {
+        let length = decoder.read_u32(field_offset) as usize;
+        let (key_bytes, value_bytes) = decoder.read_bytes2(field_offset + 4, field_offset + 12);
+        let mut key_decoder = BufferDecoder::new(key_bytes);
+        let keys = (0..length).map(|i| {
+            let mut result = Default::default();
+            K::decode_body(&mut key_decoder, K::HEADER_SIZE * i, &mut result);
+            result
+        });
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        let values = (0..length).map(|i| {
+            let mut result = Default::default();
+            V::decode_body(&mut value_decoder, V::HEADER_SIZE * i, &mut result);
+            result
+        });
+        *result = keys.zip(values).collect()
+    }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut BTreeMap<K, V>,
+    ) -> Result<(), CodecError> {
+        let length = decoder.try_read_u32(field_offset)? as usize;
+        let key_bytes = decoder.try_read_bytes(field_offset + 4)?;
+        let value_bytes = decoder.try_read_bytes(field_offset + 12)?;
+        check_length(length, K::HEADER_SIZE, key_bytes.len())?;
+        check_length(length, V::HEADER_SIZE, value_bytes.len())?;
+        let mut key_decoder = BufferDecoder::new(key_bytes);
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        result.clear();
+        for i in 0..length {
+            let (mut key, mut value) = (K::default(), V::default());
+            K::try_decode_body(&mut key_decoder, K::HEADER_SIZE * i, &mut key)?;
+            V::try_decode_body(&mut value_decoder, V::HEADER_SIZE * i, &mut value)?;
+            result.insert(key, value);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Default + Sized + Encoder<T> + Ord> Encoder<BTreeSet<T>> for BTreeSet<T> {
+    // length + keys (bytes)
+    const HEADER_SIZE: usize = 4 + 8;
+
+    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+        encoder.write_u32(field_offset, self.len() as u32);
+        let mut value_encoder = BufferEncoder::new(T::HEADER_SIZE * self.len(), None);
+        for (i, value) in self.iter().enumerate() {
+            value.encode(&mut value_encoder, T::HEADER_SIZE * i);
+        }
+        encoder.write_bytes(field_offset + 4, value_encoder.finalize().as_slice());
+    }
+
+    fn decode_header(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        _result: &mut BTreeSet<T>,
+    ) -> (usize, usize) {
+        decoder.read_bytes_header(field_offset + 4)
+    }
+
+    fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut BTreeSet<T>) {
+        let length = decoder.read_u32(field_offset) as usize;
+        let value_bytes = decoder.read_bytes(field_offset + 4);
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        *result = (0..length)
+            .map(|i| {
+                let mut result = Default::default();
+                T::decode_body(&mut value_decoder, T::HEADER_SIZE * i, &mut result);
+                result
+            })
+            .collect()
+    }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut BTreeSet<T>,
+    ) -> Result<(), CodecError> {
+        let length = decoder.try_read_u32(field_offset)? as usize;
+        let value_bytes = decoder.try_read_bytes(field_offset + 4)?;
+        check_length(length, T::HEADER_SIZE, value_bytes.len())?;
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        result.clear();
+        for i in 0..length {
+            let mut value = T::default();
+            T::try_decode_body(&mut value_decoder, T::HEADER_SIZE * i, &mut value)?;
+            result.insert(value);
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::CodecError;
 use alloc::vec::Vec;
 use byteorder::{ByteOrder, LittleEndian};
 use paste::paste;
@@ -135,6 +136,8 @@ impl WritableBuffer for BufferEncoder {
 #[derive(Default)]
 pub struct BufferDecoder<'a> {
     buffer: &'a [u8],
+    // the end of the furthest region read by fallible methods
+    consumed: usize,
 }
 
 macro_rules! decode_le_int {
@@ -147,9 +150,23 @@ macro_rules! decode_le_int {
     };
 }
 
+macro_rules! try_decode_le_int {
+    ($typ:ty) => {
+        paste! {
+            pub fn [<try_read_ $typ>](&mut self, field_offset: usize) -> Result<$typ, CodecError> {
+                let bytes = self.try_read_slice(field_offset, core::mem::size_of::<$typ>())?;
+                Ok(LittleEndian::[<read_ $typ>](bytes))
+            }
+        }
+    };
+}
+
 impl<'a> BufferDecoder<'a> {
     pub fn new(input: &'a [u8]) -> Self {
-        Self { buffer: input }
+        Self {
+            buffer: input,
+            consumed: 0,
+        }
     }
 
     pub fn read_i8(&mut self, field_offset: usize) -> i8 {
@@ -172,17 +189,56 @@ impl<'a> BufferDecoder<'a> {
         (bytes_offset, bytes_length)
     }
 
-    pub fn read_bytes(&self, field_offset: usize) -> &[u8] {
+    pub fn read_bytes(&self, field_offset: usize) -> &'a [u8] {
         let (bytes_offset, bytes_length) = self.read_bytes_header(field_offset);
         &self.buffer[bytes_offset..(bytes_offset + bytes_length)]
     }
 
-    pub fn read_bytes2(&self, field1_offset: usize, field2_offset: usize) -> (&[u8], &[u8]) {
+    pub fn read_bytes2(&self, field1_offset: usize, field2_offset: usize) -> (&'a [u8], &'a [u8]) {
         (
             self.read_bytes(field1_offset),
             self.read_bytes(field2_offset),
         )
     }
+
+    /// Returns `length` bytes at `offset` or an error if they are out of the buffer.
+    pub fn try_read_slice(&mut self, offset: usize, length: usize) -> Result<&'a [u8], CodecError> {
+        let bytes = offset
+            .checked_add(length)
+            .and_then(|end| self.buffer.get(offset..end))
+            .ok_or(CodecError::OutOfBounds { offset, length })?;
+        self.consumed = self.consumed.max(offset + length);
+        Ok(bytes)
+    }
+
+    pub fn try_read_i8(&mut self, field_offset: usize) -> Result<i8, CodecError> {
+        Ok(self.try_read_slice(field_offset, 1)?[0] as i8)
+    }
+    pub fn try_read_u8(&mut self, field_offset: usize) -> Result<u8, CodecError> {
+        Ok(self.try_read_slice(field_offset, 1)?[0])
+    }
+
+    try_decode_le_int!(i16);
+    try_decode_le_int!(u16);
+    try_decode_le_int!(i32);
+    try_decode_le_int!(u32);
+    try_decode_le_int!(i64);
+    try_decode_le_int!(u64);
+
+    pub fn try_read_bytes(&mut self, field_offset: usize) -> Result<&'a [u8], CodecError> {
+        let bytes_offset = self.try_read_u32(field_offset)? as usize;
+        let bytes_length = self.try_read_u32(field_offset + 4)? as usize;
+        self.try_read_slice(bytes_offset, bytes_length)
+    }
+
+    /// Length of the buffer prefix covered by everything read with fallible methods so far.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.buffer.len().saturating_sub(self.consumed)
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use crate::{BufferDecoder, Encoder, WritableBuffer};
+use crate::{BufferDecoder, CodecError, Encoder, WritableBuffer};
 use fluentbase_codec_derive::Codec;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -23,6 +23,18 @@ impl Encoder<EmptyVec> for EmptyVec {
         debug_assert_eq!(count, 0);
         decoder.read_bytes_header(field_offset + 4)
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        _result: &mut EmptyVec,
+    ) -> Result<(), CodecError> {
+        if decoder.try_read_u32(field_offset)? != 0 {
+            return Err(CodecError::LengthOverflow);
+        }
+        decoder.try_read_bytes(field_offset + 4)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
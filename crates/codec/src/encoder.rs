@@ -1,4 +1,7 @@
-use crate::buffer::{BufferDecoder, BufferEncoder, FixedEncoder, WritableBuffer};
+use crate::{
+    buffer::{BufferDecoder, BufferEncoder, FixedEncoder, WritableBuffer},
+    CodecError,
+};
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
@@ -31,6 +34,30 @@ pub trait Encoder<T: Sized> {
     fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut T) {
         Self::decode_header(decoder, field_offset, result);
     }
+
+    /// Same as `decode_body`, but checks the input and returns an error for malformed data
+    /// instead of panicking.
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut T,
+    ) -> Result<(), CodecError>;
+
+    /// Decodes a value from the buffer that must contain exactly one encoded value, it's the
+    /// safe way of decoding untrusted input.
+    fn try_decode(buffer: &[u8]) -> Result<T, CodecError>
+    where
+        T: Default,
+    {
+        let mut decoder = BufferDecoder::new(buffer);
+        let mut result = T::default();
+        decoder.try_read_slice(0, Self::HEADER_SIZE)?;
+        Self::try_decode_body(&mut decoder, 0, &mut result)?;
+        if decoder.remaining() > 0 {
+            return Err(CodecError::TrailingBytes);
+        }
+        Ok(result)
+    }
 }
 
 pub struct FieldEncoder<T: Sized + Encoder<T>, const FIELD_OFFSET: usize>(PhantomData<T>);
@@ -60,4 +87,9 @@ impl<T: Sized + Encoder<T>, const FIELD_OFFSET: usize> FieldEncoder<T, FIELD_OFF
         let mut buffer_decoder = BufferDecoder::new(buffer);
         T::decode_body(&mut buffer_decoder, field_offset, result)
     }
+
+    pub fn try_decode_field_body(buffer: &[u8], result: &mut T) -> Result<(), CodecError> {
+        let mut buffer_decoder = BufferDecoder::new(buffer);
+        T::try_decode_body(&mut buffer_decoder, Self::FIELD_OFFSET, result)
+    }
 }
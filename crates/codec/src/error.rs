@@ -0,0 +1,30 @@
+use core::fmt;
+
+/// An error of fallible decoding, returned for malformed input instead of panicking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecError {
+    /// A field or a data region lies outside of the buffer.
+    OutOfBounds { offset: usize, length: usize },
+    /// The number of elements doesn't fit into the encoded data.
+    LengthOverflow,
+    /// A bool, an option flag or an enum discriminant has an unknown value.
+    InvalidTag(u32),
+    /// The encoded bytes don't form a valid value of the type.
+    InvalidValue,
+    /// The buffer has bytes left after the decoded value.
+    TrailingBytes,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::OutOfBounds { offset, length } => {
+                write!(f, "{} bytes at offset {} are out of bounds", length, offset)
+            }
+            CodecError::LengthOverflow => write!(f, "length overflows encoded data"),
+            CodecError::InvalidTag(tag) => write!(f, "invalid tag {}", tag),
+            CodecError::InvalidValue => write!(f, "invalid value"),
+            CodecError::TrailingBytes => write!(f, "trailing bytes after encoded value"),
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{buffer::WritableBuffer, BufferDecoder, Encoder};
-use alloy_primitives::{Address, Bytes, FixedBytes, Uint};
+use crate::{buffer::WritableBuffer, BufferDecoder, CodecError, Encoder};
+use alloy_primitives::{Address, Bytes, FixedBytes, Signed, Uint};
 
 impl Encoder<Bytes> for Bytes {
     const HEADER_SIZE: usize = core::mem::size_of::<u32>() * 2;
@@ -20,6 +20,15 @@ impl Encoder<Bytes> for Bytes {
         let bytes = decoder.read_bytes(field_offset);
         *result = Bytes::copy_from_slice(bytes);
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Bytes,
+    ) -> Result<(), CodecError> {
+        *result = Bytes::copy_from_slice(decoder.try_read_bytes(field_offset)?);
+        Ok(())
+    }
 }
 
 impl<const N: usize> Encoder<FixedBytes<N>> for FixedBytes<N> {
@@ -35,6 +44,13 @@ impl<const N: usize> Encoder<FixedBytes<N>> for FixedBytes<N> {
         <[u8; N]>::decode_body(decoder, field_offset, &mut result.0);
         (0, 0)
     }
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut FixedBytes<N>,
+    ) -> Result<(), CodecError> {
+        <[u8; N]>::try_decode_body(decoder, field_offset, &mut result.0)
+    }
 }
 
 macro_rules! impl_evm_fixed {
@@ -56,6 +72,17 @@ macro_rules! impl_evm_fixed {
                 );
                 (0, 0)
             }
+            fn try_decode_body(
+                decoder: &mut BufferDecoder,
+                field_offset: usize,
+                result: &mut $typ,
+            ) -> Result<(), CodecError> {
+                FixedBytes::<{ Self::HEADER_SIZE }>::try_decode_body(
+                    decoder,
+                    field_offset,
+                    &mut result.0,
+                )
+            }
         }
     };
 }
@@ -77,4 +104,43 @@ impl<const BITS: usize, const LIMBS: usize> Encoder<Uint<BITS, LIMBS>> for Uint<
         }
         (0, 0)
     }
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Uint<BITS, LIMBS>,
+    ) -> Result<(), CodecError> {
+        let mut limbs = [0u64; LIMBS];
+        <[u64; LIMBS]>::try_decode_body(decoder, field_offset, &mut limbs)?;
+        // limbs can't have bits above `BITS` set
+        *result = Uint::checked_from_limbs_slice(&limbs).ok_or(CodecError::InvalidValue)?;
+        Ok(())
+    }
+}
+
+/// Signed integers are encoded as their two's complement `Uint` representation.
+impl<const BITS: usize, const LIMBS: usize> Encoder<Signed<BITS, LIMBS>> for Signed<BITS, LIMBS> {
+    const HEADER_SIZE: usize = <Uint<BITS, LIMBS>>::HEADER_SIZE;
+    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+        self.into_raw().encode(encoder, field_offset)
+    }
+    fn decode_header(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Signed<BITS, LIMBS>,
+    ) -> (usize, usize) {
+        let mut raw = Uint::ZERO;
+        <Uint<BITS, LIMBS>>::decode_header(decoder, field_offset, &mut raw);
+        *result = Signed::from_raw(raw);
+        (0, 0)
+    }
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Signed<BITS, LIMBS>,
+    ) -> Result<(), CodecError> {
+        let mut raw = Uint::ZERO;
+        <Uint<BITS, LIMBS>>::try_decode_body(decoder, field_offset, &mut raw)?;
+        *result = Signed::from_raw(raw);
+        Ok(())
+    }
 }
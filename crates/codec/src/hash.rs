@@ -1,4 +1,11 @@
-use crate::{buffer::WritableBuffer, BufferDecoder, BufferEncoder, Encoder};
+use crate::{
+    buffer::WritableBuffer,
+    vec::check_length,
+    BufferDecoder,
+    BufferEncoder,
+    CodecError,
+    Encoder,
+};
 use alloc::vec::Vec;
 use core::hash::Hash;
 use hashbrown::{HashMap, HashSet};
@@ -65,6 +72,29 @@ impl<K: Default + Sized + Encoder<K> + Eq + Hash + Ord, V: Default + Sized + Enc
         // zip into map
         *result = keys.zip(values).collect()
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut HashMap<K, V>,
+    ) -> Result<(), CodecError> {
+        let length = decoder.try_read_u32(field_offset)? as usize;
+        let key_bytes = decoder.try_read_bytes(field_offset + 4)?;
+        let value_bytes = decoder.try_read_bytes(field_offset + 12)?;
+        check_length(length, K::HEADER_SIZE, key_bytes.len())?;
+        check_length(length, V::HEADER_SIZE, value_bytes.len())?;
+        let mut key_decoder = BufferDecoder::new(key_bytes);
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        result.clear();
+        result.reserve(length);
+        for i in 0..length {
+            let (mut key, mut value) = (K::default(), V::default());
+            K::try_decode_body(&mut key_decoder, K::HEADER_SIZE * i, &mut key)?;
+            V::try_decode_body(&mut value_decoder, V::HEADER_SIZE * i, &mut value)?;
+            result.insert(key, value);
+        }
+        Ok(())
+    }
 }
 
 impl<T: Default + Sized + Encoder<T> + Eq + Hash + Ord> Encoder<HashSet<T>> for HashSet<T> {
@@ -112,4 +142,23 @@ impl<T: Default + Sized + Encoder<T> + Eq + Hash + Ord> Encoder<HashSet<T>> for
         // zip into map
         *result = values.collect()
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut HashSet<T>,
+    ) -> Result<(), CodecError> {
+        let length = decoder.try_read_u32(field_offset)? as usize;
+        let value_bytes = decoder.try_read_bytes(field_offset + 4)?;
+        check_length(length, T::HEADER_SIZE, value_bytes.len())?;
+        let mut value_decoder = BufferDecoder::new(value_bytes);
+        result.clear();
+        result.reserve(length);
+        for i in 0..length {
+            let mut value = T::default();
+            T::try_decode_body(&mut value_decoder, T::HEADER_SIZE * i, &mut value)?;
+            result.insert(value);
+        }
+        Ok(())
+    }
 }
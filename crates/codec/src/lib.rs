@@ -7,19 +7,44 @@ pub use crate::{
     buffer::{BufferDecoder, BufferEncoder, WritableBuffer},
     empty::EmptyVec,
     encoder::{Encoder, FieldEncoder},
+    error::CodecError,
+    schema::{
+        CodecSchema,
+        Definition,
+        DefinitionKind,
+        FieldSchema,
+        Schema,
+        TypeSchema,
+        VariantSchema,
+    },
+    solidity::{
+        SolidityABI,
+        SolidityDecoder,
+        SolidityEncoder,
+        SolidityTupleDecoder,
+        SolidityTupleEncoder,
+    },
+    view::{BytesRef, CodecView, VecView},
 };
 
+mod btree;
 mod buffer;
 mod empty;
 mod encoder;
+mod error;
 mod evm;
 mod hash;
 mod macros;
 mod primitive;
+mod schema;
 mod serde;
+mod solidity;
+mod string;
 #[cfg(test)]
 mod tests;
 mod tuple;
+mod typescript;
 mod vec;
+mod view;
 
 pub use fluentbase_codec_derive::Codec;
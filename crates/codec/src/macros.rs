@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::{BufferDecoder, BufferEncoder, Encoder};
+    use crate::{BufferDecoder, BufferEncoder, CodecError, Encoder};
     use fluentbase_codec_derive::Codec;
     use hashbrown::HashMap;
 
@@ -137,4 +137,188 @@ mod tests {
         ComplicatedType::decode_body(&mut buffer_decoder, 0, &mut value1);
         assert_eq!(value0, value1);
     }
+
+    #[derive(Default, Debug, Codec, PartialEq)]
+    #[repr(u32)]
+    pub enum MethodOutput {
+        #[default]
+        Stop,
+        Return(Vec<u8>, u64),
+        Revert {
+            reason: u32,
+            gas: u64,
+        },
+        Halt = 10,
+        Forward(u16),
+    }
+
+    #[derive(Default, Debug, Codec, PartialEq)]
+    pub struct MethodResult {
+        output: MethodOutput,
+        outputs: Vec<MethodOutput>,
+        checksum: u32,
+    }
+
+    #[test]
+    fn test_enum_header_layout() {
+        // the discriminant and the largest variant, which is `Return`
+        assert_eq!(MethodOutput::HEADER_SIZE, 4 + 12 + 8);
+        let encoded_value = MethodOutput::Revert {
+            reason: 0xbadcab1e,
+            gas: 21000,
+        }
+        .encode_to_vec(0);
+        assert_eq!(
+            hex::encode(&encoded_value),
+            "02000000".to_string() + "1eabdcba" + "0852000000000000" + "0000000000000000"
+        );
+        // explicit discriminants are kept, the next variant continues from them
+        assert_eq!(&MethodOutput::Halt.encode_to_vec(0)[..4], &[10, 0, 0, 0]);
+        assert_eq!(
+            &MethodOutput::Forward(7).encode_to_vec(0)[..6],
+            &[11, 0, 0, 0, 7, 0]
+        );
+    }
+
+    #[test]
+    fn test_enum_round_trip() {
+        let values = vec![
+            MethodOutput::Stop,
+            MethodOutput::Return(vec![1, 2, 3], u64::MAX),
+            MethodOutput::Revert {
+                reason: 7,
+                gas: 100,
+            },
+            MethodOutput::Halt,
+            MethodOutput::Forward(u16::MAX),
+        ];
+        for value0 in values.iter() {
+            let encoded_value = value0.encode_to_vec(0);
+            assert!(encoded_value.len() >= MethodOutput::HEADER_SIZE);
+            let mut buffer_decoder = BufferDecoder::new(&encoded_value);
+            let mut value1 = MethodOutput::default();
+            MethodOutput::decode_body(&mut buffer_decoder, 0, &mut value1);
+            assert_eq!(*value0, value1);
+        }
+        // enums nested into structs and vectors
+        let value0 = MethodResult {
+            output: MethodOutput::Return(vec![4, 5], 6),
+            outputs: values,
+            checksum: 0xdeadbeef,
+        };
+        let encoded_value = value0.encode_to_vec(0);
+        let mut buffer_decoder = BufferDecoder::new(&encoded_value);
+        let mut value1 = MethodResult::default();
+        MethodResult::decode_body(&mut buffer_decoder, 0, &mut value1);
+        assert_eq!(value0, value1);
+    }
+
+    #[test]
+    fn test_derive_try_decode() {
+        let value0 = MethodResult {
+            output: MethodOutput::Revert { reason: 1, gas: 2 },
+            outputs: vec![MethodOutput::Return(vec![3], 4), MethodOutput::Stop],
+            checksum: 5,
+        };
+        let mut encoded_value = value0.encode_to_vec(0);
+        assert_eq!(MethodResult::try_decode(&encoded_value), Ok(value0));
+        // unknown discriminant of the first field
+        encoded_value[..4].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(
+            MethodResult::try_decode(&encoded_value),
+            Err(CodecError::InvalidTag(3))
+        );
+    }
+
+    #[derive(Default, Debug, Clone, Codec, PartialEq)]
+    #[codec(extensible)]
+    struct AccountV1 {
+        balance: u64,
+        code: Vec<u8>,
+    }
+
+    #[derive(Default, Debug, Clone, Codec, PartialEq)]
+    #[codec(extensible)]
+    struct AccountV2 {
+        balance: u64,
+        code: Vec<u8>,
+        #[codec(since = 1)]
+        nonce: u32,
+        #[codec(since = 1)]
+        storage: HashMap<u32, u64>,
+    }
+
+    #[derive(Default, Debug, Codec, PartialEq)]
+    struct AccountsV1 {
+        account: AccountV1,
+        checksum: u32,
+    }
+
+    #[derive(Default, Debug, Codec, PartialEq)]
+    struct AccountsV2 {
+        account: AccountV2,
+        checksum: u32,
+    }
+
+    #[test]
+    fn test_extensible_struct() {
+        assert_eq!(AccountV1::HEADER_SIZE, AccountV2::HEADER_SIZE);
+        let v1 = AccountV1 {
+            balance: 100,
+            code: vec![1, 2, 3],
+        };
+        let v2 = AccountV2 {
+            balance: 100,
+            code: vec![1, 2, 3],
+            nonce: 7,
+            storage: HashMap::from([(1, 2)]),
+        };
+        // new fields of old values are defaults
+        let encoded_value = v1.encode_to_vec(0);
+        let mut buffer_decoder = BufferDecoder::new(&encoded_value);
+        let mut value = AccountV2::default();
+        AccountV2::decode_body(&mut buffer_decoder, 0, &mut value);
+        assert_eq!(
+            value,
+            AccountV2 {
+                nonce: 0,
+                storage: HashMap::new(),
+                ..v2.clone()
+            }
+        );
+        // old decoders skip new fields
+        let encoded_value = v2.encode_to_vec(0);
+        assert_eq!(AccountV1::try_decode(&encoded_value), Ok(v1.clone()));
+        assert_eq!(AccountV2::try_decode(&encoded_value), Ok(v2.clone()));
+        // layouts of enclosing structs don't change
+        let encoded_value = AccountsV2 {
+            account: v2,
+            checksum: 5,
+        }
+        .encode_to_vec(0);
+        assert_eq!(
+            AccountsV1::try_decode(&encoded_value),
+            Ok(AccountsV1 {
+                account: v1,
+                checksum: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_extensible_struct_malformed() {
+        let mut encoded_value = AccountV2::default().encode_to_vec(0);
+        // the body is shorter than fields of the stored version
+        encoded_value[4..8].copy_from_slice(&20u32.to_le_bytes());
+        assert!(matches!(
+            AccountV2::try_decode(&encoded_value),
+            Err(CodecError::OutOfBounds { .. })
+        ));
+        // the body is out of the buffer
+        encoded_value[4..8].copy_from_slice(&100u32.to_le_bytes());
+        assert!(matches!(
+            AccountV1::try_decode(&encoded_value),
+            Err(CodecError::OutOfBounds { .. })
+        ));
+    }
 }
@@ -1,4 +1,5 @@
-use crate::{buffer::WritableBuffer, BufferDecoder, Encoder};
+use crate::{buffer::WritableBuffer, BufferDecoder, CodecError, Encoder};
+use alloc::boxed::Box;
 
 impl Encoder<u8> for u8 {
     const HEADER_SIZE: usize = core::mem::size_of::<u8>();
@@ -13,6 +14,14 @@ impl Encoder<u8> for u8 {
         *result = decoder.read_u8(field_offset);
         (0, 0)
     }
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut u8,
+    ) -> Result<(), CodecError> {
+        *result = decoder.try_read_u8(field_offset)?;
+        Ok(())
+    }
 }
 impl Encoder<bool> for bool {
     const HEADER_SIZE: usize = core::mem::size_of::<bool>();
@@ -27,10 +36,22 @@ impl Encoder<bool> for bool {
         *result = decoder.read_u8(field_offset) != 0;
         (0, 0)
     }
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut bool,
+    ) -> Result<(), CodecError> {
+        *result = match decoder.try_read_u8(field_offset)? {
+            0 => false,
+            1 => true,
+            tag => return Err(CodecError::InvalidTag(tag as u32)),
+        };
+        Ok(())
+    }
 }
 
 macro_rules! impl_le_int {
-    ($typ:ty, $write_fn:ident, $read_fn:ident) => {
+    ($typ:ty, $write_fn:ident, $read_fn:ident, $try_read_fn:ident) => {
         impl Encoder<$typ> for $typ {
             const HEADER_SIZE: usize = core::mem::size_of::<$typ>();
             fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
@@ -44,16 +65,60 @@ macro_rules! impl_le_int {
                 *result = decoder.$read_fn(field_offset);
                 (0, 0)
             }
+            fn try_decode_body(
+                decoder: &mut BufferDecoder,
+                field_offset: usize,
+                result: &mut $typ,
+            ) -> Result<(), CodecError> {
+                *result = decoder.$try_read_fn(field_offset)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_le_int!(u16, write_u16, read_u16, try_read_u16);
+impl_le_int!(u32, write_u32, read_u32, try_read_u32);
+impl_le_int!(u64, write_u64, read_u64, try_read_u64);
+impl_le_int!(i16, write_i16, read_i16, try_read_i16);
+impl_le_int!(i32, write_i32, read_i32, try_read_i32);
+impl_le_int!(i64, write_i64, read_i64, try_read_i64);
+
+/// 128-bit integers are encoded in LE format as two 64-bit halves, the low one first.
+macro_rules! impl_le_int128 {
+    ($typ:ty) => {
+        impl Encoder<$typ> for $typ {
+            const HEADER_SIZE: usize = core::mem::size_of::<$typ>();
+            fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+                encoder.write_u64(field_offset, *self as u64);
+                encoder.write_u64(field_offset + 8, (*self >> 64) as u64);
+            }
+            fn decode_header(
+                decoder: &mut BufferDecoder,
+                field_offset: usize,
+                result: &mut $typ,
+            ) -> (usize, usize) {
+                let low = decoder.read_u64(field_offset) as u128;
+                let high = decoder.read_u64(field_offset + 8) as u128;
+                *result = (high << 64 | low) as $typ;
+                (0, 0)
+            }
+            fn try_decode_body(
+                decoder: &mut BufferDecoder,
+                field_offset: usize,
+                result: &mut $typ,
+            ) -> Result<(), CodecError> {
+                let low = decoder.try_read_u64(field_offset)? as u128;
+                let high = decoder.try_read_u64(field_offset + 8)? as u128;
+                *result = (high << 64 | low) as $typ;
+                Ok(())
+            }
         }
     };
 }
 
-impl_le_int!(u16, write_u16, read_u16);
-impl_le_int!(u32, write_u32, read_u32);
-impl_le_int!(u64, write_u64, read_u64);
-impl_le_int!(i16, write_i16, read_i16);
-impl_le_int!(i32, write_i32, read_i32);
-impl_le_int!(i64, write_i64, read_i64);
+impl_le_int128!(u128);
+impl_le_int128!(i128);
 
 impl<T: Sized + Encoder<T>, const N: usize> Encoder<[T; N]> for [T; N] {
     const HEADER_SIZE: usize = T::HEADER_SIZE * N;
@@ -74,6 +139,17 @@ impl<T: Sized + Encoder<T>, const N: usize> Encoder<[T; N]> for [T; N] {
         });
         (0, 0)
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut [T; N],
+    ) -> Result<(), CodecError> {
+        for (i, item) in result.iter_mut().enumerate() {
+            T::try_decode_body(decoder, field_offset + i * T::HEADER_SIZE, item)?;
+        }
+        Ok(())
+    }
 }
 
 impl<T: Sized + Encoder<T> + Default> Encoder<Option<T>> for Option<T> {
@@ -117,4 +193,54 @@ impl<T: Sized + Encoder<T> + Default> Encoder<Option<T>> for Option<T> {
             None
         };
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Option<T>,
+    ) -> Result<(), CodecError> {
+        *result = match decoder.try_read_u8(field_offset)? {
+            0 => {
+                // the payload of `None` is still a part of the header
+                decoder.try_read_slice(field_offset + 1, T::HEADER_SIZE)?;
+                None
+            }
+            1 => {
+                let mut result_inner: T = Default::default();
+                T::try_decode_body(decoder, field_offset + 1, &mut result_inner)?;
+                Some(result_inner)
+            }
+            tag => return Err(CodecError::InvalidTag(tag as u32)),
+        };
+        Ok(())
+    }
+}
+
+/// Boxed values are encoded as the values themselves.
+impl<T: Sized + Encoder<T>> Encoder<Box<T>> for Box<T> {
+    const HEADER_SIZE: usize = T::HEADER_SIZE;
+
+    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+        self.as_ref().encode(encoder, field_offset)
+    }
+
+    fn decode_header(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Box<T>,
+    ) -> (usize, usize) {
+        T::decode_header(decoder, field_offset, result.as_mut())
+    }
+
+    fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut Box<T>) {
+        T::decode_body(decoder, field_offset, result.as_mut())
+    }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Box<T>,
+    ) -> Result<(), CodecError> {
+        T::try_decode_body(decoder, field_offset, result.as_mut())
+    }
 }
@@ -0,0 +1,597 @@
+use crate::{tuple::for_each_tuple, EmptyVec, Encoder};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{Address, Bytes, FixedBytes, Signed, Uint};
+use core::{fmt::Write, hash::Hash};
+use hashbrown::{HashMap, HashSet};
+
+/// Layout of an encoded type, what off-chain decoders need to read it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeSchema {
+    Bool,
+    /// Unsigned integer of the given number of bytes.
+    Uint(usize),
+    /// Signed two's complement integer of the given number of bytes.
+    Int(usize),
+    Address,
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<TypeSchema>, usize),
+    Vec(Box<TypeSchema>),
+    Option(Box<TypeSchema>),
+    Tuple(Vec<TypeSchema>),
+    Map(Box<TypeSchema>, Box<TypeSchema>),
+    Set(Box<TypeSchema>),
+    EmptyVec,
+    /// A struct or an enum derived with `Codec`, it's described in schema definitions.
+    Defined(String),
+}
+
+impl TypeSchema {
+    /// Identifier of the type, it's used to name instances of generic types.
+    pub fn name(&self) -> String {
+        match self {
+            TypeSchema::Bool => "Bool".to_string(),
+            TypeSchema::Uint(size) => format!("U{}", size * 8),
+            TypeSchema::Int(size) => format!("I{}", size * 8),
+            TypeSchema::Address => "Address".to_string(),
+            TypeSchema::FixedBytes(size) => format!("FixedBytes{}", size),
+            TypeSchema::Bytes => "Bytes".to_string(),
+            TypeSchema::String => "String".to_string(),
+            TypeSchema::Array(item, length) => format!("Array{}{}", length, item.name()),
+            TypeSchema::Vec(item) => format!("Vec{}", item.name()),
+            TypeSchema::Option(item) => format!("Option{}", item.name()),
+            TypeSchema::Tuple(items) => items
+                .iter()
+                .fold("Tuple".to_string(), |name, item| name + &item.name()),
+            TypeSchema::Map(key, value) => format!("Map{}{}", key.name(), value.name()),
+            TypeSchema::Set(item) => format!("Set{}", item.name()),
+            TypeSchema::EmptyVec => "EmptyVec".to_string(),
+            TypeSchema::Defined(name) => name.clone(),
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let _ = match self {
+            TypeSchema::Bool => write!(out, r#"{{"kind":"bool"}}"#),
+            TypeSchema::Uint(size) => write!(out, r#"{{"kind":"uint","size":{}}}"#, size),
+            TypeSchema::Int(size) => write!(out, r#"{{"kind":"int","size":{}}}"#, size),
+            TypeSchema::Address => write!(out, r#"{{"kind":"address"}}"#),
+            TypeSchema::FixedBytes(size) => {
+                write!(out, r#"{{"kind":"fixedBytes","size":{}}}"#, size)
+            }
+            TypeSchema::Bytes => write!(out, r#"{{"kind":"bytes"}}"#),
+            TypeSchema::String => write!(out, r#"{{"kind":"string"}}"#),
+            TypeSchema::Array(item, length) => {
+                out.push_str(r#"{"kind":"array","item":"#);
+                item.write_json(out);
+                write!(out, r#","length":{}}}"#, length)
+            }
+            TypeSchema::Vec(item) | TypeSchema::Option(item) | TypeSchema::Set(item) => {
+                let kind = match self {
+                    TypeSchema::Vec(_) => "vec",
+                    TypeSchema::Option(_) => "option",
+                    _ => "set",
+                };
+                write!(out, r#"{{"kind":"{}","item":"#, kind).unwrap();
+                item.write_json(out);
+                write!(out, "}}")
+            }
+            TypeSchema::Tuple(items) => {
+                out.push_str(r#"{"kind":"tuple","items":["#);
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    item.write_json(out);
+                }
+                write!(out, "]}}")
+            }
+            TypeSchema::Map(key, value) => {
+                out.push_str(r#"{"kind":"map","key":"#);
+                key.write_json(out);
+                out.push_str(r#","value":"#);
+                value.write_json(out);
+                write!(out, "}}")
+            }
+            TypeSchema::EmptyVec => write!(out, r#"{{"kind":"emptyVec"}}"#),
+            TypeSchema::Defined(name) => write!(out, r#"{{"kind":"defined","name":"{}"}}"#, name),
+        };
+    }
+}
+
+/// A field of a struct or an enum variant, the offset is relative to the header of the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+    pub name: String,
+    pub ty: TypeSchema,
+    pub offset: usize,
+    pub header_size: usize,
+    /// Version of an extensible struct the field is added in.
+    pub since: u32,
+}
+
+impl FieldSchema {
+    pub fn new(name: &str, ty: TypeSchema, offset: usize, header_size: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            ty,
+            offset,
+            header_size,
+            since: 0,
+        }
+    }
+
+    pub fn since(mut self, since: u32) -> Self {
+        self.since = since;
+        self
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(out, r#"{{"name":"{}","type":"#, self.name);
+        self.ty.write_json(out);
+        let _ = write!(
+            out,
+            r#","offset":{},"headerSize":{}"#,
+            self.offset, self.header_size
+        );
+        if self.since > 0 {
+            let _ = write!(out, r#","since":{}"#, self.since);
+        }
+        out.push('}');
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSchema {
+    pub name: String,
+    pub discriminant: u32,
+    /// Fields of tuple variants are named by their indices.
+    pub fields: Vec<FieldSchema>,
+}
+
+impl VariantSchema {
+    pub fn new(name: &str, discriminant: u32, fields: &[FieldSchema]) -> Self {
+        Self {
+            name: name.to_string(),
+            discriminant,
+            fields: fields.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Struct(Vec<FieldSchema>),
+    /// Extensible structs are stored as bytes that start with a `u32` version, offsets of fields
+    /// are relative to these bytes.
+    ExtensibleStruct(Vec<FieldSchema>),
+    /// Enums start with a `u32` discriminant followed by fields of the variant.
+    Enum(Vec<VariantSchema>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub header_size: usize,
+    pub kind: DefinitionKind,
+}
+
+/// Types with a known layout, it's implemented for all encodable types and generated by
+/// `#[derive(Codec)]` for types marked with `#[codec(schema)]`.
+pub trait CodecSchema {
+    fn type_schema() -> TypeSchema;
+
+    /// Adds definitions of the type and of all types it refers to.
+    fn add_definitions(_schema: &mut Schema) {}
+}
+
+/// Machine-readable description of derived types, it can be exported as JSON or turned into
+/// TypeScript encoders and decoders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub definitions: Vec<Definition>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schema of the type and all types it refers to.
+    pub fn of<T: CodecSchema>() -> Self {
+        let mut schema = Self::new();
+        schema.add::<T>();
+        schema
+    }
+
+    pub fn add<T: CodecSchema>(&mut self) -> &mut Self {
+        T::add_definitions(self);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Definition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.name == name)
+    }
+
+    /// Adds a definition unless there is one with the same name, returns whether it's added.
+    pub fn add_definition(&mut self, definition: Definition) -> bool {
+        if self.get(&definition.name).is_some() {
+            return false;
+        }
+        self.definitions.push(definition);
+        true
+    }
+
+    pub fn add_struct(&mut self, name: String, header_size: usize, fields: &[FieldSchema]) -> bool {
+        self.add_definition(Definition {
+            name,
+            header_size,
+            kind: DefinitionKind::Struct(fields.to_vec()),
+        })
+    }
+
+    pub fn add_extensible_struct(&mut self, name: String, fields: &[FieldSchema]) -> bool {
+        self.add_definition(Definition {
+            name,
+            header_size: 8,
+            kind: DefinitionKind::ExtensibleStruct(fields.to_vec()),
+        })
+    }
+
+    pub fn add_enum(
+        &mut self,
+        name: String,
+        header_size: usize,
+        variants: &[VariantSchema],
+    ) -> bool {
+        self.add_definition(Definition {
+            name,
+            header_size,
+            kind: DefinitionKind::Enum(variants.to_vec()),
+        })
+    }
+
+    /// Name of a derived type, instances of generic types are named after their parameters.
+    pub fn type_name(name: &str, params: &[TypeSchema]) -> String {
+        params
+            .iter()
+            .fold(name.to_string(), |name, param| name + &param.name())
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from(r#"{"definitions":["#);
+        for (i, definition) in self.definitions.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(
+                out,
+                r#"{{"name":"{}","headerSize":{},"#,
+                definition.name, definition.header_size
+            );
+            match &definition.kind {
+                DefinitionKind::Struct(fields) => {
+                    out.push_str(r#""kind":"struct","fields":"#);
+                    write_fields_json(&mut out, fields);
+                }
+                DefinitionKind::ExtensibleStruct(fields) => {
+                    out.push_str(r#""kind":"extensibleStruct","fields":"#);
+                    write_fields_json(&mut out, fields);
+                }
+                DefinitionKind::Enum(variants) => {
+                    out.push_str(r#""kind":"enum","variants":["#);
+                    for (i, variant) in variants.iter().enumerate() {
+                        if i > 0 {
+                            out.push(',');
+                        }
+                        let _ = write!(
+                            out,
+                            r#"{{"name":"{}","discriminant":{},"fields":"#,
+                            variant.name, variant.discriminant
+                        );
+                        write_fields_json(&mut out, &variant.fields);
+                        out.push('}');
+                    }
+                    out.push(']');
+                }
+            }
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+fn write_fields_json(out: &mut String, fields: &[FieldSchema]) {
+    out.push('[');
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        field.write_json(out);
+    }
+    out.push(']');
+}
+
+macro_rules! impl_primitive_schema {
+    ($($typ:ty => $schema:expr),* $(,)?) => {
+        $(
+            impl CodecSchema for $typ {
+                fn type_schema() -> TypeSchema {
+                    $schema
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_schema!(
+    bool => TypeSchema::Bool,
+    u8 => TypeSchema::Uint(1),
+    u16 => TypeSchema::Uint(2),
+    u32 => TypeSchema::Uint(4),
+    u64 => TypeSchema::Uint(8),
+    u128 => TypeSchema::Uint(16),
+    i16 => TypeSchema::Int(2),
+    i32 => TypeSchema::Int(4),
+    i64 => TypeSchema::Int(8),
+    i128 => TypeSchema::Int(16),
+    Address => TypeSchema::Address,
+    Bytes => TypeSchema::Bytes,
+    String => TypeSchema::String,
+    EmptyVec => TypeSchema::EmptyVec,
+);
+
+impl<const N: usize> CodecSchema for FixedBytes<N> {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::FixedBytes(N)
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> CodecSchema for Uint<BITS, LIMBS> {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::Uint(<Self as Encoder<Self>>::HEADER_SIZE)
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> CodecSchema for Signed<BITS, LIMBS> {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::Int(<Self as Encoder<Self>>::HEADER_SIZE)
+    }
+}
+
+impl<T: CodecSchema, const N: usize> CodecSchema for [T; N] {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::Array(Box::new(T::type_schema()), N)
+    }
+    fn add_definitions(schema: &mut Schema) {
+        T::add_definitions(schema)
+    }
+}
+
+impl<T: CodecSchema> CodecSchema for Vec<T> {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::Vec(Box::new(T::type_schema()))
+    }
+    fn add_definitions(schema: &mut Schema) {
+        T::add_definitions(schema)
+    }
+}
+
+impl<T: CodecSchema> CodecSchema for Option<T> {
+    fn type_schema() -> TypeSchema {
+        TypeSchema::Option(Box::new(T::type_schema()))
+    }
+    fn add_definitions(schema: &mut Schema) {
+        T::add_definitions(schema)
+    }
+}
+
+impl<T: CodecSchema> CodecSchema for Box<T> {
+    fn type_schema() -> TypeSchema {
+        T::type_schema()
+    }
+    fn add_definitions(schema: &mut Schema) {
+        T::add_definitions(schema)
+    }
+}
+
+macro_rules! impl_map_schema {
+    ($map:ident, $set:ident, $($bound:path),*) => {
+        impl<K: CodecSchema $(+ $bound)*, V: CodecSchema> CodecSchema for $map<K, V> {
+            fn type_schema() -> TypeSchema {
+                TypeSchema::Map(Box::new(K::type_schema()), Box::new(V::type_schema()))
+            }
+            fn add_definitions(schema: &mut Schema) {
+                K::add_definitions(schema);
+                V::add_definitions(schema);
+            }
+        }
+
+        impl<T: CodecSchema $(+ $bound)*> CodecSchema for $set<T> {
+            fn type_schema() -> TypeSchema {
+                TypeSchema::Set(Box::new(T::type_schema()))
+            }
+            fn add_definitions(schema: &mut Schema) {
+                T::add_definitions(schema)
+            }
+        }
+    };
+}
+
+impl_map_schema!(HashMap, HashSet, Eq, Hash);
+impl_map_schema!(BTreeMap, BTreeSet, Ord);
+
+macro_rules! impl_tuple_schema {
+    ($($typ:ident $idx:tt),+) => {
+        impl<$($typ: CodecSchema),+> CodecSchema for ($($typ,)+) {
+            fn type_schema() -> TypeSchema {
+                TypeSchema::Tuple(alloc::vec![$($typ::type_schema()),+])
+            }
+            fn add_definitions(schema: &mut Schema) {
+                $($typ::add_definitions(schema);)+
+            }
+        }
+    };
+}
+
+for_each_tuple!(impl_tuple_schema);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Codec;
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    struct CallOutput {
+        output: Bytes,
+        exit_code: i32,
+        gas: u64,
+        logs: Vec<Log>,
+    }
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    struct Log {
+        address: Address,
+        topics: Vec<FixedBytes<32>>,
+    }
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    #[repr(u32)]
+    enum Status {
+        #[default]
+        Ok,
+        Reverted(u32, Bytes),
+        Halted {
+            reason: u8,
+        } = 5,
+    }
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    struct Input<T: Encoder<T> + Default> {
+        method_id: u32,
+        data: T,
+    }
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(extensible, schema)]
+    struct Account {
+        balance: u64,
+        #[codec(since = 1)]
+        code: Bytes,
+    }
+
+    #[test]
+    fn test_struct_schema() {
+        let schema = Schema::of::<CallOutput>();
+        assert_eq!(
+            schema
+                .definitions
+                .iter()
+                .map(|definition| definition.name.as_str())
+                .collect::<Vec<_>>(),
+            ["CallOutput", "Log"]
+        );
+        let definition = schema.get("CallOutput").unwrap();
+        assert_eq!(definition.header_size, 32);
+        assert_eq!(
+            definition.kind,
+            DefinitionKind::Struct(alloc::vec![
+                FieldSchema::new("output", TypeSchema::Bytes, 0, 8),
+                FieldSchema::new("exit_code", TypeSchema::Int(4), 8, 4),
+                FieldSchema::new("gas", TypeSchema::Uint(8), 12, 8),
+                FieldSchema::new(
+                    "logs",
+                    TypeSchema::Vec(Box::new(TypeSchema::Defined("Log".to_string()))),
+                    20,
+                    12
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_enum_schema() {
+        let schema = Schema::of::<Status>();
+        let definition = schema.get("Status").unwrap();
+        assert_eq!(definition.header_size, 16);
+        assert_eq!(
+            definition.kind,
+            DefinitionKind::Enum(alloc::vec![
+                VariantSchema::new("Ok", 0, &[]),
+                VariantSchema::new(
+                    "Reverted",
+                    1,
+                    &[
+                        FieldSchema::new("0", TypeSchema::Uint(4), 4, 4),
+                        FieldSchema::new("1", TypeSchema::Bytes, 8, 8),
+                    ]
+                ),
+                VariantSchema::new(
+                    "Halted",
+                    5,
+                    &[FieldSchema::new("reason", TypeSchema::Uint(1), 4, 1)]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extensible_struct_schema() {
+        let schema = Schema::of::<Account>();
+        let definition = schema.get("Account").unwrap();
+        assert_eq!(definition.header_size, 8);
+        assert_eq!(
+            definition.kind,
+            DefinitionKind::ExtensibleStruct(alloc::vec![
+                FieldSchema::new("balance", TypeSchema::Uint(8), 4, 8),
+                FieldSchema::new("code", TypeSchema::Bytes, 12, 8).since(1),
+            ])
+        );
+        assert!(schema.to_json().contains(
+            r#"{"name":"code","type":{"kind":"bytes"},"offset":12,"headerSize":8,"since":1}"#
+        ));
+    }
+
+    #[test]
+    fn test_generic_schema() {
+        let schema = Schema::of::<Input<Log>>();
+        assert_eq!(
+            Input::<Log>::type_schema(),
+            TypeSchema::Defined("InputLog".to_string())
+        );
+        assert!(schema.get("InputLog").is_some());
+        assert!(schema.get("Log").is_some());
+        assert_eq!(
+            Input::<(u64, Vec<u8>)>::type_schema().name(),
+            "InputTupleU64VecU8"
+        );
+    }
+
+    #[test]
+    fn test_schema_json() {
+        let schema = Schema::of::<Log>();
+        assert_eq!(
+            schema.to_json(),
+            concat!(
+                r#"{"definitions":[{"name":"Log","headerSize":32,"kind":"struct","fields":["#,
+                r#"{"name":"address","type":{"kind":"address"},"offset":0,"headerSize":20},"#,
+                r#"{"name":"topics","type":{"kind":"vec","item":{"kind":"fixedBytes","size":32}},"#,
+                r#""offset":20,"headerSize":12}]}]}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,698 @@
+use crate::{tuple::for_each_tuple, CodecError};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use alloy_primitives::{Address, Bytes, FixedBytes, Signed, Uint};
+
+/// Size of the ABI word, every value is padded to a multiple of it.
+pub const WORD_SIZE: usize = 32;
+
+/// Standard Solidity ABI encoding (big-endian 32-byte words, dynamic values referenced by
+/// offsets), an alternative to the compact `Encoder` layout for talking to EVM contracts.
+///
+/// Structs and enums get it from `#[derive(Codec)]` with the `#[codec(solidity)]` attribute,
+/// structs are encoded as tuples and enums without fields as `uint8`.
+pub trait SolidityABI: Sized {
+    /// Dynamic values are stored in the tail of the enclosing tuple and referenced by offsets.
+    const DYNAMIC: bool;
+    /// Size of the value in the head of the enclosing tuple.
+    const HEAD_SIZE: usize;
+
+    /// Appends the encoding of the value without the head of the enclosing tuple.
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder);
+
+    /// Decodes the value, which encoding starts at `offset`.
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError>;
+
+    /// Encodes the value like `abi.encode(value)` does.
+    fn abi_encode(&self) -> Vec<u8> {
+        let mut encoder = SolidityEncoder::new();
+        let mut tuple = encoder.tuple(Self::HEAD_SIZE);
+        tuple.encode_field(self);
+        tuple.finish();
+        encoder.finalize()
+    }
+
+    /// Decodes the value encoded with `abi.encode(value)`, bytes after the value are ignored.
+    fn abi_decode(buffer: &[u8]) -> Result<Self, CodecError> {
+        SolidityDecoder::new(buffer).tuple(0).decode_field()
+    }
+
+    /// Encodes a tuple or a struct as a list of parameters, like `abi.encode(a, b, ...)` and
+    /// calldata of functions do.
+    fn abi_encode_params(&self) -> Vec<u8> {
+        let mut encoder = SolidityEncoder::new();
+        self.encode_solidity(&mut encoder);
+        encoder.finalize()
+    }
+
+    /// Decodes a tuple or a struct from a list of parameters.
+    fn abi_decode_params(buffer: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_solidity(&SolidityDecoder::new(buffer), 0)
+    }
+}
+
+#[derive(Default)]
+pub struct SolidityEncoder {
+    buffer: Vec<u8>,
+}
+
+impl SolidityEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn write_word(&mut self, word: &[u8; WORD_SIZE]) {
+        self.buffer.extend_from_slice(word);
+    }
+
+    /// Writes the value right aligned in a word, the rest is filled with `padding`.
+    pub fn write_right_aligned(&mut self, value: &[u8], padding: u8) {
+        let mut word = [padding; WORD_SIZE];
+        word[WORD_SIZE - value.len()..].copy_from_slice(value);
+        self.write_word(&word);
+    }
+
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_right_aligned(&(value as u64).to_be_bytes(), 0);
+    }
+
+    /// Writes bytes left aligned and zero padded to a multiple of the word size.
+    pub fn write_padded(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        let padding = (WORD_SIZE - bytes.len() % WORD_SIZE) % WORD_SIZE;
+        self.buffer.resize(self.buffer.len() + padding, 0);
+    }
+
+    /// Starts a tuple with the head of `head_size` bytes, fields have to be encoded in order.
+    pub fn tuple(&mut self, head_size: usize) -> SolidityTupleEncoder<'_> {
+        SolidityTupleEncoder {
+            head: self,
+            head_size,
+            tail: SolidityEncoder::new(),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+pub struct SolidityTupleEncoder<'a> {
+    head: &'a mut SolidityEncoder,
+    head_size: usize,
+    tail: SolidityEncoder,
+}
+
+impl<'a> SolidityTupleEncoder<'a> {
+    pub fn encode_field<T: SolidityABI>(&mut self, value: &T) {
+        if T::DYNAMIC {
+            // offsets are relative to the start of the tuple
+            self.head.write_usize(self.head_size + self.tail.len());
+            value.encode_solidity(&mut self.tail);
+        } else {
+            value.encode_solidity(self.head);
+        }
+    }
+
+    /// Appends the tail with dynamic fields after the head.
+    pub fn finish(self) {
+        self.head.buffer.extend_from_slice(&self.tail.buffer);
+    }
+}
+
+pub struct SolidityDecoder<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> SolidityDecoder<'a> {
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    pub fn read_slice(&self, offset: usize, length: usize) -> Result<&'a [u8], CodecError> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.buffer.get(offset..end))
+            .ok_or(CodecError::OutOfBounds { offset, length })
+    }
+
+    pub fn read_word(&self, offset: usize) -> Result<&'a [u8; WORD_SIZE], CodecError> {
+        Ok(self.read_slice(offset, WORD_SIZE)?.try_into().unwrap())
+    }
+
+    /// Reads `N` right aligned bytes of a word and checks that the rest is filled with
+    /// `padding`.
+    pub fn read_right_aligned<const N: usize>(
+        &self,
+        offset: usize,
+        padding: u8,
+    ) -> Result<[u8; N], CodecError> {
+        let word = self.read_word(offset)?;
+        if word[..WORD_SIZE - N].iter().any(|byte| *byte != padding) {
+            return Err(CodecError::InvalidValue);
+        }
+        Ok(word[WORD_SIZE - N..].try_into().unwrap())
+    }
+
+    /// Reads an offset or a length, they must fit into the buffer anyway.
+    pub fn read_usize(&self, offset: usize) -> Result<usize, CodecError> {
+        let value = u32::from_be_bytes(self.read_right_aligned(offset, 0)?);
+        Ok(value as usize)
+    }
+
+    /// Reads `length` bytes at `offset` and checks that their padding is zeroed.
+    pub fn read_padded(&self, offset: usize, length: usize) -> Result<&'a [u8], CodecError> {
+        let padded_length = length
+            .checked_next_multiple_of(WORD_SIZE)
+            .ok_or(CodecError::LengthOverflow)?;
+        let bytes = self.read_slice(offset, padded_length)?;
+        if bytes[length..].iter().any(|byte| *byte != 0) {
+            return Err(CodecError::InvalidValue);
+        }
+        Ok(&bytes[..length])
+    }
+
+    /// Starts decoding of a tuple which encoding starts at `offset`.
+    pub fn tuple(&self, offset: usize) -> SolidityTupleDecoder<'_, 'a> {
+        SolidityTupleDecoder {
+            decoder: self,
+            start: offset,
+            field_offset: offset,
+        }
+    }
+}
+
+pub struct SolidityTupleDecoder<'d, 'a> {
+    decoder: &'d SolidityDecoder<'a>,
+    start: usize,
+    field_offset: usize,
+}
+
+impl<'d, 'a> SolidityTupleDecoder<'d, 'a> {
+    pub fn decode_field<T: SolidityABI>(&mut self) -> Result<T, CodecError> {
+        let value = if T::DYNAMIC {
+            let offset = self.decoder.read_usize(self.field_offset)?;
+            let offset = self
+                .start
+                .checked_add(offset)
+                .ok_or(CodecError::LengthOverflow)?;
+            T::decode_solidity(self.decoder, offset)?
+        } else {
+            T::decode_solidity(self.decoder, self.field_offset)?
+        };
+        self.field_offset += T::HEAD_SIZE;
+        Ok(value)
+    }
+}
+
+impl SolidityABI for bool {
+    const DYNAMIC: bool = false;
+    const HEAD_SIZE: usize = WORD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        encoder.write_right_aligned(&[*self as u8], 0);
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        match decoder.read_right_aligned::<1>(offset, 0)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(CodecError::InvalidValue),
+        }
+    }
+}
+
+macro_rules! impl_solidity_uint {
+    ($typ:ty) => {
+        impl SolidityABI for $typ {
+            const DYNAMIC: bool = false;
+            const HEAD_SIZE: usize = WORD_SIZE;
+
+            fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+                encoder.write_right_aligned(&self.to_be_bytes(), 0);
+            }
+
+            fn decode_solidity(
+                decoder: &SolidityDecoder,
+                offset: usize,
+            ) -> Result<Self, CodecError> {
+                Ok(<$typ>::from_be_bytes(
+                    decoder.read_right_aligned(offset, 0)?,
+                ))
+            }
+        }
+    };
+}
+
+impl_solidity_uint!(u8);
+impl_solidity_uint!(u16);
+impl_solidity_uint!(u32);
+impl_solidity_uint!(u64);
+impl_solidity_uint!(u128);
+
+macro_rules! impl_solidity_int {
+    ($typ:ty) => {
+        impl SolidityABI for $typ {
+            const DYNAMIC: bool = false;
+            const HEAD_SIZE: usize = WORD_SIZE;
+
+            fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+                // negative values are sign extended
+                let padding = if *self < 0 { 0xff } else { 0 };
+                encoder.write_right_aligned(&self.to_be_bytes(), padding);
+            }
+
+            fn decode_solidity(
+                decoder: &SolidityDecoder,
+                offset: usize,
+            ) -> Result<Self, CodecError> {
+                // the padding must be the sign extension of the value
+                let size = core::mem::size_of::<$typ>();
+                let sign = if decoder.read_word(offset)?[WORD_SIZE - size] & 0x80 != 0 {
+                    0xff
+                } else {
+                    0
+                };
+                Ok(<$typ>::from_be_bytes(
+                    decoder.read_right_aligned(offset, sign)?,
+                ))
+            }
+        }
+    };
+}
+
+impl_solidity_int!(i16);
+impl_solidity_int!(i32);
+impl_solidity_int!(i64);
+impl_solidity_int!(i128);
+
+impl SolidityABI for Address {
+    const DYNAMIC: bool = false;
+    const HEAD_SIZE: usize = WORD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        encoder.write_right_aligned(self.as_slice(), 0);
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        Ok(Address::from(decoder.read_right_aligned::<20>(offset, 0)?))
+    }
+}
+
+impl<const N: usize> SolidityABI for FixedBytes<N> {
+    const DYNAMIC: bool = false;
+    const HEAD_SIZE: usize = {
+        assert!(N <= WORD_SIZE, "bytesN can't be longer than 32 bytes");
+        WORD_SIZE
+    };
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        let _ = Self::HEAD_SIZE;
+        encoder.write_padded(self.as_slice());
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let _ = Self::HEAD_SIZE;
+        let bytes = decoder.read_word(offset)?;
+        if bytes[N..].iter().any(|byte| *byte != 0) {
+            return Err(CodecError::InvalidValue);
+        }
+        Ok(FixedBytes::from_slice(&bytes[..N]))
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> SolidityABI for Uint<BITS, LIMBS> {
+    const DYNAMIC: bool = false;
+    const HEAD_SIZE: usize = {
+        assert!(BITS <= 256, "uint can't be wider than 256 bits");
+        WORD_SIZE
+    };
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        let _ = Self::HEAD_SIZE;
+        // limbs are little-endian
+        let mut word = [0u8; WORD_SIZE];
+        for (i, limb) in self.as_limbs().iter().enumerate() {
+            word[WORD_SIZE - 8 * (i + 1)..WORD_SIZE - 8 * i].copy_from_slice(&limb.to_be_bytes());
+        }
+        encoder.write_word(&word);
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let _ = Self::HEAD_SIZE;
+        Uint::try_from_be_slice(decoder.read_word(offset)?).ok_or(CodecError::InvalidValue)
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> SolidityABI for Signed<BITS, LIMBS> {
+    const DYNAMIC: bool = false;
+    const HEAD_SIZE: usize = {
+        assert!(
+            BITS <= 256 && BITS & 7 == 0,
+            "int must be a multiple of 8 bits and not wider than 256 bits"
+        );
+        WORD_SIZE
+    };
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        let _ = Self::HEAD_SIZE;
+        let mut raw = SolidityEncoder::new();
+        self.into_raw().encode_solidity(&mut raw);
+        let mut word: [u8; WORD_SIZE] = raw.finalize().try_into().unwrap();
+        // negative values are sign extended
+        if self.is_negative() {
+            word[..WORD_SIZE - BITS / 8].fill(0xff);
+        }
+        encoder.write_word(&word);
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let _ = Self::HEAD_SIZE;
+        let word = decoder.read_word(offset)?;
+        let size = BITS / 8;
+        if size == 0 {
+            return Ok(Self::ZERO);
+        }
+        // the padding must be the sign extension of the value
+        let sign = if word[WORD_SIZE - size] & 0x80 != 0 {
+            0xff
+        } else {
+            0
+        };
+        if word[..WORD_SIZE - size].iter().any(|byte| *byte != sign) {
+            return Err(CodecError::InvalidValue);
+        }
+        let raw =
+            Uint::try_from_be_slice(&word[WORD_SIZE - size..]).ok_or(CodecError::InvalidValue)?;
+        Ok(Signed::from_raw(raw))
+    }
+}
+
+impl SolidityABI for Bytes {
+    const DYNAMIC: bool = true;
+    const HEAD_SIZE: usize = WORD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        encoder.write_usize(self.len());
+        encoder.write_padded(self);
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let length = decoder.read_usize(offset)?;
+        let bytes = decoder.read_padded(offset + WORD_SIZE, length)?;
+        Ok(Bytes::copy_from_slice(bytes))
+    }
+}
+
+impl SolidityABI for String {
+    const DYNAMIC: bool = true;
+    const HEAD_SIZE: usize = WORD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        encoder.write_usize(self.len());
+        encoder.write_padded(self.as_bytes());
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let length = decoder.read_usize(offset)?;
+        let bytes = decoder.read_padded(offset + WORD_SIZE, length)?;
+        let value = core::str::from_utf8(bytes).map_err(|_| CodecError::InvalidValue)?;
+        Ok(value.into())
+    }
+}
+
+impl<T: SolidityABI> SolidityABI for Box<T> {
+    const DYNAMIC: bool = T::DYNAMIC;
+    const HEAD_SIZE: usize = T::HEAD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        self.as_ref().encode_solidity(encoder)
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        T::decode_solidity(decoder, offset).map(Box::new)
+    }
+}
+
+impl<T: SolidityABI> SolidityABI for Vec<T> {
+    const DYNAMIC: bool = true;
+    const HEAD_SIZE: usize = WORD_SIZE;
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        encoder.write_usize(self.len());
+        let mut tuple = encoder.tuple(self.len() * T::HEAD_SIZE);
+        for value in self.iter() {
+            tuple.encode_field(value);
+        }
+        tuple.finish();
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let length = decoder.read_usize(offset)?;
+        // heads of all elements must fit into the buffer
+        let head_size = length
+            .checked_mul(T::HEAD_SIZE)
+            .ok_or(CodecError::LengthOverflow)?;
+        decoder.read_slice(offset + WORD_SIZE, head_size)?;
+        let mut tuple = decoder.tuple(offset + WORD_SIZE);
+        (0..length).map(|_| tuple.decode_field()).collect()
+    }
+}
+
+impl<T: SolidityABI, const N: usize> SolidityABI for [T; N] {
+    const DYNAMIC: bool = T::DYNAMIC;
+    const HEAD_SIZE: usize = if T::DYNAMIC {
+        WORD_SIZE
+    } else {
+        T::HEAD_SIZE * N
+    };
+
+    fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+        let mut tuple = encoder.tuple(T::HEAD_SIZE * N);
+        for value in self.iter() {
+            tuple.encode_field(value);
+        }
+        tuple.finish();
+    }
+
+    fn decode_solidity(decoder: &SolidityDecoder, offset: usize) -> Result<Self, CodecError> {
+        let mut tuple = decoder.tuple(offset);
+        let values = (0..N)
+            .map(|_| tuple.decode_field())
+            .collect::<Result<Vec<T>, _>>()?;
+        Ok(values.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+macro_rules! impl_solidity_tuple {
+    ($($typ:ident $idx:tt),+) => {
+        impl<$($typ: SolidityABI),+> SolidityABI for ($($typ,)+) {
+            const DYNAMIC: bool = false $(|| $typ::DYNAMIC)+;
+            const HEAD_SIZE: usize = if Self::DYNAMIC {
+                WORD_SIZE
+            } else {
+                0 $(+ $typ::HEAD_SIZE)+
+            };
+
+            fn encode_solidity(&self, encoder: &mut SolidityEncoder) {
+                let mut tuple = encoder.tuple(0 $(+ $typ::HEAD_SIZE)+);
+                $(tuple.encode_field(&self.$idx);)+
+                tuple.finish();
+            }
+
+            fn decode_solidity(
+                decoder: &SolidityDecoder,
+                offset: usize,
+            ) -> Result<Self, CodecError> {
+                let mut tuple = decoder.tuple(offset);
+                Ok(($(tuple.decode_field::<$typ>()?,)+))
+            }
+        }
+    };
+}
+
+for_each_tuple!(impl_solidity_tuple);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Codec;
+    use alloy_primitives::{address, Address, Bytes, FixedBytes, I256, U256};
+    use alloy_sol_types::sol;
+
+    fn sol_encode<T: alloy_sol_types::SolValue>(value: &T) -> Vec<u8> {
+        value.abi_encode()
+    }
+
+    sol! {
+        struct SolOrder {
+            address maker;
+            uint256 amount;
+            bytes data;
+            uint64[] ids;
+            bool active;
+            uint8 side;
+        }
+    }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Codec)]
+    #[codec(solidity)]
+    enum Side {
+        #[default]
+        Buy,
+        Sell,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Codec)]
+    #[codec(solidity)]
+    struct Order {
+        maker: Address,
+        amount: U256,
+        data: Bytes,
+        ids: Vec<u64>,
+        active: bool,
+        side: Side,
+    }
+
+    #[test]
+    fn test_primitives_match_alloy() {
+        assert_eq!(true.abi_encode(), sol_encode(&true));
+        assert_eq!(7u16.abi_encode(), sol_encode(&7u16));
+        assert_eq!(u64::MAX.abi_encode(), sol_encode(&u64::MAX));
+        assert_eq!((-2i32).abi_encode(), sol_encode(&-2i32));
+        assert_eq!(i64::MIN.abi_encode(), sol_encode(&i64::MIN));
+        let address = address!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert_eq!(address.abi_encode(), sol_encode(&address));
+        let bytes4 = FixedBytes::<4>::from([1, 2, 3, 4]);
+        assert_eq!(bytes4.abi_encode(), sol_encode(&bytes4));
+        let value = U256::MAX - U256::from(1);
+        assert_eq!(value.abi_encode(), sol_encode(&value));
+        assert_eq!(i32::abi_decode(&(-2i32).abi_encode()), Ok(-2));
+        assert_eq!(U256::abi_decode(&value.abi_encode()), Ok(value));
+    }
+
+    #[test]
+    fn test_dynamic_types_match_alloy() {
+        let bytes = Bytes::from_static(&[0xaa; 33]);
+        assert_eq!(bytes.abi_encode(), sol_encode(&bytes));
+        let values = vec![vec![1u32, 2], vec![], vec![3]];
+        assert_eq!(values.abi_encode(), sol_encode(&values));
+        assert_eq!(
+            Vec::<Vec<u32>>::abi_decode(&values.abi_encode()),
+            Ok(values)
+        );
+        let array = [bytes.clone(), Bytes::new()];
+        assert_eq!(array.abi_encode(), sol_encode(&array));
+        let tuple = (5u16, bytes);
+        assert_eq!(tuple.abi_encode(), sol_encode(&tuple));
+        assert_eq!(
+            tuple.abi_encode_params(),
+            alloy_sol_types::SolValue::abi_encode_params(&tuple)
+        );
+        assert_eq!(
+            <(u16, Bytes)>::abi_decode_params(&tuple.abi_encode_params()),
+            Ok(tuple)
+        );
+    }
+
+    #[test]
+    fn test_derived_struct_matches_alloy() {
+        let order = Order {
+            maker: address!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266"),
+            amount: U256::from(1_000_000u64),
+            data: Bytes::from_static(b"hello"),
+            ids: vec![1, 2, 3],
+            active: true,
+            side: Side::Sell,
+        };
+        let sol_order = SolOrder {
+            maker: order.maker,
+            amount: order.amount,
+            data: order.data.to_vec(),
+            ids: order.ids.clone(),
+            active: order.active,
+            side: 1,
+        };
+        assert_eq!(Order::HEAD_SIZE, WORD_SIZE);
+        assert_eq!(order.abi_encode(), sol_encode(&sol_order));
+        assert_eq!(
+            order.abi_encode_params(),
+            alloy_sol_types::SolValue::abi_encode_params(&sol_order)
+        );
+        assert_eq!(Order::abi_decode(&order.abi_encode()), Ok(order.clone()));
+        assert_eq!(
+            Order::abi_decode_params(&order.abi_encode_params()),
+            Ok(order)
+        );
+    }
+
+    #[test]
+    fn test_extended_types_match_alloy() {
+        let value = String::from("hello, мир");
+        assert_eq!(value.abi_encode(), sol_encode(&value));
+        assert_eq!(String::abi_decode(&value.abi_encode()), Ok(value));
+        assert_eq!(i128::MIN.abi_encode(), sol_encode(&i128::MIN));
+        assert_eq!(u128::MAX.abi_encode(), sol_encode(&u128::MAX));
+        for value in [I256::MIN, I256::MAX, I256::MINUS_ONE, I256::ZERO] {
+            assert_eq!(value.abi_encode(), sol_encode(&value));
+            assert_eq!(I256::abi_decode(&value.abi_encode()), Ok(value));
+        }
+        let value = Signed::<64, 1>::try_from(-5i64).unwrap();
+        assert_eq!(value.abi_encode(), (-5i64).abi_encode());
+        assert_eq!(Signed::<64, 1>::abi_decode(&value.abi_encode()), Ok(value));
+        let value = Box::new(vec![String::from("a"), String::new()]);
+        assert_eq!(value.abi_encode(), sol_encode(&*value));
+        let value = (1u16, String::from("b"), vec![true], 2u64);
+        assert_eq!(
+            value.abi_encode_params(),
+            alloy_sol_types::SolValue::abi_encode_params(&value)
+        );
+        assert_eq!(
+            <(u16, String, Vec<bool>, u64)>::abi_decode_params(&value.abi_encode_params()),
+            Ok(value)
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        // not a canonical bool
+        assert_eq!(
+            bool::abi_decode(&2u8.abi_encode()),
+            Err(CodecError::InvalidValue)
+        );
+        // dirty high bits of a small integer
+        assert_eq!(
+            u8::abi_decode(&256u16.abi_encode()),
+            Err(CodecError::InvalidValue)
+        );
+        // unknown enum variant
+        assert_eq!(
+            Side::abi_decode(&2u8.abi_encode()),
+            Err(CodecError::InvalidTag(2))
+        );
+        // the length is larger than the data
+        let mut encoded = vec![1u64, 2].abi_encode();
+        encoded[63] = 3;
+        assert_eq!(
+            Vec::<u64>::abi_decode(&encoded),
+            Err(CodecError::OutOfBounds {
+                offset: 64,
+                length: 96
+            })
+        );
+        // the offset points outside of the buffer
+        let mut encoded = Bytes::from_static(b"hello").abi_encode();
+        encoded[31] = 0xff;
+        assert!(matches!(
+            Bytes::abi_decode(&encoded),
+            Err(CodecError::OutOfBounds { .. })
+        ));
+    }
+}
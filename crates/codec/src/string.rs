@@ -0,0 +1,36 @@
+use crate::{buffer::WritableBuffer, BufferDecoder, CodecError, Encoder};
+use alloc::string::String;
+
+/// Strings are encoded as UTF-8 bytes, the same way as `Bytes`.
+impl Encoder<String> for String {
+    const HEADER_SIZE: usize = core::mem::size_of::<u32>() * 2;
+
+    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
+        encoder.write_bytes(field_offset, self.as_bytes());
+    }
+
+    fn decode_header(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        _result: &mut String,
+    ) -> (usize, usize) {
+        decoder.read_bytes_header(field_offset)
+    }
+
+    fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut String) {
+        let bytes = decoder.read_bytes(field_offset);
+        *result = String::from_utf8(bytes.to_vec()).expect("string is not valid UTF-8");
+    }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut String,
+    ) -> Result<(), CodecError> {
+        let bytes = decoder.try_read_bytes(field_offset)?;
+        *result = core::str::from_utf8(bytes)
+            .map_err(|_| CodecError::InvalidValue)?
+            .into();
+        Ok(())
+    }
+}
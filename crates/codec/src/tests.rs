@@ -1,5 +1,11 @@
-use super::{BufferDecoder, BufferEncoder, Encoder};
-use alloy_primitives::Bytes;
+use super::{BufferDecoder, BufferEncoder, CodecError, Encoder};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+};
+use alloy_primitives::{Bytes, I256, U256};
+use core::fmt::Debug;
 use hashbrown::{HashMap, HashSet};
 
 #[test]
@@ -275,3 +281,216 @@ fn test_complex_tuple() {
     Tuple::decode_body(&mut decoder, 0, &mut result);
     assert_eq!(result, original_data)
 }
+
+#[test]
+fn test_try_decode_round_trip() {
+    let values = vec![vec![0u32, 1, 2], vec![], vec![3]];
+    let encoded_buffer = values.encode_to_vec(0);
+    assert_eq!(Vec::<Vec<u32>>::try_decode(&encoded_buffer), Ok(values));
+    let mut values = HashMap::new();
+    values.insert(7u32, vec![1u8, 2, 3]);
+    values.insert(8u32, vec![]);
+    let encoded_buffer = values.encode_to_vec(0);
+    assert_eq!(
+        HashMap::<u32, Vec<u8>>::try_decode(&encoded_buffer),
+        Ok(values)
+    );
+    let value = (Some(vec![true, false]), Bytes::from_static(b"hello"));
+    let encoded_buffer = value.encode_to_vec(0);
+    assert_eq!(
+        <(Option<Vec<bool>>, Bytes)>::try_decode(&encoded_buffer),
+        Ok(value)
+    );
+}
+
+#[test]
+fn test_try_decode_out_of_bounds() {
+    let encoded_buffer = vec![1u64, 2, 3].encode_to_vec(0);
+    // the body is cut off
+    assert_eq!(
+        Vec::<u64>::try_decode(&encoded_buffer[..encoded_buffer.len() - 1]),
+        Err(CodecError::OutOfBounds {
+            offset: 12,
+            length: 24
+        })
+    );
+    // the header is cut off
+    assert_eq!(
+        u64::try_decode(&[0, 1, 2]),
+        Err(CodecError::OutOfBounds {
+            offset: 0,
+            length: 8
+        })
+    );
+    // the data offset points outside of the buffer
+    let mut encoded_buffer = Bytes::from_static(b"hello").encode_to_vec(0);
+    encoded_buffer[0] = 0xff;
+    assert_eq!(
+        Bytes::try_decode(&encoded_buffer),
+        Err(CodecError::OutOfBounds {
+            offset: 0xff,
+            length: 5
+        })
+    );
+}
+
+#[test]
+fn test_try_decode_length_overflow() {
+    let mut encoded_buffer = vec![1u32, 2].encode_to_vec(0);
+    // the element count doesn't match the data length
+    encoded_buffer[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        Vec::<u32>::try_decode(&encoded_buffer),
+        Err(CodecError::LengthOverflow)
+    );
+    // zero-sized elements don't occupy any bytes, so their count is capped
+    let values: Vec<[u8; 0]> = vec![[]; 3];
+    let mut encoded_buffer = values.encode_to_vec(0);
+    assert_eq!(Vec::<[u8; 0]>::try_decode(&encoded_buffer), Ok(values));
+    encoded_buffer[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        Vec::<[u8; 0]>::try_decode(&encoded_buffer),
+        Err(CodecError::LengthOverflow)
+    );
+}
+
+#[test]
+fn test_try_decode_invalid_tag() {
+    assert_eq!(bool::try_decode(&[2]), Err(CodecError::InvalidTag(2)));
+    let mut encoded_buffer = Some(5u32).encode_to_vec(0);
+    encoded_buffer[0] = 2;
+    assert_eq!(
+        Option::<u32>::try_decode(&encoded_buffer),
+        Err(CodecError::InvalidTag(2))
+    );
+}
+
+#[test]
+fn test_try_decode_trailing_bytes() {
+    let mut encoded_buffer = vec![1u16, 2].encode_to_vec(0);
+    assert_eq!(Vec::<u16>::try_decode(&encoded_buffer), Ok(vec![1, 2]));
+    encoded_buffer.push(0);
+    assert_eq!(
+        Vec::<u16>::try_decode(&encoded_buffer),
+        Err(CodecError::TrailingBytes)
+    );
+}
+
+/// Checks that the value survives both the panicking and the fallible decoding.
+fn assert_round_trip<T: Encoder<T> + Default + PartialEq + Debug>(value: T) {
+    let encoded_buffer = value.encode_to_vec(0);
+    let mut decoder = BufferDecoder::new(&encoded_buffer);
+    let mut result = T::default();
+    T::decode_body(&mut decoder, 0, &mut result);
+    assert_eq!(result, value);
+    assert_eq!(T::try_decode(&encoded_buffer), Ok(value));
+}
+
+#[test]
+fn test_tuples() {
+    assert_round_trip((7u8,));
+    assert_round_trip((1u16, String::from("two"), vec![3u32]));
+    assert_round_trip((
+        1u8,
+        2u16,
+        3u32,
+        4u64,
+        -5i16,
+        -6i32,
+        -7i64,
+        true,
+        String::from("nine"),
+        vec![10u8],
+        Some(11u32),
+        Bytes::from_static(b"twelve"),
+    ));
+    // headers of elements follow each other
+    type Tuple = (u8, u32, u16);
+    assert_eq!(Tuple::HEADER_SIZE, 7);
+    assert_eq!(
+        (1u8, 2u32, 3u16).encode_to_vec(0),
+        vec![1, 2, 0, 0, 0, 3, 0]
+    );
+}
+
+#[test]
+fn test_string() {
+    assert_round_trip(String::new());
+    assert_round_trip(String::from("Hello, World"));
+    assert_round_trip(vec![
+        String::from("ünïcödé"),
+        String::new(),
+        String::from("🦀"),
+    ]);
+    // strings have the same layout as bytes
+    assert_eq!(
+        String::from("abc").encode_to_vec(0),
+        Bytes::from_static(b"abc").encode_to_vec(0)
+    );
+    let encoded_buffer = Bytes::from_static(&[0xff, 0xfe]).encode_to_vec(0);
+    assert_eq!(
+        String::try_decode(&encoded_buffer),
+        Err(CodecError::InvalidValue)
+    );
+}
+
+#[test]
+fn test_btree_collections() {
+    let mut map = BTreeMap::new();
+    map.insert(String::from("b"), vec![1u8, 2]);
+    map.insert(String::from("a"), vec![]);
+    map.insert(String::from("c"), vec![3]);
+    assert_round_trip(map.clone());
+    assert_round_trip(BTreeMap::<u32, u64>::new());
+    assert_round_trip([3u32, 1, 2].into_iter().collect::<BTreeSet<_>>());
+    assert_round_trip(BTreeSet::<String>::new());
+    // b-tree collections have the same layout as hash ones
+    let hash_map = map.clone().into_iter().collect::<HashMap<_, _>>();
+    assert_eq!(map.encode_to_vec(0), hash_map.encode_to_vec(0));
+    let set = [5u16, 4].into_iter().collect::<BTreeSet<_>>();
+    let hash_set = set.iter().copied().collect::<HashSet<_>>();
+    assert_eq!(set.encode_to_vec(0), hash_set.encode_to_vec(0));
+}
+
+#[test]
+fn test_box() {
+    assert_round_trip(Box::new(42u64));
+    assert_round_trip(Box::new(vec![String::from("boxed")]));
+    assert_round_trip(vec![Box::new((1u8, Bytes::from_static(b"x")))]);
+    // boxes are transparent
+    assert_eq!(
+        Box::new(vec![1u32, 2]).encode_to_vec(0),
+        vec![1u32, 2].encode_to_vec(0)
+    );
+}
+
+#[test]
+fn test_wide_integers() {
+    for value in [0u128, 1, u64::MAX as u128 + 1, u128::MAX] {
+        assert_round_trip(value);
+    }
+    for value in [0i128, -1, i64::MIN as i128 - 1, i128::MIN, i128::MAX] {
+        assert_round_trip(value);
+    }
+    assert_eq!((-2i128).encode_to_vec(0), (-2i128).to_le_bytes().to_vec());
+    for value in [I256::ZERO, I256::MINUS_ONE, I256::MIN, I256::MAX] {
+        assert_round_trip(value);
+    }
+    // signed integers are encoded as two's complement
+    assert_eq!(I256::MINUS_ONE.encode_to_vec(0), U256::MAX.encode_to_vec(0));
+}
+
+#[test]
+fn test_nested_arrays_of_dynamic_types() {
+    assert_round_trip([vec![1u32, 2], vec![], vec![3]]);
+    assert_round_trip([
+        [String::from("a"), String::from("b")],
+        [String::new(), String::from("d")],
+    ]);
+    assert_round_trip(vec![
+        [Bytes::from_static(b"x"), Bytes::new()],
+        [Bytes::new(), Bytes::from_static(b"yz")],
+    ]);
+    assert_round_trip(Some([vec![Some(1u8)], vec![None]]));
+    assert_eq!(<[[Vec<u8>; 2]; 3]>::HEADER_SIZE, 6 * Vec::<u8>::HEADER_SIZE);
+}
@@ -1,26 +1,80 @@
-use crate::{BufferDecoder, Encoder, WritableBuffer};
-
-impl<A1: Encoder<A1>, A2: Encoder<A2>> Encoder<(A1, A2)> for (A1, A2) {
-    const HEADER_SIZE: usize = A1::HEADER_SIZE + A2::HEADER_SIZE;
-
-    fn encode<W: WritableBuffer>(&self, encoder: &mut W, field_offset: usize) {
-        self.0.encode(encoder, field_offset);
-        self.1.encode(encoder, field_offset + A1::HEADER_SIZE);
-    }
-
-    fn decode_header(
-        decoder: &mut BufferDecoder,
-        field_offset: usize,
-        result: &mut (A1, A2),
-    ) -> (usize, usize) {
-        let (offset1, _) = A1::decode_header(decoder, field_offset, &mut result.0);
-        let (_, length2) =
-            A2::decode_header(decoder, field_offset + A1::HEADER_SIZE, &mut result.1);
-        (offset1, length2)
-    }
-
-    fn decode_body(decoder: &mut BufferDecoder, field_offset: usize, result: &mut (A1, A2)) {
-        A1::decode_body(decoder, field_offset, &mut result.0);
-        A2::decode_body(decoder, field_offset + A1::HEADER_SIZE, &mut result.1);
-    }
+use crate::{BufferDecoder, CodecError, Encoder, WritableBuffer};
+
+/// Tuples are encoded like structs: headers of elements follow each other. The header hint of
+/// a tuple is the offset of the first element and the length of the last one.
+macro_rules! impl_tuple {
+    ($($typ:ident $idx:tt),+) => {
+        impl<$($typ: Encoder<$typ>),+> Encoder<($($typ,)+)> for ($($typ,)+) {
+            const HEADER_SIZE: usize = 0 $(+ $typ::HEADER_SIZE)+;
+
+            #[allow(unused_assignments)]
+            fn encode<W: WritableBuffer>(&self, encoder: &mut W, mut field_offset: usize) {
+                $(
+                    self.$idx.encode(encoder, field_offset);
+                    field_offset += $typ::HEADER_SIZE;
+                )+
+            }
+
+            #[allow(unused_assignments)]
+            fn decode_header(
+                decoder: &mut BufferDecoder,
+                mut field_offset: usize,
+                result: &mut ($($typ,)+),
+            ) -> (usize, usize) {
+                let headers = [$({
+                    let header = $typ::decode_header(decoder, field_offset, &mut result.$idx);
+                    field_offset += $typ::HEADER_SIZE;
+                    header
+                }),+];
+                (headers[0].0, headers[headers.len() - 1].1)
+            }
+
+            #[allow(unused_assignments)]
+            fn decode_body(
+                decoder: &mut BufferDecoder,
+                mut field_offset: usize,
+                result: &mut ($($typ,)+),
+            ) {
+                $(
+                    $typ::decode_body(decoder, field_offset, &mut result.$idx);
+                    field_offset += $typ::HEADER_SIZE;
+                )+
+            }
+
+            #[allow(unused_assignments)]
+            fn try_decode_body(
+                decoder: &mut BufferDecoder,
+                mut field_offset: usize,
+                result: &mut ($($typ,)+),
+            ) -> Result<(), CodecError> {
+                $(
+                    $typ::try_decode_body(decoder, field_offset, &mut result.$idx)?;
+                    field_offset += $typ::HEADER_SIZE;
+                )+
+                Ok(())
+            }
+        }
+    };
 }
+
+/// Calls the macro for tuples of 1 to 12 elements.
+macro_rules! for_each_tuple {
+    ($macro:ident) => {
+        $macro!(A1 0);
+        $macro!(A1 0, A2 1);
+        $macro!(A1 0, A2 1, A3 2);
+        $macro!(A1 0, A2 1, A3 2, A4 3);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6, A8 7);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6, A8 7, A9 8);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6, A8 7, A9 8, A10 9);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6, A8 7, A9 8, A10 9, A11 10);
+        $macro!(A1 0, A2 1, A3 2, A4 3, A5 4, A6 5, A7 6, A8 7, A9 8, A10 9, A11 10, A12 11);
+    };
+}
+
+pub(crate) use for_each_tuple;
+
+for_each_tuple!(impl_tuple);
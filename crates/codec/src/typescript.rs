@@ -0,0 +1,236 @@
+use crate::schema::{Definition, DefinitionKind, FieldSchema, Schema, TypeSchema};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+const RUNTIME: &str = include_str!("typescript/runtime.ts");
+
+impl Schema {
+    /// Generates a TypeScript module with a type, a codec and `encode{Name}`/`decode{Name}`
+    /// functions for every definition of the schema.
+    pub fn to_typescript(&self) -> String {
+        let mut out = String::from("// Generated by fluentbase-codec, don't edit it manually.\n\n");
+        out.push_str(RUNTIME);
+        for definition in self.definitions.iter() {
+            out.push('\n');
+            self.write_definition(&mut out, definition);
+        }
+        out
+    }
+
+    fn write_definition(&self, out: &mut String, definition: &Definition) {
+        let name = &definition.name;
+        let codec = match &definition.kind {
+            DefinitionKind::Struct(fields) => {
+                let _ = writeln!(out, "export type {} = {};", name, self.fields_type(fields));
+                format!(
+                    "codecs.struct<{}>({}, {})",
+                    name,
+                    definition.header_size,
+                    self.fields_codec(fields)
+                )
+            }
+            DefinitionKind::ExtensibleStruct(fields) => {
+                let _ = writeln!(out, "export type {} = {};", name, self.fields_type(fields));
+                let version = fields.iter().map(|field| field.since).max().unwrap_or(0);
+                let body_size = fields
+                    .last()
+                    .map_or(4, |field| field.offset + field.header_size);
+                format!(
+                    "codecs.extensible<{}>({}, {}, {})",
+                    name,
+                    version,
+                    body_size,
+                    self.fields_codec(fields)
+                )
+            }
+            DefinitionKind::Enum(variants) => {
+                let _ = writeln!(out, "export type {} =", name);
+                for variant in variants.iter() {
+                    let mut fields = format!("\"kind\": \"{}\"", variant.name);
+                    for field in variant.fields.iter() {
+                        let _ = write!(fields, "; \"{}\": {}", field.name, self.ts_type(&field.ty));
+                    }
+                    let _ = writeln!(out, "  | {{ {} }}", fields);
+                }
+                out.push_str("  ;\n");
+                let variants = variants
+                    .iter()
+                    .map(|variant| {
+                        format!(
+                            "[\"{}\", {}, {}]",
+                            variant.name,
+                            variant.discriminant,
+                            self.fields_codec(&variant.fields)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "codecs.enumeration<{}>({}, [{}])",
+                    name,
+                    definition.header_size,
+                    variants.join(", ")
+                )
+            }
+        };
+        let _ = writeln!(out, "export const {}: Codec<{}> = {};", name, name, codec);
+        let _ = writeln!(
+            out,
+            "export function encode{}(value: {}): Uint8Array {{\n  return encode({}, value);\n}}",
+            name, name, name
+        );
+        let _ = writeln!(
+            out,
+            "export function decode{}(buffer: Uint8Array): {} {{\n  return decode({}, buffer);\n}}",
+            name, name, name
+        );
+    }
+
+    fn fields_type(&self, fields: &[FieldSchema]) -> String {
+        let fields = fields
+            .iter()
+            .map(|field| format!("\"{}\": {}", field.name, self.ts_type(&field.ty)))
+            .collect::<Vec<_>>();
+        format!("{{ {} }}", fields.join("; "))
+    }
+
+    fn fields_codec(&self, fields: &[FieldSchema]) -> String {
+        let fields = fields
+            .iter()
+            .map(|field| match field.since {
+                0 => format!(
+                    "[\"{}\", {}, {}]",
+                    field.name,
+                    field.offset,
+                    self.ts_codec(&field.ty)
+                ),
+                since => format!(
+                    "[\"{}\", {}, {}, {}]",
+                    field.name,
+                    field.offset,
+                    self.ts_codec(&field.ty),
+                    since
+                ),
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", fields.join(", "))
+    }
+
+    /// Integers up to 32 bits are numbers, wider ones are bigints.
+    fn ts_type(&self, ty: &TypeSchema) -> String {
+        match ty {
+            TypeSchema::Bool => "boolean".to_string(),
+            TypeSchema::Uint(size) | TypeSchema::Int(size) if *size <= 4 => "number".to_string(),
+            TypeSchema::Uint(_) | TypeSchema::Int(_) => "bigint".to_string(),
+            TypeSchema::Address | TypeSchema::String => "string".to_string(),
+            TypeSchema::FixedBytes(_) | TypeSchema::Bytes => "Uint8Array".to_string(),
+            TypeSchema::Array(item, _) | TypeSchema::Vec(item) => {
+                format!("Array<{}>", self.ts_type(item))
+            }
+            TypeSchema::Option(item) => format!("({} | null)", self.ts_type(item)),
+            TypeSchema::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.ts_type(item))
+                    .collect::<Vec<_>>();
+                format!("[{}]", items.join(", "))
+            }
+            TypeSchema::Map(key, value) => {
+                format!("Map<{}, {}>", self.ts_type(key), self.ts_type(value))
+            }
+            TypeSchema::Set(item) => format!("Set<{}>", self.ts_type(item)),
+            TypeSchema::EmptyVec => "[]".to_string(),
+            TypeSchema::Defined(name) => name.clone(),
+        }
+    }
+
+    fn ts_codec(&self, ty: &TypeSchema) -> String {
+        match ty {
+            TypeSchema::Bool => "codecs.bool".to_string(),
+            TypeSchema::Uint(size) if *size <= 4 => format!("codecs.uint({})", size),
+            TypeSchema::Uint(size) => format!("codecs.bigUint({})", size),
+            TypeSchema::Int(size) if *size <= 4 => format!("codecs.int({})", size),
+            TypeSchema::Int(size) => format!("codecs.bigInt({})", size),
+            TypeSchema::Address => "codecs.address".to_string(),
+            TypeSchema::FixedBytes(size) => format!("codecs.fixedBytes({})", size),
+            TypeSchema::Bytes => "codecs.bytes".to_string(),
+            TypeSchema::String => "codecs.string".to_string(),
+            TypeSchema::Array(item, length) => {
+                format!("codecs.array({}, {})", self.ts_codec(item), length)
+            }
+            TypeSchema::Vec(item) => format!("codecs.vec({})", self.ts_codec(item)),
+            TypeSchema::Option(item) => format!("codecs.option({})", self.ts_codec(item)),
+            TypeSchema::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.ts_codec(item))
+                    .collect::<Vec<_>>();
+                format!("codecs.tuple<{}>([{}])", self.ts_type(ty), items.join(", "))
+            }
+            TypeSchema::Map(key, value) => {
+                format!(
+                    "codecs.map({}, {})",
+                    self.ts_codec(key),
+                    self.ts_codec(value)
+                )
+            }
+            TypeSchema::Set(item) => format!("codecs.set({})", self.ts_codec(item)),
+            TypeSchema::EmptyVec => "codecs.emptyVec".to_string(),
+            TypeSchema::Defined(name) => {
+                let definition = self
+                    .get(name)
+                    .unwrap_or_else(|| panic!("schema has no definition of {}", name));
+                format!("codecs.lazy({}, () => {})", definition.header_size, name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Codec, Encoder, Schema};
+    use alloy_primitives::{Address, Bytes};
+    use hashbrown::HashMap;
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    struct Transfer {
+        to: Address,
+        amount: u128,
+        memo: Option<Bytes>,
+        tags: HashMap<u32, bool>,
+        result: TransferResult,
+    }
+
+    #[derive(Default, Debug, Clone, Codec)]
+    #[codec(schema)]
+    enum TransferResult {
+        #[default]
+        Pending,
+        Done(u64),
+        Failed {
+            code: i32,
+        },
+    }
+
+    #[test]
+    fn test_typescript_codecs() {
+        let ts = Schema::of::<Transfer>().to_typescript();
+        assert!(ts.contains("export class Decoder"));
+        assert!(ts.contains(
+            "export type Transfer = { \"to\": string; \"amount\": bigint; \"memo\": (Uint8Array | null); \"tags\": Map<number, boolean>; \"result\": TransferResult };"
+        ));
+        assert!(ts.contains(
+            "export const Transfer: Codec<Transfer> = codecs.struct<Transfer>(77, [[\"to\", 0, codecs.address], [\"amount\", 20, codecs.bigUint(16)], [\"memo\", 36, codecs.option(codecs.bytes)], [\"tags\", 45, codecs.map(codecs.uint(4), codecs.bool)], [\"result\", 65, codecs.lazy(12, () => TransferResult)]]);"
+        ));
+        assert!(ts.contains("  | { \"kind\": \"Failed\"; \"code\": number }\n"));
+        assert!(ts.contains(
+            "codecs.enumeration<TransferResult>(12, [[\"Pending\", 0, []], [\"Done\", 1, [[\"0\", 4, codecs.bigUint(8)]]], [\"Failed\", 2, [[\"code\", 4, codecs.int(4)]]]])"
+        ));
+        assert!(ts.contains("export function decodeTransfer(buffer: Uint8Array): Transfer {"));
+        assert_eq!(<Transfer as Encoder<Transfer>>::HEADER_SIZE, 77);
+    }
+}
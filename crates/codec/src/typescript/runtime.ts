@@ -0,0 +1,420 @@
+// Encoders and decoders of the fluentbase-codec layout: little-endian headers of fixed size,
+// dynamic data is appended after the header and referenced by (offset, length) pairs.
+// Only erasable syntax is used, so the module runs with type stripping of Node.js.
+
+export interface Codec<T> {
+  size: number;
+  zero(): T;
+  encode(encoder: Encoder, offset: number, value: T): void;
+  decode(decoder: Decoder, offset: number): T;
+}
+
+export class Encoder {
+  private buffer: Uint8Array;
+  private length: number;
+
+  constructor(headerSize: number) {
+    this.buffer = new Uint8Array(Math.max(headerSize, 32));
+    this.length = headerSize;
+  }
+
+  writeLE(offset: number, value: bigint, size: number): void {
+    for (let i = 0; i < size; i++) {
+      this.buffer[offset + i] = Number((value >> BigInt(8 * i)) & 0xffn);
+    }
+  }
+
+  writeU32(offset: number, value: number): void {
+    this.writeLE(offset, BigInt(value), 4);
+  }
+
+  writeRaw(offset: number, bytes: Uint8Array): void {
+    this.buffer.set(bytes, offset);
+  }
+
+  // Appends bytes to the end of the buffer and writes their offset and length at `offset`.
+  writeBytes(offset: number, bytes: Uint8Array): void {
+    const start = this.length;
+    if (start + bytes.length > this.buffer.length) {
+      const buffer = new Uint8Array(Math.max(this.buffer.length * 2, start + bytes.length));
+      buffer.set(this.buffer);
+      this.buffer = buffer;
+    }
+    this.buffer.set(bytes, start);
+    this.length += bytes.length;
+    this.writeU32(offset, start);
+    this.writeU32(offset + 4, bytes.length);
+  }
+
+  finish(): Uint8Array {
+    return this.buffer.slice(0, this.length);
+  }
+}
+
+export class Decoder {
+  readonly buffer: Uint8Array;
+
+  constructor(buffer: Uint8Array) {
+    this.buffer = buffer;
+  }
+
+  readRaw(offset: number, length: number): Uint8Array {
+    if (offset + length > this.buffer.length) {
+      throw new RangeError(`${length} bytes at offset ${offset} are out of bounds`);
+    }
+    return this.buffer.subarray(offset, offset + length);
+  }
+
+  readLE(offset: number, size: number): bigint {
+    const bytes = this.readRaw(offset, size);
+    let value = 0n;
+    for (let i = size - 1; i >= 0; i--) {
+      value = (value << 8n) | BigInt(bytes[i]);
+    }
+    return value;
+  }
+
+  readU32(offset: number): number {
+    return Number(this.readLE(offset, 4));
+  }
+
+  readBytes(offset: number): Uint8Array {
+    return this.readRaw(this.readU32(offset), this.readU32(offset + 4));
+  }
+}
+
+// Orders keys of maps and sets the same way as `Ord` of Rust types does.
+function compareKeys(a: unknown, b: unknown): number {
+  if (typeof a === "string" && typeof b === "string") {
+    return compareKeys(utf8.encode(a), utf8.encode(b));
+  }
+  if (a instanceof Uint8Array || Array.isArray(a)) {
+    const left = a as ArrayLike<unknown>;
+    const right = b as ArrayLike<unknown>;
+    for (let i = 0; i < Math.min(left.length, right.length); i++) {
+      const order = compareKeys(left[i], right[i]);
+      if (order !== 0) {
+        return order;
+      }
+    }
+    return left.length - right.length;
+  }
+  return (a as number) < (b as number) ? -1 : (a as number) > (b as number) ? 1 : 0;
+}
+
+const utf8 = new TextEncoder();
+const utf8Decoder = new TextDecoder("utf-8", { fatal: true });
+
+function hex(bytes: Uint8Array): string {
+  return "0x" + Array.from(bytes, (byte) => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function unhex(value: string, size: number): Uint8Array {
+  const digits = value.startsWith("0x") ? value.slice(2) : value;
+  if (digits.length !== size * 2) {
+    throw new Error(`expected ${size} bytes, got ${value}`);
+  }
+  return Uint8Array.from({ length: size }, (_, i) => parseInt(digits.slice(2 * i, 2 * i + 2), 16));
+}
+
+// Encodes a list of values into a separate buffer, it's the body of vectors, maps and sets.
+function encodeItems<T>(item: Codec<T>, values: T[]): Uint8Array {
+  const encoder = new Encoder(item.size * values.length);
+  values.forEach((value, i) => item.encode(encoder, item.size * i, value));
+  return encoder.finish();
+}
+
+function decodeItems<T>(item: Codec<T>, bytes: Uint8Array, length: number): T[] {
+  if (item.size * length > bytes.length) {
+    throw new RangeError(`${length} items don't fit into ${bytes.length} bytes`);
+  }
+  const decoder = new Decoder(bytes);
+  return Array.from({ length }, (_, i) => item.decode(decoder, item.size * i));
+}
+
+// Name, offset and codec of a field, fields of extensible structs may have a version they're
+// added in.
+type Field = [string, number, Codec<any>, number?];
+
+function encodeFields(encoder: Encoder, offset: number, value: any, fields: Field[]): void {
+  for (const [name, fieldOffset, codec] of fields) {
+    codec.encode(encoder, offset + fieldOffset, value[name]);
+  }
+}
+
+function decodeFields(decoder: Decoder, offset: number, fields: Field[]): any {
+  const value: any = {};
+  for (const [name, fieldOffset, codec] of fields) {
+    value[name] = codec.decode(decoder, offset + fieldOffset);
+  }
+  return value;
+}
+
+function zeroFields(fields: Field[]): any {
+  const value: any = {};
+  for (const [name, , codec] of fields) {
+    value[name] = codec.zero();
+  }
+  return value;
+}
+
+export const codecs = {
+  bool: {
+    size: 1,
+    zero: () => false,
+    encode: (encoder, offset, value) => encoder.writeLE(offset, value ? 1n : 0n, 1),
+    decode: (decoder, offset) => decoder.readLE(offset, 1) !== 0n,
+  } as Codec<boolean>,
+
+  // Unsigned integers up to 32 bits.
+  uint(size: number): Codec<number> {
+    return {
+      size,
+      zero: () => 0,
+      encode: (encoder, offset, value) => encoder.writeLE(offset, BigInt(value), size),
+      decode: (decoder, offset) => Number(decoder.readLE(offset, size)),
+    };
+  },
+
+  // Signed integers up to 32 bits.
+  int(size: number): Codec<number> {
+    return {
+      size,
+      zero: () => 0,
+      encode: (encoder, offset, value) =>
+        encoder.writeLE(offset, BigInt.asUintN(size * 8, BigInt(value)), size),
+      decode: (decoder, offset) => Number(BigInt.asIntN(size * 8, decoder.readLE(offset, size))),
+    };
+  },
+
+  bigUint(size: number): Codec<bigint> {
+    return {
+      size,
+      zero: () => 0n,
+      encode: (encoder, offset, value) => encoder.writeLE(offset, value, size),
+      decode: (decoder, offset) => decoder.readLE(offset, size),
+    };
+  },
+
+  bigInt(size: number): Codec<bigint> {
+    return {
+      size,
+      zero: () => 0n,
+      encode: (encoder, offset, value) =>
+        encoder.writeLE(offset, BigInt.asUintN(size * 8, value), size),
+      decode: (decoder, offset) => BigInt.asIntN(size * 8, decoder.readLE(offset, size)),
+    };
+  },
+
+  // Addresses are hex strings with the `0x` prefix.
+  address: {
+    size: 20,
+    zero: () => hex(new Uint8Array(20)),
+    encode: (encoder, offset, value) => encoder.writeRaw(offset, unhex(value, 20)),
+    decode: (decoder, offset) => hex(decoder.readRaw(offset, 20)),
+  } as Codec<string>,
+
+  fixedBytes(size: number): Codec<Uint8Array> {
+    return {
+      size,
+      zero: () => new Uint8Array(size),
+      encode: (encoder, offset, value) => {
+        if (value.length !== size) {
+          throw new Error(`expected ${size} bytes, got ${value.length}`);
+        }
+        encoder.writeRaw(offset, value);
+      },
+      decode: (decoder, offset) => decoder.readRaw(offset, size).slice(),
+    };
+  },
+
+  bytes: {
+    size: 8,
+    zero: () => new Uint8Array(0),
+    encode: (encoder, offset, value) => encoder.writeBytes(offset, value),
+    decode: (decoder, offset) => decoder.readBytes(offset).slice(),
+  } as Codec<Uint8Array>,
+
+  string: {
+    size: 8,
+    zero: () => "",
+    encode: (encoder, offset, value) => encoder.writeBytes(offset, utf8.encode(value)),
+    decode: (decoder, offset) => utf8Decoder.decode(decoder.readBytes(offset)),
+  } as Codec<string>,
+
+  emptyVec: {
+    size: 12,
+    zero: () => [],
+    encode: (encoder, offset) => {
+      encoder.writeU32(offset, 0);
+      encoder.writeBytes(offset + 4, new Uint8Array(0));
+    },
+    decode: () => [],
+  } as Codec<[]>,
+
+  array<T>(item: Codec<T>, length: number): Codec<T[]> {
+    return {
+      size: item.size * length,
+      zero: () => Array.from({ length }, () => item.zero()),
+      encode: (encoder, offset, value) => {
+        if (value.length !== length) {
+          throw new Error(`expected ${length} items, got ${value.length}`);
+        }
+        value.forEach((value, i) => item.encode(encoder, offset + item.size * i, value));
+      },
+      decode: (decoder, offset) =>
+        Array.from({ length }, (_, i) => item.decode(decoder, offset + item.size * i)),
+    };
+  },
+
+  vec<T>(item: Codec<T>): Codec<T[]> {
+    return {
+      size: 12,
+      zero: () => [],
+      encode: (encoder, offset, value) => {
+        encoder.writeU32(offset, value.length);
+        encoder.writeBytes(offset + 4, encodeItems(item, value));
+      },
+      decode: (decoder, offset) =>
+        decodeItems(item, decoder.readBytes(offset + 4), decoder.readU32(offset)),
+    };
+  },
+
+  option<T>(item: Codec<T>): Codec<T | null> {
+    return {
+      size: 1 + item.size,
+      zero: () => null,
+      encode: (encoder, offset, value) => {
+        encoder.writeLE(offset, value === null ? 0n : 1n, 1);
+        item.encode(encoder, offset + 1, value === null ? item.zero() : value);
+      },
+      decode: (decoder, offset) =>
+        decoder.readLE(offset, 1) !== 0n ? item.decode(decoder, offset + 1) : null,
+    };
+  },
+
+  tuple<T extends unknown[]>(items: { [K in keyof T]: Codec<T[K]> }): Codec<T> {
+    const codecs = items as Codec<unknown>[];
+    const offsets = codecs.map((_, i) => codecs.slice(0, i).reduce((sum, item) => sum + item.size, 0));
+    return {
+      size: codecs.reduce((sum, item) => sum + item.size, 0),
+      zero: () => codecs.map((item) => item.zero()) as T,
+      encode: (encoder, offset, value) =>
+        codecs.forEach((item, i) => item.encode(encoder, offset + offsets[i], value[i])),
+      decode: (decoder, offset) =>
+        codecs.map((item, i) => item.decode(decoder, offset + offsets[i])) as T,
+    };
+  },
+
+  // Keys are sorted before encoding, like the Rust encoder does.
+  map<K, V>(key: Codec<K>, value: Codec<V>): Codec<Map<K, V>> {
+    return {
+      size: 20,
+      zero: () => new Map(),
+      encode: (encoder, offset, map) => {
+        const entries = Array.from(map.entries()).sort((a, b) => compareKeys(a[0], b[0]));
+        encoder.writeU32(offset, entries.length);
+        encoder.writeBytes(offset + 4, encodeItems(key, entries.map((entry) => entry[0])));
+        encoder.writeBytes(offset + 12, encodeItems(value, entries.map((entry) => entry[1])));
+      },
+      decode: (decoder, offset) => {
+        const length = decoder.readU32(offset);
+        const keys = decodeItems(key, decoder.readBytes(offset + 4), length);
+        const values = decodeItems(value, decoder.readBytes(offset + 12), length);
+        return new Map(keys.map((key, i) => [key, values[i]]));
+      },
+    };
+  },
+
+  set<T>(item: Codec<T>): Codec<Set<T>> {
+    return {
+      size: 12,
+      zero: () => new Set(),
+      encode: (encoder, offset, set) => {
+        const values = Array.from(set).sort(compareKeys);
+        encoder.writeU32(offset, values.length);
+        encoder.writeBytes(offset + 4, encodeItems(item, values));
+      },
+      decode: (decoder, offset) =>
+        new Set(decodeItems(item, decoder.readBytes(offset + 4), decoder.readU32(offset))),
+    };
+  },
+
+  struct<T>(size: number, fields: Field[]): Codec<T> {
+    return {
+      size,
+      zero: () => zeroFields(fields),
+      encode: (encoder, offset, value) => encodeFields(encoder, offset, value, fields),
+      decode: (decoder, offset) => decodeFields(decoder, offset, fields),
+    };
+  },
+
+  // Extensible structs are stored as bytes: a `u32` version followed by fields. Fields added in
+  // later versions are zero when they're missing and unknown trailing data is skipped.
+  extensible<T>(version: number, bodySize: number, fields: Field[]): Codec<T> {
+    return {
+      size: 8,
+      zero: () => zeroFields(fields),
+      encode: (encoder, offset, value) => {
+        const body = new Encoder(bodySize);
+        body.writeU32(0, version);
+        encodeFields(body, 0, value, fields);
+        encoder.writeBytes(offset, body.finish());
+      },
+      decode: (decoder, offset) => {
+        const body = new Decoder(decoder.readBytes(offset));
+        const stored = body.readU32(0);
+        const value: any = {};
+        for (const [name, fieldOffset, codec, since] of fields) {
+          value[name] = (since ?? 0) <= stored ? codec.decode(body, fieldOffset) : codec.zero();
+        }
+        return value;
+      },
+    };
+  },
+
+  // Enum values are objects with the variant name in `kind` and fields of the variant, the
+  // zero value is the first variant.
+  enumeration<T extends { kind: string }>(size: number, variants: [string, number, Field[]][]): Codec<T> {
+    return {
+      size,
+      zero: () => ({ kind: variants[0][0], ...zeroFields(variants[0][2]) }),
+      encode: (encoder, offset, value) => {
+        const variant = variants.find(([kind]) => kind === value.kind);
+        if (variant === undefined) {
+          throw new Error(`unknown enum variant ${value.kind}`);
+        }
+        encoder.writeU32(offset, variant[1]);
+        encodeFields(encoder, offset, value, variant[2]);
+      },
+      decode: (decoder, offset) => {
+        const discriminant = decoder.readU32(offset);
+        const variant = variants.find(([, value]) => value === discriminant);
+        if (variant === undefined) {
+          throw new Error(`unknown enum discriminant ${discriminant}`);
+        }
+        return { kind: variant[0], ...decodeFields(decoder, offset, variant[2]) };
+      },
+    };
+  },
+
+  // Refers to a codec defined later, it makes the order of definitions irrelevant.
+  lazy<T>(size: number, get: () => Codec<T>): Codec<T> {
+    return {
+      size,
+      zero: () => get().zero(),
+      encode: (encoder, offset, value) => get().encode(encoder, offset, value),
+      decode: (decoder, offset) => get().decode(decoder, offset),
+    };
+  },
+};
+
+export function encode<T>(codec: Codec<T>, value: T): Uint8Array {
+  const encoder = new Encoder(codec.size);
+  codec.encode(encoder, 0, value);
+  return encoder.finish();
+}
+
+export function decode<T>(codec: Codec<T>, buffer: Uint8Array): T {
+  return codec.decode(new Decoder(buffer), 0);
+}
@@ -1,4 +1,4 @@
-use crate::{buffer::WritableBuffer, BufferDecoder, BufferEncoder, Encoder};
+use crate::{buffer::WritableBuffer, BufferDecoder, BufferEncoder, CodecError, Encoder};
 use alloc::vec::Vec;
 
 ///
@@ -54,4 +54,46 @@ impl<T: Default + Sized + Encoder<T>> Encoder<Vec<T>> for Vec<T> {
             })
             .collect()
     }
+
+    fn try_decode_body(
+        decoder: &mut BufferDecoder,
+        field_offset: usize,
+        result: &mut Vec<T>,
+    ) -> Result<(), CodecError> {
+        let input_len = decoder.try_read_u32(field_offset)? as usize;
+        let input_bytes = decoder.try_read_bytes(field_offset + 4)?;
+        check_length(input_len, T::HEADER_SIZE, input_bytes.len())?;
+        let mut value_decoder = BufferDecoder::new(input_bytes);
+        result.clear();
+        result.reserve(input_len);
+        for i in 0..input_len {
+            let mut value = T::default();
+            T::try_decode_body(&mut value_decoder, T::HEADER_SIZE * i, &mut value)?;
+            result.push(value);
+        }
+        Ok(())
+    }
+}
+
+/// Max number of zero-sized elements (e.g. `[u8; 0]`) in a collection, such elements don't
+/// occupy any encoded bytes, so their count can't be checked against the data length.
+pub(crate) const MAX_ZERO_SIZED_LENGTH: usize = 0x10000;
+
+/// Checks that `length` elements with headers of `header_size` fit into `data_length` bytes.
+pub(crate) fn check_length(
+    length: usize,
+    header_size: usize,
+    data_length: usize,
+) -> Result<(), CodecError> {
+    if header_size == 0 {
+        return if length <= MAX_ZERO_SIZED_LENGTH {
+            Ok(())
+        } else {
+            Err(CodecError::LengthOverflow)
+        };
+    }
+    match length.checked_mul(header_size) {
+        Some(size) if size <= data_length => Ok(()),
+        _ => Err(CodecError::LengthOverflow),
+    }
 }
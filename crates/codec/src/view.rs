@@ -0,0 +1,325 @@
+use crate::{tuple::for_each_tuple, BufferDecoder, EmptyVec, Encoder};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+};
+use alloy_primitives::{Address, Bytes, FixedBytes, Signed, Uint};
+use core::{hash::Hash, marker::PhantomData, ops::Deref};
+use hashbrown::{HashMap, HashSet};
+
+/// A borrowed representation of an encoded value that is read from the original buffer
+/// without copying.
+///
+/// `Bytes` are viewed as `BytesRef`, vectors as `VecView` and structs derived with
+/// `#[codec(view)]` as generated view structs, other types are decoded into owned values.
+pub trait CodecView<'a> {
+    type View;
+
+    /// Returns a view of the value which header starts at `field_offset`.
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View;
+}
+
+/// Decodes an owned value, it's what views of primitive types do.
+fn decode_owned<T: Encoder<T>>(buffer: &[u8], field_offset: usize, mut result: T) -> T {
+    let mut decoder = BufferDecoder::new(buffer);
+    T::decode_body(&mut decoder, field_offset, &mut result);
+    result
+}
+
+macro_rules! impl_owned_view {
+    ($($typ:ty),*) => {
+        $(
+            impl<'a> CodecView<'a> for $typ {
+                type View = $typ;
+                fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+                    decode_owned(buffer, field_offset, Default::default())
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_view!(u8, bool, u16, u32, u64, u128, i16, i32, i64, i128, Address, EmptyVec);
+
+impl<'a, const N: usize> CodecView<'a> for FixedBytes<N> {
+    type View = FixedBytes<N>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, const BITS: usize, const LIMBS: usize> CodecView<'a> for Uint<BITS, LIMBS> {
+    type View = Uint<BITS, LIMBS>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, const BITS: usize, const LIMBS: usize> CodecView<'a> for Signed<BITS, LIMBS> {
+    type View = Signed<BITS, LIMBS>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, T: Encoder<T> + Default, const N: usize> CodecView<'a> for [T; N] {
+    type View = [T; N];
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, core::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<'a, T: Encoder<T> + Default> CodecView<'a> for Option<T> {
+    type View = Option<T>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, None)
+    }
+}
+
+macro_rules! impl_tuple_view {
+    ($($typ:ident $idx:tt),+) => {
+        impl<'a, $($typ: Encoder<$typ> + Default),+> CodecView<'a> for ($($typ,)+) {
+            type View = ($($typ,)+);
+            fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+                decode_owned(buffer, field_offset, Default::default())
+            }
+        }
+    };
+}
+
+for_each_tuple!(impl_tuple_view);
+
+impl<'a, K, V> CodecView<'a> for HashMap<K, V>
+where
+    K: Default + Encoder<K> + Eq + Hash + Ord,
+    V: Default + Encoder<V>,
+{
+    type View = HashMap<K, V>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, T: Default + Encoder<T> + Eq + Hash + Ord> CodecView<'a> for HashSet<T> {
+    type View = HashSet<T>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, K: Default + Encoder<K> + Ord, V: Default + Encoder<V>> CodecView<'a> for BTreeMap<K, V> {
+    type View = BTreeMap<K, V>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, T: Default + Encoder<T> + Ord> CodecView<'a> for BTreeSet<T> {
+    type View = BTreeSet<T>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        decode_owned(buffer, field_offset, Default::default())
+    }
+}
+
+impl<'a, T: CodecView<'a>> CodecView<'a> for Box<T> {
+    type View = T::View;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        T::view(buffer, field_offset)
+    }
+}
+
+impl<'a> CodecView<'a> for String {
+    type View = &'a str;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        let bytes = BufferDecoder::new(buffer).read_bytes(field_offset);
+        core::str::from_utf8(bytes).expect("string is not valid UTF-8")
+    }
+}
+
+/// Borrowed `Bytes`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytesRef<'a>(&'a [u8]);
+
+impl<'a> BytesRef<'a> {
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.0
+    }
+
+    pub fn to_bytes(&self) -> Bytes {
+        Bytes::copy_from_slice(self.0)
+    }
+}
+
+impl<'a> Deref for BytesRef<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<'a> CodecView<'a> for Bytes {
+    type View = BytesRef<'a>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        BytesRef(BufferDecoder::new(buffer).read_bytes(field_offset))
+    }
+}
+
+/// Borrowed `Vec<T>`, elements are decoded on access.
+pub struct VecView<'a, T> {
+    length: usize,
+    // headers of elements followed by their bodies
+    data: &'a [u8],
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> Clone for VecView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for VecView<'a, T> {}
+
+impl<'a, T: Encoder<T> + CodecView<'a>> VecView<'a, T> {
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<T::View> {
+        if index >= self.length {
+            return None;
+        }
+        Some(T::view(self.data, index * T::HEADER_SIZE))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T::View> + 'a {
+        let view = *self;
+        (0..self.length).map(move |i| T::view(view.data, i * T::HEADER_SIZE))
+    }
+
+    /// Encoded elements, for `u8` they are the elements themselves.
+    pub fn raw_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> VecView<'a, u8> {
+    pub fn as_slice(&self) -> &'a [u8] {
+        &self.data[..self.length]
+    }
+}
+
+impl<'a, T: Encoder<T> + CodecView<'a> + Default> CodecView<'a> for alloc::vec::Vec<T> {
+    type View = VecView<'a, T>;
+    fn view(buffer: &'a [u8], field_offset: usize) -> Self::View {
+        let decoder = BufferDecoder::new(buffer);
+        VecView {
+            length: decoder.read_u32(field_offset) as usize,
+            data: decoder.read_bytes(field_offset + 4),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Codec;
+    use alloc::vec;
+
+    #[derive(Default, Debug, Clone, PartialEq, Codec)]
+    #[codec(view)]
+    struct Log {
+        topics: Vec<FixedBytes<32>>,
+        data: Bytes,
+    }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Codec)]
+    #[codec(view)]
+    enum Status {
+        #[default]
+        Ok,
+        Failed(u32),
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Codec)]
+    #[codec(view)]
+    struct Receipt {
+        gas_used: u64,
+        status: Status,
+        output: Vec<u8>,
+        logs: Vec<Log>,
+    }
+
+    fn receipt() -> Receipt {
+        Receipt {
+            gas_used: 21000,
+            status: Status::Failed(3),
+            output: vec![1, 2, 3],
+            logs: vec![
+                Log {
+                    topics: vec![FixedBytes::repeat_byte(0xaa)],
+                    data: Bytes::from_static(b"first"),
+                },
+                Log {
+                    topics: vec![],
+                    data: Bytes::from_static(b"second"),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_view_fields() {
+        let value = receipt();
+        let buffer = value.encode_to_vec(0);
+        let view = ReceiptView::new(&buffer);
+        assert_eq!(view.gas_used(), 21000);
+        assert_eq!(view.status(), Status::Failed(3));
+        assert_eq!(view.output().as_slice(), &[1, 2, 3]);
+        let logs = view.logs();
+        assert_eq!(logs.len(), 2);
+        assert!(logs.get(2).is_none());
+        let log = logs.get(0).unwrap();
+        assert_eq!(
+            log.topics().iter().collect::<Vec<_>>(),
+            value.logs[0].topics
+        );
+        assert_eq!(&*log.data(), b"first");
+        let data = logs
+            .iter()
+            .map(|log| log.data().to_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            data,
+            vec![value.logs[0].data.clone(), value.logs[1].data.clone()]
+        );
+    }
+
+    #[test]
+    fn test_view_borrows_buffer() {
+        let buffer = receipt().encode_to_vec(0);
+        let range = buffer.as_ptr_range();
+        let view = ReceiptView::new(&buffer);
+        let output = view.output().as_slice();
+        assert!(range.contains(&output.as_ptr()));
+        let data = view.logs().get(1).unwrap().data().as_slice();
+        assert!(range.contains(&data.as_ptr()));
+        assert_eq!(data, b"second");
+    }
+
+    #[test]
+    fn test_view_nested_field_offset() {
+        // a view can start at any field offset of an enclosing structure
+        let value = (7u32, Bytes::from_static(b"nested"));
+        let buffer = value.encode_to_vec(0);
+        assert_eq!(<u32 as CodecView>::view(&buffer, 0), 7);
+        assert_eq!(&*<Bytes as CodecView>::view(&buffer, 4), b"nested");
+    }
+}
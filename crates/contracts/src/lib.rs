@@ -8,6 +8,7 @@ mod evm;
     feature = "identity",
     feature = "modexp",
     feature = "ecrecover",
+    feature = "groth16",
 ))]
 mod precompile;
 #[cfg(feature = "evm")]
@@ -26,6 +27,8 @@ fluentbase_sdk::basic_entrypoint!(precompile::PRECOMPILE<precompile::IdentityInv
 fluentbase_sdk::basic_entrypoint!(precompile::PRECOMPILE<precompile::ModexpInvokeFunc>);
 #[cfg(feature = "ecrecover")]
 fluentbase_sdk::basic_entrypoint!(precompile::PRECOMPILE<precompile::EcrecoverInvokeFunc>);
+#[cfg(feature = "groth16")]
+fluentbase_sdk::basic_entrypoint!(precompile::PRECOMPILE<precompile::Groth16InvokeFunc>);
 
 #[cfg(feature = "evm")]
 fluentbase_sdk::basic_entrypoint!(
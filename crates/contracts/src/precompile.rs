@@ -1,7 +1,15 @@
 extern crate fluentbase_sdk;
 
 use core::marker::PhantomData;
-use fluentbase_sdk::{alloc_slice, Bytes, ExitCode, LowLevelSDK, SharedAPI};
+use fluentbase_sdk::{
+    alloc_slice,
+    bn254::{groth16_vk_size, try_groth16_verify, GROTH16_PROOF_SIZE},
+    Bytes,
+    ExitCode,
+    LowLevelSDK,
+    SharedAPI,
+    U256,
+};
 use revm_precompile::{PrecompileError, PrecompileResult};
 
 pub trait PrecompileInvokeFunc {
@@ -32,6 +40,47 @@ define_precompile_func!(
     EcrecoverInvokeFunc,
     revm_precompile::secp256k1::ec_recover_run
 );
+define_precompile_func!(Groth16InvokeFunc, groth16_run);
+
+/// Cost of four pairings (EIP-1108)
+pub const GROTH16_BASE_GAS: u64 = 45_000 + 4 * 34_000;
+/// Cost of `ecMul` and `ecAdd` (EIP-1108) for every public input
+pub const GROTH16_PER_INPUT_GAS: u64 = 6_000 + 150;
+
+/// Verifies Groth16 proof over BN254, input is `proof || n || public_inputs[n] || vk`, where `n`
+/// is a 32-byte big-endian word and the rest is encoded as described in
+/// `fluentbase_sdk::bn254::groth16_verify`. Returns 32-byte word with 1 if proof is valid,
+/// otherwise 0 (same as `ecPairing`).
+pub fn groth16_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if input.len() < GROTH16_PROOF_SIZE + 32 {
+        return Err(PrecompileError::Bn128PairLength);
+    }
+    let (proof, input) = input.split_at(GROTH16_PROOF_SIZE);
+    let (num_inputs, input) = input.split_at(32);
+    let num_inputs: usize = U256::from_be_slice(num_inputs)
+        .try_into()
+        .map_err(|_| PrecompileError::Bn128PairLength)?;
+    // make sure that size computation below can't overflow
+    if num_inputs > input.len() / 32 {
+        return Err(PrecompileError::Bn128PairLength);
+    }
+    let inputs_len = num_inputs * 32;
+    if input.len() != inputs_len + groth16_vk_size(num_inputs) {
+        return Err(PrecompileError::Bn128PairLength);
+    }
+    let gas_used = GROTH16_BASE_GAS + GROTH16_PER_INPUT_GAS * num_inputs as u64;
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let (inputs, vk) = input.split_at(inputs_len);
+    // points that aren't on the curve are rejected the same way `ecPairing` does
+    let proof: &[u8; GROTH16_PROOF_SIZE] = proof.try_into().unwrap();
+    let is_valid = try_groth16_verify(vk, proof, inputs)
+        .map_err(|_| PrecompileError::Bn128AffineGFailedToCreate)?;
+    let mut output = [0u8; 32];
+    output[31] = is_valid as u8;
+    Ok((gas_used, Bytes::copy_from_slice(&output)))
+}
 
 #[derive(Default)]
 pub struct PRECOMPILE<FN: PrecompileInvokeFunc> {
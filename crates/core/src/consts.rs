@@ -13,3 +13,5 @@ pub const PRECOMPILE_KZG_POINT_EVALUATION_ADDRESS: Address =
 pub const PRECOMPILE_MODEXP_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
 pub const PRECOMPILE_SECP256K1_ADDRESS: Address =
     address!("0000000000000000000000000000000000000006");
+pub const PRECOMPILE_GROTH16_VERIFY_ADDRESS: Address =
+    address!("5200000000000000000000000000000000000003");
@@ -5,6 +5,7 @@ use revm_interpreter::{
     primitives::{
         Address,
         AnalysisKind,
+        BlobExcessGasAndPrice,
         BlockEnv,
         Bytecode,
         CfgEnv,
@@ -42,9 +43,13 @@ impl<'cr, 'am, CR: ContextReader, AM: AccountManager> FluentHost<'cr, 'am, CR, A
                     timestamp: U256::from(cr.block_timestamp()),
                     gas_limit: U256::from(cr.block_gas_limit()),
                     basefee: cr.block_base_fee(),
-                    difficulty: U256::from(cr.block_difficulty()),
-                    prevrandao: Some(B256::from(U256::from(cr.block_difficulty()))),
-                    blob_excess_gas_and_price: None,
+                    difficulty: cr.block_difficulty(),
+                    prevrandao: Some(cr.block_prevrandao()),
+                    // the excess gas isn't known, but only the price is used by `BLOBBASEFEE`
+                    blob_excess_gas_and_price: Some(BlobExcessGasAndPrice {
+                        excess_blob_gas: 0,
+                        blob_gasprice: cr.block_blob_base_fee().try_into().unwrap_or(u128::MAX),
+                    }),
                 },
                 tx: TxEnv {
                     caller: cr.tx_caller(),
@@ -1,5 +1,11 @@
 use crate::fluent_host::FluentHost;
-use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::mem::take;
 use fluentbase_codec::Encoder;
 use fluentbase_sdk::{
@@ -13,6 +19,7 @@ use fluentbase_sdk::{
     LowLevelSDK,
     SharedAPI,
 };
+use fluentbase_softfloat::lower_floats;
 use fluentbase_types::{
     create_sovereign_import_linker,
     Address,
@@ -22,6 +29,7 @@ use fluentbase_types::{
     STATE_DEPLOY,
     STATE_MAIN,
 };
+use fluentbase_wasi::shim_wasi_imports;
 use revm_interpreter::{
     opcode::make_instruction_table,
     CallInputs,
@@ -52,8 +60,15 @@ macro_rules! decode_method_input {
     }};
 }
 
+/// Reason why `wasm2rwasm` rejects a module, it's the revert reason of the create.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilationError(pub String);
+
+/// Translates a WASM module into rWASM, floats are lowered into soft-float routines. If
+/// `shim_wasi` is set, WASI preview1 imports of binaries built for `wasm32-wasi` are forwarded
+/// into syscalls, otherwise they're left to the import linker that rejects them.
 #[inline(always)]
-pub fn wasm2rwasm(wasm_binary: &[u8]) -> Result<Vec<u8>, ExitCode> {
+pub fn wasm2rwasm(wasm_binary: &[u8], shim_wasi: bool) -> Result<Vec<u8>, CompilationError> {
     let mut config = RwasmModule::default_config(None);
     config.rwasm_config(RwasmConfig {
         state_router: Some(StateRouterConfig {
@@ -67,8 +82,16 @@ pub fn wasm2rwasm(wasm_binary: &[u8]) -> Result<Vec<u8>, ExitCode> {
         import_linker: Some(create_sovereign_import_linker()),
         wrap_import_functions: true,
     });
-    let rwasm_module = RwasmModule::compile_with_config(wasm_binary, &config)
-        .map_err(|_| ExitCode::CompilationError)?;
+    let wasm_binary = if shim_wasi {
+        shim_wasi_imports(wasm_binary).map_err(|err| CompilationError(err.to_string()))?
+    } else {
+        wasm_binary.to_vec()
+    };
+    // rWASM has no float instructions, so floats are replaced with soft-float routines
+    let wasm_binary = lower_floats(&wasm_binary)
+        .map_err(|err| CompilationError(format!("failed to lower floats: {:?}", err)))?;
+    let rwasm_module = RwasmModule::compile_with_config(&wasm_binary, &config)
+        .map_err(|_| CompilationError("failed to compile rWASM module".to_string()))?;
     let length = rwasm_module.encoded_length();
     let mut rwasm_bytecode = vec![0u8; length];
     let mut binary_format_writer = BinaryFormatWriter::new(&mut rwasm_bytecode);
@@ -78,6 +101,89 @@ pub fn wasm2rwasm(wasm_binary: &[u8]) -> Result<Vec<u8>, ExitCode> {
     Ok(rwasm_bytecode)
 }
 
+/// Name of the custom section with ABI-encoded constructor arguments of WASM init code.
+pub const CONSTRUCTOR_ARGS_SECTION: &str = "constructor_args";
+
+/// Appends ABI-encoded constructor arguments to a WASM module as its last custom section, so
+/// init code is still a valid module.
+pub fn wasm_init_code(module: &[u8], args: &[u8]) -> Vec<u8> {
+    let name = CONSTRUCTOR_ARGS_SECTION.as_bytes();
+    let mut payload = Vec::with_capacity(5 + name.len() + args.len());
+    write_leb128_u32(&mut payload, name.len() as u32);
+    payload.extend_from_slice(name);
+    payload.extend_from_slice(args);
+    let mut init_code = Vec::with_capacity(module.len() + 6 + payload.len());
+    init_code.extend_from_slice(module);
+    init_code.push(0x00);
+    write_leb128_u32(&mut init_code, payload.len() as u32);
+    init_code.extend_from_slice(&payload);
+    init_code
+}
+
+/// Splits WASM init code into the module and constructor arguments, they're the payload of the
+/// `constructor_args` custom section if it's the last section of the init code. Anything else,
+/// including malformed sections, is left to the compiler as a module without arguments.
+pub fn split_wasm_init_code(init_code: &[u8]) -> (&[u8], &[u8]) {
+    if init_code.len() < 8 || init_code[0..4] != *b"\0asm" {
+        return (init_code, &[]);
+    }
+    let mut offset = 8;
+    let mut last_section = None;
+    while offset < init_code.len() {
+        let id = init_code[offset];
+        let Some((size, payload)) = read_leb128_u32(init_code, offset + 1) else {
+            return (init_code, &[]);
+        };
+        let end = payload + size as usize;
+        if end > init_code.len() {
+            return (init_code, &[]);
+        }
+        last_section = Some((offset, id, payload, end));
+        offset = end;
+    }
+    let Some((start, 0, payload, end)) = last_section else {
+        return (init_code, &[]);
+    };
+    let name = CONSTRUCTOR_ARGS_SECTION.as_bytes();
+    match read_leb128_u32(init_code, payload) {
+        Some((name_len, name_offset))
+            if init_code.get(name_offset..name_offset + name_len as usize) == Some(name) =>
+        {
+            (
+                &init_code[..start],
+                &init_code[name_offset + name.len()..end],
+            )
+        }
+        _ => (init_code, &[]),
+    }
+}
+
+fn write_leb128_u32(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Reads an unsigned LEB128 at `offset`, returns the value and the offset right after it.
+fn read_leb128_u32(data: &[u8], mut offset: usize) -> Option<(u32, usize)> {
+    let mut result = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *data.get(offset)?;
+        offset += 1;
+        result |= ((byte & 0x7f) as u32).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some((result, offset));
+        }
+    }
+    None
+}
+
 #[macro_export]
 macro_rules! result_value {
     ($result:expr) => {
@@ -110,9 +216,12 @@ macro_rules! debug_log {
 fn contract_context_from_call_inputs<CR: ContextReader>(
     cr: &CR,
     call_inputs: &Box<CallInputs>,
+    caller_gas_remaining: u64,
+    depth: u32,
 ) -> ContractInput {
     ContractInput {
         contract_gas_limit: call_inputs.gas_limit,
+        contract_caller_gas_remaining: caller_gas_remaining,
         contract_address: call_inputs.context.address,
         contract_caller: call_inputs.context.caller,
         contract_value: call_inputs.context.apparent_value,
@@ -121,9 +230,14 @@ fn contract_context_from_call_inputs<CR: ContextReader>(
         block_coinbase: cr.block_coinbase(),
         block_timestamp: cr.block_timestamp(),
         block_number: cr.block_number(),
+        block_difficulty_u64: cr.block_difficulty().saturating_to(),
         block_difficulty: cr.block_difficulty(),
+        block_prevrandao: cr.block_prevrandao(),
         block_gas_limit: cr.block_gas_limit(),
         block_base_fee: cr.block_base_fee(),
+        block_blob_base_fee: cr.block_blob_base_fee(),
+        tx_hash: cr.tx_hash(),
+        tx_type: cr.tx_type(),
         tx_gas_limit: cr.tx_gas_limit(),
         tx_nonce: cr.tx_nonce(),
         tx_gas_price: cr.tx_gas_price(),
@@ -132,15 +246,19 @@ fn contract_context_from_call_inputs<CR: ContextReader>(
         tx_access_list: cr.tx_access_list(),
         tx_blob_hashes: cr.tx_blob_hashes(),
         tx_max_fee_per_blob_gas: cr.tx_max_fee_per_blob_gas(),
+        contract_depth: depth,
     }
 }
 
 fn contract_context_from_create_inputs<CR: ContextReader>(
     cr: &CR,
     create_inputs: &Box<CreateInputs>,
+    caller_gas_remaining: u64,
+    depth: u32,
 ) -> ContractInput {
     ContractInput {
         contract_gas_limit: create_inputs.gas_limit,
+        contract_caller_gas_remaining: caller_gas_remaining,
         contract_address: Address::ZERO,
         contract_caller: create_inputs.caller,
         contract_value: create_inputs.value,
@@ -149,9 +267,14 @@ fn contract_context_from_create_inputs<CR: ContextReader>(
         block_coinbase: cr.block_coinbase(),
         block_timestamp: cr.block_timestamp(),
         block_number: cr.block_number(),
+        block_difficulty_u64: cr.block_difficulty().saturating_to(),
         block_difficulty: cr.block_difficulty(),
+        block_prevrandao: cr.block_prevrandao(),
         block_gas_limit: cr.block_gas_limit(),
         block_base_fee: cr.block_base_fee(),
+        block_blob_base_fee: cr.block_blob_base_fee(),
+        tx_hash: cr.tx_hash(),
+        tx_type: cr.tx_type(),
         tx_gas_limit: cr.tx_gas_limit(),
         tx_nonce: cr.tx_nonce(),
         tx_gas_price: cr.tx_gas_price(),
@@ -160,6 +283,7 @@ fn contract_context_from_create_inputs<CR: ContextReader>(
         tx_access_list: cr.tx_access_list(),
         tx_blob_hashes: cr.tx_blob_hashes(),
         tx_max_fee_per_blob_gas: cr.tx_max_fee_per_blob_gas(),
+        contract_depth: depth,
     }
 }
 
@@ -169,9 +293,11 @@ fn exec_evm_create<CR: ContextReader, AM: AccountManager>(
     am: &AM,
     inputs: Box<CreateInputs>,
     depth: u32,
+    caller_gas_remaining: u64,
 ) -> CreateOutcome {
     // calc create input
-    let contract_input = contract_context_from_create_inputs(cr, &inputs);
+    let contract_input =
+        contract_context_from_create_inputs(cr, &inputs, caller_gas_remaining, depth);
     let method_data = EvmCreateMethodInput {
         value: inputs.value,
         bytecode: inputs.init_code,
@@ -203,10 +329,12 @@ fn exec_evm_call<CR: ContextReader, AM: AccountManager>(
     am: &AM,
     mut inputs: Box<CallInputs>,
     depth: u32,
+    caller_gas_remaining: u64,
 ) -> CallOutcome {
     let return_memory_offset = inputs.return_memory_offset.clone();
 
-    let contract_input = contract_context_from_call_inputs(cr, &inputs);
+    let contract_input =
+        contract_context_from_call_inputs(cr, &inputs, caller_gas_remaining, depth);
     let method_data = EvmCallMethodInput {
         callee: inputs.contract,
         // here we take transfer value, because for DELEGATECALL it's not apparent
@@ -310,7 +438,8 @@ pub(crate) fn exec_evm_bytecode<CR: ContextReader, AM: AccountManager>(
                     hex::encode(inputs.transfer.value.to_be_bytes::<32>()),
                     hex::encode(inputs.context.apparent_value.to_be_bytes::<32>()),
                 );
-                let call_outcome = exec_evm_call(cr, am, inputs, depth + 1);
+                let call_outcome =
+                    exec_evm_call(cr, am, inputs, depth + 1, interpreter.gas.remaining());
                 interpreter.insert_call_outcome(&mut shared_memory, call_outcome);
             }
             InterpreterAction::Create { inputs } => {
@@ -319,7 +448,8 @@ pub(crate) fn exec_evm_bytecode<CR: ContextReader, AM: AccountManager>(
                     inputs.caller,
                     hex::encode(inputs.value.to_be_bytes::<32>())
                 );
-                let create_outcome = exec_evm_create(cr, am, inputs, depth + 1);
+                let create_outcome =
+                    exec_evm_create(cr, am, inputs, depth + 1, interpreter.gas.remaining());
                 interpreter.insert_create_outcome(create_outcome);
             }
             InterpreterAction::Return { result } => {
@@ -432,3 +562,42 @@ impl InputHelper {
         core_input
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{split_wasm_init_code, wasm_init_code};
+    use alloc::{vec, vec::Vec};
+
+    #[test]
+    fn test_split_wasm_init_code() {
+        let mut module: Vec<u8> = b"\0asm\x01\0\0\0".to_vec();
+        // type section with a single `() -> ()` signature
+        module.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // custom section called `name`
+        module.extend_from_slice(&[0x00, 0x05, 0x04, b'n', b'a', b'm', b'e']);
+        assert_eq!(split_wasm_init_code(&module), (&module[..], &[][..]));
+        // words that look like sections when they're appended without a delimiter
+        let mut custom_like_word = [0u8; 32];
+        custom_like_word[1] = 0x1e;
+        let mut data_like_word = [0u8; 32];
+        data_like_word[0] = 0x0b;
+        data_like_word[1] = 0x1e;
+        for args in [
+            vec![],
+            custom_like_word.to_vec(),
+            data_like_word.to_vec(),
+            [0xffu8; 200].to_vec(),
+        ] {
+            let init_code = wasm_init_code(&module, &args);
+            assert_eq!(split_wasm_init_code(&init_code), (&module[..], &args[..]));
+            // arguments without the section are a part of the module
+            let init_code = [module.as_slice(), &args].concat();
+            assert_eq!(split_wasm_init_code(&init_code), (&init_code[..], &[][..]));
+        }
+        // not a WASM module at all
+        assert_eq!(
+            split_wasm_init_code(&[0x60, 0x80]),
+            (&[0x60, 0x80][..], &[][..])
+        );
+    }
+}
@@ -1,10 +1,10 @@
 use crate::debug_log;
 use fluentbase_codec::Encoder;
 use fluentbase_sdk::{
+    contract_fuel_remaining,
     AccountManager,
     ContextReader,
     ContractInput,
-    EvmCallMethodOutput,
     WasmCallMethodInput,
     WasmCallMethodOutput,
 };
@@ -24,12 +24,14 @@ pub fn _wasm_call<CR: ContextReader, AM: AccountManager>(
             "_wasm_call return: Err: exit_code: {}",
             ExitCode::WriteProtection
         );
-        return WasmCallMethodOutput::from_exit_code(ExitCode::WriteProtection);
+        return WasmCallMethodOutput::from_exit_code(ExitCode::WriteProtection)
+            .with_revert_reason();
     }
 
     // call depth check
     if input.depth > 1024 {
-        return EvmCallMethodOutput::from_exit_code(ExitCode::CallDepthOverflow);
+        return WasmCallMethodOutput::from_exit_code(ExitCode::CallDepthOverflow)
+            .with_revert_reason();
     }
 
     // create new checkpoint position in the journal
@@ -42,7 +44,9 @@ pub fn _wasm_call<CR: ContextReader, AM: AccountManager>(
 
     let mut context = ContractInput::clone_from_cr(cr);
     context.contract_gas_limit = gas_limit as u64;
+    context.contract_caller_gas_remaining = contract_fuel_remaining(cr);
     context.contract_address = input.callee;
+    context.contract_depth = input.depth;
     let contract_context = context.encode_to_vec(0);
 
     let bytecode_hash = callee_account.rwasm_code_hash;
@@ -68,4 +72,5 @@ pub fn _wasm_call<CR: ContextReader, AM: AccountManager>(
         gas_remaining: gas_limit as u64,
         gas_refund: 0,
     }
+    .with_revert_reason()
 }
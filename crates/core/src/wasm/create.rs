@@ -1,6 +1,10 @@
-use crate::{debug_log, helpers::wasm2rwasm};
+use crate::{
+    debug_log,
+    helpers::{split_wasm_init_code, wasm2rwasm, CompilationError},
+};
 use fluentbase_codec::Encoder;
 use fluentbase_sdk::{
+    contract_fuel_remaining,
     Account,
     AccountManager,
     ContextReader,
@@ -10,7 +14,7 @@ use fluentbase_sdk::{
     WasmCreateMethodInput,
     WasmCreateMethodOutput,
 };
-use fluentbase_types::{Bytes, ExitCode, B256, STATE_DEPLOY};
+use fluentbase_types::{encode_solidity_error_message, Bytes, ExitCode, B256, STATE_DEPLOY};
 use revm_primitives::WASM_MAX_CODE_SIZE;
 
 pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
@@ -29,7 +33,8 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
             "_wasm_create return: Err: exit_code: {}",
             ExitCode::WriteProtection
         );
-        return WasmCreateMethodOutput::from_exit_code(ExitCode::WriteProtection);
+        return WasmCreateMethodOutput::from_exit_code(ExitCode::WriteProtection)
+            .with_revert_reason();
     }
 
     // code length can't exceed max constructor limit
@@ -38,9 +43,13 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
             "_wasm_create return: Err: exit_code: {}",
             ExitCode::ContractSizeLimit
         );
-        return WasmCreateMethodOutput::from_exit_code(ExitCode::ContractSizeLimit);
+        return WasmCreateMethodOutput::from_exit_code(ExitCode::ContractSizeLimit)
+            .with_revert_reason();
     }
 
+    // ABI-encoded constructor arguments are appended to the module as a custom section
+    let (wasm_bytecode, constructor_input) = split_wasm_init_code(&input.bytecode);
+
     let mut source_code_hash: B256 = B256::ZERO;
     LowLevelSDK::keccak256(
         input.bytecode.as_ptr(),
@@ -62,7 +71,7 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
     ) {
         Ok(result) => result,
         Err(exit_code) => {
-            return WasmCreateMethodOutput::from_exit_code(exit_code);
+            return WasmCreateMethodOutput::from_exit_code(exit_code).with_revert_reason();
         }
     };
     if !input.value.is_zero() {
@@ -80,13 +89,15 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
         hex::encode(contract_account.balance.to_be_bytes::<32>())
     );
 
-    // translate WASM to rWASM
-    let rwasm_bytecode = match wasm2rwasm(&input.bytecode) {
+    // translate WASM to rWASM, binaries built for `wasm32-wasi` get their WASI imports
+    // forwarded into syscalls
+    let rwasm_bytecode = match wasm2rwasm(wasm_bytecode, true) {
         Ok(result) => result,
-        Err(exit_code) => {
+        Err(CompilationError(message)) => {
             am.rollback(checkpoint);
-            debug_log!("_wasm_create return: panic: exit_code: {}", exit_code);
-            return WasmCreateMethodOutput::from_exit_code(exit_code);
+            debug_log!("_wasm_create return: panic: {}", message);
+            return WasmCreateMethodOutput::from_exit_code(ExitCode::CompilationError)
+                .with_output(encode_solidity_error_message(&message).into());
         }
     };
     // let exit_code = LowLevelSDK::wasm_to_rwasm(
@@ -110,19 +121,27 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
     am.write_account(&deployer_account);
 
     // write contract to the trie
-    contract_account.update_bytecode(am, &input.bytecode, None, &rwasm_bytecode.into(), None);
+    contract_account.update_bytecode(
+        am,
+        &Bytes::copy_from_slice(wasm_bytecode),
+        None,
+        &rwasm_bytecode.into(),
+        None,
+    );
 
     let mut context = ContractInput::clone_from_cr(cr);
     context.contract_value = input.value;
     context.contract_gas_limit = input.gas_limit;
+    context.contract_caller_gas_remaining = contract_fuel_remaining(cr);
     context.contract_address = contract_account.address;
+    context.contract_depth = input.depth;
     let contract_context = context.encode_to_vec(0);
 
     let mut gas_limit = input.gas_limit as u32;
-    let (_, exit_code) = am.exec_hash(
+    let (output_buffer, exit_code) = am.exec_hash(
         contract_account.rwasm_code_hash.as_ptr(),
         &contract_context,
-        &[],
+        constructor_input,
         &mut gas_limit as *mut u32,
         STATE_DEPLOY,
     );
@@ -130,7 +149,10 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
     if exit_code != ExitCode::Ok.into_i32() {
         am.rollback(checkpoint);
         debug_log!("_wasm_create return: Err: ExitCode::TransactError");
-        return WasmCreateMethodOutput::from_exit_code(ExitCode::from(exit_code));
+        // keep revert data of the constructor
+        return WasmCreateMethodOutput::from_exit_code(ExitCode::from(exit_code))
+            .with_output(output_buffer.into())
+            .with_revert_reason();
     }
 
     debug_log!(
@@ -149,3 +171,118 @@ pub fn _wasm_create<CR: ContextReader, AM: AccountManager>(
         gas_refund: 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::_wasm_create;
+    use crate::helpers::wasm_init_code;
+    use alloc::vec::Vec;
+    use fluentbase_codec::Encoder;
+    use fluentbase_sdk::{
+        AccountManager,
+        ContractInput,
+        ExecutionContext,
+        JzktAccountManager,
+        LowLevelSDK,
+        WasmCreateMethodInput,
+    };
+    use fluentbase_types::{decode_solidity_error_message, Address, Bytes, ExitCode, U256};
+
+    // the constructor exits with an error unless its argument is `uint256(42)`
+    const CONTRACT: &str = r#"
+(module
+  (import "fluentbase_v1preview" "_input_size" (func $_input_size (result i32)))
+  (import "fluentbase_v1preview" "_read" (func $_read (param i32 i32 i32)))
+  (import "fluentbase_v1preview" "_exit" (func $_exit (param i32)))
+  (func (export "deploy")
+    (if (i32.ne (call $_input_size) (i32.const 32))
+      (then (call $_exit (i32.const -1))))
+    (call $_read (i32.const 0) (i32.const 0) (i32.const 32))
+    (if (i32.eqz
+          (i32.and
+            (i32.and (i64.eqz (i64.load (i32.const 0))) (i64.eqz (i64.load (i32.const 8))))
+            (i32.and
+              (i64.eqz (i64.load (i32.const 16)))
+              (i64.eq (i64.load (i32.const 24)) (i64.const 0x2a00000000000000)))))
+      (then (call $_exit (i32.const -1)))))
+  (func (export "main"))
+  (memory 1))
+    "#;
+
+    fn create(bytecode: Vec<u8>) -> (Option<Address>, i32, Bytes) {
+        let caller = Address::repeat_byte(1);
+        LowLevelSDK::with_test_context(
+            ContractInput {
+                contract_caller: caller,
+                contract_gas_limit: 10_000_000,
+                ..Default::default()
+            }
+            .encode_to_vec(0),
+        );
+        let output = _wasm_create(
+            &ExecutionContext::DEFAULT,
+            &JzktAccountManager::DEFAULT,
+            WasmCreateMethodInput {
+                bytecode: bytecode.into(),
+                value: U256::ZERO,
+                gas_limit: 10_000_000,
+                salt: None,
+                depth: 0,
+            },
+        );
+        (output.address, output.exit_code, output.output)
+    }
+
+    #[test]
+    fn test_deploy_with_constructor_args() {
+        let module = wat::parse_str(CONTRACT).unwrap();
+
+        let args = U256::from(42).to_be_bytes::<32>();
+        let (address, exit_code, _) = create(wasm_init_code(&module, &args));
+        assert_eq!(exit_code, ExitCode::Ok.into_i32());
+        // the arguments aren't a part of the deployed code
+        let am = JzktAccountManager::DEFAULT;
+        let (account, _) = am.account(address.unwrap());
+        assert_eq!(
+            am.preimage(&account.source_code_hash.0),
+            Bytes::from(module.clone())
+        );
+
+        // the constructor gets exactly the arguments appended to the module
+        let args = U256::from(43).to_be_bytes::<32>();
+        let (address, exit_code, output) = create(wasm_init_code(&module, &args));
+        assert_eq!(address, None);
+        assert_ne!(exit_code, ExitCode::Ok.into_i32());
+        // the constructor exits without data, so the exit code is a revert reason
+        assert_eq!(
+            decode_solidity_error_message(&output).as_deref(),
+            Some("UnknownError")
+        );
+
+        // and nothing if there are none
+        let (address, exit_code, _) = create(module);
+        assert_eq!(address, None);
+        assert_ne!(exit_code, ExitCode::Ok.into_i32());
+    }
+
+    #[test]
+    fn test_unsupported_wasi_import() {
+        let module = wat::parse_str(
+            r#"
+(module
+  (import "wasi_snapshot_preview1" "sock_accept" (func (param i32 i32 i32) (result i32)))
+  (func (export "deploy"))
+  (func (export "main"))
+  (memory 1))
+            "#,
+        )
+        .unwrap();
+        let (address, exit_code, output) = create(module);
+        assert_eq!(address, None);
+        assert_eq!(exit_code, ExitCode::CompilationError.into_i32());
+        assert_eq!(
+            decode_solidity_error_message(&output).as_deref(),
+            Some("WASI import `wasi_snapshot_preview1::sock_accept` is not supported")
+        );
+    }
+}
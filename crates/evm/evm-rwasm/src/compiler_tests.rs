@@ -120,8 +120,8 @@ static HOST_NUMBER: [u8; 8] = [10; 8];
 // u64 - 8 bytes
 static HOST_TIMESTAMP: [u8; 8] = [11; 8];
 // u64 - 8 bytes
-static HOST_ENV_DIFFICULTY: [u8; 8] = [12; 8];
-// u64 - 8 bytes
+static HOST_ENV_PREVRANDAO: [u8; 32] = [12; 32];
+// B256 - 32 bytes
 static HOST_ENV_BLOBBASEFEE: [u8; 8] = [13; 8];
 // u64 - 8 bytes
 static HOST_ENV_GASPRICE: [u8; 32] = [14; 32];
@@ -404,9 +404,9 @@ fn run_test(
     contract_input.block_gas_limit = u64::from_be_bytes(HOST_GASLIMIT);
     contract_input.block_number = u64::from_be_bytes(HOST_NUMBER);
     contract_input.block_timestamp = u64::from_be_bytes(HOST_TIMESTAMP);
-    contract_input.block_difficulty = u64::from_be_bytes(HOST_ENV_DIFFICULTY);
+    contract_input.block_prevrandao = B256::new(HOST_ENV_PREVRANDAO);
     contract_input.contract_bytecode = Bytes::copy_from_slice(CONTRACT_BYTECODE);
-    // contract_input.tx_blob_gas_price = u64::from_be_bytes(HOST_ENV_BLOBBASEFEE);
+    contract_input.block_blob_base_fee = U256::from_be_slice(&HOST_ENV_BLOBBASEFEE);
     contract_input.tx_gas_price = U256::from_be_bytes(HOST_ENV_GASPRICE);
     contract_input.tx_caller = Address::new(HOST_ENV_ORIGIN);
     // contract_input.tx_blob_hashes = HOST_ENV_BLOB_HASHES
@@ -2773,11 +2773,8 @@ fn gas() {
 
 #[test]
 fn difficulty() {
-    let cases = [Case::Args0({
-        let mut v = vec![0; 32 - HOST_ENV_DIFFICULTY.len()];
-        v.extend(&HOST_ENV_DIFFICULTY);
-        v
-    })];
+    // after the merge `DIFFICULTY` returns `prevrandao`
+    let cases = [Case::Args0(HOST_ENV_PREVRANDAO.to_vec())];
 
     test_cases(
         DIFFICULTY,
@@ -2789,7 +2786,6 @@ fn difficulty() {
     );
 }
 
-#[ignore] // TODO no implementation yet
 #[test]
 fn blobbasefee() {
     let cases = [Case::Args0({
@@ -1,9 +1,12 @@
-use crate::common_sp::{stack_push_u256, u256_zero, SP_BASE_MEM_OFFSET_DEFAULT};
+use crate::{
+    common::u256_from_be_slice,
+    common_sp::{stack_push_u256, SP_BASE_MEM_OFFSET_DEFAULT},
+};
+use fluentbase_sdk::evm::ExecutionContext;
 
-// #[no_mangle]
-// pub fn host_env_blobbasefee() {
-//     // let v: [u8; 8] = ExecutionContext::tx_blob_gas_price().to_be_bytes();
-//
-//     // stack_push_u256(SP_BASE_MEM_OFFSET_DEFAULT, u256_from_be_slice(&v));
-//     stack_push_u256(SP_BASE_MEM_OFFSET_DEFAULT, u256_zero());
-// }
+#[no_mangle]
+pub fn host_env_blobbasefee() {
+    let v: [u8; 32] = ExecutionContext::block_blob_base_fee().to_be_bytes();
+
+    stack_push_u256(SP_BASE_MEM_OFFSET_DEFAULT, u256_from_be_slice(&v));
+}
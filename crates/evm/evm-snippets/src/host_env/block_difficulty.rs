@@ -6,7 +6,8 @@ use fluentbase_sdk::evm::ExecutionContext;
 
 #[no_mangle]
 pub fn host_env_block_difficulty() {
-    let v: [u8; 8] = ExecutionContext::block_difficulty().to_be_bytes();
+    // after the merge `DIFFICULTY` (0x44) is `PREVRANDAO`
+    let v: [u8; 32] = ExecutionContext::block_prevrandao().0;
 
     stack_push_u256(SP_BASE_MEM_OFFSET_DEFAULT, u256_from_be_slice(&v));
 }
@@ -138,6 +138,10 @@ pub fn devnet_genesis() -> Genesis {
     //     "../../contracts/assets/precompile_secp256k1.rwasm"
     // );
     // enable_rwasm_contract!(
+    //     PRECOMPILE_GROTH16_VERIFY_ADDRESS,
+    //     "../../contracts/assets/precompile_groth16.rwasm"
+    // );
+    // enable_rwasm_contract!(
     //     EXAMPLE_GREETING_ADDRESS,
     //     "../../../examples/greeting/lib.rwasm"
     // );
@@ -10,6 +10,7 @@ use crate::{
         HandlerCfg,
         SpecId,
         TxEnv,
+        B256,
     },
     Context,
     ContextWithHandlerCfg,
@@ -404,6 +405,18 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
         self
     }
 
+    /// Sets hash of the transaction, contracts read it from the context.
+    pub fn with_tx_hash(mut self, tx_hash: B256) -> Self {
+        self.context.evm.tx_hash = tx_hash;
+        self
+    }
+
+    /// Sets EIP-2718 type of the transaction, contracts read it from the context.
+    pub fn with_tx_type(mut self, tx_type: u8) -> Self {
+        self.context.evm.tx_type = Some(tx_type);
+        self
+    }
+
     /// Allows modification of Evm's Block Environment.
     pub fn modify_block_env(mut self, f: impl FnOnce(&mut BlockEnv)) -> Self {
         f(&mut self.context.evm.env.block);
@@ -431,6 +444,8 @@ impl<'a, BuilderStage, EXT, DB: Database> EvmBuilder<'a, BuilderStage, EXT, DB>
     /// Clears Transaction environment of EVM.
     pub fn with_clear_tx_env(mut self) -> Self {
         self.context.evm.env.tx.clear();
+        self.context.evm.tx_hash = B256::ZERO;
+        self.context.evm.tx_type = None;
         self
     }
     /// Clears Block environment of EVM.
@@ -295,6 +295,8 @@ pub(crate) mod test_utils {
                 journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::new()),
                 db,
                 error: Ok(()),
+                tx_hash: B256::ZERO,
+                tx_type: None,
                 #[cfg(feature = "optimism")]
                 l1_block_info: None,
             },
@@ -310,6 +312,8 @@ pub(crate) mod test_utils {
                 journaled_state: JournaledState::new(SpecId::CANCUN, HashSet::new()),
                 db,
                 error: Ok(()),
+                tx_hash: B256::ZERO,
+                tx_type: None,
                 #[cfg(feature = "optimism")]
                 l1_block_info: None,
             },
@@ -50,6 +50,10 @@ pub struct InnerEvmContext<DB: Database> {
     pub db: DB,
     /// Error that happened during execution.
     pub error: Result<(), EVMError<ExitCode>>,
+    /// Hash of the transaction, `TxEnv` doesn't have it, so it's set by the caller.
+    pub tx_hash: B256,
+    /// EIP-2718 type of the transaction, it's derived from `TxEnv` if the caller doesn't set it.
+    pub tx_type: Option<u8>,
     /// Used as temporary value holder to store L1 block info.
     #[cfg(feature = "optimism")]
     pub l1_block_info: Option<crate::optimism::L1BlockInfo>,
@@ -62,6 +66,8 @@ impl<DB: Database + Clone> Clone for InnerEvmContext<DB> {
             journaled_state: self.journaled_state.clone(),
             db: self.db.clone(),
             error: self.error.clone(),
+            tx_hash: self.tx_hash,
+            tx_type: self.tx_type,
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info.clone(),
         }
@@ -75,6 +81,8 @@ impl<DB: Database> InnerEvmContext<DB> {
             journaled_state: JournaledState::new(SpecId::LATEST, HashSet::new()),
             db,
             error: Ok(()),
+            tx_hash: B256::ZERO,
+            tx_type: None,
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -88,6 +96,8 @@ impl<DB: Database> InnerEvmContext<DB> {
             journaled_state: JournaledState::new(SpecId::LATEST, HashSet::new()),
             db,
             error: Ok(()),
+            tx_hash: B256::ZERO,
+            tx_type: None,
             #[cfg(feature = "optimism")]
             l1_block_info: None,
         }
@@ -103,6 +113,8 @@ impl<DB: Database> InnerEvmContext<DB> {
             journaled_state: self.journaled_state,
             db,
             error: Ok(()),
+            tx_hash: self.tx_hash,
+            tx_type: self.tx_type,
             #[cfg(feature = "optimism")]
             l1_block_info: self.l1_block_info,
         }
@@ -531,27 +531,40 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
         callee_address: Address,
         value: U256,
     ) -> ContractInput {
+        let env = &self.context.evm.env;
         ContractInput {
             contract_gas_limit: gas.remaining(),
+            // it's the root call, so the caller has the same gas
+            contract_caller_gas_remaining: gas.remaining(),
             contract_address: callee_address,
             contract_caller: caller_address,
             contract_value: value,
             contract_is_static: false,
-            block_chain_id: self.context.evm.env.cfg.chain_id,
-            block_coinbase: self.context.evm.env.block.coinbase,
-            block_timestamp: self.context.evm.env.block.timestamp.as_limbs()[0],
-            block_number: self.context.evm.env.block.number.as_limbs()[0],
-            block_difficulty: self.context.evm.env.block.difficulty.as_limbs()[0],
-            block_gas_limit: self.context.evm.env.block.gas_limit.as_limbs()[0],
-            block_base_fee: self.context.evm.env.block.basefee,
-            tx_gas_limit: self.context.evm.env.tx.gas_limit,
-            tx_nonce: self.context.evm.env.tx.nonce.unwrap_or_default(),
-            tx_gas_price: self.context.evm.env.tx.gas_price,
-            tx_gas_priority_fee: self.context.evm.env.tx.gas_priority_fee,
-            tx_caller: self.context.evm.env.tx.caller,
-            tx_access_list: self.context.evm.env.tx.access_list.clone(),
-            tx_blob_hashes: self.context.evm.env.tx.blob_hashes.clone(),
-            tx_max_fee_per_blob_gas: self.context.evm.env.tx.max_fee_per_blob_gas,
+            block_chain_id: env.cfg.chain_id,
+            block_coinbase: env.block.coinbase,
+            block_timestamp: env.block.timestamp.as_limbs()[0],
+            block_number: env.block.number.as_limbs()[0],
+            block_difficulty_u64: env.block.difficulty.saturating_to(),
+            block_difficulty: env.block.difficulty,
+            block_prevrandao: env.block.prevrandao.unwrap_or_default(),
+            block_gas_limit: env.block.gas_limit.as_limbs()[0],
+            block_base_fee: env.block.basefee,
+            block_blob_base_fee: env
+                .block
+                .get_blob_gasprice()
+                .map(U256::from)
+                .unwrap_or_default(),
+            tx_hash: self.context.evm.tx_hash,
+            tx_type: self.context.evm.tx_type.unwrap_or_else(|| tx_type(&env.tx)),
+            tx_gas_limit: env.tx.gas_limit,
+            tx_nonce: env.tx.nonce.unwrap_or_default(),
+            tx_gas_price: env.tx.gas_price,
+            tx_gas_priority_fee: env.tx.gas_priority_fee,
+            tx_caller: env.tx.caller,
+            tx_access_list: env.tx.access_list.clone(),
+            tx_blob_hashes: env.tx.blob_hashes.clone(),
+            tx_max_fee_per_blob_gas: env.tx.max_fee_per_blob_gas,
+            contract_depth: 0,
         }
     }
 
@@ -640,6 +653,21 @@ impl<EXT, DB: Database> Evm<'_, EXT, DB> {
     }
 }
 
+/// EIP-2718 type of the transaction for callers that don't set it with `with_tx_type`, it's
+/// guessed by fields set, so an access list transaction without an access list looks like a
+/// legacy one.
+fn tx_type(tx: &TxEnv) -> u8 {
+    if !tx.blob_hashes.is_empty() {
+        3
+    } else if tx.gas_priority_fee.is_some() {
+        2
+    } else if !tx.access_list.is_empty() {
+        1
+    } else {
+        0
+    }
+}
+
 struct JournalDbWrapper<'a, DB: Database> {
     ctx: RefCell<&'a mut EvmContext<DB>>,
 }
@@ -1,6 +1,7 @@
 use crate::{Evm, InMemoryDB};
 use core::{mem::take, str::from_utf8};
 use fluentbase_codec::{BufferDecoder, Encoder};
+use fluentbase_core::helpers::wasm_init_code;
 use fluentbase_genesis::{
     devnet::{devnet_genesis_from_file, KECCAK_HASH_KEY, POSEIDON_HASH_KEY},
     Genesis,
@@ -11,6 +12,7 @@ use fluentbase_sdk::{calc_create_address, Account, ContractInput, CoreInput, Evm
 use fluentbase_types::{
     address,
     bytes,
+    decode_solidity_error_message,
     Address,
     Bytes,
     ExitCode,
@@ -321,6 +323,109 @@ fn test_deploy_keccak256() {
     );
 }
 
+#[test]
+fn test_deploy_contract_with_constructor_args() {
+    // deploy counter WASM contract, the initial value is its constructor argument
+    let mut ctx = TestingContext::default();
+    const DEPLOYER_ADDRESS: Address = Address::ZERO;
+    let init_code = wasm_init_code(
+        include_bytes!("../../../examples/counter/lib.wasm"),
+        &U256::from(100).to_be_bytes::<32>(),
+    );
+    let contract_address = deploy_evm_tx(&mut ctx, DEPLOYER_ADDRESS, init_code.into());
+    let mut call = |input: Vec<u8>| {
+        let result = call_evm_tx(
+            &mut ctx,
+            DEPLOYER_ADDRESS,
+            contract_address,
+            input.into(),
+            None,
+        )
+        .unwrap();
+        assert!(result.is_success());
+        U256::from_be_slice(result.output().unwrap_or_default())
+    };
+    assert_eq!(call(keccak256("value()")[..4].to_vec()), U256::from(100));
+    let increment = [
+        &keccak256("increment(uint256)")[..4],
+        &U256::from(5).to_be_bytes::<32>(),
+    ]
+    .concat();
+    assert_eq!(call(increment), U256::from(105));
+    assert_eq!(call(keccak256("value()")[..4].to_vec()), U256::from(105));
+}
+
+/// Returns the number of wasm pages the binary starts with, it's the memory taken by the stack and
+/// data before an allocator grows it.
+fn initial_memory_pages(wasm_bytecode: &[u8]) -> u64 {
+    wasmparser::Parser::new(0)
+        .parse_all(wasm_bytecode)
+        .find_map(|payload| match payload.unwrap() {
+            wasmparser::Payload::MemorySection(memories) => Some(
+                memories
+                    .into_iter()
+                    .map(|memory| memory.unwrap().initial)
+                    .sum(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+#[ignore]
+fn test_allocator_fuel() {
+    // examples must be built with both allocators, like `make lib.wasm lib.size-class.wasm`
+    const DEPLOYER_ADDRESS: Address = Address::ZERO;
+    println!("| example | allocator | memory pages | gas used (deploy) | gas used (call) |");
+    println!("|---|---|---|---|---|");
+    for (example, input) in [
+        ("greeting", Bytes::default()),
+        ("hashing", "Hello, World".into()),
+    ] {
+        let mut results = vec![];
+        for (allocator, file) in [
+            ("LeakingAllocator", "lib.wasm"),
+            ("SizeClassAllocator", "lib.size-class.wasm"),
+        ] {
+            let path = format!(
+                "{}/../../examples/{example}/{file}",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            let wasm_bytecode = std::fs::read(&path).expect("example isn't built");
+            let memory_pages = initial_memory_pages(&wasm_bytecode);
+            let mut ctx = TestingContext::default();
+            let result = TxBuilder::create(&mut ctx, DEPLOYER_ADDRESS, wasm_bytecode.into(), None)
+                .exec()
+                .unwrap();
+            assert!(result.is_success());
+            let deploy_gas_used = result.gas_used();
+            let contract_address = calc_create_address(&DEPLOYER_ADDRESS, 0);
+            let result = call_evm_tx(
+                &mut ctx,
+                DEPLOYER_ADDRESS,
+                contract_address,
+                input.clone(),
+                None,
+            )
+            .unwrap();
+            assert!(result.is_success());
+            println!(
+                "| {example} | {allocator} | {memory_pages} | {deploy_gas_used} | {} |",
+                result.gas_used()
+            );
+            results.push((memory_pages, result.gas_used(), result.output().cloned()));
+        }
+        let (leaking, size_class) = (&results[0], &results[1]);
+        // the allocator doesn't change what the example returns
+        assert_eq!(leaking.2, size_class.2, "{example}");
+        // freed memory is tracked by size classes, so allocations can't get cheaper
+        assert!(size_class.1 >= leaking.1, "{example}");
+        // free lists are kept in the data of the binary
+        assert!(size_class.0 >= leaking.0, "{example}");
+    }
+}
+
 #[test]
 fn test_deploy_panic() {
     // deploy greeting WASM contract
@@ -344,7 +449,7 @@ fn test_deploy_panic() {
     let bytes = result.output().unwrap_or_default();
     assert_eq!(
         "panicked at examples/panic/lib.rs:15:9: it is panic time",
-        from_utf8(bytes.as_ref()).unwrap()
+        decode_solidity_error_message(bytes.as_ref()).unwrap()
     );
 }
 
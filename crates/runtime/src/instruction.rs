@@ -1,3 +1,10 @@
+pub(crate) mod bn254;
+pub mod bn254_fr_add;
+pub mod bn254_fr_batch_inv;
+pub mod bn254_fr_inner_product;
+pub mod bn254_fr_inv;
+pub mod bn254_fr_mul;
+pub mod bn254_fr_pow;
 pub mod charge_fuel;
 pub mod checkpoint;
 pub mod commit;
@@ -10,6 +17,7 @@ pub mod exec;
 pub mod exit;
 pub mod forward_output;
 pub mod get_leaf;
+pub mod groth16_verify;
 pub mod input_size;
 pub mod keccak256;
 pub mod output_size;
@@ -29,6 +37,12 @@ pub mod write;
 use crate::{
     impl_runtime_handler,
     instruction::{
+        bn254_fr_add::SyscallBn254FrAdd,
+        bn254_fr_batch_inv::SyscallBn254FrBatchInv,
+        bn254_fr_inner_product::SyscallBn254FrInnerProduct,
+        bn254_fr_inv::SyscallBn254FrInv,
+        bn254_fr_mul::SyscallBn254FrMul,
+        bn254_fr_pow::SyscallBn254FrPow,
         charge_fuel::SyscallChargeFuel,
         checkpoint::SyscallCheckpoint,
         commit::SyscallCommit,
@@ -41,6 +55,7 @@ use crate::{
         exit::SyscallExit,
         forward_output::SyscallForwardOutput,
         get_leaf::SyscallGetLeaf,
+        groth16_verify::SyscallGroth16Verify,
         input_size::SyscallInputSize,
         keccak256::SyscallKeccak256,
         output_size::SyscallOutputSize,
@@ -77,6 +92,13 @@ impl_runtime_handler!(SyscallKeccak256, KECCAK256, fn fluentbase_v1preview::_kec
 impl_runtime_handler!(SyscallPoseidon, POSEIDON, fn fluentbase_v1preview::_poseidon(f32s_ptr: u32, f32s_len: u32, output_ptr: u32) -> ());
 impl_runtime_handler!(SyscallPoseidonHash, POSEIDON_HASH, fn fluentbase_v1preview::_poseidon_hash(fa32_ptr: u32, fb32_ptr: u32, fd32_ptr: u32, output_ptr: u32) -> ());
 impl_runtime_handler!(SyscallEcrecover, ECRECOVER, fn fluentbase_v1preview::_ecrecover(digest32_ptr: u32, sig64_ptr: u32, output65_ptr: u32, rec_id: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrAdd, BN254_FR_ADD, fn fluentbase_v1preview::_bn254_fr_add(a32s_ptr: u32, b32s_ptr: u32, f32s_len: u32, output32s_ptr: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrMul, BN254_FR_MUL, fn fluentbase_v1preview::_bn254_fr_mul(a32s_ptr: u32, b32s_ptr: u32, f32s_len: u32, output32s_ptr: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrInv, BN254_FR_INV, fn fluentbase_v1preview::_bn254_fr_inv(f32_ptr: u32, output32_ptr: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrBatchInv, BN254_FR_BATCH_INV, fn fluentbase_v1preview::_bn254_fr_batch_inv(f32s_ptr: u32, f32s_len: u32, output32s_ptr: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrPow, BN254_FR_POW, fn fluentbase_v1preview::_bn254_fr_pow(base32_ptr: u32, exp32_ptr: u32, output32_ptr: u32) -> ());
+impl_runtime_handler!(SyscallBn254FrInnerProduct, BN254_FR_INNER_PRODUCT, fn fluentbase_v1preview::_bn254_fr_inner_product(a32s_ptr: u32, b32s_ptr: u32, f32s_len: u32, output32_ptr: u32) -> ());
+impl_runtime_handler!(SyscallGroth16Verify, GROTH16_VERIFY, fn fluentbase_v1preview::_groth16_verify(vk_ptr: u32, vk_len: u32, proof_ptr: u32, inputs_ptr: u32, inputs_len: u32) -> i32);
 impl_runtime_handler!(SyscallExit, EXIT, fn fluentbase_v1preview::_exit(exit_code: i32) -> ());
 impl_runtime_handler!(SyscallWrite, WRITE, fn fluentbase_v1preview::_write(offset: u32, length: u32) -> ());
 impl_runtime_handler!(SyscallInputSize, INPUT_SIZE, fn fluentbase_v1preview::_input_size() -> u32);
@@ -109,6 +131,13 @@ fn runtime_register_handlers<DB: IJournaledTrie, const IS_SOVEREIGN: bool>(
     SyscallPoseidon::register_handler(linker, store);
     SyscallPoseidonHash::register_handler(linker, store);
     SyscallEcrecover::register_handler(linker, store);
+    SyscallBn254FrAdd::register_handler(linker, store);
+    SyscallBn254FrMul::register_handler(linker, store);
+    SyscallBn254FrInv::register_handler(linker, store);
+    SyscallBn254FrBatchInv::register_handler(linker, store);
+    SyscallBn254FrPow::register_handler(linker, store);
+    SyscallBn254FrInnerProduct::register_handler(linker, store);
+    SyscallGroth16Verify::register_handler(linker, store);
     SyscallExit::register_handler(linker, store);
     SyscallWrite::register_handler(linker, store);
     SyscallForwardOutput::register_handler(linker, store);
@@ -0,0 +1,179 @@
+use fluentbase_types::ExitCode;
+use halo2curves::{
+    bn256::{Fq, Fq2, Fr, G1Affine, G2Affine, G2},
+    group::{ff::Field, prime::PrimeCurveAffine, Group},
+    CurveAffine,
+};
+
+/// Decodes a canonical little-endian 32-byte BN254 scalar field element
+pub(crate) fn fr_from_bytes(data: &[u8]) -> Result<Fr, ExitCode> {
+    let data: [u8; 32] = data.try_into().map_err(|_| ExitCode::Bn254Error)?;
+    Option::from(Fr::from_bytes(&data)).ok_or(ExitCode::Bn254Error)
+}
+
+/// Decodes a packed array of 32-byte field elements, length must be a multiple of 32
+pub(crate) fn fr_vec_from_bytes(data: &[u8]) -> Result<Vec<Fr>, ExitCode> {
+    if data.len() % 32 != 0 {
+        return Err(ExitCode::Bn254Error);
+    }
+    data.chunks(32).map(fr_from_bytes).collect()
+}
+
+pub(crate) fn fr_vec_to_bytes<I: IntoIterator<Item = Fr>>(elems: I) -> Vec<u8> {
+    elems.into_iter().flat_map(|fr| fr.to_bytes()).collect()
+}
+
+fn reverse32(data: &[u8]) -> Result<[u8; 32], ExitCode> {
+    let mut result: [u8; 32] = data.try_into().map_err(|_| ExitCode::Bn254Error)?;
+    result.reverse();
+    Ok(result)
+}
+
+/// Decodes a canonical big-endian 32-byte scalar (EVM word representation)
+pub(crate) fn fr_from_be_bytes(data: &[u8]) -> Result<Fr, ExitCode> {
+    Option::from(Fr::from_bytes(&reverse32(data)?)).ok_or(ExitCode::Bn254Error)
+}
+
+fn fq_from_be_bytes(data: &[u8]) -> Result<Fq, ExitCode> {
+    Option::from(Fq::from_bytes(&reverse32(data)?)).ok_or(ExitCode::Bn254Error)
+}
+
+/// Decodes a 64-byte G1 point `x || y` as in EIP-196, where all zeros is a point at infinity
+pub(crate) fn g1_from_be_bytes(data: &[u8]) -> Result<G1Affine, ExitCode> {
+    if data.len() != 64 {
+        return Err(ExitCode::Bn254Error);
+    }
+    let x = fq_from_be_bytes(&data[0..32])?;
+    let y = fq_from_be_bytes(&data[32..64])?;
+    if bool::from(x.is_zero() & y.is_zero()) {
+        return Ok(G1Affine::identity());
+    }
+    // G1 has cofactor 1, so every point on the curve is in the right subgroup
+    Option::from(G1Affine::from_xy(x, y)).ok_or(ExitCode::Bn254Error)
+}
+
+/// Decodes a 128-byte G2 point `x.c1 || x.c0 || y.c1 || y.c0` as in EIP-197
+pub(crate) fn g2_from_be_bytes(data: &[u8]) -> Result<G2Affine, ExitCode> {
+    if data.len() != 128 {
+        return Err(ExitCode::Bn254Error);
+    }
+    let x = Fq2 {
+        c0: fq_from_be_bytes(&data[32..64])?,
+        c1: fq_from_be_bytes(&data[0..32])?,
+    };
+    let y = Fq2 {
+        c0: fq_from_be_bytes(&data[96..128])?,
+        c1: fq_from_be_bytes(&data[64..96])?,
+    };
+    if bool::from(x.is_zero() & y.is_zero()) {
+        return Ok(G2Affine::identity());
+    }
+    let point: G2Affine = Option::from(G2Affine::from_xy(x, y)).ok_or(ExitCode::Bn254Error)?;
+    // G2 has a non-trivial cofactor, so we check that `[r]P = [r-1]P + P` is zero
+    let point_r = G2::from(point) * (-Fr::ONE) + point;
+    if !bool::from(point_r.is_identity()) {
+        return Err(ExitCode::Bn254Error);
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instruction::{
+        bn254::{fr_vec_from_bytes, fr_vec_to_bytes},
+        bn254_fr_add::SyscallBn254FrAdd,
+        bn254_fr_batch_inv::SyscallBn254FrBatchInv,
+        bn254_fr_inner_product::SyscallBn254FrInnerProduct,
+        bn254_fr_inv::SyscallBn254FrInv,
+        bn254_fr_mul::SyscallBn254FrMul,
+        bn254_fr_pow::SyscallBn254FrPow,
+    };
+    use fluentbase_types::ExitCode;
+    use halo2curves::{bn256::Fr, group::ff::Field};
+
+    fn frs(values: &[u64]) -> Vec<u8> {
+        fr_vec_to_bytes(values.iter().map(|v| Fr::from(*v)))
+    }
+
+    #[test]
+    fn test_add_mul() {
+        let a = frs(&[1, 2, 3]);
+        let b = frs(&[10, 20, 30]);
+        assert_eq!(
+            SyscallBn254FrAdd::fn_impl(&a, &b).unwrap(),
+            frs(&[11, 22, 33])
+        );
+        assert_eq!(
+            SyscallBn254FrMul::fn_impl(&a, &b).unwrap(),
+            frs(&[10, 40, 90])
+        );
+        assert_eq!(
+            SyscallBn254FrAdd::fn_impl(&a, &frs(&[1])),
+            Err(ExitCode::Bn254Error)
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_element() {
+        // modulus itself is not a canonical representation
+        let mut modulus = (-Fr::ONE).to_bytes();
+        modulus[0] += 1;
+        assert_eq!(
+            SyscallBn254FrInv::fn_impl(&modulus),
+            Err(ExitCode::Bn254Error)
+        );
+        assert_eq!(fr_vec_from_bytes(&[0u8; 33]), Err(ExitCode::Bn254Error));
+    }
+
+    #[test]
+    fn test_batch_inv() {
+        let elems = frs(&[1, 2, 3, 7, 123456789]);
+        let result = SyscallBn254FrBatchInv::fn_impl(&elems).unwrap();
+        for (elem, inv) in elems.chunks(32).zip(result.chunks(32)) {
+            assert_eq!(SyscallBn254FrInv::fn_impl(elem).unwrap().as_slice(), inv);
+        }
+        assert_eq!(SyscallBn254FrBatchInv::fn_impl(&[]).unwrap(), vec![]);
+        assert_eq!(
+            SyscallBn254FrBatchInv::fn_impl(&frs(&[1, 0, 2])),
+            Err(ExitCode::Bn254Error)
+        );
+        assert_eq!(
+            SyscallBn254FrInv::fn_impl(&frs(&[0])),
+            Err(ExitCode::Bn254Error)
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let mut exp = [0u8; 32];
+        exp[0] = 10;
+        assert_eq!(
+            SyscallBn254FrPow::fn_impl(&frs(&[2]), &exp)
+                .unwrap()
+                .as_slice(),
+            frs(&[1024]).as_slice()
+        );
+        // Fermat's little theorem: a^(r-1) = 1
+        let exp = (-Fr::ONE).to_bytes();
+        assert_eq!(
+            SyscallBn254FrPow::fn_impl(&frs(&[12345]), &exp).unwrap(),
+            Fr::ONE.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let a = frs(&[1, 2, 3]);
+        let b = frs(&[4, 5, 6]);
+        assert_eq!(
+            SyscallBn254FrInnerProduct::fn_impl(&a, &b)
+                .unwrap()
+                .as_slice(),
+            frs(&[32]).as_slice()
+        );
+        assert_eq!(
+            SyscallBn254FrInnerProduct::fn_impl(&[], &[]).unwrap(),
+            Fr::ZERO.to_bytes()
+        );
+    }
+}
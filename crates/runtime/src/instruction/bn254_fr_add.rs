@@ -0,0 +1,35 @@
+use crate::{
+    instruction::bn254::{fr_vec_from_bytes, fr_vec_to_bytes},
+    RuntimeContext,
+};
+use fluentbase_types::{ExitCode, IJournaledTrie};
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallBn254FrAdd;
+
+impl SyscallBn254FrAdd {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        mut caller: Caller<'_, RuntimeContext<DB>>,
+        a32s_offset: u32,
+        b32s_offset: u32,
+        f32s_len: u32,
+        output32s_offset: u32,
+    ) -> Result<(), Trap> {
+        let output = Self::fn_impl(
+            caller.read_memory(a32s_offset, f32s_len)?,
+            caller.read_memory(b32s_offset, f32s_len)?,
+        )
+        .map_err(|err| err.into_trap())?;
+        caller.write_memory(output32s_offset, &output)?;
+        Ok(())
+    }
+
+    pub fn fn_impl(a: &[u8], b: &[u8]) -> Result<Vec<u8>, ExitCode> {
+        let a = fr_vec_from_bytes(a)?;
+        let b = fr_vec_from_bytes(b)?;
+        if a.len() != b.len() {
+            return Err(ExitCode::Bn254Error);
+        }
+        Ok(fr_vec_to_bytes(a.into_iter().zip(b).map(|(a, b)| a + b)))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    instruction::bn254::{fr_vec_from_bytes, fr_vec_to_bytes},
+    RuntimeContext,
+};
+use fluentbase_types::{ExitCode, IJournaledTrie};
+use halo2curves::{bn256::Fr, group::ff::Field};
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallBn254FrBatchInv;
+
+impl SyscallBn254FrBatchInv {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        mut caller: Caller<'_, RuntimeContext<DB>>,
+        f32s_offset: u32,
+        f32s_len: u32,
+        output32s_offset: u32,
+    ) -> Result<(), Trap> {
+        let output = Self::fn_impl(caller.read_memory(f32s_offset, f32s_len)?)
+            .map_err(|err| err.into_trap())?;
+        caller.write_memory(output32s_offset, &output)?;
+        Ok(())
+    }
+
+    pub fn fn_impl(data: &[u8]) -> Result<Vec<u8>, ExitCode> {
+        let elems = fr_vec_from_bytes(data)?;
+        // Montgomery's trick: one inversion and 3(n-1) multiplications for the whole batch
+        let mut prefix = Vec::with_capacity(elems.len());
+        let mut acc = Fr::ONE;
+        for elem in elems.iter() {
+            prefix.push(acc);
+            acc *= elem;
+        }
+        // if any of elements is zero then the accumulated product is zero as well
+        let mut acc_inv: Fr = Option::from(acc.invert()).ok_or(ExitCode::Bn254Error)?;
+        let mut result = vec![Fr::ZERO; elems.len()];
+        for (i, elem) in elems.iter().enumerate().rev() {
+            result[i] = acc_inv * prefix[i];
+            acc_inv *= elem;
+        }
+        Ok(fr_vec_to_bytes(result))
+    }
+}
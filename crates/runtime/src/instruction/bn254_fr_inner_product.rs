@@ -0,0 +1,37 @@
+use crate::{instruction::bn254::fr_vec_from_bytes, RuntimeContext};
+use fluentbase_types::{ExitCode, IJournaledTrie};
+use halo2curves::{bn256::Fr, group::ff::Field};
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallBn254FrInnerProduct;
+
+impl SyscallBn254FrInnerProduct {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        mut caller: Caller<'_, RuntimeContext<DB>>,
+        a32s_offset: u32,
+        b32s_offset: u32,
+        f32s_len: u32,
+        output32_offset: u32,
+    ) -> Result<(), Trap> {
+        let output = Self::fn_impl(
+            caller.read_memory(a32s_offset, f32s_len)?,
+            caller.read_memory(b32s_offset, f32s_len)?,
+        )
+        .map_err(|err| err.into_trap())?;
+        caller.write_memory(output32_offset, &output)?;
+        Ok(())
+    }
+
+    pub fn fn_impl(a: &[u8], b: &[u8]) -> Result<[u8; 32], ExitCode> {
+        let a = fr_vec_from_bytes(a)?;
+        let b = fr_vec_from_bytes(b)?;
+        if a.len() != b.len() {
+            return Err(ExitCode::Bn254Error);
+        }
+        let result = a
+            .into_iter()
+            .zip(b)
+            .fold(Fr::ZERO, |acc, (a, b)| acc + a * b);
+        Ok(result.to_bytes())
+    }
+}
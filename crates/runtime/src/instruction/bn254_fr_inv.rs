@@ -0,0 +1,26 @@
+use crate::{instruction::bn254::fr_from_bytes, RuntimeContext};
+use fluentbase_types::{ExitCode, IJournaledTrie};
+use halo2curves::group::ff::Field;
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallBn254FrInv;
+
+impl SyscallBn254FrInv {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        mut caller: Caller<'_, RuntimeContext<DB>>,
+        f32_offset: u32,
+        output32_offset: u32,
+    ) -> Result<(), Trap> {
+        let output =
+            Self::fn_impl(caller.read_memory(f32_offset, 32)?).map_err(|err| err.into_trap())?;
+        caller.write_memory(output32_offset, &output)?;
+        Ok(())
+    }
+
+    pub fn fn_impl(f: &[u8]) -> Result<[u8; 32], ExitCode> {
+        let f = fr_from_bytes(f)?;
+        // zero has no inverse
+        let inv = Option::from(f.invert()).ok_or(ExitCode::Bn254Error)?;
+        Ok(inv.to_bytes())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{instruction::bn254::fr_from_bytes, RuntimeContext};
+use byteorder::{ByteOrder, LittleEndian};
+use fluentbase_types::{ExitCode, IJournaledTrie};
+use halo2curves::group::ff::Field;
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallBn254FrPow;
+
+impl SyscallBn254FrPow {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        mut caller: Caller<'_, RuntimeContext<DB>>,
+        base32_offset: u32,
+        exp32_offset: u32,
+        output32_offset: u32,
+    ) -> Result<(), Trap> {
+        let output = Self::fn_impl(
+            caller.read_memory(base32_offset, 32)?,
+            caller.read_memory(exp32_offset, 32)?,
+        )
+        .map_err(|err| err.into_trap())?;
+        caller.write_memory(output32_offset, &output)?;
+        Ok(())
+    }
+
+    /// Exponent is a little-endian 256-bit unsigned integer, it doesn't have to be reduced
+    pub fn fn_impl(base: &[u8], exp: &[u8]) -> Result<[u8; 32], ExitCode> {
+        let base = fr_from_bytes(base)?;
+        if exp.len() != 32 {
+            return Err(ExitCode::Bn254Error);
+        }
+        let mut limbs = [0u64; 4];
+        LittleEndian::read_u64_into(exp, &mut limbs);
+        Ok(base.pow_vartime(limbs).to_bytes())
+    }
+}
@@ -97,4 +97,18 @@ mod secp256k1_tests {
             assert_eq!(result, pk);
         }
     }
+
+    /// The handler turns these errors into traps, contracts have to check signatures before the
+    /// call, like `recover_signer` of the SDK does.
+    #[test]
+    fn malformed_signatures() {
+        let digest = Sha256::new_with_prefix(b"example message").finalize();
+        let mut sig = RECOVERY_TEST_VECTORS[0].sig;
+        // `r` is zero
+        sig[..32].fill(0);
+        assert!(SyscallEcrecover::fn_impl(&digest, &sig, 0).is_err());
+        // `r` isn't an x-coordinate of a point on the curve
+        sig[31] = 5;
+        assert!(SyscallEcrecover::fn_impl(&digest, &sig, 0).is_err());
+    }
 }
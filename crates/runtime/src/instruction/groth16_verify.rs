@@ -0,0 +1,201 @@
+use crate::{
+    instruction::bn254::{fr_from_be_bytes, g1_from_be_bytes, g2_from_be_bytes},
+    RuntimeContext,
+};
+use fluentbase_types::{
+    groth16_vk_size,
+    ExitCode,
+    IJournaledTrie,
+    GROTH16_G1_SIZE,
+    GROTH16_G2_SIZE,
+    GROTH16_PROOF_SIZE,
+};
+use halo2curves::{
+    bn256::{Bn256, G1Affine, G2Prepared, G1},
+    group::Group,
+    pairing::{MillerLoopResult, MultiMillerLoop},
+};
+use rwasm::{core::Trap, Caller};
+
+pub struct SyscallGroth16Verify;
+
+impl SyscallGroth16Verify {
+    pub fn fn_handler<DB: IJournaledTrie>(
+        caller: Caller<'_, RuntimeContext<DB>>,
+        vk_offset: u32,
+        vk_len: u32,
+        proof_offset: u32,
+        inputs_offset: u32,
+        inputs_len: u32,
+    ) -> Result<i32, Trap> {
+        // malformed parameters are returned as an error code, so precompiles can reject them
+        // instead of trapping
+        let result = Self::fn_impl(
+            caller.read_memory(vk_offset, vk_len)?,
+            caller.read_memory(proof_offset, GROTH16_PROOF_SIZE as u32)?,
+            caller.read_memory(inputs_offset, inputs_len)?,
+        );
+        Ok(match result {
+            Ok(is_valid) => is_valid as i32,
+            Err(err) => err.into_i32(),
+        })
+    }
+
+    /// Verifies Groth16 proof over BN254, all points and scalars are big-endian and encoded
+    /// the same way as for EVM's `ecAdd`, `ecMul` and `ecPairing` precompiles.
+    ///
+    /// Returns an error if any of the parameters is malformed (wrong length, non-canonical field
+    /// element, point isn't on the curve or not in the right subgroup), otherwise result of
+    /// the verification.
+    pub fn fn_impl(vk: &[u8], proof: &[u8], inputs: &[u8]) -> Result<bool, ExitCode> {
+        if proof.len() != GROTH16_PROOF_SIZE || inputs.len() % 32 != 0 {
+            return Err(ExitCode::Bn254Error);
+        }
+        let num_inputs = inputs.len() / 32;
+        if vk.len() != groth16_vk_size(num_inputs) {
+            return Err(ExitCode::Bn254Error);
+        }
+        // decode verification key
+        let (alpha, vk) = vk.split_at(GROTH16_G1_SIZE);
+        let (beta, vk) = vk.split_at(GROTH16_G2_SIZE);
+        let (gamma, vk) = vk.split_at(GROTH16_G2_SIZE);
+        let (delta, ic) = vk.split_at(GROTH16_G2_SIZE);
+        let alpha = g1_from_be_bytes(alpha)?;
+        let beta = g2_from_be_bytes(beta)?;
+        let gamma = g2_from_be_bytes(gamma)?;
+        let delta = g2_from_be_bytes(delta)?;
+        // decode proof
+        let a = g1_from_be_bytes(&proof[0..64])?;
+        let b = g2_from_be_bytes(&proof[64..192])?;
+        let c = g1_from_be_bytes(&proof[192..256])?;
+        // compute linear combination of public inputs `L = IC[0] + sum(x[i] * IC[i + 1])`
+        let mut ic = ic.chunks(GROTH16_G1_SIZE);
+        let mut acc = G1::from(g1_from_be_bytes(ic.next().unwrap())?);
+        for (input, ic) in inputs.chunks(32).zip(ic) {
+            acc += g1_from_be_bytes(ic)? * fr_from_be_bytes(input)?;
+        }
+        let acc = G1Affine::from(acc);
+        // check `e(A, B) * e(-alpha, beta) * e(-L, gamma) * e(-C, delta) == 1`
+        let result = Bn256::multi_miller_loop(&[
+            (&a, &G2Prepared::from(b)),
+            (&-alpha, &G2Prepared::from(beta)),
+            (&-acc, &G2Prepared::from(gamma)),
+            (&-c, &G2Prepared::from(delta)),
+        ])
+        .final_exponentiation();
+        Ok(bool::from(result.is_identity()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2curves::{
+        bn256::{Fr, G2Affine},
+        group::{ff::Field, Curve},
+    };
+
+    fn encode_g1(point: G1) -> Vec<u8> {
+        let point = point.to_affine();
+        let mut result = vec![];
+        for mut coord in [point.x.to_bytes(), point.y.to_bytes()] {
+            coord.reverse();
+            result.extend(coord);
+        }
+        result
+    }
+
+    fn encode_g2(point: G2Affine) -> Vec<u8> {
+        let mut result = vec![];
+        for mut coord in [
+            point.x.c1.to_bytes(),
+            point.x.c0.to_bytes(),
+            point.y.c1.to_bytes(),
+            point.y.c0.to_bytes(),
+        ] {
+            coord.reverse();
+            result.extend(coord);
+        }
+        result
+    }
+
+    fn encode_fr(value: Fr) -> Vec<u8> {
+        let mut result = value.to_bytes();
+        result.reverse();
+        result.to_vec()
+    }
+
+    /// Builds a verification key and a proof with known discrete logs, so the pairing equation
+    /// `a*b = alpha*beta + l*gamma + c*delta` holds for the given inputs.
+    fn make_fixture(inputs: &[Fr]) -> (Vec<u8>, Vec<u8>) {
+        let g1 = G1::generator();
+        let g2 = halo2curves::bn256::G2::generator();
+        let (alpha, beta, gamma, delta) = (Fr::from(3), Fr::from(5), Fr::from(7), Fr::from(11));
+        let ic = (0..=inputs.len())
+            .map(|i| Fr::from(13 + i as u64))
+            .collect::<Vec<_>>();
+        let l = inputs
+            .iter()
+            .zip(ic.iter().skip(1))
+            .fold(ic[0], |acc, (x, ic)| acc + x * ic);
+        let (b, c) = (Fr::from(17), Fr::from(19));
+        let a = (alpha * beta + l * gamma + c * delta) * b.invert().unwrap();
+        let mut vk = encode_g1(g1 * alpha);
+        for scalar in [beta, gamma, delta] {
+            vk.extend(encode_g2((g2 * scalar).to_affine()));
+        }
+        for scalar in ic {
+            vk.extend(encode_g1(g1 * scalar));
+        }
+        let mut proof = encode_g1(g1 * a);
+        proof.extend(encode_g2((g2 * b).to_affine()));
+        proof.extend(encode_g1(g1 * c));
+        (vk, proof)
+    }
+
+    #[test]
+    fn test_valid_proof() {
+        let inputs = [Fr::from(100), Fr::from(200)];
+        let (vk, proof) = make_fixture(&inputs);
+        let encoded_inputs = inputs
+            .iter()
+            .flat_map(|x| encode_fr(*x))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            SyscallGroth16Verify::fn_impl(&vk, &proof, &encoded_inputs),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_wrong_public_input() {
+        let (vk, proof) = make_fixture(&[Fr::from(100)]);
+        assert_eq!(
+            SyscallGroth16Verify::fn_impl(&vk, &proof, &encode_fr(Fr::from(101))),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let (vk, proof) = make_fixture(&[Fr::from(100)]);
+        let inputs = encode_fr(Fr::from(100));
+        // wrong number of public inputs
+        assert_eq!(
+            SyscallGroth16Verify::fn_impl(&vk, &proof, &[]),
+            Err(ExitCode::Bn254Error)
+        );
+        // point isn't on the curve
+        let mut bad_proof = proof.clone();
+        bad_proof[63] ^= 1;
+        assert_eq!(
+            SyscallGroth16Verify::fn_impl(&vk, &bad_proof, &inputs),
+            Err(ExitCode::Bn254Error)
+        );
+        // public input isn't reduced
+        assert_eq!(
+            SyscallGroth16Verify::fn_impl(&vk, &proof, &[0xff; 32]),
+            Err(ExitCode::Bn254Error)
+        );
+    }
+}
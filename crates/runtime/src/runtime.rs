@@ -215,6 +215,10 @@ impl<DB: IJournaledTrie> RuntimeContext<DB> {
         self.state
     }
 
+    pub fn change_return_data(&mut self, return_data: Vec<u8>) {
+        self.execution_result.return_data = return_data;
+    }
+
     pub fn clean_output(&mut self) {
         self.execution_result.output = vec![];
     }
@@ -1,6 +1,7 @@
 use crate::{runtime::Runtime, DefaultEmptyRuntimeDatabase, RuntimeContext};
 use fluentbase_types::{
     create_sovereign_import_linker,
+    ExitCode,
     SysFuncIdx::STATE,
     STATE_DEPLOY,
     STATE_MAIN,
@@ -127,3 +128,33 @@ fn test_keccak256() {
         execution_result.output.as_slice()
     );
 }
+
+#[test]
+fn test_ecrecover_traps_on_unrecoverable_signature() {
+    // `r` is 5, it isn't an x-coordinate of a point on the curve, `s` is 1
+    let rwasm_binary = wat2rwasm(
+        r#"
+(module
+  (type (;0;) (func (param i32 i32 i32 i32)))
+  (type (;1;) (func))
+  (import "fluentbase_v1preview" "_ecrecover" (func $_ecrecover (type 0)))
+  (func $main (type 1)
+    i32.const 0
+    i32.const 32
+    i32.const 96
+    i32.const 0
+    call $_ecrecover
+    )
+  (memory (;0;) 1)
+  (data (;0;) (i32.const 32) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\05")
+  (data (;1;) (i32.const 64) "\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\01")
+  (export "main" (func $main)))
+    "#,
+    );
+    let ctx = RuntimeContext::new(rwasm_binary).with_fuel_limit(1_000_000);
+    let execution_result = Runtime::<DefaultEmptyRuntimeDatabase>::run_with_context(ctx).unwrap();
+    assert_eq!(
+        execution_result.exit_code,
+        ExitCode::EcrecoverError.into_i32()
+    );
+}
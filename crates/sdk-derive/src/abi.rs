@@ -0,0 +1,526 @@
+use crate::{result_types, rust_type_to_sol};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::{env, fs, path::PathBuf};
+use syn::{FnArg, ImplItemFn, ReturnType, Type};
+
+/// Environment variable with a directory to write ABI files into, nothing is written if it's
+/// not set.
+const ABI_DIR_ENV: &str = "FLUENTBASE_ABI_DIR";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AbiParam {
+    pub name: String,
+    /// Canonical type the selector is computed from, structs are written as tuples.
+    pub ty: String,
+    pub indexed: bool,
+    /// Components of a tuple or a struct type.
+    pub components: Vec<AbiParam>,
+}
+
+/// A struct declared in a `sol!` block with its fields.
+type SolStruct = (String, Vec<AbiParam>);
+
+impl AbiParam {
+    fn new(name: String, ty: &str, indexed: bool, structs: &[SolStruct]) -> Self {
+        let (ty, components) = expand_type(ty, structs, &mut Vec::new());
+        Self {
+            name,
+            ty,
+            indexed,
+            components,
+        }
+    }
+}
+
+/// An entry of the Solidity JSON ABI.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AbiEntry {
+    Function {
+        name: String,
+        inputs: Vec<AbiParam>,
+        outputs: Vec<AbiParam>,
+        state_mutability: &'static str,
+    },
+    Constructor {
+        inputs: Vec<AbiParam>,
+        state_mutability: &'static str,
+    },
+    Fallback {
+        state_mutability: &'static str,
+    },
+    Receive,
+    Event {
+        name: String,
+        inputs: Vec<AbiParam>,
+        anonymous: bool,
+    },
+    Error {
+        name: String,
+        inputs: Vec<AbiParam>,
+    },
+}
+
+impl AbiEntry {
+    pub(crate) fn function(func: &ImplItemFn, name: &str, state_mutability: &'static str) -> Self {
+        let output = match (&func.sig.output, result_types(&func.sig.output)) {
+            (_, Some((ok, _))) => Some(ok),
+            (ReturnType::Type(_, ty), None) => Some(&**ty),
+            (ReturnType::Default, None) => None,
+        };
+        let outputs = match output {
+            Some(Type::Tuple(tuple)) if tuple.elems.is_empty() => Vec::new(),
+            Some(ty) => vec![AbiParam::new(String::new(), &sol_type_name(ty), false, &[])],
+            None => Vec::new(),
+        };
+        AbiEntry::Function {
+            name: name.to_string(),
+            inputs: fn_inputs(func),
+            outputs,
+            state_mutability,
+        }
+    }
+
+    pub(crate) fn constructor(func: &ImplItemFn, state_mutability: &'static str) -> Self {
+        AbiEntry::Constructor {
+            inputs: fn_inputs(func),
+            state_mutability,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        match self {
+            AbiEntry::Function {
+                name,
+                inputs,
+                outputs,
+                state_mutability,
+            } => format!(
+                "{{\"type\":\"function\",\"name\":\"{}\",\"inputs\":{},\"outputs\":{},\"stateMutability\":\"{}\"}}",
+                name,
+                params_json(inputs, false),
+                params_json(outputs, false),
+                state_mutability
+            ),
+            AbiEntry::Constructor {
+                inputs,
+                state_mutability,
+            } => format!(
+                "{{\"type\":\"constructor\",\"inputs\":{},\"stateMutability\":\"{}\"}}",
+                params_json(inputs, false),
+                state_mutability
+            ),
+            AbiEntry::Fallback { state_mutability } => format!(
+                "{{\"type\":\"fallback\",\"stateMutability\":\"{}\"}}",
+                state_mutability
+            ),
+            AbiEntry::Receive => {
+                "{\"type\":\"receive\",\"stateMutability\":\"payable\"}".to_string()
+            }
+            AbiEntry::Event {
+                name,
+                inputs,
+                anonymous,
+            } => format!(
+                "{{\"type\":\"event\",\"name\":\"{}\",\"inputs\":{},\"anonymous\":{}}}",
+                name,
+                params_json(inputs, true),
+                anonymous
+            ),
+            AbiEntry::Error { name, inputs } => format!(
+                "{{\"type\":\"error\",\"name\":\"{}\",\"inputs\":{}}}",
+                name,
+                params_json(inputs, false)
+            ),
+        }
+    }
+}
+
+/// Renders entries as a JSON ABI, one entry per line.
+pub(crate) fn abi_json(entries: &[AbiEntry]) -> String {
+    let entries: Vec<_> = entries
+        .iter()
+        .map(|entry| format!("  {}", entry.to_json()))
+        .collect();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Writes the ABI of a contract into `{crate}/{name}.json` of `FLUENTBASE_ABI_DIR` if it's set,
+/// the file is left untouched if it's up to date.
+pub(crate) fn write_abi_file(name: &str, json: &str) -> Result<(), String> {
+    let Some(dir) = env::var_os(ABI_DIR_ENV) else {
+        return Ok(());
+    };
+    // contracts with the same name in different crates don't overwrite each other
+    let crate_name = env::var("CARGO_CRATE_NAME").unwrap_or_default();
+    let dir = PathBuf::from(dir).join(crate_name);
+    let path = dir.join(format!("{}.json", name));
+    if fs::read_to_string(&path).is_ok_and(|current| current == json) {
+        return Ok(());
+    }
+    fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, json))
+        .map_err(|err| format!("can't write ABI into {}: {}", path.display(), err))
+}
+
+/// Collects events and errors declared in Solidity syntax, parameters of struct types declared
+/// in any of the blocks are written as tuples, other declarations are skipped.
+pub(crate) fn parse_sol_declarations(declarations: &[TokenStream]) -> Vec<AbiEntry> {
+    let tokens: Vec<_> = declarations.iter().cloned().flatten().collect();
+    let mut structs = Vec::new();
+    let mut declared = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let (keyword, name, params) = match &tokens[i..] {
+            [TokenTree::Ident(keyword), TokenTree::Ident(name), TokenTree::Group(params), ..]
+                if matches!(
+                    params.delimiter(),
+                    Delimiter::Parenthesis | Delimiter::Brace
+                ) =>
+            {
+                (keyword.to_string(), name.to_string(), params)
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        i += 3;
+        match (keyword.as_str(), params.delimiter()) {
+            ("struct", Delimiter::Brace) => {
+                structs.push((name, parse_sol_params(params.stream(), ';')));
+            }
+            ("event", Delimiter::Parenthesis) => {
+                let anonymous = matches!(
+                    tokens.get(i),
+                    Some(TokenTree::Ident(ident)) if ident == "anonymous"
+                );
+                declared.push((
+                    true,
+                    name,
+                    parse_sol_params(params.stream(), ','),
+                    anonymous,
+                ));
+            }
+            ("error", Delimiter::Parenthesis) => {
+                declared.push((false, name, parse_sol_params(params.stream(), ','), false));
+            }
+            _ => {}
+        }
+    }
+    declared
+        .into_iter()
+        .map(|(is_event, name, params, anonymous)| {
+            let inputs = params
+                .into_iter()
+                .map(|param| AbiParam::new(param.name, &param.ty, param.indexed, &structs))
+                .collect();
+            if is_event {
+                AbiEntry::Event {
+                    name,
+                    inputs,
+                    anonymous,
+                }
+            } else {
+                AbiEntry::Error { name, inputs }
+            }
+        })
+        .collect()
+}
+
+/// Parses parameters or struct fields separated by `separator`, their types are left as written.
+fn parse_sol_params(tokens: TokenStream, separator: char) -> Vec<AbiParam> {
+    let mut params = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == separator => params.push(Vec::new()),
+            token => params.last_mut().unwrap().push(token),
+        }
+    }
+    params
+        .into_iter()
+        .filter(|tokens| !tokens.is_empty())
+        .map(|mut tokens| {
+            let mut indexed = false;
+            tokens.retain(|token| match token {
+                TokenTree::Ident(ident) if ident == "indexed" => {
+                    indexed = true;
+                    false
+                }
+                TokenTree::Ident(ident) => ident != "memory" && ident != "calldata",
+                _ => true,
+            });
+            let name = match tokens.as_slice() {
+                [.., _, TokenTree::Ident(name)] => {
+                    let name = name.to_string();
+                    tokens.pop();
+                    name
+                }
+                _ => String::new(),
+            };
+            AbiParam {
+                name,
+                ty: canonical_type(&tokens.into_iter().collect::<TokenStream>()),
+                indexed,
+                components: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn fn_inputs(func: &ImplItemFn) -> Vec<AbiParam> {
+    func.sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) => Some(AbiParam::new(
+                    pat_ident.ident.to_string(),
+                    &sol_type_name(&pat_type.ty),
+                    false,
+                    &[],
+                )),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn sol_type_name(ty: &Type) -> String {
+    canonical_type(&rust_type_to_sol(ty))
+}
+
+/// Solidity type without whitespaces and with `uint`/`int` aliases expanded.
+fn canonical_type(tokens: &TokenStream) -> String {
+    let mut ty = String::new();
+    for token in tokens.clone() {
+        match token {
+            TokenTree::Ident(ident) if ident == "uint" => ty.push_str("uint256"),
+            TokenTree::Ident(ident) if ident == "int" => ty.push_str("int256"),
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                ty.push_str(open);
+                ty.push_str(&canonical_type(&group.stream()));
+                ty.push_str(close);
+            }
+            token => ty.push_str(&token.to_string()),
+        }
+    }
+    ty
+}
+
+fn params_json(params: &[AbiParam], with_indexed: bool) -> String {
+    let params: Vec<_> = params
+        .iter()
+        .map(|param| {
+            let indexed = if with_indexed {
+                format!(",\"indexed\":{}", param.indexed)
+            } else {
+                String::new()
+            };
+            format!(
+                "{{\"name\":\"{}\",{}{}}}",
+                param.name,
+                type_json(param),
+                indexed
+            )
+        })
+        .collect();
+    format!("[{}]", params.join(","))
+}
+
+/// Tuples and structs are described with `tuple` and their components, like `(uint8,bool)[]`
+/// becomes `tuple[]` with two components.
+fn type_json(param: &AbiParam) -> String {
+    let Some(inner) = param.ty.strip_prefix('(') else {
+        return format!("\"type\":\"{}\"", param.ty);
+    };
+    let (_, suffix) = split_tuple(inner);
+    format!(
+        "\"type\":\"tuple{}\",\"components\":{}",
+        suffix,
+        params_json(&param.components, false)
+    )
+}
+
+/// Writes struct types as tuples and collects components of tuples, struct fields keep their
+/// names. `parents` are the structs being expanded, a struct can't contain itself.
+fn expand_type(
+    ty: &str,
+    structs: &[SolStruct],
+    parents: &mut Vec<String>,
+) -> (String, Vec<AbiParam>) {
+    let (fields, suffix): (Vec<(String, &str)>, &str) = match ty.strip_prefix('(') {
+        Some(inner) => {
+            let (components, suffix) = split_tuple(inner);
+            let components = components
+                .into_iter()
+                .map(|component| (String::new(), component))
+                .collect();
+            (components, suffix)
+        }
+        None => {
+            let (base, suffix) = ty.split_at(ty.find('[').unwrap_or(ty.len()));
+            match structs.iter().find(|(name, _)| name == base) {
+                Some((name, fields)) if !parents.contains(name) => {
+                    parents.push(name.clone());
+                    let fields = fields
+                        .iter()
+                        .map(|field| (field.name.clone(), field.ty.as_str()))
+                        .collect();
+                    let result = expand_fields(fields, suffix, structs, parents);
+                    parents.pop();
+                    return result;
+                }
+                _ => return (ty.to_string(), Vec::new()),
+            }
+        }
+    };
+    expand_fields(fields, suffix, structs, parents)
+}
+
+fn expand_fields(
+    fields: Vec<(String, &str)>,
+    suffix: &str,
+    structs: &[SolStruct],
+    parents: &mut Vec<String>,
+) -> (String, Vec<AbiParam>) {
+    let components: Vec<_> = fields
+        .into_iter()
+        .map(|(name, ty)| {
+            let (ty, components) = expand_type(ty, structs, parents);
+            AbiParam {
+                name,
+                ty,
+                indexed: false,
+                components,
+            }
+        })
+        .collect();
+    let types: Vec<_> = components
+        .iter()
+        .map(|component| component.ty.as_str())
+        .collect();
+    (format!("({}){}", types.join(","), suffix), components)
+}
+
+/// Splits the inside of a tuple type (without the opening parenthesis) into component types and
+/// the array suffix after the closing parenthesis.
+fn split_tuple(inner: &str) -> (Vec<&str>, &str) {
+    let mut depth = 0;
+    let mut components = Vec::new();
+    let mut start = 0;
+    let mut end = inner.len();
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                end = i;
+                break;
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                components.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < end {
+        components.push(&inner[start..end]);
+    }
+    (components, &inner[(end + 1).min(inner.len())..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_parse_sol_declarations() {
+        let entries = parse_sol_declarations(&[quote! {
+            event Transfer(address indexed from, address indexed to, uint amount);
+            event Ping() anonymous;
+            error InsufficientBalance(uint256 available, uint256 required);
+            struct Ignored { uint8 value; }
+            error Unnamed(bytes32[2], (uint8, bool)[] pairs);
+        }]);
+        assert_eq!(
+            abi_json(&entries),
+            concat!(
+                "[\n",
+                "  {\"type\":\"event\",\"name\":\"Transfer\",\"inputs\":[{\"name\":\"from\",\"type\":\"address\",\"indexed\":true},{\"name\":\"to\",\"type\":\"address\",\"indexed\":true},{\"name\":\"amount\",\"type\":\"uint256\",\"indexed\":false}],\"anonymous\":false},\n",
+                "  {\"type\":\"event\",\"name\":\"Ping\",\"inputs\":[],\"anonymous\":true},\n",
+                "  {\"type\":\"error\",\"name\":\"InsufficientBalance\",\"inputs\":[{\"name\":\"available\",\"type\":\"uint256\"},{\"name\":\"required\",\"type\":\"uint256\"}]},\n",
+                "  {\"type\":\"error\",\"name\":\"Unnamed\",\"inputs\":[{\"name\":\"\",\"type\":\"bytes32[2]\"},{\"name\":\"pairs\",\"type\":\"tuple[]\",\"components\":[{\"name\":\"\",\"type\":\"uint8\"},{\"name\":\"\",\"type\":\"bool\"}]}]}\n",
+                "]\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_sol_struct_params() {
+        // structs can be declared in another block and after they're used
+        let entries = parse_sol_declarations(&[
+            quote! {
+                event OrderFilled(Order order, Order[] indexed batch);
+            },
+            quote! {
+                struct Order { address maker; Amount amount; }
+                struct Amount { uint value; bytes32 token; }
+            },
+        ]);
+        assert_eq!(
+            abi_json(&entries),
+            concat!(
+                "[\n",
+                "  {\"type\":\"event\",\"name\":\"OrderFilled\",\"inputs\":[",
+                "{\"name\":\"order\",\"type\":\"tuple\",\"components\":[{\"name\":\"maker\",\"type\":\"address\"},{\"name\":\"amount\",\"type\":\"tuple\",\"components\":[{\"name\":\"value\",\"type\":\"uint256\"},{\"name\":\"token\",\"type\":\"bytes32\"}]}],\"indexed\":false},",
+                "{\"name\":\"batch\",\"type\":\"tuple[]\",\"components\":[{\"name\":\"maker\",\"type\":\"address\"},{\"name\":\"amount\",\"type\":\"tuple\",\"components\":[{\"name\":\"value\",\"type\":\"uint256\"},{\"name\":\"token\",\"type\":\"bytes32\"}]}],\"indexed\":true}",
+                "],\"anonymous\":false}\n",
+                "]\n",
+            )
+        );
+        let AbiEntry::Event { inputs, .. } = &entries[0] else {
+            unreachable!()
+        };
+        // the canonical type is what the event signature is computed from
+        assert_eq!(inputs[1].ty, "(address,(uint256,bytes32))[]");
+    }
+
+    #[test]
+    fn test_function_entries() {
+        let func: ImplItemFn = parse_quote! {
+            pub fn balance_of(&self, owner: Address) -> U256 {
+                U256::ZERO
+            }
+        };
+        assert_eq!(
+            AbiEntry::function(&func, "balanceOf", "view").to_json(),
+            "{\"type\":\"function\",\"name\":\"balanceOf\",\"inputs\":[{\"name\":\"owner\",\"type\":\"address\"}],\"outputs\":[{\"name\":\"\",\"type\":\"uint256\"}],\"stateMutability\":\"view\"}"
+        );
+        let func: ImplItemFn = parse_quote! {
+            pub fn withdraw(&self, amounts: Vec<u128>) -> Result<(), InsufficientBalance> {
+                Ok(())
+            }
+        };
+        assert_eq!(
+            AbiEntry::function(&func, "withdraw", "nonpayable").to_json(),
+            "{\"type\":\"function\",\"name\":\"withdraw\",\"inputs\":[{\"name\":\"amounts\",\"type\":\"uint128[]\"}],\"outputs\":[],\"stateMutability\":\"nonpayable\"}"
+        );
+        let func: ImplItemFn = parse_quote! {
+            fn constructor(&self, pair: (u8, String)) {}
+        };
+        assert_eq!(
+            AbiEntry::constructor(&func, "payable").to_json(),
+            "{\"type\":\"constructor\",\"inputs\":[{\"name\":\"pair\",\"type\":\"tuple\",\"components\":[{\"name\":\"\",\"type\":\"uint8\"},{\"name\":\"\",\"type\":\"string\"}]}],\"stateMutability\":\"payable\"}"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, LitStr};
+
+fn sdk_crate() -> TokenStream {
+    if std::env::var("CARGO_PKG_NAME").unwrap_or_default() == "fluentbase-sdk" {
+        quote! { crate }
+    } else {
+        quote! { fluentbase_sdk }
+    }
+}
+
+/// Takes the name out of `#[eip712(name = "...")]`.
+fn eip712_name(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("eip712")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported eip712 attribute, expected `name`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+/// Implements `Eip712Type` and `Eip712` for a struct with named fields, members of the struct
+/// type are the fields in the order they are declared.
+pub(crate) fn expand_eip712(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let sdk = sdk_crate();
+    let Data::Struct(data) = &ast.data else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "Eip712 can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "Eip712 can only be derived for structs with named fields",
+        ));
+    };
+    let ident = &ast.ident;
+    let type_name = eip712_name(&ast.attrs)?.unwrap_or_else(|| ident.to_string());
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut member_formats = Vec::new();
+    let mut member_types = Vec::new();
+    let mut field_idents = Vec::new();
+    for field in &fields.named {
+        let field_ident = field.ident.as_ref().unwrap();
+        let name = eip712_name(&field.attrs)?.unwrap_or_else(|| field_ident.to_string());
+        member_formats.push(format!("{{}} {}", name));
+        member_types.push(&field.ty);
+        field_idents.push(field_ident);
+    }
+    let format = format!("{}({})", type_name, member_formats.join(","));
+    let data_len = field_idents.len() * 32;
+
+    Ok(quote! {
+        impl #impl_generics #sdk::Eip712Type for #ident #ty_generics #where_clause {
+            fn type_name() -> #sdk::alloc::string::String {
+                #type_name.into()
+            }
+
+            fn encode_value(&self) -> #sdk::B256 {
+                #sdk::Eip712::struct_hash(self)
+            }
+
+            fn add_struct_types(
+                types: &mut #sdk::alloc::collections::BTreeMap<
+                    #sdk::alloc::string::String,
+                    #sdk::alloc::string::String,
+                >,
+            ) {
+                // a type is added before its members, so recursive types don't loop
+                if types.contains_key(#type_name) {
+                    return;
+                }
+                types.insert(
+                    #type_name.into(),
+                    #sdk::alloc::format!(
+                        #format,
+                        #(<#member_types as #sdk::Eip712Type>::type_name()),*
+                    ),
+                );
+                #(<#member_types as #sdk::Eip712Type>::add_struct_types(types);)*
+            }
+        }
+
+        impl #impl_generics #sdk::Eip712 for #ident #ty_generics #where_clause {
+            fn encode_data(&self) -> #sdk::alloc::vec::Vec<u8> {
+                let mut data = #sdk::alloc::vec::Vec::with_capacity(#data_len);
+                #(data.extend_from_slice(
+                    #sdk::Eip712Type::encode_value(&self.#field_idents).as_slice()
+                );)*
+                data
+            }
+        }
+    })
+}
@@ -1,3 +1,4 @@
+use crate::abi::{abi_json, fn_inputs, parse_sol_declarations, write_abi_file, AbiEntry};
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
@@ -11,8 +12,10 @@ use syn::{
     FnArg,
     Ident,
     ImplItem,
+    ImplItemFn,
     ItemImpl,
     Lit,
+    LitStr,
     Meta,
     Token,
     Type,
@@ -20,14 +23,12 @@ use syn::{
     Visibility,
 };
 
+mod abi;
+mod eip712;
+
 #[proc_macro]
 pub fn derive_keccak256_id(token: TokenStream) -> TokenStream {
-    use crypto_hashes::{digest::Digest, sha3::Keccak256};
-    let mut hash = Keccak256::new();
-    hash.update(token.to_string());
-    let mut dst = [0u8; 4];
-    dst.copy_from_slice(hash.finalize().as_slice()[0..4].as_ref());
-    let method_id: u32 = u32::from_be_bytes(dst);
+    let method_id: u32 = u32::from_be_bytes(keccak256_selector(&token.to_string()));
     TokenStream::from(quote! {
         #method_id
     })
@@ -70,23 +71,72 @@ impl Parse for SolidityRouterInput {
 #[proc_macro_attribute]
 pub fn derive_solidity_router(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(attr as SolidityRouterInput);
-    let ast: ItemImpl = parse_macro_input!(item as ItemImpl);
+    let mut ast: ItemImpl = parse_macro_input!(item as ItemImpl);
 
+    let declarations = take_sol_declarations(&mut ast);
+    let function_ids = match take_function_ids(&mut ast) {
+        Ok(function_ids) => function_ids,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let struct_name = &ast.self_ty;
 
-    let router_match_arms: Vec<_> = ast
+    let methods: Vec<_> = ast
         .items
         .iter()
-        .filter_map(expand_router_match_arm)
+        .filter(|item| matches!(item, ImplItem::Fn(_)))
         .collect();
 
-    let methods: Vec<_> = ast
-        .items
+    let public_methods: Vec<_> = methods
         .iter()
-        .filter(|item| matches!(item, ImplItem::Fn(_)))
+        .filter_map(|item| match item {
+            ImplItem::Fn(func) if matches!(func.vis, Visibility::Public(_)) => Some(func),
+            _ => None,
+        })
         .collect();
+    let sol_functions = match parse_sol_functions(&public_methods, &function_ids) {
+        Ok(sol_functions) => sol_functions,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
-    let expanded = expand_solidity_router(struct_name, router_match_arms, methods, input.with_main);
+    let router_match_arms: Vec<_> = public_methods
+        .iter()
+        .zip(&sol_functions)
+        .map(|(func, sol_function)| expand_router_match_arm(func, sol_function))
+        .collect();
+
+    let sol_signatures: Vec<_> = public_methods
+        .iter()
+        .zip(&sol_functions)
+        .map(|(func, sol_function)| expand_sol_function(func, &sol_function.name, quote! {}))
+        .collect();
+
+    let mut abi_entries: Vec<_> = public_methods
+        .iter()
+        .zip(&sol_functions)
+        // `sol!` signatures of the router have no state mutability, so they're nonpayable
+        .map(|(func, sol_function)| {
+            AbiEntry::function(func, &sol_function.name.to_string(), "nonpayable")
+        })
+        .collect();
+    abi_entries.extend(parse_sol_declarations(&declarations));
+    let abi = expand_abi(struct_name, &abi_entries, &sol_functions);
+
+    let client = expand_client(struct_name, &public_methods, &sol_functions);
+
+    let expanded = expand_solidity_router(
+        struct_name,
+        router_match_arms,
+        methods,
+        sol_signatures,
+        &declarations,
+        input.with_main,
+    );
+
+    let expanded = quote! {
+        #expanded
+        #abi
+        #client
+    };
 
     TokenStream::from(expanded)
 }
@@ -95,10 +145,12 @@ fn expand_solidity_router(
     struct_name: &Box<Type>,
     router_match_arms: Vec<proc_macro2::TokenStream>,
     methods: Vec<&ImplItem>,
+    sol_functions: Vec<proc_macro2::TokenStream>,
+    declarations: &[proc_macro2::TokenStream],
     with_main: bool,
 ) -> proc_macro2::TokenStream {
-    let router = expand_router(struct_name, router_match_arms, methods.clone());
-    let sol_signatures = expand_sol(methods);
+    let router = expand_router(struct_name, router_match_arms, methods);
+    let sol_signatures = expand_sol(sol_functions, declarations);
 
     let main_fn = if with_main {
         expand_main_fn(struct_name)
@@ -118,6 +170,8 @@ fn expand_router(
     router_match_arms: Vec<proc_macro2::TokenStream>,
     methods: Vec<&ImplItem>,
 ) -> proc_macro2::TokenStream {
+    let input_too_short = expand_revert_message("input too short, cannot extract selector");
+    let unknown_method = expand_revert_message("unknown method");
     quote! {
 
         impl #struct_name {
@@ -125,13 +179,13 @@ fn expand_router(
 
             pub fn route(&self, input: &alloc::Vec<u8>) -> alloc::Vec<u8> {
                 if input.len() < 4 {
-                    panic!("input too short, cannot extract selector");
+                    #input_too_short
                 }
                 let mut selector: [u8; 4] = [0; 4];
                 selector.copy_from_slice(&input[0..4]);
                 match selector {
                     #(#router_match_arms),*,
-                    _ => panic!("unknown method"),
+                    _ => #unknown_method,
                 }
             }
         }
@@ -153,56 +207,835 @@ fn expand_main_fn(struct_name: &Box<Type>) -> proc_macro2::TokenStream {
     }
 }
 
-fn expand_router_match_arm(item: &ImplItem) -> Option<proc_macro2::TokenStream> {
-    if let ImplItem::Fn(func) = item {
+/// Derives EIP-712 `type_hash` and `struct_hash` of a struct, names of the struct and its
+/// fields can be changed with `#[eip712(name = "...")]`.
+#[proc_macro_derive(Eip712, attributes(eip712))]
+pub fn derive_eip712(item: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(item as syn::DeriveInput);
+    match eip712::expand_eip712(&ast) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Turns an `impl` block into a contract with `deploy` and `main` entrypoints.
+///
+/// A method called `constructor` runs on deploy with its arguments ABI-decoded from the
+/// `constructor_args` custom section appended to the init code. Public methods are routed by their
+/// Solidity selectors, `#[view]` and `#[payable]` set their state mutability, and methods marked
+/// with `#[fallback]` and `#[receive]` handle unknown selectors and plain value transfers. Calls
+/// with value to anything that isn't payable are reverted, so are methods returning `Err` of
+/// `Result<T, E>` where `E` is a `sol!` custom error. A method can be exposed under another name or
+/// as an overload with `#[function_id("transfer(address,uint256,bytes)")]`, methods with colliding
+/// selectors are rejected at compile time.
+///
+/// Events and errors can be declared in a `sol!` block inside the `impl`, the JSON ABI of the
+/// contract with them is available as `ABI_JSON`, if `FLUENTBASE_ABI_DIR` is set it's also written
+/// into `{crate}/{Contract}.json` there at build time. Other contracts can call it with
+/// the generated `{Contract}Client`. Both are generated by `derive_solidity_router` as well.
+#[proc_macro_attribute]
+pub fn contract(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "contract doesn't take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let ast: ItemImpl = parse_macro_input!(item as ItemImpl);
+
+    match expand_contract(ast) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+struct ContractMethod {
+    func: ImplItemFn,
+    payable: bool,
+    view: bool,
+    function_id: Option<LitStr>,
+}
+
+impl ContractMethod {
+    fn mutability(&self) -> proc_macro2::TokenStream {
+        if self.payable {
+            quote! { payable }
+        } else if self.view {
+            quote! { view }
+        } else {
+            quote! {}
+        }
+    }
+
+    fn state_mutability(&self) -> &'static str {
+        if self.payable {
+            "payable"
+        } else if self.view {
+            "view"
+        } else {
+            "nonpayable"
+        }
+    }
+}
+
+#[derive(Default)]
+struct ContractMethods {
+    constructor: Option<ContractMethod>,
+    functions: Vec<ContractMethod>,
+    fallback: Option<ContractMethod>,
+    receive: Option<ContractMethod>,
+}
+
+/// Sorts methods of the contract out and strips the handler attributes off them.
+fn parse_contract_methods(ast: &mut ItemImpl) -> syn::Result<ContractMethods> {
+    let mut methods = ContractMethods::default();
+    for item in ast.items.iter_mut() {
+        let ImplItem::Fn(func) = item else {
+            continue;
+        };
+        let function_id = take_function_id(func)?;
+        let (mut payable, mut view, mut fallback, mut receive) = (false, false, false, false);
+        func.attrs.retain(|attr| {
+            let flag = match attr.path().get_ident().map(Ident::to_string).as_deref() {
+                Some("payable") => &mut payable,
+                Some("view") => &mut view,
+                Some("fallback") => &mut fallback,
+                Some("receive") => &mut receive,
+                _ => return true,
+            };
+            *flag = true;
+            false
+        });
+        let ident = &func.sig.ident;
+        if payable && view {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "method can't be both payable and view",
+            ));
+        }
+        let routed = !fallback && !receive && ident != "constructor";
+        if let (Some(function_id), false) = (&function_id, routed) {
+            return Err(syn::Error::new_spanned(
+                function_id,
+                "function_id is only allowed on routed methods",
+            ));
+        }
+        let method = ContractMethod {
+            func: func.clone(),
+            payable,
+            view,
+            function_id,
+        };
+        let slot = if fallback {
+            &mut methods.fallback
+        } else if receive {
+            if func.sig.inputs.len() != 1 || view {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "receive handler takes no arguments and can't be view",
+                ));
+            }
+            &mut methods.receive
+        } else if ident == "constructor" {
+            &mut methods.constructor
+        } else {
+            if let Visibility::Public(_) = func.vis {
+                methods.functions.push(method);
+            } else if let Some(function_id) = method.function_id {
+                return Err(syn::Error::new_spanned(
+                    function_id,
+                    "function_id is only allowed on routed methods",
+                ));
+            } else if payable || view {
+                // private methods aren't routed, the attribute would be ignored silently
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "payable and view are only allowed on public methods, constructor and handlers",
+                ));
+            }
+            continue;
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "duplicated contract handler",
+            ));
+        }
+        *slot = Some(method);
+    }
+    Ok(methods)
+}
+
+fn expand_contract(mut ast: ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    let declarations = take_sol_declarations(&mut ast);
+    let methods = parse_contract_methods(&mut ast)?;
+    let struct_name = &ast.self_ty;
+
+    let deploy_body = expand_contract_constructor(methods.constructor.as_ref());
+
+    let functions: Vec<_> = methods
+        .functions
+        .iter()
+        .map(|method| &method.func)
+        .collect();
+    let function_ids: Vec<_> = methods
+        .functions
+        .iter()
+        .map(|method| method.function_id.clone())
+        .collect();
+    let sol_functions = parse_sol_functions(&functions, &function_ids)?;
+
+    let router_match_arms: Vec<_> = methods
+        .functions
+        .iter()
+        .zip(&sol_functions)
+        .map(|(method, sol_function)| {
+            let method_name_call = &sol_function.call_name;
+            let payable_check = expand_payable_check(method);
+            let call = expand_router_call(&method.func, method_name_call);
+            quote! {
+                #method_name_call::SELECTOR => return {
+                    #payable_check
+                    #call
+                }
+            }
+        })
+        .collect();
+
+    // a receive handler takes plain value transfers, the rest goes to the fallback
+    let receive = methods.receive.as_ref().map(|method| {
+        let method_name = &method.func.sig.ident;
+        quote! {
+            if input.is_empty() {
+                self.#method_name();
+                return alloc::vec::Vec::new();
+            }
+        }
+    });
+    let fallback = match methods.fallback.as_ref() {
+        Some(method) => expand_contract_fallback(method),
+        None => expand_revert_message("unknown method"),
+    };
+
+    let sol_signatures: Vec<_> = methods
+        .functions
+        .iter()
+        .zip(&sol_functions)
+        .map(|(method, sol_function)| {
+            expand_sol_function(&method.func, &sol_function.name, method.mutability())
+        })
+        .collect();
+
+    let mut abi_entries = Vec::new();
+    if let Some(method) = methods.constructor.as_ref() {
+        abi_entries.push(AbiEntry::constructor(
+            &method.func,
+            method.state_mutability(),
+        ));
+    }
+    for (method, sol_function) in methods.functions.iter().zip(&sol_functions) {
+        abi_entries.push(AbiEntry::function(
+            &method.func,
+            &sol_function.name.to_string(),
+            method.state_mutability(),
+        ));
+    }
+    if let Some(method) = methods.fallback.as_ref() {
+        abi_entries.push(AbiEntry::Fallback {
+            state_mutability: method.state_mutability(),
+        });
+    }
+    if methods.receive.is_some() {
+        abi_entries.push(AbiEntry::Receive);
+    }
+    abi_entries.extend(parse_sol_declarations(&declarations));
+    let abi = expand_abi(struct_name, &abi_entries, &sol_functions);
+
+    let client = expand_client(struct_name, &functions, &sol_functions);
+
+    Ok(quote! {
+        #ast
+
+        impl #struct_name {
+            pub fn deploy(&mut self, input: &[u8]) {
+                #deploy_body
+            }
+
+            pub fn route(&mut self, input: &[u8]) -> alloc::vec::Vec<u8> {
+                #receive
+                if input.len() >= 4 {
+                    let mut selector: [u8; 4] = [0; 4];
+                    selector.copy_from_slice(&input[0..4]);
+                    match selector {
+                        #(#router_match_arms,)*
+                        _ => {}
+                    }
+                }
+                #fallback
+            }
+        }
+
+        sol! {
+            #(#sol_signatures)*
+            #(#declarations)*
+        }
+
+        #abi
+
+        #client
+
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        extern "C" fn deploy() {
+            let input = fluentbase_sdk::ExecutionContext::contract_input();
+            let mut contract = <#struct_name as Default>::default();
+            contract.deploy(input);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        #[no_mangle]
+        extern "C" fn main() {
+            use fluentbase_sdk::SharedAPI;
+            let input = fluentbase_sdk::ExecutionContext::contract_input();
+            let mut contract = <#struct_name as Default>::default();
+            let output = contract.route(input);
+            fluentbase_sdk::LowLevelSDK::write(&output);
+        }
+    })
+}
+
+fn expand_contract_constructor(constructor: Option<&ContractMethod>) -> proc_macro2::TokenStream {
+    let Some(method) = constructor else {
+        // the same as the default constructor in Solidity, it's not payable
+        let value_revert = expand_revert_message("constructor is not payable");
+        return quote! {
+            let _ = input;
+            if !fluentbase_sdk::ContextReader::contract_value(&fluentbase_sdk::ExecutionContext::DEFAULT).is_zero() {
+                #value_revert;
+            }
+        };
+    };
+    let method_name = &method.func.sig.ident;
+    let payable_check = expand_payable_check(method);
+    let param_names = parse_param_names(&method.func.sig.inputs);
+    let param_types: Vec<_> = method
+        .func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(&pat_type.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    if param_names.is_empty() {
+        return quote! {
+            let _ = input;
+            #payable_check
+            self.#method_name();
+        };
+    }
+    let input_revert = expand_revert_message(INPUT_DECODE_ERROR);
+    quote! {
+        #payable_check
+        let (#(#param_names,)*) = match <(#(#param_types,)*) as alloy_sol_types::SolValue>::abi_decode_params(input, true) {
+            Ok(decoded) => decoded,
+            Err(_) => #input_revert,
+        };
+        self.#method_name(#(#param_names),*);
+    }
+}
+
+fn expand_contract_fallback(method: &ContractMethod) -> proc_macro2::TokenStream {
+    let method_name = &method.func.sig.ident;
+    let payable_check = expand_payable_check(method);
+    // the fallback may take the raw input and return raw output, like in Solidity
+    let args = if method.func.sig.inputs.len() > 1 {
+        quote! { input }
+    } else {
+        quote! {}
+    };
+    match &method.func.sig.output {
+        syn::ReturnType::Type(_, ty) if !is_unit_type(ty) => quote! {
+            #payable_check
+            let output = self.#method_name(#args);
+            AsRef::<[u8]>::as_ref(&output).to_vec()
+        },
+        _ => quote! {
+            #payable_check
+            self.#method_name(#args);
+            alloc::vec::Vec::new()
+        },
+    }
+}
+
+fn expand_payable_check(method: &ContractMethod) -> proc_macro2::TokenStream {
+    if method.payable {
+        return quote! {};
+    }
+    let value_revert = expand_revert_message(&format!("{} is not payable", method.func.sig.ident));
+    quote! {
+        if !fluentbase_sdk::ContextReader::contract_value(&fluentbase_sdk::ExecutionContext::DEFAULT).is_zero() {
+            #value_revert;
+        }
+    }
+}
+
+/// Takes `sol!` blocks with events and errors of the contract out of the `impl` block, they're
+/// declared next to the functions and added to the ABI.
+fn take_sol_declarations(ast: &mut ItemImpl) -> Vec<proc_macro2::TokenStream> {
+    let mut declarations = Vec::new();
+    ast.items.retain(|item| match item {
+        ImplItem::Macro(item_macro) if item_macro.mac.path.is_ident("sol") => {
+            declarations.push(item_macro.mac.tokens.clone());
+            false
+        }
+        _ => true,
+    });
+    declarations
+}
+
+/// Strips `#[function_id]` attributes off the methods and returns the ones of the public methods in
+/// the order they're declared.
+fn take_function_ids(ast: &mut ItemImpl) -> syn::Result<Vec<Option<LitStr>>> {
+    let mut function_ids = Vec::new();
+    for item in ast.items.iter_mut() {
+        let ImplItem::Fn(func) = item else {
+            continue;
+        };
+        let function_id = take_function_id(func)?;
         if let Visibility::Public(_) = func.vis {
-            let method_name = &func.sig.ident;
-            let method_name_call = sol_call_fn_name(method_name);
-            let selector_name = quote! { #method_name_call::SELECTOR };
-            let abi_decode = quote! { #method_name_call::abi_decode };
-
-            let param_names: Vec<_> = func
-                .sig
-                .inputs
+            function_ids.push(function_id);
+        } else if let Some(function_id) = function_id {
+            return Err(syn::Error::new_spanned(
+                function_id,
+                "function_id is only allowed on routed methods",
+            ));
+        }
+    }
+    Ok(function_ids)
+}
+
+/// Takes the Solidity signature out of `#[function_id("transfer(address,uint256)")]`, it sets
+/// the name the method is exposed with, so several methods can be overloads of one function.
+fn take_function_id(func: &mut ImplItemFn) -> syn::Result<Option<LitStr>> {
+    let Some(index) = func
+        .attrs
+        .iter()
+        .position(|attr| attr.path().is_ident("function_id"))
+    else {
+        return Ok(None);
+    };
+    let attr = func.attrs.remove(index);
+    if func
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("function_id"))
+    {
+        return Err(syn::Error::new_spanned(
+            &func.sig.ident,
+            "duplicated function_id attribute",
+        ));
+    }
+    attr.parse_args::<LitStr>().map(Some)
+}
+
+/// A routed method as it's seen from Solidity.
+struct SolFunction {
+    /// Name of the function in the ABI.
+    name: Ident,
+    /// Call struct `sol!` generates for the function.
+    call_name: Ident,
+    /// Canonical signature the selector is computed from, like `transfer(address,uint256)`.
+    signature: String,
+}
+
+/// Resolves Solidity functions of the routed methods and checks that no two of them have the same
+/// selector, the router can't tell them apart otherwise.
+fn parse_sol_functions(
+    methods: &[&ImplItemFn],
+    function_ids: &[Option<LitStr>],
+) -> syn::Result<Vec<SolFunction>> {
+    let mut signatures: Vec<(Ident, String, [u8; 4])> = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for (func, function_id) in methods.iter().zip(function_ids) {
+        let types: Vec<_> = fn_inputs(func).into_iter().map(|param| param.ty).collect();
+        let types = types.join(",");
+        let name = match function_id {
+            Some(function_id) => parse_function_id(function_id, &types)?,
+            None => rust_name_to_sol(&func.sig.ident),
+        };
+        let signature = format!("{}({})", name, types);
+        let selector = keccak256_selector(&signature);
+
+        if let Some((_, other, _)) = signatures.iter().find(|(_, _, other)| *other == selector) {
+            let message = if *other == signature {
+                format!("function `{}` is routed twice", signature)
+            } else {
+                format!(
+                    "selector 0x{} of `{}` collides with `{}`",
+                    selector
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>(),
+                    signature,
+                    other
+                )
+            };
+            let err = match function_id {
+                Some(function_id) => syn::Error::new_spanned(function_id, message),
+                None => syn::Error::new_spanned(&func.sig.ident, message),
+            };
+            match errors.as_mut() {
+                Some(errors) => errors.combine(err),
+                None => errors = Some(err),
+            }
+        }
+        signatures.push((name, signature, selector));
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    Ok(signatures
+        .iter()
+        .enumerate()
+        .map(|(i, (name, signature, _))| {
+            let overloads = signatures
                 .iter()
-                .filter_map(|arg| {
-                    if let FnArg::Typed(pat_type) = arg {
-                        if let syn::Pat::Ident(pat_ident) = &*pat_type.pat {
-                            Some(&pat_ident.ident)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+                .filter(|(other, ..)| other == name)
+                .count();
+            let overload = (overloads > 1).then(|| {
+                signatures[..i]
+                    .iter()
+                    .filter(|(other, ..)| other == name)
+                    .count()
+            });
+            SolFunction {
+                name: name.clone(),
+                call_name: sol_call_fn_name(name, overload),
+                signature: signature.clone(),
+            }
+        })
+        .collect())
+}
 
-            let args_expr = expand_args_expr(&abi_decode, &param_names);
+/// Parses a signature like `transfer(address,uint256)` and returns the function name, parameters
+/// of the signature have to be the ones of the method.
+fn parse_function_id(function_id: &LitStr, types: &str) -> syn::Result<Ident> {
+    let value: String = function_id
+        .value()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let Some((name, params)) = value
+        .strip_suffix(')')
+        .and_then(|value| value.split_once('('))
+    else {
+        return Err(syn::Error::new_spanned(
+            function_id,
+            "expected a Solidity signature like `transfer(address,uint256)`",
+        ));
+    };
+    let Ok(name) = syn::parse_str::<Ident>(name) else {
+        return Err(syn::Error::new_spanned(
+            function_id,
+            format!("`{}` isn't a valid function name", name),
+        ));
+    };
+    if params != types {
+        return Err(syn::Error::new_spanned(
+            function_id,
+            format!(
+                "`{}` doesn't match parameters of the method, expected `{}({})`",
+                value, name, types
+            ),
+        ));
+    }
+    Ok(Ident::new(&name.to_string(), function_id.span()))
+}
 
-            return Some(quote! {
-                #selector_name => {
-                    #args_expr
-                    self.#method_name(#(#param_names),*).abi_encode()
+fn keccak256_selector(signature: &str) -> [u8; 4] {
+    use crypto_hashes::{digest::Digest, sha3::Keccak256};
+    let mut hash = Keccak256::new();
+    hash.update(signature);
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(hash.finalize().as_slice()[0..4].as_ref());
+    selector
+}
+
+/// Embeds the JSON ABI of the contract as `ABI_JSON` and writes it into `FLUENTBASE_ABI_DIR` for
+/// deployment scripts and clients, signatures of the routed functions are embedded as
+/// `FUNCTION_SIGNATURES` for the fuzzer.
+fn expand_abi(
+    struct_name: &Type,
+    entries: &[AbiEntry],
+    sol_functions: &[SolFunction],
+) -> proc_macro2::TokenStream {
+    let json = abi_json(entries);
+    let write_error = write_abi_file(&contract_name(struct_name), &json)
+        .err()
+        .map(|err| syn::Error::new_spanned(struct_name, err).to_compile_error());
+    let signatures = sol_functions
+        .iter()
+        .map(|sol_function| &sol_function.signature);
+    quote! {
+        #write_error
+
+        impl #struct_name {
+            pub const ABI_JSON: &'static str = #json;
+            pub const FUNCTION_SIGNATURES: &'static [&'static str] = &[#(#signatures),*];
+        }
+    }
+}
+
+fn contract_name(struct_name: &Type) -> String {
+    match struct_name {
+        Type::Path(type_path) => type_path.path.segments.last().unwrap().ident.to_string(),
+        _ => struct_name.to_token_stream().to_string(),
+    }
+}
+
+/// Generates `{Contract}Client` that calls the contract at an address with ABI encoded calldata
+/// and decodes its output, methods returning `Result<T, E>` decode revert data into `E`.
+fn expand_client(
+    struct_name: &Type,
+    methods: &[&ImplItemFn],
+    sol_functions: &[SolFunction],
+) -> proc_macro2::TokenStream {
+    let client_name = Ident::new(
+        &format!("{}Client", contract_name(struct_name)),
+        proc_macro2::Span::call_site(),
+    );
+    let client_methods: Vec<_> = methods
+        .iter()
+        .zip(sol_functions)
+        .map(|(func, sol_function)| expand_client_method(func, &sol_function.call_name))
+        .collect();
+
+    quote! {
+        pub struct #client_name {
+            pub address: fluentbase_sdk::Address,
+            pub value: fluentbase_sdk::U256,
+            pub gas_limit: Option<u64>,
+        }
+
+        impl #client_name {
+            pub fn new(address: fluentbase_sdk::Address) -> Self {
+                Self {
+                    address,
+                    value: fluentbase_sdk::U256::ZERO,
+                    gas_limit: None,
                 }
-            });
+            }
+
+            pub fn with_value(mut self, value: fluentbase_sdk::U256) -> Self {
+                self.value = value;
+                self
+            }
+
+            pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+                self.gas_limit = Some(gas_limit);
+                self
+            }
+
+            fn call(&self, input: &[u8]) -> Result<fluentbase_sdk::Bytes, fluentbase_sdk::CallError> {
+                let cr = &fluentbase_sdk::ExecutionContext::DEFAULT;
+                // the callee gets all the gas left by default
+                let gas_limit = self
+                    .gas_limit
+                    .unwrap_or_else(|| fluentbase_sdk::contract_fuel_remaining(cr));
+                let input = fluentbase_sdk::EvmCallMethodInput {
+                    callee: self.address,
+                    value: self.value,
+                    input: fluentbase_sdk::Bytes::copy_from_slice(input),
+                    gas_limit,
+                    depth: fluentbase_sdk::ContextReader::contract_depth(cr) + 1,
+                };
+                fluentbase_sdk::call_contract(cr, &fluentbase_sdk::JzktAccountManager::DEFAULT, input)
+            }
+
+            #(#client_methods)*
         }
     }
-    None
+}
+
+fn expand_client_method(func: &ImplItemFn, method_name_call: &Ident) -> proc_macro2::TokenStream {
+    let method_name = &func.sig.ident;
+    let params: Vec<_> = func
+        .sig
+        .inputs
+        .iter()
+        .filter(|arg| matches!(arg, FnArg::Typed(_)))
+        .collect();
+    let param_names = parse_param_names(&func.sig.inputs);
+
+    let (output, error, map_error) = match result_types(&func.sig.output) {
+        Some((ok, err)) => (
+            quote! { #ok },
+            quote! { #err },
+            quote! {
+                .map_err(|err| err.with_custom(|revert_data| {
+                    <#err as alloy_sol_types::SolError>::abi_decode(revert_data, true).ok()
+                }))
+            },
+        ),
+        None => {
+            let output = match &func.sig.output {
+                syn::ReturnType::Type(_, ty) => quote! { #ty },
+                syn::ReturnType::Default => quote! { () },
+            };
+            (output, quote! { core::convert::Infallible }, quote! {})
+        }
+    };
+    // a single return value is `_0` of the returns struct, no return values give a unit
+    let returns = match &func.sig.output {
+        syn::ReturnType::Type(_, ty)
+            if !is_unit_type(ty)
+                && !matches!(result_types(&func.sig.output), Some((ok, _)) if is_unit_type(ok)) =>
+        {
+            quote! { decoded._0 }
+        }
+        _ => quote! { () },
+    };
+
+    quote! {
+        #[allow(clippy::useless_conversion, unused_variables)]
+        pub fn #method_name(&self, #(#params),*) -> Result<#output, fluentbase_sdk::CallError<#error>> {
+            let input = #method_name_call {
+                #(#param_names: #param_names.into()),*
+            }
+            .abi_encode();
+            let output = self.call(&input)#map_error?;
+            match #method_name_call::abi_decode_returns(&output, true) {
+                Ok(decoded) => Ok(#returns),
+                Err(_) => Err(fluentbase_sdk::CallError::InvalidOutput(output)),
+            }
+        }
+    }
+}
+
+fn expand_router_match_arm(
+    func: &ImplItemFn,
+    sol_function: &SolFunction,
+) -> proc_macro2::TokenStream {
+    let method_name_call = &sol_function.call_name;
+    let selector_name = quote! { #method_name_call::SELECTOR };
+    let call = expand_router_call(func, method_name_call);
+
+    quote! {
+        #selector_name => {
+            #call
+        }
+    }
+}
+
+fn expand_router_call(func: &ImplItemFn, method_name_call: &Ident) -> proc_macro2::TokenStream {
+    let method_name = &func.sig.ident;
+    let abi_decode = quote! { #method_name_call::abi_decode };
+
+    let param_names = parse_param_names(&func.sig.inputs);
+
+    let args_expr = expand_args_expr(&abi_decode, &param_names);
+
+    // errors of `Result` returning methods are custom errors reverted with their selector
+    if result_types(&func.sig.output).is_some() {
+        let error_revert = expand_revert(quote! {
+            &alloy_sol_types::SolError::abi_encode(&err)
+        });
+        return quote! {
+            #args_expr
+            match self.#method_name(#(#param_names),*) {
+                Ok(output) => output.abi_encode(),
+                Err(err) => #error_revert,
+            }
+        };
+    }
+
+    quote! {
+        #args_expr
+        self.#method_name(#(#param_names),*).abi_encode()
+    }
+}
+
+/// Revert reason of calls with input that can't be ABI-decoded into the method arguments.
+const INPUT_DECODE_ERROR: &str = "failed to decode input";
+
+/// Reverts with `Error(string)`, the same as `revert("...")` in Solidity.
+fn expand_revert_message(message: &str) -> proc_macro2::TokenStream {
+    quote! {
+        fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+            &fluentbase_sdk::SolidityErrorMessage(#message)
+        )
+    }
+}
+
+/// Reverts with encoded revert data of a custom error. `ExitCode::Panic` is the only exit code the
+/// core turns into EVM `REVERT`, that keeps the data and returns the gas left, any other code
+/// halts the call. Panics exit with it too, callers tell them apart by revert data: a panic
+/// reverts with `Error(string)`, a custom error with its own selector.
+fn expand_revert(revert_data: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        fluentbase_sdk::revert_with_data::<fluentbase_sdk::LowLevelSDK>(
+            fluentbase_sdk::ExitCode::Panic,
+            #revert_data
+        )
+    }
+}
+
+/// Returns the `Ok` and `Err` types of a method returning `Result<T, E>`.
+fn result_types(output: &syn::ReturnType) -> Option<(&Type, &Type)> {
+    let syn::ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+    let Type::Path(type_path) = &**ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match (args.args.first(), args.args.get(1)) {
+        (Some(syn::GenericArgument::Type(ok)), Some(syn::GenericArgument::Type(err))) => {
+            Some((ok, err))
+        }
+        _ => None,
+    }
+}
+
+fn parse_param_names(inputs: &Punctuated<FnArg, Token![,]>) -> Vec<&Ident> {
+    inputs
+        .iter()
+        .filter_map(|arg| {
+            if let FnArg::Typed(pat_type) = arg {
+                if let syn::Pat::Ident(pat_ident) = &*pat_type.pat {
+                    Some(&pat_ident.ident)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+        .collect()
 }
 
 fn expand_args_expr(
     abi_decode: &proc_macro2::TokenStream,
     param_names: &[&Ident],
 ) -> proc_macro2::TokenStream {
+    let input_revert = expand_revert_message(INPUT_DECODE_ERROR);
     if param_names.len() == 1 {
         let param_name = &param_names[0];
         quote! {
             let #param_name = match #abi_decode(&input, true) {
                 Ok(decoded) => decoded.#param_name,
-                Err(e) => {
-                    panic!("Failed to decode input {:?} {:?}", stringify!(#param_name), e);
-                }
+                Err(_) => #input_revert,
             };
         }
     } else {
@@ -213,55 +1046,58 @@ fn expand_args_expr(
         quote! {
             let (#(#param_names),*) = match #abi_decode(&input, true) {
                 Ok(decoded) => (#(#fields),*),
-                Err(e) => {
-                    panic!("Failed to decode input {:?}", e);
-                }
+                Err(_) => #input_revert,
             };
         }
     }
 }
 
-fn expand_sol(methods: Vec<&ImplItem>) -> proc_macro2::TokenStream {
-    let sol_functions: Vec<_> = methods
-        .iter()
-        .filter_map(|item| {
-            if let ImplItem::Fn(func) = item {
-                if let Visibility::Public(_) = func.vis {
-                    let method_name = &func.sig.ident;
-                    let sol_method_name = rust_name_to_sol(method_name);
-
-                    // Collect input parameter types and names
-                    let inputs = parse_function_inputs(&func.sig.inputs);
-
-                    // Collect output parameter type
-                    let output = if let syn::ReturnType::Type(_, ty) = &func.sig.output {
-                        rust_type_to_sol(ty)
-                    } else {
-                        quote! { void }
-                    };
-
-                    // Generate function signature in Solidity syntax
-                    Some(quote! {
-                        function #sol_method_name(#(#inputs),*) external view returns (#output);
-                    })
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect();
-
+fn expand_sol(
+    sol_functions: Vec<proc_macro2::TokenStream>,
+    declarations: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
     let sol_block = quote! {
         sol! {
             #(#sol_functions)*
+            #(#declarations)*
         }
     };
 
     sol_block
 }
 
+fn expand_sol_function(
+    func: &ImplItemFn,
+    sol_method_name: &Ident,
+    mutability: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    // Collect input parameter types and names
+    let inputs = parse_function_inputs(&func.sig.inputs);
+
+    // Collect output parameter type, functions without one return nothing
+    let output = match (&func.sig.output, result_types(&func.sig.output)) {
+        (_, Some((ok, _))) => Some(ok),
+        (syn::ReturnType::Type(_, ty), None) => Some(&**ty),
+        (syn::ReturnType::Default, None) => None,
+    };
+    let returns = match output {
+        Some(ty) if !is_unit_type(ty) => {
+            let output = rust_type_to_sol(ty);
+            quote! { returns (#output) }
+        }
+        _ => quote! {},
+    };
+
+    // Generate function signature in Solidity syntax
+    quote! {
+        function #sol_method_name(#(#inputs),*) external #mutability #returns;
+    }
+}
+
+fn is_unit_type(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
 fn parse_function_inputs(inputs: &Punctuated<FnArg, Token![,]>) -> Vec<proc_macro2::TokenStream> {
     inputs
         .iter()
@@ -281,9 +1117,14 @@ fn parse_function_inputs(inputs: &Punctuated<FnArg, Token![,]>) -> Vec<proc_macr
         .collect()
 }
 
-fn sol_call_fn_name(method_name: &Ident) -> Ident {
-    let method_name_sol = rust_name_to_sol(method_name);
-    Ident::new(&(method_name_sol.to_string() + "Call"), method_name.span())
+/// Name of the call struct `sol!` generates for a function, overloaded functions are numbered in
+/// the order they're declared.
+fn sol_call_fn_name(sol_method_name: &Ident, overload: Option<usize>) -> Ident {
+    let name = match overload {
+        Some(index) => format!("{}_{}Call", sol_method_name, index),
+        None => format!("{}Call", sol_method_name),
+    };
+    Ident::new(&name, sol_method_name.span())
 }
 
 fn rust_name_to_sol(ident: &Ident) -> Ident {
@@ -343,14 +1184,16 @@ fn convert_path_type(type_path: &TypePath) -> proc_macro2::TokenStream {
         "u32" => quote! { uint32 },
         "u64" => quote! { uint64 },
         "u128" => quote! { uint128 },
-        "u256" | "uint" => quote! { uint256 },
+        "u256" | "uint" | "U256" => quote! { uint256 },
         "i8" => quote! { int8 },
         "i16" => quote! { int16 },
         "i32" => quote! { int32 },
         "i64" => quote! { int64 },
         "i128" => quote! { int128 },
-        "i256" | "int" => quote! { int256 },
+        "i256" | "int" | "I256" => quote! { int256 },
         "Address" => quote! { address },
+        "Bytes" => quote! { bytes },
+        "B256" => quote! { bytes32 },
         "Vec" => {
             if let syn::PathArguments::AngleBracketed(args) =
                 &type_path.path.segments.last().unwrap().arguments
@@ -383,7 +1226,18 @@ fn convert_path_type(type_path: &TypePath) -> proc_macro2::TokenStream {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use syn::{parse_quote, Ident, ImplItem, TypeArray, TypeParen, TypePath, TypeSlice, TypeTuple};
+    use syn::{
+        parse_quote,
+        Ident,
+        ImplItem,
+        ImplItemFn,
+        ItemImpl,
+        TypeArray,
+        TypeParen,
+        TypePath,
+        TypeSlice,
+        TypeTuple,
+    };
 
     #[test]
     fn test_rust_name_to_sol() {
@@ -395,13 +1249,153 @@ mod tests {
     #[test]
     fn test_get_method_call() {
         let method_name = Ident::new("test_function", proc_macro2::Span::call_site());
-        let method_call_ident = sol_call_fn_name(&method_name);
+        let sol_method_name = rust_name_to_sol(&method_name);
+        let method_call_ident = sol_call_fn_name(&sol_method_name, None);
         assert_eq!(method_call_ident.to_string(), "testFunctionCall");
+        let method_call_ident = sol_call_fn_name(&sol_method_name, Some(1));
+        assert_eq!(method_call_ident.to_string(), "testFunction_1Call");
+    }
+
+    #[test]
+    fn test_parse_sol_functions() {
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                pub fn transfer(&self, to: Address, amount: U256) -> bool {
+                    true
+                }
+                #[function_id("transfer(address, uint256, bytes)")]
+                pub fn transfer_with_data(&self, to: Address, amount: U256, data: Bytes) -> bool {
+                    true
+                }
+                #[function_id("totalSupply()")]
+                pub fn supply(&self) -> U256 {
+                    U256::ZERO
+                }
+                fn helper(&self) {}
+            }
+        };
+        let function_ids = take_function_ids(&mut ast).unwrap();
+        let methods: Vec<_> = ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Fn(func) if matches!(func.vis, Visibility::Public(_)) => Some(func),
+                _ => None,
+            })
+            .collect();
+        assert!(methods.iter().all(|func| func.attrs.is_empty()));
+        let sol_functions = parse_sol_functions(&methods, &function_ids).unwrap();
+        let names: Vec<_> = sol_functions
+            .iter()
+            .map(|sol_function| {
+                (
+                    sol_function.name.to_string(),
+                    sol_function.call_name.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("transfer".to_string(), "transfer_0Call".to_string()),
+                ("transfer".to_string(), "transfer_1Call".to_string()),
+                ("totalSupply".to_string(), "totalSupplyCall".to_string()),
+            ]
+        );
+        let signatures: Vec<_> = sol_functions
+            .iter()
+            .map(|sol_function| sol_function.signature.as_str())
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "transfer(address,uint256)",
+                "transfer(address,uint256,bytes)",
+                "totalSupply()"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sol_functions_errors() {
+        let parse = |ast: ItemImpl| {
+            let mut ast = ast;
+            let function_ids = take_function_ids(&mut ast)?;
+            let methods: Vec<_> = ast
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    ImplItem::Fn(func) if matches!(func.vis, Visibility::Public(_)) => Some(func),
+                    _ => None,
+                })
+                .collect();
+            parse_sol_functions(&methods, &function_ids).map(|_| ())
+        };
+        // `transferFrom(address,address,uint256)` and `gasprice_bit_ether(int128)` are both
+        // 0x23b872dd
+        let err = parse(parse_quote! {
+            impl Token {
+                pub fn transfer_from(&self, from: Address, to: Address, amount: U256) {}
+                #[function_id("gasprice_bit_ether(int128)")]
+                pub fn gas_price(&self, value: i128) {}
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "selector 0x23b872dd of `gasprice_bit_ether(int128)` collides with `transferFrom(address,address,uint256)`"
+        );
+        let err = parse(parse_quote! {
+            impl Token {
+                pub fn total_supply(&self) -> U256 {
+                    U256::ZERO
+                }
+                #[function_id("totalSupply()")]
+                pub fn supply(&self) -> U256 {
+                    U256::ZERO
+                }
+            }
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "function `totalSupply()` is routed twice");
+        let err = parse(parse_quote! {
+            impl Token {
+                #[function_id("transfer(address)")]
+                pub fn transfer(&self, to: Address, amount: U256) {}
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`transfer(address)` doesn't match parameters of the method, expected `transfer(address,uint256)`"
+        );
+        let err = parse(parse_quote! {
+            impl Token {
+                #[function_id("transfer")]
+                pub fn transfer(&self) {}
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a Solidity signature like `transfer(address,uint256)`"
+        );
+        let err = parse(parse_quote! {
+            impl Token {
+                #[function_id("helper()")]
+                fn helper(&self) {}
+            }
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function_id is only allowed on routed methods"
+        );
     }
 
     #[test]
     fn test_expand_match_arm_single_param() {
-        let item: ImplItem = parse_quote! {
+        let func: ImplItemFn = parse_quote! {
             pub fn is_checkmate(&self, board: String) -> bool {
                 true
             }
@@ -411,24 +1405,22 @@ mod tests {
             isCheckmateCall::SELECTOR => {
                 let board = match isCheckmateCall::abi_decode(&input, true) {
                     Ok(decoded) => decoded.board,
-                    Err(e) => {
-                        panic!("Failed to decode input {:?} {:?}", stringify!(board), e);
-                    }
+                    Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                        &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                    ),
                 };
                 self.is_checkmate(board).abi_encode()
             }
         };
 
-        if let Some(actual) = expand_router_match_arm(&item) {
-            assert_eq!(actual.to_string(), expected.to_string());
-        } else {
-            panic!("Failed to expand match arm");
-        }
+        let sol_functions = parse_sol_functions(&[&func], &[None]).unwrap();
+        let actual = expand_router_match_arm(&func, &sol_functions[0]);
+        assert_eq!(actual.to_string(), expected.to_string());
     }
 
     #[test]
     fn test_expand_match_arm_multiple_params() {
-        let item: ImplItem = parse_quote! {
+        let func: ImplItemFn = parse_quote! {
             pub fn is_checkmate(&self, board: String, mv: String) -> bool {
                 true
             }
@@ -438,19 +1430,271 @@ mod tests {
             isCheckmateCall::SELECTOR => {
                 let (board, mv) = match isCheckmateCall::abi_decode(&input, true) {
                     Ok(decoded) => (decoded.board, decoded.mv),
-                    Err(e) => {
-                        panic!("Failed to decode input {:?}", e);
-                    }
+                    Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                        &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                    ),
                 };
                 self.is_checkmate(board, mv).abi_encode()
             }
         };
 
-        if let Some(actual) = expand_router_match_arm(&item) {
-            assert_eq!(actual.to_string(), expected.to_string());
-        } else {
-            panic!("Failed to expand match arm");
-        }
+        let sol_functions = parse_sol_functions(&[&func], &[None]).unwrap();
+        let actual = expand_router_match_arm(&func, &sol_functions[0]);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_match_arm_result() {
+        let func: ImplItemFn = parse_quote! {
+            pub fn withdraw(&self, amount: U256) -> Result<U256, InsufficientBalance> {
+                Ok(amount)
+            }
+        };
+
+        let expected = quote! {
+            withdrawCall::SELECTOR => {
+                let amount = match withdrawCall::abi_decode(&input, true) {
+                    Ok(decoded) => decoded.amount,
+                    Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                        &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                    ),
+                };
+                match self.withdraw(amount) {
+                    Ok(output) => output.abi_encode(),
+                    Err(err) => fluentbase_sdk::revert_with_data::<fluentbase_sdk::LowLevelSDK>(
+                        fluentbase_sdk::ExitCode::Panic,
+                        &alloy_sol_types::SolError::abi_encode(&err)
+                    ),
+                }
+            }
+        };
+
+        let sol_functions = parse_sol_functions(&[&func], &[None]).unwrap();
+        let actual = expand_router_match_arm(&func, &sol_functions[0]);
+        assert_eq!(actual.to_string(), expected.to_string());
+
+        let func: ImplItemFn = parse_quote! {
+            pub fn withdraw(&self, amount: U256) -> Result<(), InsufficientBalance> {
+                Ok(())
+            }
+        };
+        let expected = quote! {
+            function withdraw(uint256 amount) external;
+        };
+        let actual = expand_sol_function(&func, &parse_quote!(withdraw), quote! {});
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_client_method() {
+        let func: ImplItemFn = parse_quote! {
+            pub fn withdraw(&self, amount: U256) -> Result<U256, InsufficientBalance> {
+                Ok(amount)
+            }
+        };
+        let expected = quote! {
+            #[allow(clippy::useless_conversion, unused_variables)]
+            pub fn withdraw(&self, amount: U256) -> Result<U256, fluentbase_sdk::CallError<InsufficientBalance>> {
+                let input = withdrawCall {
+                    amount: amount.into()
+                }
+                .abi_encode();
+                let output = self.call(&input).map_err(|err| err.with_custom(|revert_data| {
+                    <InsufficientBalance as alloy_sol_types::SolError>::abi_decode(revert_data, true).ok()
+                }))?;
+                match withdrawCall::abi_decode_returns(&output, true) {
+                    Ok(decoded) => Ok(decoded._0),
+                    Err(_) => Err(fluentbase_sdk::CallError::InvalidOutput(output)),
+                }
+            }
+        };
+        assert_eq!(
+            expand_client_method(&func, &parse_quote!(withdrawCall)).to_string(),
+            expected.to_string()
+        );
+
+        let func: ImplItemFn = parse_quote! {
+            pub fn burn(&self) {}
+        };
+        let expected = quote! {
+            #[allow(clippy::useless_conversion, unused_variables)]
+            pub fn burn(&self, ) -> Result<(), fluentbase_sdk::CallError<core::convert::Infallible>> {
+                let input = burnCall {}.abi_encode();
+                let output = self.call(&input)?;
+                match burnCall::abi_decode_returns(&output, true) {
+                    Ok(decoded) => Ok(()),
+                    Err(_) => Err(fluentbase_sdk::CallError::InvalidOutput(output)),
+                }
+            }
+        };
+        assert_eq!(
+            expand_client_method(&func, &parse_quote!(burnCall)).to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_contract_methods() {
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                #[payable]
+                fn constructor(&self, owner: Address) {}
+                #[view]
+                pub fn balance_of(&self, owner: Address) -> U256 {
+                    U256::ZERO
+                }
+                pub fn transfer(&self, to: Address, amount: U256) -> bool {
+                    true
+                }
+                fn helper(&self) {}
+                #[receive]
+                fn receive(&self) {}
+                #[fallback]
+                #[inline]
+                fn fallback(&self, input: &[u8]) -> Vec<u8> {
+                    input.to_vec()
+                }
+            }
+        };
+        let methods = parse_contract_methods(&mut ast).unwrap();
+
+        let constructor = methods.constructor.unwrap();
+        assert!(constructor.payable);
+        let functions: Vec<_> = methods
+            .functions
+            .iter()
+            .map(|method| (method.func.sig.ident.to_string(), method.view))
+            .collect();
+        assert_eq!(
+            functions,
+            vec![
+                ("balance_of".to_string(), true),
+                ("transfer".to_string(), false)
+            ]
+        );
+        assert_eq!(methods.receive.unwrap().func.sig.ident, "receive");
+        assert_eq!(methods.fallback.unwrap().func.attrs.len(), 1);
+        // only foreign attributes are left
+        let attrs: Vec<_> = ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Fn(func) => Some(func.attrs.len()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attrs, vec![0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_parse_contract_methods_errors() {
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                #[payable]
+                #[view]
+                pub fn balance_of(&self) -> U256 {
+                    U256::ZERO
+                }
+            }
+        };
+        assert!(parse_contract_methods(&mut ast).is_err());
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                #[receive]
+                fn receive(&self, input: &[u8]) {}
+            }
+        };
+        assert!(parse_contract_methods(&mut ast).is_err());
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                #[fallback]
+                fn fallback(&self) {}
+                #[fallback]
+                fn other_fallback(&self) {}
+            }
+        };
+        assert!(parse_contract_methods(&mut ast).is_err());
+        let mut ast: ItemImpl = parse_quote! {
+            impl Token {
+                #[payable]
+                fn deposit(&self) {}
+            }
+        };
+        assert_eq!(
+            parse_contract_methods(&mut ast).err().unwrap().to_string(),
+            "payable and view are only allowed on public methods, constructor and handlers"
+        );
+    }
+
+    #[test]
+    fn test_expand_sol_function() {
+        let func: ImplItemFn = parse_quote! {
+            pub fn transfer(&self, to: Address, amount: U256) -> bool {
+                true
+            }
+        };
+        let expected = quote! {
+            function transfer(address to, uint256 amount) external payable returns (bool);
+        };
+        let actual = expand_sol_function(&func, &parse_quote!(transfer), quote! { payable });
+        assert_eq!(actual.to_string(), expected.to_string());
+
+        let func: ImplItemFn = parse_quote! {
+            pub fn burn(&self, amount: U256) {}
+        };
+        let expected = quote! {
+            function burn(uint256 amount) external;
+        };
+        let actual = expand_sol_function(&func, &parse_quote!(burn), quote! {});
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_contract_constructor() {
+        let method = ContractMethod {
+            func: parse_quote! {
+                fn constructor(&self, owner: Address, supply: U256) {}
+            },
+            payable: true,
+            view: false,
+            function_id: None,
+        };
+        let expected = quote! {
+            let (owner, supply,) = match <(Address, U256,) as alloy_sol_types::SolValue>::abi_decode_params(input, true) {
+                Ok(decoded) => decoded,
+                Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                    &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                ),
+            };
+            self.constructor(owner, supply);
+        };
+        let actual = expand_contract_constructor(Some(&method));
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_expand_payable_check() {
+        let mut method = ContractMethod {
+            func: parse_quote! {
+                pub fn deposit(&self) {}
+            },
+            payable: false,
+            view: false,
+            function_id: None,
+        };
+        let expected = quote! {
+            if !fluentbase_sdk::ContextReader::contract_value(&fluentbase_sdk::ExecutionContext::DEFAULT).is_zero() {
+                fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                    &fluentbase_sdk::SolidityErrorMessage("deposit is not payable")
+                );
+            }
+        };
+        assert_eq!(
+            expand_payable_check(&method).to_string(),
+            expected.to_string()
+        );
+        method.payable = true;
+        assert!(expand_payable_check(&method).is_empty());
     }
 
     #[test]
@@ -463,9 +1707,9 @@ mod tests {
         let expected = quote! {
             let board = match isCheckmateCall::abi_decode(&input, true) {
                 Ok(decoded) => decoded.board,
-                Err(e) => {
-                    panic!("Failed to decode input {:?} {:?}", stringify!(board), e);
-                }
+                Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                    &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                ),
             };
         };
 
@@ -483,9 +1727,9 @@ mod tests {
         let expected = quote! {
             let (board, mv) = match isCheckmateCall::abi_decode(&input, true) {
                 Ok(decoded) => (decoded.board, decoded.mv),
-                Err(e) => {
-                    panic!("Failed to decode input {:?}", e);
-                }
+                Err(_) => fluentbase_sdk::revert::<fluentbase_sdk::LowLevelSDK>(
+                    &fluentbase_sdk::SolidityErrorMessage("failed to decode input")
+                ),
             };
         };
 
@@ -0,0 +1,292 @@
+//! Allocator that reuses freed memory.
+//!
+//! `LeakingAllocator` never frees anything, so contracts that allocate and free in loops run
+//! out of linear memory. `SizeClassAllocator` rounds every allocation up to a power of two and
+//! keeps freed blocks in a free list of their size class, a block is reused by the next
+//! allocation of the same class. New blocks are carved from the end of the heap and the heap
+//! grows by wasm pages. It doesn't depend on anything but the sequence of allocations, so the
+//! footprint of a contract is deterministic.
+//!
+//! It's the global allocator of contracts if the `size-class-allocator` feature is enabled.
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::UnsafeCell,
+    ptr,
+};
+
+const PAGE_SIZE: usize = 0x10000;
+const MIN_CLASS_BITS: u32 = 3;
+const CLASSES: usize = (usize::BITS - MIN_CLASS_BITS) as usize;
+
+struct Heap {
+    /// Start of the heap, the heap is rewound to it by `reset`.
+    start: usize,
+    /// The end of carved blocks.
+    top: usize,
+    /// The end of grown memory.
+    end: usize,
+    /// Heads of free lists, a free block keeps the address of the next one.
+    free: [usize; CLASSES],
+}
+
+pub struct SizeClassAllocator {
+    heap: UnsafeCell<Heap>,
+}
+
+// contracts are single threaded, the same as `lol_alloc::AssumeSingleThreaded` assumes
+unsafe impl Sync for SizeClassAllocator {}
+
+impl Default for SizeClassAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Size of the class of a layout, it's aligned to itself up to a page, so a block of a class
+/// can be reused by any layout of the class.
+fn class_size(layout: Layout) -> Option<usize> {
+    if layout.align() > PAGE_SIZE {
+        return None;
+    }
+    layout
+        .size()
+        .max(layout.align())
+        .max(1 << MIN_CLASS_BITS)
+        .checked_next_power_of_two()
+}
+
+fn class_index(class_size: usize) -> usize {
+    (class_size.trailing_zeros() - MIN_CLASS_BITS) as usize
+}
+
+impl SizeClassAllocator {
+    pub const fn new() -> Self {
+        Self {
+            heap: UnsafeCell::new(Heap {
+                start: 0,
+                top: 0,
+                end: 0,
+                free: [0; CLASSES],
+            }),
+        }
+    }
+
+    /// Frees all the memory allocated, the heap starts from the beginning, but the memory
+    /// isn't returned to the host.
+    ///
+    /// # Safety
+    ///
+    /// Nothing allocated before can be used after the reset.
+    pub unsafe fn reset(&self) {
+        let heap = &mut *self.heap.get();
+        heap.top = heap.start;
+        heap.free = [0; CLASSES];
+    }
+
+    /// Size of the heap in bytes, including free blocks.
+    pub fn heap_size(&self) -> usize {
+        let heap = unsafe { &*self.heap.get() };
+        heap.top - heap.start
+    }
+}
+
+impl Heap {
+    fn push(&mut self, block: usize, class_size: usize) {
+        let index = class_index(class_size);
+        unsafe { ptr::write(block as *mut usize, self.free[index]) };
+        self.free[index] = block;
+    }
+
+    fn pop(&mut self, class_size: usize) -> Option<usize> {
+        let index = class_index(class_size);
+        let block = self.free[index];
+        if block == 0 {
+            return None;
+        }
+        self.free[index] = unsafe { ptr::read(block as *const usize) };
+        Some(block)
+    }
+
+    /// Splits unused memory, like padding before an aligned block, into free blocks.
+    fn free_range(&mut self, mut from: usize, to: usize) {
+        while from < to {
+            let bits = from
+                .trailing_zeros()
+                .min(usize::BITS - 1 - (to - from).leading_zeros());
+            self.push(from, 1 << bits);
+            from += 1 << bits;
+        }
+    }
+
+    fn carve(&mut self, class_size: usize) -> Option<usize> {
+        let align = class_size.min(PAGE_SIZE);
+        let mut block = align_up(self.top, align)?;
+        if block.checked_add(class_size)? > self.end {
+            let required = block + class_size - self.end;
+            let pages = required.div_ceil(PAGE_SIZE);
+            let base = grow_memory(pages)?;
+            if base != self.end {
+                // memory isn't contiguous, what is left of the previous region isn't used
+                self.free_range(self.top, self.end);
+                if self.start == self.end {
+                    self.start = base;
+                }
+                self.top = base;
+                block = align_up(base, align)?;
+            }
+            self.end = base + pages * PAGE_SIZE;
+            if block + class_size > self.end {
+                return self.carve(class_size);
+            }
+        }
+        self.free_range(self.top, block);
+        self.top = block + class_size;
+        Some(block)
+    }
+}
+
+fn align_up(value: usize, align: usize) -> Option<usize> {
+    Some(value.checked_add(align - 1)? & !(align - 1))
+}
+
+unsafe impl GlobalAlloc for SizeClassAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(class_size) = class_size(layout) else {
+            return ptr::null_mut();
+        };
+        let heap = &mut *self.heap.get();
+        heap.pop(class_size)
+            .or_else(|| heap.carve(class_size))
+            .map_or(ptr::null_mut(), |block| block as *mut u8)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let heap = &mut *self.heap.get();
+        heap.push(ptr as usize, class_size(layout).unwrap());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        // the block is big enough already, it happens often when vectors grow
+        if class_size(layout) == class_size(new_layout) {
+            return ptr;
+        }
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+/// Grows memory by pages and returns the address of new memory.
+#[cfg(target_arch = "wasm32")]
+fn grow_memory(pages: usize) -> Option<usize> {
+    match core::arch::wasm32::memory_grow(0, pages) {
+        usize::MAX => None,
+        previous_pages => Some(previous_pages * PAGE_SIZE),
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+fn grow_memory(pages: usize) -> Option<usize> {
+    tests::grow_arena(pages)
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+fn grow_memory(_pages: usize) -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    const ARENA_PAGES: usize = 64;
+
+    std::thread_local! {
+        /// Memory of the thread, it's grown contiguously like wasm memory.
+        static ARENA: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+    }
+
+    pub(super) fn grow_arena(pages: usize) -> Option<usize> {
+        let (mut next, mut end) = ARENA.with(Cell::get);
+        if next == 0 {
+            let layout = Layout::from_size_align(ARENA_PAGES * PAGE_SIZE, PAGE_SIZE).unwrap();
+            next = unsafe { std::alloc::alloc(layout) } as usize;
+            end = next + ARENA_PAGES * PAGE_SIZE;
+        }
+        if next + pages * PAGE_SIZE > end {
+            return None;
+        }
+        ARENA.with(|arena| arena.set((next + pages * PAGE_SIZE, end)));
+        Some(next)
+    }
+
+    #[test]
+    fn test_memory_is_reused() {
+        let allocator = SizeClassAllocator::new();
+        let layout = Layout::from_size_align(100, 8).unwrap();
+        unsafe {
+            let a = allocator.alloc(layout);
+            let b = allocator.alloc(layout);
+            assert_ne!(a, b);
+            assert_eq!(a as usize % 128, 0);
+            allocator.dealloc(a, layout);
+            assert_eq!(allocator.alloc(Layout::from_size_align(65, 1).unwrap()), a);
+            // the same class is reused by realloc
+            assert_eq!(allocator.realloc(b, layout, 128), b);
+            let c = allocator.realloc(b, layout, 129);
+            assert_ne!(c, b);
+            assert_eq!(allocator.alloc(layout), b);
+        }
+        assert_eq!(allocator.heap_size(), 128 * 2 + 256);
+    }
+
+    #[test]
+    fn test_alignment_padding_is_reused() {
+        let allocator = SizeClassAllocator::new();
+        unsafe {
+            let small = allocator.alloc(Layout::from_size_align(8, 8).unwrap());
+            let page = allocator.alloc(Layout::from_size_align(PAGE_SIZE, 4096).unwrap());
+            assert_eq!(page as usize % PAGE_SIZE, 0);
+            assert_eq!(page as usize - small as usize, PAGE_SIZE);
+            // the padding between them is split into free blocks
+            let padding = allocator.alloc(Layout::from_size_align(PAGE_SIZE / 2, 8).unwrap());
+            assert_eq!(padding as usize - small as usize, PAGE_SIZE / 2);
+            assert_eq!(allocator.heap_size(), PAGE_SIZE * 2);
+            assert!(allocator
+                .alloc(Layout::from_size_align(8, PAGE_SIZE * 2).unwrap())
+                .is_null());
+        }
+    }
+
+    #[test]
+    fn test_footprint_is_bounded() {
+        let allocator = SizeClassAllocator::new();
+        let first = unsafe { allocator.alloc(Layout::from_size_align(16, 8).unwrap()) };
+        let run = || unsafe {
+            // a growing vector, like decoding of calldata does
+            let mut layout = Layout::from_size_align(16, 8).unwrap();
+            let mut ptr = allocator.alloc(layout);
+            for size in (32..=4096).step_by(16) {
+                ptr = allocator.realloc(ptr, layout, size);
+                layout = Layout::from_size_align(size, 8).unwrap();
+            }
+            allocator.dealloc(ptr, layout);
+        };
+        run();
+        let heap_size = allocator.heap_size();
+        for _ in 0..1000 {
+            run();
+        }
+        assert_eq!(allocator.heap_size(), heap_size);
+
+        unsafe { allocator.reset() };
+        assert_eq!(allocator.heap_size(), 0);
+        let block = unsafe { allocator.alloc(Layout::from_size_align(64, 8).unwrap()) };
+        assert_eq!(block, first);
+    }
+}
@@ -4,6 +4,8 @@ extern "C" {
     /// - Keccak256
     /// - Poseidon (two modes, message hash and two elements hash)
     /// - Ecrecover
+    /// - BN254 scalar field (Fr) arithmetic
+    /// - Groth16 proof verification over BN254
     pub fn _keccak256(data_offset: *const u8, data_len: u32, output32_offset: *mut u8);
     pub fn _poseidon(data_offset: *const u8, data_len: u32, output32_offset: *mut u8);
     pub fn _poseidon_hash(
@@ -19,6 +21,47 @@ extern "C" {
         rec_id: u32,
     );
 
+    /// BN254 scalar field arithmetic, every element is a canonical 32-byte little-endian
+    /// value, and `f32s_len` is a length of packed elements in bytes (must be a multiple of 32).
+    /// Batched operations are applied element-wise, non-canonical elements or inversion of zero
+    /// halts execution with `Bn254Error`.
+    pub fn _bn254_fr_add(
+        a32s_offset: *const u8,
+        b32s_offset: *const u8,
+        f32s_len: u32,
+        output32s_offset: *mut u8,
+    );
+    pub fn _bn254_fr_mul(
+        a32s_offset: *const u8,
+        b32s_offset: *const u8,
+        f32s_len: u32,
+        output32s_offset: *mut u8,
+    );
+    pub fn _bn254_fr_inv(f32_offset: *const u8, output32_offset: *mut u8);
+    pub fn _bn254_fr_batch_inv(f32s_offset: *const u8, f32s_len: u32, output32s_offset: *mut u8);
+    pub fn _bn254_fr_pow(
+        base32_offset: *const u8,
+        exp32_offset: *const u8,
+        output32_offset: *mut u8,
+    );
+    pub fn _bn254_fr_inner_product(
+        a32s_offset: *const u8,
+        b32s_offset: *const u8,
+        f32s_len: u32,
+        output32_offset: *mut u8,
+    );
+
+    /// Verifies Groth16 proof over BN254 (see `fluentbase_sdk::bn254::groth16_verify` for the
+    /// binary layout of the verification key, proof and public inputs). Returns 1 for a valid
+    /// proof, 0 for an invalid one and `Bn254Error` exit code for malformed parameters.
+    pub fn _groth16_verify(
+        vk_offset: *const u8,
+        vk_len: u32,
+        proof_offset: *const u8,
+        inputs_offset: *const u8,
+        inputs_len: u32,
+    ) -> i32;
+
     /// Basic system methods that are available for every app (shared and sovereign)
     pub fn _exit(code: i32) -> !;
     pub fn _write(offset: *const u8, length: u32);
@@ -0,0 +1,153 @@
+//! Safe wrappers over BN254 syscalls.
+//!
+//! For scalar field (Fr) arithmetic every element is a canonical little-endian 32-byte value
+//! (the same representation `halo2curves` uses), non-canonical inputs or inversion of zero halt
+//! execution.
+use crate::{LowLevelSDK, SharedAPI};
+use alloc::{vec, vec::Vec};
+pub use fluentbase_types::{
+    groth16_vk_size,
+    GROTH16_G1_SIZE,
+    GROTH16_G2_SIZE,
+    GROTH16_PROOF_SIZE,
+    GROTH16_VK_HEADER_SIZE,
+};
+use fluentbase_types::{Bytes32, ExitCode};
+
+#[inline(always)]
+fn packed_len(elems: &[Bytes32]) -> u32 {
+    (elems.len() * 32) as u32
+}
+
+#[inline(always)]
+pub fn fr_add(a: &[Bytes32], b: &[Bytes32]) -> Vec<Bytes32> {
+    assert_eq!(a.len(), b.len(), "bn254: length mismatch");
+    let mut output = vec![[0u8; 32]; a.len()];
+    LowLevelSDK::bn254_fr_add(
+        a.as_ptr() as *const u8,
+        b.as_ptr() as *const u8,
+        packed_len(a),
+        output.as_mut_ptr() as *mut u8,
+    );
+    output
+}
+
+#[inline(always)]
+pub fn fr_mul(a: &[Bytes32], b: &[Bytes32]) -> Vec<Bytes32> {
+    assert_eq!(a.len(), b.len(), "bn254: length mismatch");
+    let mut output = vec![[0u8; 32]; a.len()];
+    LowLevelSDK::bn254_fr_mul(
+        a.as_ptr() as *const u8,
+        b.as_ptr() as *const u8,
+        packed_len(a),
+        output.as_mut_ptr() as *mut u8,
+    );
+    output
+}
+
+#[inline(always)]
+pub fn fr_inv(f: &Bytes32) -> Bytes32 {
+    let mut output = [0u8; 32];
+    LowLevelSDK::bn254_fr_inv(f.as_ptr(), output.as_mut_ptr());
+    output
+}
+
+/// Inverts all elements using one field inversion (Montgomery's trick)
+#[inline(always)]
+pub fn fr_batch_inv(elems: &[Bytes32]) -> Vec<Bytes32> {
+    let mut output = vec![[0u8; 32]; elems.len()];
+    LowLevelSDK::bn254_fr_batch_inv(
+        elems.as_ptr() as *const u8,
+        packed_len(elems),
+        output.as_mut_ptr() as *mut u8,
+    );
+    output
+}
+
+/// Computes `base^exp`, where `exp` is a little-endian 256-bit integer
+#[inline(always)]
+pub fn fr_pow(base: &Bytes32, exp: &Bytes32) -> Bytes32 {
+    let mut output = [0u8; 32];
+    LowLevelSDK::bn254_fr_pow(base.as_ptr(), exp.as_ptr(), output.as_mut_ptr());
+    output
+}
+
+/// Computes `sum(a[i] * b[i])`
+#[inline(always)]
+pub fn fr_inner_product(a: &[Bytes32], b: &[Bytes32]) -> Bytes32 {
+    assert_eq!(a.len(), b.len(), "bn254: length mismatch");
+    let mut output = [0u8; 32];
+    LowLevelSDK::bn254_fr_inner_product(
+        a.as_ptr() as *const u8,
+        b.as_ptr() as *const u8,
+        packed_len(a),
+        output.as_mut_ptr(),
+    );
+    output
+}
+
+/// Verifies Groth16 proof over BN254.
+///
+/// Points and scalars use big-endian encoding from EIP-196/EIP-197, so data produced for
+/// Solidity verifiers (snarkjs, gnark, circom) can be passed as is:
+/// - G1 point is `x || y` (64 bytes), G2 point is `x.c1 || x.c0 || y.c1 || y.c0` (128 bytes), all
+///   zeros stands for a point at infinity
+/// - `vk` is `alpha (G1) || beta (G2) || gamma (G2) || delta (G2) || IC[0..=n] (G1)`
+/// - `proof` is `A (G1) || B (G2) || C (G1)`
+/// - `public_inputs` is `n` packed 32-byte scalars, each must be less than the group order
+///
+/// Malformed parameters (wrong sizes, points not on the curve) halt execution with `Bn254Error`,
+/// use [`try_groth16_verify`] to handle them.
+#[inline(always)]
+pub fn groth16_verify(vk: &[u8], proof: &[u8; GROTH16_PROOF_SIZE], public_inputs: &[u8]) -> bool {
+    match try_groth16_verify(vk, proof, public_inputs) {
+        Ok(is_valid) => is_valid,
+        Err(err) => LowLevelSDK::exit(err.into_i32()),
+    }
+}
+
+/// Same as [`groth16_verify`], but returns `Bn254Error` for malformed parameters instead of
+/// halting execution
+#[inline(always)]
+pub fn try_groth16_verify(
+    vk: &[u8],
+    proof: &[u8; GROTH16_PROOF_SIZE],
+    public_inputs: &[u8],
+) -> Result<bool, ExitCode> {
+    let result = LowLevelSDK::groth16_verify(
+        vk.as_ptr(),
+        vk.len() as u32,
+        proof.as_ptr(),
+        public_inputs.as_ptr(),
+        public_inputs.len() as u32,
+    );
+    match result {
+        0 => Ok(false),
+        1 => Ok(true),
+        err => Err(ExitCode::from(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fr(value: u64) -> Bytes32 {
+        let mut result = [0u8; 32];
+        result[..8].copy_from_slice(&value.to_le_bytes());
+        result
+    }
+
+    #[test]
+    fn test_fr_arithmetic() {
+        let a = [fr(2), fr(3)];
+        let b = [fr(5), fr(7)];
+        assert_eq!(fr_add(&a, &b), vec![fr(7), fr(10)]);
+        assert_eq!(fr_mul(&a, &b), vec![fr(10), fr(21)]);
+        assert_eq!(fr_inner_product(&a, &b), fr(31));
+        assert_eq!(fr_pow(&fr(3), &fr(4)), fr(81));
+        let inv = fr_batch_inv(&a);
+        assert_eq!(inv[0], fr_inv(&a[0]));
+        assert_eq!(fr_mul(&a, &inv), vec![fr(1), fr(1)]);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::{
+    contract_fuel_remaining,
+    AccountManager,
+    ContextReader,
+    ContractInput,
+    EvmCallMethodInput,
+};
+use core::convert::Infallible;
+use fluentbase_codec::Encoder;
+use fluentbase_types::{BytecodeType, Bytes, ExitCode, STATE_MAIN};
+
+/// Failure of a call into another contract, `E` is a custom error the callee can revert with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallError<E = Infallible> {
+    /// The callee reverted with one of its custom errors.
+    Custom(E),
+    /// The callee failed with the exit code and revert data that isn't a known error.
+    Reverted { exit_code: i32, revert_data: Bytes },
+    /// Output of the callee can't be decoded.
+    InvalidOutput(Bytes),
+}
+
+impl CallError {
+    /// Decodes revert data into a custom error of the callee, failures that can't be decoded
+    /// are kept as is.
+    pub fn with_custom<E>(self, decode: impl FnOnce(&[u8]) -> Option<E>) -> CallError<E> {
+        match self {
+            CallError::Custom(never) => match never {},
+            CallError::Reverted {
+                exit_code,
+                revert_data,
+            } => match decode(&revert_data) {
+                Some(err) => CallError::Custom(err),
+                None => CallError::Reverted {
+                    exit_code,
+                    revert_data,
+                },
+            },
+            CallError::InvalidOutput(output) => CallError::InvalidOutput(output),
+        }
+    }
+}
+
+/// Calls the contract at `input.callee` on behalf of the current contract and transfers
+/// `input.value` to it, all changes made by the call are rolled back if it fails. Changes of
+/// successful calls are left in the journal, they're committed with the transaction.
+///
+/// Callees are dispatched the same way `_evm_call` and `_wasm_call` of the core do it: calls
+/// deeper than 1024 fail with `CallDepthOverflow`, precompiles are executed by the account
+/// manager and an account without code only receives the value. EVM bytecode can't be executed
+/// from a WASM contract, so calls of EVM contracts fail with `NotSupportedCall`.
+pub fn call_contract<CR: ContextReader, AM: AccountManager>(
+    cr: &CR,
+    am: &AM,
+    input: EvmCallMethodInput,
+) -> Result<Bytes, CallError> {
+    let failure = |exit_code: ExitCode| CallError::Reverted {
+        exit_code: exit_code.into_i32(),
+        revert_data: Bytes::new(),
+    };
+    if cr.contract_is_static() && !input.value.is_zero() {
+        return Err(failure(ExitCode::WriteProtection));
+    }
+    if input.depth > 1024 {
+        return Err(failure(ExitCode::CallDepthOverflow));
+    }
+    let checkpoint = am.checkpoint();
+    let (mut callee_account, _) = am.account(input.callee);
+    if !input.value.is_zero() {
+        let (mut caller_account, _) = am.account(cr.contract_address());
+        if let Err(exit_code) = am.transfer(&mut caller_account, &mut callee_account, input.value) {
+            am.rollback(checkpoint);
+            return Err(failure(exit_code));
+        }
+        am.write_account(&caller_account);
+        am.write_account(&callee_account);
+    }
+
+    let (output, exit_code) =
+        if let Some(result) = am.precompile(&input.callee, &input.input, input.gas_limit) {
+            (result.output, result.exit_code)
+        } else if callee_account.is_empty_code_hash() {
+            (Bytes::new(), ExitCode::Ok.into_i32())
+        } else if let BytecodeType::EVM =
+            BytecodeType::from_slice(&am.preimage(&callee_account.source_code_hash))
+        {
+            (Bytes::new(), ExitCode::NotSupportedCall.into_i32())
+        } else {
+            let mut context = ContractInput::clone_from_cr(cr);
+            context.contract_gas_limit = input.gas_limit;
+            context.contract_caller_gas_remaining = contract_fuel_remaining(cr);
+            context.contract_address = input.callee;
+            context.contract_caller = cr.contract_address();
+            context.contract_value = input.value;
+            context.contract_depth = input.depth;
+            let contract_context = context.encode_to_vec(0);
+
+            let mut fuel = input.gas_limit.min(u32::MAX as u64) as u32;
+            am.exec_hash(
+                callee_account.rwasm_code_hash.as_ptr(),
+                &contract_context,
+                &input.input,
+                &mut fuel as *mut u32,
+                STATE_MAIN,
+            )
+        };
+    if ExitCode::from(exit_code).is_ok() {
+        return Ok(output);
+    }
+    am.rollback(checkpoint);
+    Err(CallError::Reverted {
+        exit_code,
+        revert_data: output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contract,
+        Account,
+        AccountCheckpoint,
+        AccountStorage,
+        EvmCallMethodOutput,
+        ExecutionContext,
+        JzktAccountManager,
+        LowLevelSDK,
+        SharedAPI,
+        StorageBackend,
+        TestEnv,
+    };
+    use alloc::vec;
+    use alloy_sol_types::{sol, SolCall, SolValue};
+    use core::cell::RefCell;
+    use fluentbase_codec::BufferDecoder;
+    use fluentbase_types::{Address, JournalLog, B256, U256};
+
+    const PRECOMPILE: Address = Address::with_last_byte(1);
+    const EVM_CONTRACT: Address = Address::repeat_byte(0xee);
+    const EMPTY_ACCOUNT: Address = Address::repeat_byte(0xff);
+
+    /// Calls nested contracts, every callee makes a call with `call_contract` in its turn.
+    #[derive(Default)]
+    struct NestedCalls {
+        contexts: RefCell<Vec<ContractInput>>,
+    }
+
+    fn call_input(callee: Address, gas_limit: u64, depth: u32) -> EvmCallMethodInput {
+        EvmCallMethodInput {
+            callee,
+            input: Bytes::new(),
+            gas_limit,
+            depth,
+            ..Default::default()
+        }
+    }
+
+    impl AccountManager for NestedCalls {
+        fn checkpoint(&self) -> AccountCheckpoint {
+            0
+        }
+        fn commit(&self) {
+            // changes of nested calls are committed by the transaction
+            unreachable!()
+        }
+        fn rollback(&self, _checkpoint: AccountCheckpoint) {}
+        fn account(&self, address: Address) -> (Account, bool) {
+            let mut account = Account::new(address);
+            if address != EMPTY_ACCOUNT {
+                account.source_code_hash = B256::left_padding_from(address.as_slice());
+                account.rwasm_code_hash = B256::left_padding_from(address.as_slice());
+            }
+            (account, false)
+        }
+        fn exec_hash(
+            &self,
+            _hash32_offset: *const u8,
+            context: &[u8],
+            _input: &[u8],
+            _fuel_offset: *mut u32,
+            _state: u32,
+        ) -> (Bytes, i32) {
+            let mut callee_context = ContractInput::default();
+            ContractInput::decode_body(&mut BufferDecoder::new(context), 0, &mut callee_context);
+            self.contexts.borrow_mut().push(callee_context.clone());
+            if self.contexts.borrow().len() < 3 {
+                let callee = Address::repeat_byte(self.contexts.borrow().len() as u8 + 2);
+                let gas_limit = callee_context.contract_gas_limit / 2;
+                let depth = callee_context.contract_depth + 1;
+                call_contract(&callee_context, self, call_input(callee, gas_limit, depth)).unwrap();
+            }
+            (Bytes::new(), ExitCode::Ok.into_i32())
+        }
+        fn write_account(&self, _account: &Account) {
+            unreachable!()
+        }
+        fn preimage_size(&self, _hash: &[u8; 32]) -> u32 {
+            unreachable!()
+        }
+        fn preimage(&self, hash: &[u8; 32]) -> Bytes {
+            if &hash[12..] == EVM_CONTRACT.as_slice() {
+                Bytes::from_static(&[0x60, 0x80, 0x60, 0x40])
+            } else {
+                Bytes::from_static(&[0x00, 0x61, 0x73, 0x6d])
+            }
+        }
+        fn update_preimage(&self, _key: &[u8; 32], _field: u32, _preimage: &[u8]) {
+            unreachable!()
+        }
+        fn storage(&self, _address: Address, _slot: U256, _committed: bool) -> (U256, bool) {
+            unreachable!()
+        }
+        fn write_storage(&self, _address: Address, _slot: U256, _value: U256) -> bool {
+            unreachable!()
+        }
+        fn log(&self, _address: Address, _data: Bytes, _topics: &[B256]) {
+            unreachable!()
+        }
+        fn inc_nonce(&self, _account: &mut Account) -> Option<u64> {
+            unreachable!()
+        }
+        fn transfer(
+            &self,
+            _from: &mut Account,
+            _to: &mut Account,
+            _value: U256,
+        ) -> Result<(), ExitCode> {
+            unreachable!()
+        }
+        fn precompile(
+            &self,
+            address: &Address,
+            _input: &Bytes,
+            _gas: u64,
+        ) -> Option<EvmCallMethodOutput> {
+            (*address == PRECOMPILE).then(|| {
+                EvmCallMethodOutput::from_exit_code(ExitCode::Ok)
+                    .with_output(Bytes::from_static(b"precompile"))
+            })
+        }
+        fn is_precompile(&self, _address: &Address) -> bool {
+            unreachable!()
+        }
+        fn self_destruct(&self, _address: Address, _target: Address) -> [bool; 4] {
+            unreachable!()
+        }
+        fn block_hash(&self, _number: U256) -> B256 {
+            unreachable!()
+        }
+        fn write_transient_storage(&self, _address: Address, _index: U256, _value: U256) {
+            unreachable!()
+        }
+        fn transient_storage(&self, _address: Address, _index: U256) -> U256 {
+            unreachable!()
+        }
+        fn mark_account_created(&self, _address: Address) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_nested_calls_see_caller_gas_and_depth() {
+        let context = ContractInput {
+            contract_gas_limit: 80_000,
+            contract_caller_gas_remaining: 1_000_000,
+            contract_address: Address::repeat_byte(1),
+            ..Default::default()
+        };
+        let am = NestedCalls::default();
+        call_contract(
+            &context,
+            &am,
+            call_input(Address::repeat_byte(2), 40_000, 1),
+        )
+        .unwrap();
+        let contexts = am.contexts.into_inner();
+        let gas: Vec<(u64, u64)> = contexts
+            .iter()
+            .map(|context| {
+                (
+                    context.contract_gas_limit,
+                    context.contract_caller_gas_remaining,
+                )
+            })
+            .collect();
+        // fuel isn't metered natively, so the caller gas is the gas limit of the caller
+        assert_eq!(
+            gas,
+            vec![(40_000, 80_000), (20_000, 40_000), (10_000, 20_000)]
+        );
+        assert_eq!(contexts[2].contract_caller, Address::repeat_byte(3));
+        let depths: Vec<u32> = contexts
+            .iter()
+            .map(|context| context.contract_depth)
+            .collect();
+        assert_eq!(depths, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_callee_dispatch() {
+        let context = ContractInput::default();
+        let am = NestedCalls::default();
+        let failure = |exit_code: ExitCode| {
+            Err(CallError::Reverted {
+                exit_code: exit_code.into_i32(),
+                revert_data: Bytes::new(),
+            })
+        };
+        assert_eq!(
+            call_contract(&context, &am, call_input(PRECOMPILE, 1000, 0)),
+            Ok(Bytes::from_static(b"precompile"))
+        );
+        assert_eq!(
+            call_contract(&context, &am, call_input(EMPTY_ACCOUNT, 1000, 0)),
+            Ok(Bytes::new())
+        );
+        assert_eq!(
+            call_contract(&context, &am, call_input(EVM_CONTRACT, 1000, 0)),
+            failure(ExitCode::NotSupportedCall)
+        );
+        assert_eq!(
+            call_contract(
+                &context,
+                &am,
+                call_input(Address::repeat_byte(2), 1000, 1025)
+            ),
+            failure(ExitCode::CallDepthOverflow)
+        );
+        // none of them is executed as a WASM contract
+        assert!(am.contexts.borrow().is_empty());
+    }
+
+    #[derive(Default)]
+    struct Counter;
+
+    #[contract]
+    impl Counter {
+        pub fn increment(&mut self) -> U256 {
+            let am = JzktAccountManager::DEFAULT;
+            let address = ExecutionContext::DEFAULT.contract_address();
+            let storage = AccountStorage::new(&am, address);
+            let counter = storage.sload(U256::ZERO) + U256::from(1);
+            storage.sstore(U256::ZERO, counter);
+            am.log(address, Bytes::new(), &[]);
+            counter
+        }
+
+        /// Calls itself until a call fails, returns the depth of the failed call.
+        pub fn nest(&mut self) -> u32 {
+            let cr = ExecutionContext::DEFAULT;
+            match CounterClient::new(cr.contract_address()).nest() {
+                Ok(depth) => depth,
+                Err(CallError::Reverted { exit_code, .. })
+                    if exit_code == ExitCode::CallDepthOverflow.into_i32() =>
+                {
+                    cr.contract_depth() + 1
+                }
+                Err(err) => panic!("unexpected call error: {:?}", err),
+            }
+        }
+    }
+
+    fn counter_main() {
+        let output = Counter.route(ExecutionContext::contract_input());
+        LowLevelSDK::write(&output);
+    }
+
+    #[test]
+    fn test_nested_call_is_rolled_back_with_caller() {
+        let caller = Address::repeat_byte(1);
+        let counter = Address::repeat_byte(2);
+        let mut env = TestEnv::new().with_address(caller);
+        env.set_contract(counter, counter_main);
+        let main = |revert: bool| {
+            move || {
+                let am = JzktAccountManager::DEFAULT;
+                am.log(caller, Bytes::new(), &[]);
+                assert_eq!(CounterClient::new(counter).increment(), Ok(U256::from(1)));
+                if revert {
+                    LowLevelSDK::exit(ExitCode::Panic.into_i32());
+                }
+            }
+        };
+        let log = |address: Address| JournalLog {
+            address,
+            topics: vec![],
+            data: Bytes::new(),
+        };
+
+        let result = env.call(&[], main(true));
+        assert_eq!(result.exit_code, ExitCode::Panic.into_i32());
+        assert!(result.logs.is_empty());
+        assert_eq!(env.storage(counter, U256::ZERO), U256::ZERO);
+
+        let result = env.call(&[], main(false));
+        assert!(result.is_ok());
+        assert_eq!(result.logs, vec![log(caller), log(counter)]);
+        assert_eq!(env.storage(counter, U256::ZERO), U256::from(1));
+    }
+
+    #[test]
+    fn test_nested_calls_overflow_call_depth() {
+        // every nested call takes a few native stack frames
+        let thread = std::thread::Builder::new().stack_size(256 << 20).spawn(|| {
+            let counter = Address::repeat_byte(2);
+            let mut env = TestEnv::new().with_address(counter);
+            env.set_contract(counter, counter_main);
+            let result = env.call(&nestCall {}.abi_encode(), counter_main);
+            assert!(result.is_ok());
+            assert_eq!(u32::abi_decode(&result.output, true).unwrap(), 1025);
+        });
+        thread.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn test_call_error_with_custom() {
+        let reverted = CallError::Reverted {
+            exit_code: ExitCode::Panic.into_i32(),
+            revert_data: Bytes::from(vec![1, 2, 3]),
+        };
+        let decoded = reverted
+            .clone()
+            .with_custom(|data| (data[0] == 1).then_some(data.len()));
+        assert_eq!(decoded, CallError::Custom(3));
+        let kept = reverted.clone().with_custom(|_| None::<usize>);
+        assert_eq!(
+            kept,
+            CallError::Reverted {
+                exit_code: ExitCode::Panic.into_i32(),
+                revert_data: Bytes::from(vec![1, 2, 3]),
+            }
+        );
+        let invalid = CallError::InvalidOutput(Bytes::new()).with_custom(|_| Some(0u8));
+        assert_eq!(invalid, CallError::InvalidOutput(Bytes::new()));
+    }
+}
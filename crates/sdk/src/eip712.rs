@@ -0,0 +1,530 @@
+//! EIP-712 hashing of typed structured data and recovery of signers.
+//!
+//! Structs get `type_hash` and `struct_hash` with `#[derive(Eip712)]`, field names are used as
+//! they are, a field can be renamed with `#[eip712(name = "...")]` and a struct as well.
+//! Signed messages are hashed with a domain of the contract, like:
+//!
+//! ```rust,ignore
+//! #[derive(Eip712)]
+//! struct Permit {
+//!     owner: Address,
+//!     spender: Address,
+//!     value: U256,
+//!     nonce: U256,
+//!     deadline: U256,
+//! }
+//!
+//! let domain = Eip712Domain::from_context(&ExecutionContext::DEFAULT, "Token", "1");
+//! let signer = recover_signer::<LowLevelSDK>(&permit.eip712_hash(&domain), &signature);
+//! ```
+use crate::{ContextReader, SharedAPI};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use fluentbase_types::{Address, Bytes, FixedBytes, B256, U256};
+
+/// A type that can be a member of an EIP-712 struct.
+pub trait Eip712Type {
+    /// Name of the type in an encoded type, like `uint256` or `Person[]`.
+    fn type_name() -> String;
+
+    /// Encoding of the value as a member of a struct, it's always one word.
+    fn encode_value(&self) -> B256;
+
+    /// Adds encoded types of structs the type refers to, keyed by names of structs.
+    fn add_struct_types(_types: &mut BTreeMap<String, String>) {}
+}
+
+/// A struct of EIP-712 typed data, it's implemented by `#[derive(Eip712)]`.
+pub trait Eip712: Eip712Type {
+    /// Encoded members of the struct, one word per member.
+    fn encode_data(&self) -> Vec<u8>;
+
+    /// Encoded type of the struct followed by types it refers to, sorted by name, like
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+    fn encode_type() -> String {
+        let mut types = BTreeMap::new();
+        Self::add_struct_types(&mut types);
+        let mut encoded = types.remove(&Self::type_name()).unwrap_or_default();
+        encoded.extend(types.into_values());
+        encoded
+    }
+
+    fn type_hash() -> B256 {
+        keccak256(Self::encode_type().as_bytes())
+    }
+
+    fn struct_hash(&self) -> B256 {
+        let mut data = Self::type_hash().to_vec();
+        data.extend(self.encode_data());
+        keccak256(&data)
+    }
+
+    /// Digest of the struct signed in the domain, `keccak256(0x1901 ‖ domain ‖ struct)`.
+    fn eip712_hash(&self, domain: &Eip712Domain) -> B256 {
+        let mut data = [0u8; 66];
+        data[0..2].copy_from_slice(&[0x19, 0x01]);
+        data[2..34].copy_from_slice(domain.separator().as_slice());
+        data[34..66].copy_from_slice(self.struct_hash().as_slice());
+        keccak256(&data)
+    }
+}
+
+/// Domain of signed messages, fields that are `None` aren't a part of the domain type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Eip712Domain {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<U256>,
+    pub verifying_contract: Option<Address>,
+    pub salt: Option<B256>,
+}
+
+impl Eip712Domain {
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: Some(name.into()),
+            version: Some(version.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Domain of the executed contract, it's bound to the chain id and the address of the
+    /// contract, so a signature can't be replayed on another chain or contract.
+    pub fn from_context<CR: ContextReader>(cr: &CR, name: &str, version: &str) -> Self {
+        Self::new(name, version)
+            .with_chain_id(cr.block_chain_id())
+            .with_verifying_contract(cr.contract_address())
+    }
+
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(U256::from(chain_id));
+        self
+    }
+
+    pub fn with_verifying_contract(mut self, verifying_contract: Address) -> Self {
+        self.verifying_contract = Some(verifying_contract);
+        self
+    }
+
+    pub fn with_salt(mut self, salt: B256) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    pub fn encode_type(&self) -> String {
+        let mut members = Vec::new();
+        if self.name.is_some() {
+            members.push("string name");
+        }
+        if self.version.is_some() {
+            members.push("string version");
+        }
+        if self.chain_id.is_some() {
+            members.push("uint256 chainId");
+        }
+        if self.verifying_contract.is_some() {
+            members.push("address verifyingContract");
+        }
+        if self.salt.is_some() {
+            members.push("bytes32 salt");
+        }
+        format!("EIP712Domain({})", members.join(","))
+    }
+
+    /// Domain separator, the hash of the domain struct.
+    pub fn separator(&self) -> B256 {
+        let mut data = keccak256(self.encode_type().as_bytes()).to_vec();
+        if let Some(name) = &self.name {
+            data.extend(name.encode_value());
+        }
+        if let Some(version) = &self.version {
+            data.extend(version.encode_value());
+        }
+        if let Some(chain_id) = &self.chain_id {
+            data.extend(chain_id.encode_value());
+        }
+        if let Some(verifying_contract) = &self.verifying_contract {
+            data.extend(verifying_contract.encode_value());
+        }
+        if let Some(salt) = &self.salt {
+            data.extend(salt.encode_value());
+        }
+        keccak256(&data)
+    }
+}
+
+/// Order of the secp256k1 curve, `r` and `s` of a signature are below it.
+const SECP256K1_ORDER: U256 = U256::from_be_bytes([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+]);
+
+/// Prime of the field of secp256k1 coordinates.
+const SECP256K1_PRIME: U256 = U256::from_be_bytes([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xfc, 0x2f,
+]);
+
+/// Order of the secp256k1 curve divided by two, greater `s` values are malleable (EIP-2).
+const SECP256K1_HALF_ORDER: U256 = U256::from_be_bytes([
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+]);
+
+/// Recovers the signer of a digest from a `r ‖ s ‖ v` signature, `v` is either 27/28 or 0/1.
+///
+/// Returns `None` for a malformed signature, a malleable one with high `s` (like `ecrecover` of
+/// OpenZeppelin does) or a signature with `r` that isn't an x-coordinate of a curve point.
+///
+/// The `ecrecover` syscall traps on signatures that don't recover to a public key, and these
+/// checks don't catch all of them: a signature can be crafted to recover to the point at
+/// infinity, so `recover_signer` traps on it and the whole call fails.
+pub fn recover_signer<SDK: SharedAPI>(digest: &B256, signature: &[u8; 65]) -> Option<Address> {
+    let r = U256::from_be_slice(&signature[0..32]);
+    let s = U256::from_be_slice(&signature[32..64]);
+    if r.is_zero() || r >= SECP256K1_ORDER || s.is_zero() || s > SECP256K1_HALF_ORDER {
+        return None;
+    }
+    if !is_curve_x(r) {
+        return None;
+    }
+    let rec_id = match signature[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return None,
+    };
+    let mut public_key = [0u8; 65];
+    SDK::ecrecover(
+        digest.as_ptr(),
+        signature.as_ptr(),
+        public_key.as_mut_ptr(),
+        rec_id,
+    );
+    let mut hash = B256::ZERO;
+    SDK::keccak256(public_key[1..].as_ptr(), 64, hash.as_mut_ptr());
+    Some(Address::from_word(hash))
+}
+
+/// Whether there is a point of secp256k1 with the x-coordinate, that is `x³ + 7` is a square
+/// modulo the field prime (Euler's criterion).
+fn is_curve_x(x: U256) -> bool {
+    let y2 = x
+        .mul_mod(x, SECP256K1_PRIME)
+        .mul_mod(x, SECP256K1_PRIME)
+        .add_mod(U256::from(7), SECP256K1_PRIME);
+    y2.is_zero()
+        || y2.pow_mod((SECP256K1_PRIME - U256::from(1)) >> 1, SECP256K1_PRIME) == U256::from(1)
+}
+
+fn keccak256(data: &[u8]) -> B256 {
+    let mut hash = B256::ZERO;
+    crate::LowLevelSDK::keccak256(data.as_ptr(), data.len() as u32, hash.as_mut_ptr());
+    hash
+}
+
+macro_rules! impl_eip712_uint {
+    ($($typ:ty => $name:literal),*) => {$(
+        impl Eip712Type for $typ {
+            fn type_name() -> String {
+                $name.into()
+            }
+
+            fn encode_value(&self) -> B256 {
+                U256::from(*self).into()
+            }
+        }
+    )*};
+}
+
+impl_eip712_uint!(u8 => "uint8", u16 => "uint16", u32 => "uint32", u64 => "uint64", u128 => "uint128");
+
+macro_rules! impl_eip712_int {
+    ($($typ:ty => $name:literal),*) => {$(
+        impl Eip712Type for $typ {
+            fn type_name() -> String {
+                $name.into()
+            }
+
+            fn encode_value(&self) -> B256 {
+                // sign extended to a word, the same as ABI encoding
+                let fill = if *self < 0 { 0xff } else { 0 };
+                let mut word = B256::repeat_byte(fill);
+                let bytes = (*self as i128).to_be_bytes();
+                word[16..].copy_from_slice(&bytes);
+                word
+            }
+        }
+    )*};
+}
+
+impl_eip712_int!(i8 => "int8", i16 => "int16", i32 => "int32", i64 => "int64", i128 => "int128");
+
+impl Eip712Type for U256 {
+    fn type_name() -> String {
+        "uint256".into()
+    }
+
+    fn encode_value(&self) -> B256 {
+        (*self).into()
+    }
+}
+
+impl Eip712Type for bool {
+    fn type_name() -> String {
+        "bool".into()
+    }
+
+    fn encode_value(&self) -> B256 {
+        U256::from(*self as u8).into()
+    }
+}
+
+impl Eip712Type for Address {
+    fn type_name() -> String {
+        "address".into()
+    }
+
+    fn encode_value(&self) -> B256 {
+        self.into_word()
+    }
+}
+
+impl<const N: usize> Eip712Type for FixedBytes<N> {
+    fn type_name() -> String {
+        format!("bytes{}", N)
+    }
+
+    fn encode_value(&self) -> B256 {
+        let mut word = B256::ZERO;
+        word[..N].copy_from_slice(self.as_slice());
+        word
+    }
+}
+
+impl Eip712Type for Bytes {
+    fn type_name() -> String {
+        "bytes".into()
+    }
+
+    fn encode_value(&self) -> B256 {
+        keccak256(self)
+    }
+}
+
+impl Eip712Type for String {
+    fn type_name() -> String {
+        "string".into()
+    }
+
+    fn encode_value(&self) -> B256 {
+        keccak256(self.as_bytes())
+    }
+}
+
+/// Arrays are hashed as concatenated encodings of their elements.
+fn encode_array<T: Eip712Type>(elements: &[T]) -> B256 {
+    let mut data = Vec::with_capacity(elements.len() * 32);
+    for element in elements {
+        data.extend(element.encode_value());
+    }
+    keccak256(&data)
+}
+
+impl<T: Eip712Type> Eip712Type for Vec<T> {
+    fn type_name() -> String {
+        format!("{}[]", T::type_name())
+    }
+
+    fn encode_value(&self) -> B256 {
+        encode_array(self)
+    }
+
+    fn add_struct_types(types: &mut BTreeMap<String, String>) {
+        T::add_struct_types(types)
+    }
+}
+
+impl<T: Eip712Type, const N: usize> Eip712Type for [T; N] {
+    fn type_name() -> String {
+        format!("{}[{}]", T::type_name(), N)
+    }
+
+    fn encode_value(&self) -> B256 {
+        encode_array(self)
+    }
+
+    fn add_struct_types(types: &mut BTreeMap<String, String>) {
+        T::add_struct_types(types)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContractInput, Eip712, LowLevelSDK};
+    use fluentbase_types::{address, b256, hex};
+
+    #[derive(Eip712)]
+    struct Person {
+        name: String,
+        wallet: Address,
+    }
+
+    #[derive(Eip712)]
+    struct Mail {
+        from: Person,
+        to: Person,
+        contents: String,
+    }
+
+    /// The example of the EIP.
+    fn mail() -> (Eip712Domain, Mail) {
+        let domain = Eip712Domain::new("Ether Mail", "1")
+            .with_chain_id(1)
+            .with_verifying_contract(address!("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"));
+        let mail = Mail {
+            from: Person {
+                name: "Cow".into(),
+                wallet: address!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+            },
+            to: Person {
+                name: "Bob".into(),
+                wallet: address!("bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+            },
+            contents: "Hello, Bob!".into(),
+        };
+        (domain, mail)
+    }
+
+    #[test]
+    fn test_eip712_hash() {
+        let (domain, mail) = mail();
+        assert_eq!(
+            Mail::encode_type(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            Mail::type_hash(),
+            b256!("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+        assert_eq!(
+            domain.separator(),
+            b256!("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            mail.struct_hash(),
+            b256!("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            mail.eip712_hash(&domain),
+            b256!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+    }
+
+    #[derive(Eip712)]
+    #[eip712(name = "Order")]
+    struct SignedOrder {
+        #[eip712(name = "tokenIds")]
+        token_ids: Vec<U256>,
+        flags: [bool; 2],
+        delta: i32,
+        data: Bytes,
+    }
+
+    #[test]
+    fn test_encode_type_with_renames_and_arrays() {
+        assert_eq!(
+            SignedOrder::encode_type(),
+            "Order(uint256[] tokenIds,bool[2] flags,int32 delta,bytes data)"
+        );
+        let order = SignedOrder {
+            token_ids: vec![U256::from(1), U256::from(2)],
+            flags: [true, false],
+            delta: -1,
+            data: Bytes::from_static(&[1, 2, 3]),
+        };
+        let data = order.encode_data();
+        assert_eq!(data.len(), 4 * 32);
+        assert_eq!(
+            &data[0..32],
+            keccak256(&hex!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+                "0000000000000000000000000000000000000000000000000000000000000002"
+            ))
+            .as_slice()
+        );
+        assert_eq!(&data[64..96], [0xff; 32].as_slice());
+        assert_eq!(&data[96..128], keccak256(&[1, 2, 3]).as_slice());
+    }
+
+    #[test]
+    fn test_domain_from_context() {
+        let context = ContractInput {
+            block_chain_id: 20993,
+            contract_address: Address::repeat_byte(1),
+            ..Default::default()
+        };
+        let domain = Eip712Domain::from_context(&context, "Token", "1");
+        assert_eq!(domain.chain_id, Some(U256::from(20993)));
+        assert_eq!(domain.verifying_contract, Some(Address::repeat_byte(1)));
+        assert_eq!(
+            domain.encode_type(),
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        );
+    }
+
+    #[test]
+    fn test_recover_signer() {
+        let (domain, mail) = mail();
+        let digest = mail.eip712_hash(&domain);
+        // signed by the key `keccak256("cow")` in the example of the EIP
+        let mut signature = [0u8; 65];
+        signature.copy_from_slice(&hex!(
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"
+            "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"
+            "1c"
+        ));
+        assert_eq!(
+            recover_signer::<LowLevelSDK>(&digest, &signature),
+            Some(mail.from.wallet)
+        );
+        signature[64] = 1;
+        assert_eq!(
+            recover_signer::<LowLevelSDK>(&digest, &signature),
+            Some(mail.from.wallet)
+        );
+        signature[64] = 2;
+        assert_eq!(recover_signer::<LowLevelSDK>(&digest, &signature), None);
+        signature[64] = 27;
+        // `r` out of range
+        let mut malformed = signature;
+        malformed[0..32].copy_from_slice(&SECP256K1_ORDER.to_be_bytes::<32>());
+        assert_eq!(recover_signer::<LowLevelSDK>(&digest, &malformed), None);
+        // `r` isn't an x-coordinate of a point on the curve, the syscall would trap on it
+        assert!(!is_curve_x(U256::from(5)));
+        assert!(is_curve_x(U256::from_be_slice(&signature[0..32])));
+        malformed[0..32].copy_from_slice(&U256::from(5).to_be_bytes::<32>());
+        assert_eq!(recover_signer::<LowLevelSDK>(&digest, &malformed), None);
+        // the same signature with `s` negated
+        let s = SECP256K1_ORDER - U256::from_be_slice(&signature[32..64]);
+        signature[32..64].copy_from_slice(&s.to_be_bytes::<32>());
+        assert_eq!(recover_signer::<LowLevelSDK>(&digest, &signature), None);
+    }
+
+    /// `R = kG` with `k = 1`, `r = R.x` and `s = e/k` pass all the checks, but the recovered key
+    /// is `r⁻¹(sR - eG)`, the point at infinity.
+    #[test]
+    #[should_panic(expected = "EcrecoverError")]
+    fn test_recover_signer_traps_on_signature_recovering_to_infinity() {
+        let digest = B256::repeat_byte(0x11);
+        let mut signature = [0u8; 65];
+        // the x-coordinate of the generator, its y-coordinate is even
+        signature[0..32].copy_from_slice(&hex!(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        ));
+        signature[32..64].copy_from_slice(digest.as_slice());
+        signature[64] = 27;
+        assert!(is_curve_x(U256::from_be_slice(&signature[0..32])));
+        assert!(U256::from_be_slice(&signature[32..64]) <= SECP256K1_HALF_ORDER);
+        recover_signer::<LowLevelSDK>(&digest, &signature);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::B256;
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{EventTopic, SolType, SolValue};
+use fluentbase_types::Bytes;
+
+/// A value that can be set to a field of a `sol!` event by `emit!`.
+///
+/// `sol!` keeps indexed fields of dynamic types (strings, bytes and arrays) as `keccak256`
+/// hashes, so values of these types are hashed the same way Solidity does it, other fields take
+/// values as is.
+pub trait EventField<T> {
+    fn into_event_field(self) -> T;
+}
+
+impl<T> EventField<T> for T {
+    #[inline(always)]
+    fn into_event_field(self) -> T {
+        self
+    }
+}
+
+/// Computes a topic of an indexed field of a dynamic type.
+#[inline(always)]
+pub fn event_topic<V: SolValue + ?Sized>(value: &V) -> B256
+where
+    V::SolType: EventTopic,
+{
+    let value = <V::SolType as SolType>::detokenize(value.tokenize());
+    <V::SolType as EventTopic>::encode_topic(&value).0
+}
+
+macro_rules! impl_hashed_event_field {
+    ($([$($gen:tt)*] $ty:ty;)+) => {$(
+        impl<$($gen)*> EventField<B256> for $ty
+        where
+            $ty: SolValue,
+            <$ty as SolValue>::SolType: EventTopic,
+        {
+            #[inline(always)]
+            fn into_event_field(self) -> B256 {
+                event_topic(&self)
+            }
+        }
+    )+};
+}
+
+// vectors and slices of bytes are `bytes`, of other types are arrays
+impl_hashed_event_field! {
+    ['a] &'a str;
+    [] String;
+    ['a] &'a String;
+    [] Bytes;
+    ['a] &'a Bytes;
+    [T] Vec<T>;
+    ['a, T] &'a [T];
+    [T, const N: usize] [T; N];
+}
+
+impl<'a> EventField<String> for &'a str {
+    #[inline(always)]
+    fn into_event_field(self) -> String {
+        self.into()
+    }
+}
+
+impl<'a> EventField<Bytes> for &'a [u8] {
+    #[inline(always)]
+    fn into_event_field(self) -> Bytes {
+        Bytes::copy_from_slice(self)
+    }
+}
+
+impl EventField<Bytes> for Vec<u8> {
+    #[inline(always)]
+    fn into_event_field(self) -> Bytes {
+        self.into()
+    }
+}
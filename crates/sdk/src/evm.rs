@@ -23,9 +23,16 @@ pub trait ContextReader {
     fn block_coinbase(&self) -> Address;
     fn block_timestamp(&self) -> u64;
     fn block_number(&self) -> u64;
-    fn block_difficulty(&self) -> u64;
+    fn block_difficulty(&self) -> U256;
+    fn block_prevrandao(&self) -> B256;
     fn block_gas_limit(&self) -> u64;
     fn block_base_fee(&self) -> U256;
+    fn block_blob_base_fee(&self) -> U256;
+    /// Hash of the transaction, it's zero if the node executing it doesn't provide the hash
+    /// (see `EvmBuilder::with_tx_hash`)
+    fn tx_hash(&self) -> B256;
+    /// EIP-2718 type of the transaction
+    fn tx_type(&self) -> u8;
     fn tx_gas_limit(&self) -> u64;
     fn tx_nonce(&self) -> u64;
     fn tx_gas_price(&self) -> U256;
@@ -36,10 +43,14 @@ pub trait ContextReader {
     fn tx_blob_hashes_size(&self) -> (u32, u32);
     fn tx_max_fee_per_blob_gas(&self) -> Option<U256>;
     fn contract_gas_limit(&self) -> u64;
+    fn contract_caller_gas_remaining(&self) -> u64;
     fn contract_address(&self) -> Address;
     fn contract_caller(&self) -> Address;
     fn contract_value(&self) -> U256;
     fn contract_is_static(&self) -> bool;
+    /// Number of calls the contract is nested in, it's zero for the contract called by the
+    /// transaction
+    fn contract_depth(&self) -> u32;
 }
 
 #[derive(Clone, Debug, Default, Codec)]
@@ -49,7 +60,9 @@ pub struct ContractInput {
     pub block_coinbase: Address,
     pub block_timestamp: u64,
     pub block_number: u64,
-    pub block_difficulty: u64,
+    /// Difficulty saturated to `u64`, it's left for contracts compiled before `block_difficulty`
+    /// became `U256`
+    pub block_difficulty_u64: u64,
     pub block_gas_limit: u64,
     pub block_base_fee: U256,
     // tx info
@@ -67,6 +80,16 @@ pub struct ContractInput {
     pub contract_caller: Address,
     pub contract_value: U256,
     pub contract_is_static: bool,
+    // new fields are appended, offsets of the fields above are compiled into deployed contracts
+    pub block_difficulty: U256,
+    /// Randomness of the block after the merge, it replaced `mix_hash`
+    pub block_prevrandao: B256,
+    pub block_blob_base_fee: U256,
+    pub tx_hash: B256,
+    pub tx_type: u8,
+    /// Gas left to the caller when it made the call
+    pub contract_caller_gas_remaining: u64,
+    pub contract_depth: u32,
 }
 
 impl ContractInput {
@@ -76,7 +99,7 @@ impl ContractInput {
             block_coinbase: cr.block_coinbase(),
             block_timestamp: cr.block_timestamp(),
             block_number: cr.block_number(),
-            block_difficulty: cr.block_difficulty(),
+            block_difficulty_u64: cr.block_difficulty().saturating_to(),
             block_gas_limit: cr.block_gas_limit(),
             block_base_fee: cr.block_base_fee(),
             tx_gas_limit: cr.tx_gas_limit(),
@@ -92,10 +115,23 @@ impl ContractInput {
             contract_caller: cr.contract_caller(),
             contract_value: cr.contract_value(),
             contract_is_static: cr.contract_is_static(),
+            block_difficulty: cr.block_difficulty(),
+            block_prevrandao: cr.block_prevrandao(),
+            block_blob_base_fee: cr.block_blob_base_fee(),
+            tx_hash: cr.tx_hash(),
+            tx_type: cr.tx_type(),
+            contract_caller_gas_remaining: cr.contract_caller_gas_remaining(),
+            contract_depth: cr.contract_depth(),
         }
     }
 }
 
+/// Fuel left to the executed contract, it's the caller gas of calls the contract makes. Fuel
+/// isn't metered if contracts run natively, so it's capped by the gas limit of the contract.
+pub fn contract_fuel_remaining<CR: ContextReader>(cr: &CR) -> u64 {
+    LowLevelSDK::charge_fuel(0).min(cr.contract_gas_limit())
+}
+
 impl ContextReader for ContractInput {
     fn block_chain_id(&self) -> u64 {
         self.block_chain_id
@@ -113,10 +149,14 @@ impl ContextReader for ContractInput {
         self.block_number
     }
 
-    fn block_difficulty(&self) -> u64 {
+    fn block_difficulty(&self) -> U256 {
         self.block_difficulty
     }
 
+    fn block_prevrandao(&self) -> B256 {
+        self.block_prevrandao
+    }
+
     fn block_gas_limit(&self) -> u64 {
         self.block_gas_limit
     }
@@ -125,6 +165,18 @@ impl ContextReader for ContractInput {
         self.block_base_fee
     }
 
+    fn block_blob_base_fee(&self) -> U256 {
+        self.block_blob_base_fee
+    }
+
+    fn tx_hash(&self) -> B256 {
+        self.tx_hash
+    }
+
+    fn tx_type(&self) -> u8 {
+        self.tx_type
+    }
+
     fn tx_gas_limit(&self) -> u64 {
         self.tx_gas_limit
     }
@@ -165,6 +217,10 @@ impl ContextReader for ContractInput {
         self.contract_gas_limit
     }
 
+    fn contract_caller_gas_remaining(&self) -> u64 {
+        self.contract_caller_gas_remaining
+    }
+
     fn contract_address(&self) -> Address {
         self.contract_address
     }
@@ -180,6 +236,10 @@ impl ContextReader for ContractInput {
     fn contract_is_static(&self) -> bool {
         self.contract_is_static
     }
+
+    fn contract_depth(&self) -> u32 {
+        self.contract_depth
+    }
 }
 
 macro_rules! impl_reader_helper {
@@ -271,10 +331,14 @@ impl ContextReader for ExecutionContext {
     impl_reader_func!(fn block_coinbase() -> Address, BlockCoinbase);
     impl_reader_func!(fn block_timestamp() -> u64, BlockTimestamp);
     impl_reader_func!(fn block_number() -> u64, BlockNumber);
-    impl_reader_func!(fn block_difficulty() -> u64, BlockDifficulty);
+    impl_reader_func!(fn block_difficulty() -> U256, BlockDifficulty);
+    impl_reader_func!(fn block_prevrandao() -> B256, BlockPrevrandao);
     impl_reader_func!(fn block_gas_limit() -> u64, BlockGasLimit);
     impl_reader_func!(fn block_base_fee() -> U256, BlockBaseFee);
+    impl_reader_func!(fn block_blob_base_fee() -> U256, BlockBlobBaseFee);
     // tx info
+    impl_reader_func!(fn tx_hash() -> B256, TxHash);
+    impl_reader_func!(fn tx_type() -> u8, TxType);
     impl_reader_func!(fn tx_gas_limit() -> u64, TxGasLimit);
     impl_reader_func!(fn tx_nonce() -> u64, TxNonce);
     impl_reader_func!(fn tx_gas_price() -> U256, TxGasPrice);
@@ -285,10 +349,12 @@ impl ContextReader for ExecutionContext {
     impl_reader_func!(fn tx_max_fee_per_blob_gas() -> Option<U256>, TxMaxFeePerBlobGas);
     // contract info
     impl_reader_func!(fn contract_gas_limit() -> u64, ContractGasLimit);
+    impl_reader_func!(fn contract_caller_gas_remaining() -> u64, ContractCallerGasRemaining);
     impl_reader_func!(fn contract_address() -> Address, ContractAddress);
     impl_reader_func!(fn contract_caller() -> Address, ContractCaller);
     impl_reader_func!(fn contract_value() -> U256, ContractValue);
     impl_reader_func!(fn contract_is_static() -> bool, ContractIsStatic);
+    impl_reader_func!(fn contract_depth() -> u32, ContractDepth);
 }
 
 impl ExecutionContext {
@@ -299,3 +365,58 @@ impl ExecutionContext {
         input
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestEnv;
+
+    #[test]
+    fn test_context_reader() {
+        let mut env = TestEnv::new();
+        env.context = ContractInput {
+            block_difficulty: U256::MAX,
+            block_prevrandao: B256::repeat_byte(1),
+            block_blob_base_fee: U256::from(7),
+            tx_hash: B256::repeat_byte(2),
+            tx_type: 3,
+            tx_blob_hashes: vec![B256::repeat_byte(3)],
+            contract_gas_limit: 100,
+            contract_caller_gas_remaining: 200,
+            contract_address: Address::repeat_byte(4),
+            contract_depth: 5,
+            ..Default::default()
+        };
+        let expected = env.context.clone();
+        let result = env.call(&[], || {
+            let cr = ExecutionContext::DEFAULT;
+            assert_eq!(cr.block_difficulty(), expected.block_difficulty);
+            assert_eq!(cr.block_prevrandao(), expected.block_prevrandao);
+            assert_eq!(cr.block_blob_base_fee(), expected.block_blob_base_fee);
+            assert_eq!(cr.tx_hash(), expected.tx_hash);
+            assert_eq!(cr.tx_type(), expected.tx_type);
+            assert_eq!(cr.tx_blob_hashes(), expected.tx_blob_hashes);
+            assert_eq!(cr.contract_gas_limit(), expected.contract_gas_limit);
+            assert_eq!(
+                cr.contract_caller_gas_remaining(),
+                expected.contract_caller_gas_remaining
+            );
+            assert_eq!(cr.contract_address(), expected.contract_address);
+            assert_eq!(cr.contract_depth(), expected.contract_depth);
+        });
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_field_offsets_are_stable() {
+        // contracts compiled before new fields were added read these offsets
+        type CI = ContractInput;
+        assert_eq!(<CI as IContractInput>::BlockDifficultyU64::FIELD_OFFSET, 44);
+        assert_eq!(<CI as IContractInput>::BlockBaseFee::FIELD_OFFSET, 60);
+        assert_eq!(<CI as IContractInput>::TxCaller::FIELD_OFFSET, 173);
+        assert_eq!(<CI as IContractInput>::TxBlobHashes::FIELD_OFFSET, 205);
+        assert_eq!(<CI as IContractInput>::ContractGasLimit::FIELD_OFFSET, 250);
+        assert_eq!(<CI as IContractInput>::ContractAddress::FIELD_OFFSET, 258);
+        assert_eq!(<CI as IContractInput>::ContractIsStatic::FIELD_OFFSET, 330);
+    }
+}
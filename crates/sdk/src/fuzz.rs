@@ -0,0 +1,691 @@
+//! Property based fuzzing of contracts.
+//!
+//! `Fuzzer` makes random sequences of calls to the functions of a contract, like ones from
+//! `FUNCTION_SIGNATURES` generated by `contract` and `derive_solidity_router`, and checks
+//! invariants after every call. Calls are made with `TestEnv`, every sequence starts from an
+//! empty in-memory state. A failing sequence is shrunk, calls that aren't needed to break the
+//! invariant are dropped and arguments are simplified, so the report is short:
+//!
+//! ```ignore
+//! Fuzzer::new(Token::FUNCTION_SIGNATURES, main)
+//!     .with_setup(|env| assert!(env.deploy(&[], deploy).is_ok()))
+//!     .invariant("supply is the sum of balances", |env| total_supply(env) == sum_of_balances(env))
+//!     .run();
+//! ```
+//!
+//! The seed is taken from `FLUENTBASE_FUZZ_SEED` if it's set, so a failure can be reproduced.
+use crate::{LowLevelSDK, SharedAPI, TestEnv};
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use fluentbase_types::{Address, B256, I256, U256};
+use std::{env, fmt};
+
+/// Environment variable with a seed of the fuzzer.
+const FUZZ_SEED_ENV: &str = "FLUENTBASE_FUZZ_SEED";
+const DEFAULT_SEED: u64 = 0x5eed;
+const MAX_DYNAMIC_LEN: u64 = 4;
+const MAX_BYTES_LEN: u64 = 64;
+
+/// A function of a contract parsed from its signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbiFunction {
+    pub signature: String,
+    pub selector: [u8; 4],
+    pub inputs: Vec<DynSolType>,
+}
+
+impl AbiFunction {
+    /// Parses a canonical signature, like `transfer(address,uint256)`. Function pointers aren't
+    /// supported, there is nothing to generate for them.
+    pub fn parse(signature: &str) -> Option<Self> {
+        let open = signature.find('(')?;
+        let inputs = match DynSolType::parse(&signature[open..]).ok()? {
+            DynSolType::Tuple(inputs) => inputs,
+            _ => return None,
+        };
+        if inputs.iter().any(has_function) {
+            return None;
+        }
+        let mut hash = B256::ZERO;
+        LowLevelSDK::keccak256(
+            signature.as_ptr(),
+            signature.len() as u32,
+            hash.as_mut_ptr(),
+        );
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&hash[0..4]);
+        Some(Self {
+            signature: signature.to_string(),
+            selector,
+            inputs,
+        })
+    }
+
+    /// Encodes calldata of the function, `args` have to be values of its inputs.
+    pub fn encode_call(&self, args: &[DynSolValue]) -> Vec<u8> {
+        let mut input = self.selector.to_vec();
+        input.extend(DynSolValue::Tuple(args.to_vec()).abi_encode_params());
+        input
+    }
+}
+
+fn has_function(ty: &DynSolType) -> bool {
+    match ty {
+        DynSolType::Function => true,
+        DynSolType::Array(elem) | DynSolType::FixedArray(elem, _) => has_function(elem),
+        DynSolType::Tuple(types) => types.iter().any(has_function),
+        _ => false,
+    }
+}
+
+fn format_value(value: &DynSolValue) -> String {
+    let hex = |bytes: &[u8]| {
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("0x{}", hex)
+    };
+    let list = |values: &[DynSolValue]| {
+        let values: Vec<_> = values.iter().map(format_value).collect();
+        values.join(", ")
+    };
+    match value {
+        DynSolValue::Uint(value, _) => value.to_string(),
+        DynSolValue::Int(value, _) => value.to_string(),
+        DynSolValue::Address(address) => address.to_string(),
+        DynSolValue::Bool(value) => value.to_string(),
+        DynSolValue::FixedBytes(word, size) => hex(&word[..*size]),
+        DynSolValue::String(value) => format!("{:?}", value),
+        DynSolValue::Bytes(bytes) => hex(bytes),
+        DynSolValue::Array(values) | DynSolValue::FixedArray(values) => {
+            format!("[{}]", list(values))
+        }
+        DynSolValue::Tuple(values) => format!("({})", list(values)),
+        DynSolValue::Function(_) => unreachable!("functions aren't generated"),
+    }
+}
+
+/// Simpler values to try instead of a failing one, every candidate is strictly smaller, so
+/// shrinking always ends.
+fn shrink_value(value: &DynSolValue) -> Vec<DynSolValue> {
+    match value {
+        DynSolValue::Uint(value, _) if value.is_zero() => Vec::new(),
+        DynSolValue::Uint(value, bits) => vec![
+            DynSolValue::Uint(U256::ZERO, *bits),
+            DynSolValue::Uint(*value >> 1, *bits),
+        ],
+        DynSolValue::Int(value, _) if value.is_zero() => Vec::new(),
+        DynSolValue::Int(_, bits) => vec![DynSolValue::Int(I256::ZERO, *bits)],
+        DynSolValue::Address(address) if address.is_zero() => Vec::new(),
+        DynSolValue::Address(_) => vec![DynSolValue::Address(Address::ZERO)],
+        DynSolValue::Bool(value) if !value => Vec::new(),
+        DynSolValue::Bool(_) => vec![DynSolValue::Bool(false)],
+        DynSolValue::FixedBytes(word, _) if word.is_zero() => Vec::new(),
+        DynSolValue::FixedBytes(_, size) => vec![DynSolValue::FixedBytes(B256::ZERO, *size)],
+        DynSolValue::Bytes(bytes) if bytes.is_empty() => Vec::new(),
+        DynSolValue::Bytes(bytes) => vec![
+            DynSolValue::Bytes(Vec::new()),
+            DynSolValue::Bytes(bytes[..bytes.len() / 2].to_vec()),
+        ],
+        DynSolValue::String(value) if value.is_empty() => Vec::new(),
+        DynSolValue::String(value) => vec![
+            DynSolValue::String(String::new()),
+            // generated strings are ASCII, so any length is a char boundary
+            DynSolValue::String(value[..value.len() / 2].to_string()),
+        ],
+        DynSolValue::Array(values) => {
+            let mut candidates = Vec::new();
+            if !values.is_empty() {
+                candidates.push(DynSolValue::Array(Vec::new()));
+                candidates.push(DynSolValue::Array(values[..values.len() - 1].to_vec()));
+            }
+            candidates.extend(shrink_elements(values, DynSolValue::Array));
+            candidates
+        }
+        DynSolValue::FixedArray(values) => shrink_elements(values, DynSolValue::FixedArray),
+        DynSolValue::Tuple(values) => shrink_elements(values, DynSolValue::Tuple),
+        DynSolValue::Function(_) => unreachable!("functions aren't generated"),
+    }
+}
+
+fn shrink_elements(
+    values: &[DynSolValue],
+    wrap: fn(Vec<DynSolValue>) -> DynSolValue,
+) -> Vec<DynSolValue> {
+    let mut candidates = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        for candidate in shrink_value(value) {
+            let mut values = values.to_vec();
+            values[i] = candidate;
+            candidates.push(wrap(values));
+        }
+    }
+    candidates
+}
+
+/// SplitMix64, it's enough to generate inputs and keeps runs reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+
+    fn word(&mut self) -> U256 {
+        U256::from_limbs([
+            self.next_u64(),
+            self.next_u64(),
+            self.next_u64(),
+            self.next_u64(),
+        ])
+    }
+
+    /// Edge cases are generated more often than random values, they break contracts most.
+    fn uint(&mut self, bits: usize) -> U256 {
+        let max = U256::MAX >> (256 - bits);
+        match self.below(8) {
+            0 => U256::ZERO,
+            1 => U256::from(1),
+            2 => max,
+            3 => max >> 1,
+            4 | 5 => U256::from(self.below(1000)),
+            _ => self.word() & max,
+        }
+    }
+}
+
+/// A call made by the fuzzer.
+#[derive(Debug, Clone)]
+struct FuzzCall {
+    sender: Address,
+    function: usize,
+    args: Vec<DynSolValue>,
+}
+
+/// A shrunk sequence of calls that breaks an invariant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzFailure {
+    pub invariant: String,
+    pub seed: u64,
+    /// Senders and calldata of the calls to replay the failure.
+    pub calls: Vec<(Address, Vec<u8>)>,
+    /// Calls in a readable form, like `transfer(0x.., 100)`.
+    pub trace: Vec<String>,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "invariant `{}` is broken after {} calls (seed {}):",
+            self.invariant,
+            self.trace.len(),
+            self.seed
+        )?;
+        for (i, call) in self.trace.iter().enumerate() {
+            writeln!(f, "  {}. {}", i + 1, call)?;
+        }
+        Ok(())
+    }
+}
+
+type Setup<'a> = Box<dyn Fn(&mut TestEnv) + 'a>;
+type Invariant<'a> = Box<dyn Fn(&mut TestEnv) -> bool + 'a>;
+
+/// Runs random sequences of calls to a contract and checks invariants after every call.
+pub struct Fuzzer<'a> {
+    functions: Vec<AbiFunction>,
+    main: Box<dyn Fn() + 'a>,
+    setup: Option<Setup<'a>>,
+    invariants: Vec<(String, Invariant<'a>)>,
+    address: Address,
+    senders: Vec<Address>,
+    runs: usize,
+    depth: usize,
+    seed: u64,
+}
+
+impl<'a> Fuzzer<'a> {
+    /// Creates a fuzzer of a contract with the `main` entrypoint and functions with the
+    /// signatures, it panics if a signature can't be parsed.
+    pub fn new<M: Fn() + 'a>(signatures: &[&str], main: M) -> Self {
+        let functions = signatures
+            .iter()
+            .map(|signature| {
+                AbiFunction::parse(signature)
+                    .unwrap_or_else(|| panic!("unsupported function signature: {}", signature))
+            })
+            .collect();
+        let seed = env::var(FUZZ_SEED_ENV)
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or(DEFAULT_SEED);
+        Self {
+            functions,
+            main: Box::new(main),
+            setup: None,
+            invariants: Vec::new(),
+            address: Address::repeat_byte(0xc0),
+            senders: (1..=3).map(Address::repeat_byte).collect(),
+            runs: 256,
+            depth: 16,
+            seed,
+        }
+    }
+
+    /// Prepares the state before every sequence, like deploys the contract.
+    pub fn with_setup<S: Fn(&mut TestEnv) + 'a>(mut self, setup: S) -> Self {
+        self.setup = Some(Box::new(setup));
+        self
+    }
+
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Sets the accounts calls are made from, they're used as address arguments too.
+    pub fn with_senders(mut self, senders: &[Address]) -> Self {
+        assert!(!senders.is_empty(), "fuzzer needs at least one sender");
+        self.senders = senders.to_vec();
+        self
+    }
+
+    pub fn with_runs(mut self, runs: usize) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Sets the number of calls in a sequence.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Adds an invariant that has to hold after every call.
+    pub fn invariant<I: Fn(&mut TestEnv) -> bool + 'a>(mut self, name: &str, invariant: I) -> Self {
+        self.invariants
+            .push((name.to_string(), Box::new(invariant)));
+        self
+    }
+
+    /// Runs the fuzzer and panics with a shrunk sequence of calls if an invariant is broken.
+    pub fn run(&self) {
+        if let Err(failure) = self.try_run() {
+            panic!("{}", failure);
+        }
+    }
+
+    pub fn try_run(&self) -> Result<(), FuzzFailure> {
+        if self.functions.is_empty() {
+            return Ok(());
+        }
+        let mut rng = Rng(self.seed);
+        for _ in 0..self.runs {
+            let calls: Vec<_> = (0..self.depth)
+                .map(|_| self.generate_call(&mut rng))
+                .collect();
+            if let Some((step, invariant)) = self.execute(&calls) {
+                let calls = self.shrink(calls[..=step].to_vec(), invariant);
+                return Err(self.failure(&calls, invariant));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replays calls from an empty state, returns the call after which an invariant is broken
+    /// and the invariant.
+    fn execute(&self, calls: &[FuzzCall]) -> Option<(usize, usize)> {
+        let mut env = TestEnv::new().with_address(self.address);
+        if let Some(setup) = &self.setup {
+            setup(&mut env);
+        }
+        for (step, call) in calls.iter().enumerate() {
+            env.context.contract_caller = call.sender;
+            env.context.tx_caller = call.sender;
+            let input = self.functions[call.function].encode_call(&call.args);
+            env.call(&input, || (self.main)());
+            let broken = self
+                .invariants
+                .iter()
+                .position(|(_, invariant)| !invariant(&mut env));
+            if let Some(invariant) = broken {
+                return Some((step, invariant));
+            }
+        }
+        None
+    }
+
+    fn shrink(&self, mut calls: Vec<FuzzCall>, invariant: usize) -> Vec<FuzzCall> {
+        let breaks = |calls: &[FuzzCall]| {
+            self.execute(calls)
+                .filter(|(_, broken)| *broken == invariant)
+                .map(|(step, _)| step)
+        };
+        loop {
+            let mut shrunk = false;
+            // drop calls that aren't needed, the last one is needed always
+            let mut i = calls.len();
+            while i > 0 {
+                i -= 1;
+                let mut candidate = calls.clone();
+                candidate.remove(i);
+                if let Some(step) = breaks(&candidate) {
+                    candidate.truncate(step + 1);
+                    calls = candidate;
+                    i = i.min(calls.len());
+                    shrunk = true;
+                }
+            }
+            // simplify arguments of the calls left
+            let mut i = 0;
+            while i < calls.len() {
+                let mut j = 0;
+                while i < calls.len() && j < calls[i].args.len() {
+                    let candidates = shrink_value(&calls[i].args[j]);
+                    for value in candidates {
+                        let mut candidate = calls.clone();
+                        candidate[i].args[j] = value;
+                        if let Some(step) = breaks(&candidate) {
+                            candidate.truncate(step + 1);
+                            calls = candidate;
+                            shrunk = true;
+                            break;
+                        }
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+            if !shrunk {
+                return calls;
+            }
+        }
+    }
+
+    fn failure(&self, calls: &[FuzzCall], invariant: usize) -> FuzzFailure {
+        let trace = calls
+            .iter()
+            .map(|call| {
+                let function = &self.functions[call.function];
+                let name = &function.signature[..function.signature.find('(').unwrap()];
+                let args: Vec<_> = call.args.iter().map(format_value).collect();
+                format!("{}({}) from {}", name, args.join(", "), call.sender)
+            })
+            .collect();
+        FuzzFailure {
+            invariant: self.invariants[invariant].0.clone(),
+            seed: self.seed,
+            calls: calls
+                .iter()
+                .map(|call| {
+                    let function = &self.functions[call.function];
+                    (call.sender, function.encode_call(&call.args))
+                })
+                .collect(),
+            trace,
+        }
+    }
+
+    fn generate_call(&self, rng: &mut Rng) -> FuzzCall {
+        let function = rng.below(self.functions.len() as u64) as usize;
+        let args = self.functions[function]
+            .inputs
+            .iter()
+            .map(|ty| self.generate_value(ty, rng))
+            .collect();
+        FuzzCall {
+            sender: self.senders[rng.below(self.senders.len() as u64) as usize],
+            function,
+            args,
+        }
+    }
+
+    fn generate_value(&self, ty: &DynSolType, rng: &mut Rng) -> DynSolValue {
+        match ty {
+            DynSolType::Uint(bits) => DynSolValue::Uint(rng.uint(*bits), *bits),
+            DynSolType::Int(bits) => {
+                // sign extended from the highest bit of the type
+                let value = rng.uint(*bits);
+                let value = if value.bit(bits - 1) {
+                    value | (U256::MAX << bits)
+                } else {
+                    value
+                };
+                DynSolValue::Int(I256::from_raw(value), *bits)
+            }
+            DynSolType::Address => {
+                // addresses the contract knows about are more interesting than random ones
+                let address = match rng.below(self.senders.len() as u64 + 3) as usize {
+                    i if i < self.senders.len() => self.senders[i],
+                    i if i == self.senders.len() => self.address,
+                    i if i == self.senders.len() + 1 => Address::ZERO,
+                    _ => Address::from_slice(&rng.bytes(20)),
+                };
+                DynSolValue::Address(address)
+            }
+            DynSolType::Bool => DynSolValue::Bool(rng.below(2) == 1),
+            DynSolType::FixedBytes(size) => {
+                let mut word = B256::ZERO;
+                word[..*size].copy_from_slice(&rng.bytes(*size));
+                DynSolValue::FixedBytes(word, *size)
+            }
+            DynSolType::Bytes => {
+                let len = rng.below(MAX_BYTES_LEN + 1) as usize;
+                DynSolValue::Bytes(rng.bytes(len))
+            }
+            DynSolType::String => {
+                const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+                let len = rng.below(MAX_BYTES_LEN + 1) as usize;
+                let chars = (0..len)
+                    .map(|_| CHARS[rng.below(CHARS.len() as u64) as usize] as char)
+                    .collect();
+                DynSolValue::String(chars)
+            }
+            DynSolType::Array(elem) => {
+                let len = rng.below(MAX_DYNAMIC_LEN + 1);
+                DynSolValue::Array((0..len).map(|_| self.generate_value(elem, rng)).collect())
+            }
+            DynSolType::FixedArray(elem, len) => {
+                DynSolValue::FixedArray((0..*len).map(|_| self.generate_value(elem, rng)).collect())
+            }
+            DynSolType::Tuple(types) => DynSolValue::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.generate_value(ty, rng))
+                    .collect(),
+            ),
+            DynSolType::Function => unreachable!("functions are rejected by the parser"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contract,
+        AccountStorage,
+        ContextReader,
+        ExecutionContext,
+        JzktAccountManager,
+        StorageBackend,
+    };
+    use alloy_sol_types::{sol, SolCall, SolValue};
+    use revm_primitives::hex;
+
+    #[test]
+    fn test_parse_function() {
+        let function = AbiFunction::parse("swap(uint8[2][],(address,bytes32),int)").unwrap();
+        assert_eq!(
+            function.inputs,
+            vec![
+                DynSolType::Array(Box::new(DynSolType::FixedArray(
+                    Box::new(DynSolType::Uint(8)),
+                    2
+                ))),
+                DynSolType::Tuple(vec![DynSolType::Address, DynSolType::FixedBytes(32)]),
+                DynSolType::Int(256),
+            ]
+        );
+        assert_eq!(
+            AbiFunction::parse("transfer(address,uint256)")
+                .unwrap()
+                .selector,
+            hex!("a9059cbb")
+        );
+        assert!(AbiFunction::parse("totalSupply()")
+            .unwrap()
+            .inputs
+            .is_empty());
+        assert_eq!(AbiFunction::parse("transfer(uint7)"), None);
+        assert_eq!(AbiFunction::parse("transfer(bytes33)"), None);
+        assert_eq!(AbiFunction::parse("transfer"), None);
+        assert_eq!(AbiFunction::parse("call(function)"), None);
+    }
+
+    #[test]
+    fn test_encode_call() {
+        let function = AbiFunction::parse(
+            "complex(uint8,int16,string,(address,bool)[],bytes32[2],uint256[][])",
+        )
+        .unwrap();
+        let uint = |value: u64, bits: usize| DynSolValue::Uint(U256::from(value), bits);
+        let args = vec![
+            uint(7, 8),
+            DynSolValue::Int(I256::MINUS_ONE, 16),
+            DynSolValue::String("hello".to_string()),
+            DynSolValue::Array(vec![DynSolValue::Tuple(vec![
+                DynSolValue::Address(Address::repeat_byte(1)),
+                DynSolValue::Bool(true),
+            ])]),
+            DynSolValue::FixedArray(vec![
+                DynSolValue::FixedBytes(B256::from(U256::from(2)), 32),
+                DynSolValue::FixedBytes(B256::from(U256::from(3)), 32),
+            ]),
+            DynSolValue::Array(vec![
+                DynSolValue::Array(vec![uint(4, 256)]),
+                DynSolValue::Array(vec![]),
+            ]),
+        ];
+        // complexCall { a: 7, b: -1, s: "hello", list: [(0x0101..01, true)], fixed: [2, 3],
+        // nested: [[4], []] } encoded by alloy
+        let expected = hex::decode(concat!(
+            "4bec0434",
+            "0000000000000000000000000000000000000000000000000000000000000007",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "00000000000000000000000000000000000000000000000000000000000000e0",
+            "0000000000000000000000000000000000000000000000000000000000000120",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "0000000000000000000000000000000000000000000000000000000000000180",
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "68656c6c6f000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000101010101010101010101010101010101010101",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        assert_eq!(function.encode_call(&args), expected);
+    }
+
+    const TOKEN: Address = Address::repeat_byte(0xc0);
+
+    fn balance_slot(owner: Address) -> U256 {
+        U256::from_be_bytes(owner.into_word().0)
+    }
+
+    fn storage() -> AccountStorage<'static, JzktAccountManager> {
+        AccountStorage::new(
+            &JzktAccountManager::DEFAULT,
+            ExecutionContext::DEFAULT.contract_address(),
+        )
+    }
+
+    /// A token with a bug, a transfer to yourself mints tokens.
+    #[derive(Default)]
+    struct Token;
+
+    #[contract]
+    impl Token {
+        pub fn mint(&mut self, amount: U256) {
+            let caller = balance_slot(ExecutionContext::DEFAULT.contract_caller());
+            let storage = storage();
+            let supply = storage.sload(U256::ZERO).checked_add(amount);
+            storage.sstore(U256::ZERO, supply.expect("supply overflow"));
+            storage.sstore(caller, storage.sload(caller) + amount);
+        }
+
+        pub fn transfer(&mut self, to: Address, amount: U256) {
+            let caller = balance_slot(ExecutionContext::DEFAULT.contract_caller());
+            let to = balance_slot(to);
+            let storage = storage();
+            let (from_balance, to_balance) = (storage.sload(caller), storage.sload(to));
+            let from_balance = from_balance.checked_sub(amount);
+            storage.sstore(caller, from_balance.expect("insufficient balance"));
+            storage.sstore(to, to_balance + amount);
+        }
+    }
+
+    fn token_main() {
+        let input = ExecutionContext::contract_input();
+        let output = Token.route(input);
+        LowLevelSDK::write(&output);
+    }
+
+    #[test]
+    fn test_fuzzer_finds_and_shrinks_failure() {
+        let senders = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let failure = Fuzzer::new(Token::FUNCTION_SIGNATURES, token_main)
+            .with_address(TOKEN)
+            .with_senders(&senders)
+            .invariant("balance is at most the supply", |env| {
+                let supply = env.storage(TOKEN, U256::ZERO);
+                senders
+                    .iter()
+                    .all(|sender| env.storage(TOKEN, balance_slot(*sender)) <= supply)
+            })
+            .try_run()
+            .unwrap_err();
+        assert_eq!(failure.invariant, "balance is at most the supply");
+        // the shortest way to break it is to mint and transfer to yourself
+        assert_eq!(failure.trace.len(), 2);
+        assert!(failure.trace[0].starts_with("mint(1) from "));
+        let (sender, input) = &failure.calls[1];
+        assert_eq!(input[..4], hex!("a9059cbb"));
+        assert_eq!(input[4..36], sender.into_word().0);
+        assert_eq!(U256::from_be_slice(&input[36..68]), U256::from(1));
+    }
+
+    #[test]
+    fn test_fuzzer_passes() {
+        Fuzzer::new(&["mint(uint256)"], token_main)
+            .with_address(TOKEN)
+            .with_runs(16)
+            .invariant("supply is minted", |env| {
+                let supply = env.storage(TOKEN, U256::ZERO);
+                (1..=3).all(|i| env.storage(TOKEN, balance_slot(Address::repeat_byte(i))) <= supply)
+            })
+            .run();
+    }
+}
@@ -1,9 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(unused_crate_dependencies)]
 #![allow(unused_imports)]
-extern crate alloc;
+// code generated by `Eip712` refers to `alloc` through the crate
+pub extern crate alloc;
 extern crate core;
 extern crate lol_alloc;
+// code generated by `contract` in tests refers to the crate by its name
+#[cfg(test)]
+extern crate self as fluentbase_sdk;
 
 pub struct LowLevelSDK;
 
@@ -16,19 +20,39 @@ pub use sdk::{SharedAPI, SovereignAPI};
 
 mod account;
 pub use account::*;
+mod allocator;
+pub use allocator::SizeClassAllocator;
+mod call;
+pub use call::*;
 #[cfg(not(feature = "std"))]
 mod bindings;
+pub mod bn254;
+mod eip712;
+pub use eip712::*;
+mod event;
+pub use event::*;
 mod jzkt;
 pub use jzkt::*;
+#[cfg(feature = "std")]
+mod fuzz;
+pub mod mpt;
+#[cfg(feature = "std")]
+pub use fuzz::*;
 #[macro_use]
 pub mod macros;
 #[cfg(feature = "std")]
 mod runtime;
+#[cfg(feature = "std")]
+mod test_env;
+#[cfg(feature = "std")]
+pub use test_env::*;
 #[cfg(not(feature = "std"))]
 mod rwasm;
+mod storage;
 mod types;
 mod utils;
 
+pub use storage::*;
 pub use types::*;
 pub use utils::*;
 
@@ -38,17 +62,26 @@ pub use utils::*;
 #[inline(always)]
 fn panic(info: &core::panic::PanicInfo) -> ! {
     let panic_message = alloc::format!("{}", info).replace("\n", " ");
-    LowLevelSDK::write(panic_message.as_bytes());
+    // the message is encoded as `Error(string)`, so it's shown as a revert reason
+    LowLevelSDK::write(&fluentbase_types::encode_solidity_error_message(
+        &panic_message,
+    ));
     LowLevelSDK::exit(fluentbase_types::ExitCode::Panic.into_i32());
 }
 
 #[cfg(not(feature = "std"))]
 #[global_allocator]
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "size-class-allocator")))]
 static ALLOCATOR: lol_alloc::AssumeSingleThreaded<lol_alloc::LeakingAllocator> =
     unsafe { lol_alloc::AssumeSingleThreaded::new(lol_alloc::LeakingAllocator::new()) };
 
-pub use fluentbase_sdk_derive::{derive_keccak256_id, derive_solidity_router};
+#[cfg(not(feature = "std"))]
+#[global_allocator]
+#[cfg(all(target_arch = "wasm32", feature = "size-class-allocator"))]
+pub static ALLOCATOR: SizeClassAllocator = SizeClassAllocator::new();
+
+pub use alloy_sol_types;
+pub use fluentbase_sdk_derive::{contract, derive_keccak256_id, derive_solidity_router, Eip712};
 
 pub mod codec {
     pub use fluentbase_codec::*;
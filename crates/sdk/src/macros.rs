@@ -15,3 +15,195 @@ macro_rules! basic_entrypoint {
         }
     };
 }
+
+/// Emits an event defined with `sol!` from the contract.
+///
+/// Topic0 is the signature hash of the event unless it's anonymous, indexed fields are encoded
+/// into topics and the rest of fields are encoded as data. Indexed fields of dynamic types, like
+/// `string indexed name`, take values of these types and hash them into topics (see
+/// [`EventField`](crate::EventField)):
+///
+/// ```ignore
+/// sol! {
+///     event Transfer(address indexed from, address indexed to, uint256 value);
+///     event Named(string indexed name, uint256[] indexed ids);
+/// }
+///
+/// emit!(Transfer { from, to, value });
+/// emit!(Named { name: "name", ids: vec![U256::from(1)] });
+/// // or with an account manager and an address of the emitter
+/// emit!(&JzktAccountManager::DEFAULT, address, Transfer { from, to, value });
+/// ```
+///
+/// `sol!` rejects events with more than 4 topics, so up to 4 indexed fields of an anonymous event
+/// or 3 of a regular one are emitted.
+#[macro_export]
+macro_rules! emit {
+    (@value $field:ident) => {
+        $field
+    };
+    (@value $field:ident $value:expr) => {
+        $value
+    };
+    (@emit $am:expr, $address:expr, $event:tt) => {{
+        fn emit<E: $crate::alloy_sol_types::SolEvent, AM: $crate::AccountManager>(
+            am: &AM,
+            address: $crate::Address,
+            event: &E,
+        ) {
+            // `TopicList` is implemented for up to 4 topics only
+            let count = <E::TopicList as $crate::alloy_sol_types::TopicList>::COUNT;
+            let mut tokens = [$crate::alloy_sol_types::abi::token::WordToken::default(); 4];
+            $crate::alloy_sol_types::SolEvent::encode_topics_raw(event, &mut tokens[..count])
+                .expect("topics of an event are encoded");
+            let mut topics = [$crate::B256::ZERO; 4];
+            for (topic, token) in topics.iter_mut().zip(tokens) {
+                *topic = token.0;
+            }
+            let data = $crate::alloy_sol_types::SolEvent::encode_data(event);
+            $crate::AccountManager::log(am, address, data.into(), &topics[..count]);
+        }
+        emit($am, $address, &$event)
+    }};
+    ($am:expr, $address:expr, $($event:ident)::+ { $($field:ident $(: $value:expr)?),* $(,)? }) => {
+        $crate::emit!(
+            @emit $am,
+            $address,
+            ($($event)::+ {
+                $($field: $crate::EventField::into_event_field(
+                    $crate::emit!(@value $field $($value)?)
+                ),)*
+            })
+        )
+    };
+    ($am:expr, $address:expr, $event:expr) => {
+        $crate::emit!(@emit $am, $address, ($event))
+    };
+    ($($event:ident)::+ { $($field:ident $(: $value:expr)?),* $(,)? }) => {
+        $crate::emit!(
+            @emit &$crate::JzktAccountManager::DEFAULT,
+            $crate::ContextReader::contract_address(&$crate::ExecutionContext::DEFAULT),
+            ($($event)::+ {
+                $($field: $crate::EventField::into_event_field(
+                    $crate::emit!(@value $field $($value)?)
+                ),)*
+            })
+        )
+    };
+    ($event:expr) => {
+        $crate::emit!(
+            @emit &$crate::JzktAccountManager::DEFAULT,
+            $crate::ContextReader::contract_address(&$crate::ExecutionContext::DEFAULT),
+            ($event)
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Address, Bytes, JournalLog, JzktAccountManager, TestEnv, B256, U256};
+    use alloy_sol_types::{sol, SolEvent};
+    use revm_primitives::keccak256;
+
+    sol! {
+        event Transfer(address indexed from, address indexed to, uint256 value);
+        event Named(string indexed name, bytes data) anonymous;
+        event Batch(uint256[] indexed ids, bytes indexed payload, string note);
+        event Approval(
+            address indexed owner,
+            address indexed spender,
+            address indexed operator,
+            address indexed token
+        ) anonymous;
+    }
+
+    #[test]
+    fn test_emit() {
+        let address = Address::repeat_byte(1);
+        let (from, to) = (Address::repeat_byte(2), Address::repeat_byte(3));
+        let mut env = TestEnv::new().with_address(address);
+        let result = env.call(&[], || {
+            emit!(Transfer {
+                from,
+                to,
+                value: U256::from(5),
+            });
+            emit!(
+                &JzktAccountManager::DEFAULT,
+                to,
+                Named {
+                    name: "name",
+                    data: vec![1],
+                }
+            );
+            let ids = vec![U256::from(1), U256::from(2)];
+            emit!(Batch {
+                ids,
+                payload: vec![1u8, 2, 3],
+                note: "note",
+            });
+            emit!(Approval {
+                owner: from,
+                spender: to,
+                operator: address,
+                token: address,
+            });
+        });
+        assert!(result.is_ok());
+        let mut named_data = U256::from(32).to_be_bytes_vec();
+        named_data.extend(U256::from(1).to_be_bytes::<32>());
+        named_data.extend([1].iter().chain(&[0; 31]));
+        assert_eq!(
+            result.logs,
+            vec![
+                JournalLog {
+                    address,
+                    topics: vec![
+                        Transfer::SIGNATURE_HASH,
+                        B256::from(from.into_word()),
+                        B256::from(to.into_word()),
+                    ],
+                    data: Bytes::from(U256::from(5).to_be_bytes_vec()),
+                },
+                JournalLog {
+                    address: to,
+                    topics: vec![keccak256("name")],
+                    data: Bytes::from(named_data),
+                },
+                JournalLog {
+                    address,
+                    topics: vec![
+                        Batch::SIGNATURE_HASH,
+                        // elements of indexed arrays are hashed padded to 32 bytes
+                        keccak256(
+                            [
+                                U256::from(1).to_be_bytes::<32>(),
+                                U256::from(2).to_be_bytes::<32>(),
+                            ]
+                            .concat()
+                        ),
+                        keccak256([1, 2, 3]),
+                    ],
+                    data: Bytes::from(
+                        Batch {
+                            ids: B256::ZERO,
+                            payload: B256::ZERO,
+                            note: "note".into(),
+                        }
+                        .encode_data()
+                    ),
+                },
+                JournalLog {
+                    address,
+                    topics: vec![
+                        B256::from(from.into_word()),
+                        B256::from(to.into_word()),
+                        B256::from(address.into_word()),
+                        B256::from(address.into_word()),
+                    ],
+                    data: Bytes::new(),
+                },
+            ]
+        );
+    }
+}
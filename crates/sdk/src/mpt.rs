@@ -0,0 +1,480 @@
+//! Verification of Ethereum Merkle Patricia trie proofs.
+//!
+//! A proof is a list of RLP encoded nodes from the root to the key, like `accountProof` and
+//! `storageProof` of `eth_getProof`. Nodes shorter than 32 bytes are embedded into their parents,
+//! so they aren't a part of the list. A proof can prove that a key is absent as well, then it ends
+//! at the node where the path of the key diverges.
+use crate::{LowLevelSDK, SharedAPI};
+use alloc::vec::Vec;
+use alloy_rlp::{Encodable, Header};
+use fluentbase_types::{Address, B256, U256};
+
+/// Root of an empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT_HASH: B256 =
+    fluentbase_types::b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofError {
+    /// A node isn't valid RLP or it isn't a branch, an extension or a leaf.
+    InvalidNode,
+    /// Hash of a node doesn't match the reference to it.
+    HashMismatch,
+    /// The proof ends before the key is resolved.
+    MissingNode,
+    /// There are nodes left after the key is resolved.
+    UnexpectedNode,
+    /// The proven value can't be decoded, like an account that isn't a list of 4 items.
+    InvalidValue,
+}
+
+/// Account as it's stored in the state trie.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrieAccount {
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_root: B256,
+    pub code_hash: B256,
+}
+
+impl TrieAccount {
+    /// Decodes an account from `rlp([nonce, balance, storage_root, code_hash])`.
+    pub fn decode(rlp: &[u8]) -> Result<Self, ProofError> {
+        let items = decode_list(rlp).map_err(|_| ProofError::InvalidValue)?;
+        let [nonce, balance, storage_root, code_hash] = items[..] else {
+            return Err(ProofError::InvalidValue);
+        };
+        let word = |item: &[u8]| -> Result<B256, ProofError> {
+            let bytes = decode_string(item).map_err(|_| ProofError::InvalidValue)?;
+            B256::try_from(bytes).map_err(|_| ProofError::InvalidValue)
+        };
+        let balance = decode_uint(balance)?;
+        let nonce = decode_uint(nonce)?;
+        Ok(Self {
+            nonce: nonce.try_into().map_err(|_| ProofError::InvalidValue)?,
+            balance,
+            storage_root: word(storage_root)?,
+            code_hash: word(code_hash)?,
+        })
+    }
+}
+
+/// Verifies a proof of `key` against the root of a trie, it returns the value of the key or
+/// `None` if the proof shows that the key isn't in the trie.
+pub fn verify_proof<N: AsRef<[u8]>>(
+    root: &B256,
+    key: &[u8],
+    proof: &[N],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if proof.is_empty() && *root == EMPTY_ROOT_HASH {
+        return Ok(None);
+    }
+    let nibbles: Vec<u8> = key
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect();
+    let mut path = &nibbles[..];
+    let mut nodes = proof.iter().map(AsRef::as_ref);
+    let mut next = NodeRef::Hash(*root);
+    loop {
+        let node = match next {
+            NodeRef::Hash(hash) => {
+                let node = nodes.next().ok_or(ProofError::MissingNode)?;
+                if keccak256(node) != hash {
+                    return Err(ProofError::HashMismatch);
+                }
+                node
+            }
+            NodeRef::Inline(node) => node,
+        };
+        let items = decode_list(node)?;
+        let child = match items.len() {
+            // branch
+            17 => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    return finish(decode_value(items[16])?, nodes);
+                };
+                path = rest;
+                items[nibble as usize]
+            }
+            // extension or leaf
+            2 => {
+                let (node_path, is_leaf) = decode_node_path(decode_string(items[0])?)?;
+                if is_leaf {
+                    let value = if path == &node_path[..] {
+                        decode_value(items[1])?
+                    } else {
+                        None
+                    };
+                    return finish(value, nodes);
+                }
+                if node_path.is_empty() {
+                    return Err(ProofError::InvalidNode);
+                }
+                if !path.starts_with(&node_path) {
+                    return finish(None, nodes);
+                }
+                path = &path[node_path.len()..];
+                items[1]
+            }
+            _ => return Err(ProofError::InvalidNode),
+        };
+        next = match decode_node_ref(child)? {
+            Some(node_ref) => node_ref,
+            None => return finish(None, nodes),
+        };
+    }
+}
+
+/// Verifies an account proof against a state root, accounts are keyed by `keccak256(address)`.
+pub fn verify_account_proof<N: AsRef<[u8]>>(
+    state_root: &B256,
+    address: &Address,
+    proof: &[N],
+) -> Result<Option<TrieAccount>, ProofError> {
+    verify_proof(state_root, keccak256(address.as_slice()).as_slice(), proof)?
+        .map(|rlp| TrieAccount::decode(&rlp))
+        .transpose()
+}
+
+/// Verifies a storage proof against the storage root of an account, slots are keyed by
+/// `keccak256(slot)`. Absent slots are zero.
+pub fn verify_storage_proof<N: AsRef<[u8]>>(
+    storage_root: &B256,
+    slot: &U256,
+    proof: &[N],
+) -> Result<U256, ProofError> {
+    let key = keccak256(&slot.to_be_bytes::<32>());
+    match verify_proof(storage_root, key.as_slice(), proof)? {
+        Some(rlp) => decode_uint(&rlp),
+        None => Ok(U256::ZERO),
+    }
+}
+
+/// Verifies that a receipt is included into a block, it returns the encoded receipt at `index`,
+/// for typed receipts it's the type byte followed by RLP of the receipt.
+pub fn verify_receipt_proof<N: AsRef<[u8]>>(
+    receipts_root: &B256,
+    index: u64,
+    proof: &[N],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    verify_index_proof(receipts_root, index, proof)
+}
+
+/// Verifies that a transaction is included into a block, it returns the encoded transaction at
+/// `index` in the same form as receipts.
+pub fn verify_transaction_proof<N: AsRef<[u8]>>(
+    transactions_root: &B256,
+    index: u64,
+    proof: &[N],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    verify_index_proof(transactions_root, index, proof)
+}
+
+/// Receipts and transactions are keyed by `rlp(index)`.
+fn verify_index_proof<N: AsRef<[u8]>>(
+    root: &B256,
+    index: u64,
+    proof: &[N],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    let mut key = [0u8; 9];
+    let len = index.length();
+    index.encode(&mut &mut key[..]);
+    verify_proof(root, &key[..len], proof)
+}
+
+enum NodeRef<'a> {
+    Hash(B256),
+    /// A node shorter than 32 bytes embedded into its parent.
+    Inline(&'a [u8]),
+}
+
+fn finish<'a>(
+    value: Option<Vec<u8>>,
+    mut nodes: impl Iterator<Item = &'a [u8]>,
+) -> Result<Option<Vec<u8>>, ProofError> {
+    match nodes.next() {
+        Some(_) => Err(ProofError::UnexpectedNode),
+        None => Ok(value),
+    }
+}
+
+fn keccak256(data: &[u8]) -> B256 {
+    let mut hash = B256::ZERO;
+    LowLevelSDK::keccak256(data.as_ptr(), data.len() as u32, hash.as_mut_ptr());
+    hash
+}
+
+/// Splits an RLP list into encoded items.
+fn decode_list(rlp: &[u8]) -> Result<Vec<&[u8]>, ProofError> {
+    let mut payload = rlp;
+    let header = Header::decode(&mut payload).map_err(|_| ProofError::InvalidNode)?;
+    if !header.list || payload.len() != header.payload_length {
+        return Err(ProofError::InvalidNode);
+    }
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let item_header = Header::decode(&mut rest).map_err(|_| ProofError::InvalidNode)?;
+        let len = payload.len() - rest.len() + item_header.payload_length;
+        if len > payload.len() {
+            return Err(ProofError::InvalidNode);
+        }
+        items.push(&payload[..len]);
+        payload = &payload[len..];
+    }
+    Ok(items)
+}
+
+fn decode_string(rlp: &[u8]) -> Result<&[u8], ProofError> {
+    let mut payload = rlp;
+    let header = Header::decode(&mut payload).map_err(|_| ProofError::InvalidNode)?;
+    if header.list || payload.len() != header.payload_length {
+        return Err(ProofError::InvalidNode);
+    }
+    Ok(payload)
+}
+
+fn decode_uint(rlp: &[u8]) -> Result<U256, ProofError> {
+    let bytes = decode_string(rlp).map_err(|_| ProofError::InvalidValue)?;
+    U256::try_from_be_slice(bytes).ok_or(ProofError::InvalidValue)
+}
+
+/// Values are RLP strings, an empty string is no value.
+fn decode_value(rlp: &[u8]) -> Result<Option<Vec<u8>>, ProofError> {
+    let value = decode_string(rlp)?;
+    Ok((!value.is_empty()).then(|| value.to_vec()))
+}
+
+/// A child is either a hash of the node, an embedded node or nothing.
+fn decode_node_ref(rlp: &[u8]) -> Result<Option<NodeRef<'_>>, ProofError> {
+    if rlp.first().is_some_and(|&prefix| prefix >= 0xc0) {
+        return Ok(Some(NodeRef::Inline(rlp)));
+    }
+    match decode_string(rlp)? {
+        [] => Ok(None),
+        hash if hash.len() == 32 => Ok(Some(NodeRef::Hash(B256::from_slice(hash)))),
+        _ => Err(ProofError::InvalidNode),
+    }
+}
+
+/// Decodes a hex-prefix encoded path of an extension or a leaf, the high nibble of the first
+/// byte tells whether it's a leaf and whether the number of nibbles is odd.
+fn decode_node_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let (&first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode);
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    } else if first & 0x0f != 0 {
+        return Err(ProofError::InvalidNode);
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use eth_trie::{EthTrie, MemoryDB, Trie};
+    use std::sync::Arc;
+
+    fn new_trie() -> EthTrie<MemoryDB> {
+        EthTrie::new(Arc::new(MemoryDB::new(true)))
+    }
+
+    fn root_hash(trie: &mut EthTrie<MemoryDB>) -> B256 {
+        B256::from(trie.root_hash().unwrap().0)
+    }
+
+    fn encode_uint(value: &U256) -> Vec<u8> {
+        let mut out = Vec::new();
+        value.to_be_bytes_trimmed_vec().as_slice().encode(&mut out);
+        out
+    }
+
+    fn encode_account(account: &TrieAccount) -> Vec<u8> {
+        let balance = account.balance.to_be_bytes_trimmed_vec();
+        let items: [&dyn Encodable; 4] = [
+            &account.nonce,
+            &balance.as_slice(),
+            &account.storage_root.as_slice(),
+            &account.code_hash.as_slice(),
+        ];
+        let mut out = Vec::new();
+        alloy_rlp::encode_list::<_, dyn Encodable>(&items, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_account_and_storage_proofs() {
+        let mut storage = new_trie();
+        for slot in 0..32u64 {
+            let key = keccak256(&U256::from(slot).to_be_bytes::<32>());
+            let value = U256::from(slot * 1000 + 1);
+            storage
+                .insert(key.as_slice(), &encode_uint(&value))
+                .unwrap();
+        }
+        let storage_root = root_hash(&mut storage);
+
+        let mut state = new_trie();
+        let accounts: Vec<(Address, TrieAccount)> = (1..=64u8)
+            .map(|i| {
+                let account = TrieAccount {
+                    nonce: i as u64,
+                    balance: U256::from(i) << 64,
+                    storage_root: if i == 7 {
+                        storage_root
+                    } else {
+                        EMPTY_ROOT_HASH
+                    },
+                    code_hash: B256::repeat_byte(i),
+                };
+                (Address::repeat_byte(i), account)
+            })
+            .collect();
+        for (address, account) in &accounts {
+            let key = keccak256(address.as_slice());
+            state
+                .insert(key.as_slice(), &encode_account(account))
+                .unwrap();
+        }
+        let state_root = root_hash(&mut state);
+
+        for (address, account) in &accounts {
+            let proof = state
+                .get_proof(keccak256(address.as_slice()).as_slice())
+                .unwrap();
+            assert_eq!(
+                verify_account_proof(&state_root, address, &proof),
+                Ok(Some(account.clone()))
+            );
+        }
+        let absent = Address::repeat_byte(0xff);
+        let proof = state
+            .get_proof(keccak256(absent.as_slice()).as_slice())
+            .unwrap();
+        assert_eq!(verify_account_proof(&state_root, &absent, &proof), Ok(None));
+
+        // the storage root of the proven account is the root of its storage proofs
+        let account = verify_account_proof(
+            &state_root,
+            &accounts[6].0,
+            &state
+                .get_proof(keccak256(accounts[6].0.as_slice()).as_slice())
+                .unwrap(),
+        )
+        .unwrap()
+        .unwrap();
+        for slot in [0u64, 5, 31, 32, 1000] {
+            let slot = U256::from(slot);
+            let proof = storage
+                .get_proof(keccak256(&slot.to_be_bytes::<32>()).as_slice())
+                .unwrap();
+            let expected = if slot < U256::from(32) {
+                slot * U256::from(1000) + U256::from(1)
+            } else {
+                U256::ZERO
+            };
+            assert_eq!(
+                verify_storage_proof(&account.storage_root, &slot, &proof),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_receipt_and_transaction_proofs() {
+        let mut receipts = new_trie();
+        // short values make nodes shorter than 32 bytes, they are embedded into parents
+        let receipt = |index: u64| -> Vec<u8> {
+            match index % 3 {
+                0 => vec![0xc2, 0x01, index as u8 & 0x7f],
+                1 => vec![0x02, 0xc1, 0x01],
+                _ => vec![0x02; 100],
+            }
+        };
+        for index in 0..300u64 {
+            let mut key = Vec::new();
+            index.encode(&mut key);
+            receipts.insert(&key, &receipt(index)).unwrap();
+        }
+        let receipts_root = root_hash(&mut receipts);
+        for index in 0..=300u64 {
+            let mut key = Vec::new();
+            index.encode(&mut key);
+            let proof = receipts.get_proof(&key).unwrap();
+            let expected = (index < 300).then(|| receipt(index));
+            assert_eq!(
+                verify_receipt_proof(&receipts_root, index, &proof),
+                Ok(expected.clone())
+            );
+            assert_eq!(
+                verify_transaction_proof(&receipts_root, index, &proof),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_proofs() {
+        let mut trie = new_trie();
+        for i in 0..16u8 {
+            let key = keccak256(&[i]);
+            trie.insert(key.as_slice(), &[i + 1; 40]).unwrap();
+        }
+        let root = root_hash(&mut trie);
+        let key = keccak256(&[3]);
+        let proof = trie.get_proof(key.as_slice()).unwrap();
+        assert_eq!(
+            verify_proof(&root, key.as_slice(), &proof),
+            Ok(Some(vec![4; 40]))
+        );
+
+        let mut tampered = proof.clone();
+        let last = tampered.last_mut().unwrap();
+        *last.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            verify_proof(&root, key.as_slice(), &tampered),
+            Err(ProofError::HashMismatch)
+        );
+        assert_eq!(
+            verify_proof(&root, key.as_slice(), &proof[..proof.len() - 1]),
+            Err(ProofError::MissingNode)
+        );
+        let mut extended = proof.clone();
+        extended.push(proof[0].clone());
+        assert_eq!(
+            verify_proof(&root, key.as_slice(), &extended),
+            Err(ProofError::UnexpectedNode)
+        );
+        assert_eq!(
+            verify_proof(&B256::repeat_byte(1), key.as_slice(), &proof),
+            Err(ProofError::HashMismatch)
+        );
+        // a proof of another key doesn't prove the value of this one
+        let other = keccak256(&[4]);
+        assert_eq!(
+            verify_proof(&root, other.as_slice(), &proof),
+            Err(ProofError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn test_empty_trie() {
+        let proof: [&[u8]; 0] = [];
+        assert_eq!(verify_proof(&EMPTY_ROOT_HASH, &[1, 2, 3], &proof), Ok(None));
+        assert_eq!(
+            verify_storage_proof(&EMPTY_ROOT_HASH, &U256::from(1), &proof),
+            Ok(U256::ZERO)
+        );
+        assert_eq!(
+            verify_proof(&B256::repeat_byte(1), &[1, 2, 3], &proof),
+            Err(ProofError::MissingNode)
+        );
+    }
+}
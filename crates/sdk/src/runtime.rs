@@ -5,11 +5,17 @@ use crate::{
 use byteorder::{ByteOrder, LittleEndian};
 use fluentbase_runtime::{
     instruction::{
+        bn254_fr_add::SyscallBn254FrAdd,
+        bn254_fr_batch_inv::SyscallBn254FrBatchInv,
+        bn254_fr_inner_product::SyscallBn254FrInnerProduct,
+        bn254_fr_inv::SyscallBn254FrInv,
+        bn254_fr_mul::SyscallBn254FrMul,
+        bn254_fr_pow::SyscallBn254FrPow,
         charge_fuel::SyscallChargeFuel,
         checkpoint::SyscallCheckpoint,
         commit::SyscallCommit,
         compute_root::SyscallComputeRoot,
-        context_call::SyscallContextCall,
+        context_call::{SyscallContextCall, CALL_STACK_LIMIT},
         debug_log::SyscallDebugLog,
         ecrecover::SyscallEcrecover,
         emit_log::SyscallEmitLog,
@@ -17,6 +23,7 @@ use fluentbase_runtime::{
         exit::SyscallExit,
         forward_output::SyscallForwardOutput,
         get_leaf::SyscallGetLeaf,
+        groth16_verify::SyscallGroth16Verify,
         input_size::SyscallInputSize,
         keccak256::SyscallKeccak256,
         output_size::SyscallOutputSize,
@@ -38,14 +45,42 @@ use fluentbase_runtime::{
     DefaultEmptyRuntimeDatabase,
     RuntimeContext,
 };
-use fluentbase_types::{Address, Bytes, ExitCode, JournalCheckpoint, B256};
-use std::ptr;
+use fluentbase_types::{
+    encode_solidity_error_message,
+    Address,
+    Bytes,
+    ExitCode,
+    JournalCheckpoint,
+    B256,
+    GROTH16_PROOF_SIZE,
+    U256,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    mem::take,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
 
 type Context = RuntimeContext<DefaultEmptyRuntimeDatabase>;
 
 thread_local! {
-    pub static CONTEXT: std::cell::Cell<Context> = std::cell::Cell::new(Context::new(&[0u8; 0])
-        .with_jzkt(DefaultEmptyRuntimeDatabase::new(ZkTrieStateDb::new_empty(InMemoryTrieDb::default()))));
+    pub static CONTEXT: std::cell::Cell<Context> = std::cell::Cell::new(new_context());
+    // set by `exit`, it unwinds natively, so it can't be told apart from a panic otherwise
+    static EXITED: Cell<bool> = Cell::new(false);
+    // storage keys are hashed, preimages are kept to report storage changes in tests
+    static STORAGE_KEYS: RefCell<HashMap<[u8; 32], (Address, U256)>> = RefCell::new(HashMap::new());
+    // number of live `TestEnv`s, preimages aren't kept without them
+    static TEST_ENVS: Cell<usize> = Cell::new(0);
+    // entrypoints of contracts deployed by `TestEnv::set_contract` by their rWASM code hash
+    static NATIVE_CONTRACTS: RefCell<HashMap<B256, fn()>> = RefCell::new(HashMap::new());
+}
+
+fn new_context() -> Context {
+    Context::new(&[0u8; 0]).with_jzkt(DefaultEmptyRuntimeDatabase::new(ZkTrieStateDb::new_empty(
+        InMemoryTrieDb::default(),
+    )))
 }
 
 fn with_context<F, R>(func: F) -> R
@@ -116,6 +151,79 @@ impl SharedAPI for LowLevelSDK {
         output.copy_from_slice(&result);
     }
 
+    fn bn254_fr_add(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    ) {
+        let a = unsafe { &*ptr::slice_from_raw_parts(a32s_ptr, f32s_len as usize) };
+        let b = unsafe { &*ptr::slice_from_raw_parts(b32s_ptr, f32s_len as usize) };
+        let result = SyscallBn254FrAdd::fn_impl(a, b).expect("bn254: malformed field elements");
+        unsafe { ptr::copy(result.as_ptr(), output32s_ptr, result.len()) }
+    }
+
+    fn bn254_fr_mul(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    ) {
+        let a = unsafe { &*ptr::slice_from_raw_parts(a32s_ptr, f32s_len as usize) };
+        let b = unsafe { &*ptr::slice_from_raw_parts(b32s_ptr, f32s_len as usize) };
+        let result = SyscallBn254FrMul::fn_impl(a, b).expect("bn254: malformed field elements");
+        unsafe { ptr::copy(result.as_ptr(), output32s_ptr, result.len()) }
+    }
+
+    fn bn254_fr_inv(f32_ptr: *const u8, output32_ptr: *mut u8) {
+        let f = unsafe { &*ptr::slice_from_raw_parts(f32_ptr, 32) };
+        let result = SyscallBn254FrInv::fn_impl(f).expect("bn254: can't invert field element");
+        unsafe { ptr::copy(result.as_ptr(), output32_ptr, result.len()) }
+    }
+
+    fn bn254_fr_batch_inv(f32s_ptr: *const u8, f32s_len: u32, output32s_ptr: *mut u8) {
+        let f = unsafe { &*ptr::slice_from_raw_parts(f32s_ptr, f32s_len as usize) };
+        let result =
+            SyscallBn254FrBatchInv::fn_impl(f).expect("bn254: can't invert field elements");
+        unsafe { ptr::copy(result.as_ptr(), output32s_ptr, result.len()) }
+    }
+
+    fn bn254_fr_pow(base32_ptr: *const u8, exp32_ptr: *const u8, output32_ptr: *mut u8) {
+        let base = unsafe { &*ptr::slice_from_raw_parts(base32_ptr, 32) };
+        let exp = unsafe { &*ptr::slice_from_raw_parts(exp32_ptr, 32) };
+        let result = SyscallBn254FrPow::fn_impl(base, exp).expect("bn254: malformed field element");
+        unsafe { ptr::copy(result.as_ptr(), output32_ptr, result.len()) }
+    }
+
+    fn bn254_fr_inner_product(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32_ptr: *mut u8,
+    ) {
+        let a = unsafe { &*ptr::slice_from_raw_parts(a32s_ptr, f32s_len as usize) };
+        let b = unsafe { &*ptr::slice_from_raw_parts(b32s_ptr, f32s_len as usize) };
+        let result =
+            SyscallBn254FrInnerProduct::fn_impl(a, b).expect("bn254: malformed field elements");
+        unsafe { ptr::copy(result.as_ptr(), output32_ptr, result.len()) }
+    }
+
+    fn groth16_verify(
+        vk_ptr: *const u8,
+        vk_len: u32,
+        proof_ptr: *const u8,
+        inputs_ptr: *const u8,
+        inputs_len: u32,
+    ) -> i32 {
+        let vk = unsafe { &*ptr::slice_from_raw_parts(vk_ptr, vk_len as usize) };
+        let proof = unsafe { &*ptr::slice_from_raw_parts(proof_ptr, GROTH16_PROOF_SIZE) };
+        let inputs = unsafe { &*ptr::slice_from_raw_parts(inputs_ptr, inputs_len as usize) };
+        match SyscallGroth16Verify::fn_impl(vk, proof, inputs) {
+            Ok(is_valid) => is_valid as i32,
+            Err(err) => err.into_i32(),
+        }
+    }
+
     fn read(target: &mut [u8], offset: u32) {
         let result =
             with_context(|ctx| SyscallRead::fn_impl(ctx, offset, target.len() as u32).unwrap());
@@ -136,6 +244,7 @@ impl SharedAPI for LowLevelSDK {
 
     fn exit(exit_code: i32) -> ! {
         with_context_mut(|ctx| SyscallExit::fn_impl(ctx, exit_code));
+        EXITED.with(|exited| exited.set(true));
         unreachable!("exit code: {}", exit_code);
     }
 
@@ -212,8 +321,19 @@ impl SovereignAPI for LowLevelSDK {
         fuel_ptr: *mut u32,
         state: u32,
     ) -> i32 {
+        let bytecode_hash32 = unsafe { &*ptr::slice_from_raw_parts(bytecode_hash32_ptr, 32) };
+        let native_contract = NATIVE_CONTRACTS.with(|contracts| {
+            contracts
+                .borrow()
+                .get(&B256::from_slice(bytecode_hash32))
+                .copied()
+        });
+        if let Some(main) = native_contract {
+            let input = unsafe { &*ptr::slice_from_raw_parts(input_ptr, input_len as usize) };
+            let context = unsafe { &*ptr::slice_from_raw_parts(context_ptr, context_len as usize) };
+            return call_native_contract(main, input.to_vec(), context.to_vec(), state);
+        }
         with_context_mut(|ctx| {
-            let bytecode_hash32 = unsafe { &*ptr::slice_from_raw_parts(bytecode_hash32_ptr, 32) };
             let input =
                 unsafe { &*ptr::slice_from_raw_parts(input_ptr, input_len as usize) }.to_vec();
             let context =
@@ -293,8 +413,9 @@ impl SovereignAPI for LowLevelSDK {
     ) {
         with_context_mut(|ctx| {
             let key = unsafe { &*ptr::slice_from_raw_parts(address20_ptr, 20) };
+            // the length is in bytes, the same as the wasm runtime reads it
             let topics =
-                unsafe { &*ptr::slice_from_raw_parts(topics32s_ptr, topics32s_len as usize) }
+                unsafe { &*ptr::slice_from_raw_parts(topics32s_ptr, topics32s_len as usize / 32) }
                     .iter()
                     .map(|v| B256::new(*v))
                     .collect::<Vec<_>>();
@@ -363,3 +484,107 @@ impl LowLevelSDK {
         with_context_mut(|ctx| ctx.jzkt().clone())
     }
 }
+
+/// Replaces the state and context of the current thread with empty ones.
+pub(crate) fn reset_context() {
+    CONTEXT.with(|ctx| ctx.set(new_context()));
+    STORAGE_KEYS.with(|keys| keys.borrow_mut().clear());
+    NATIVE_CONTRACTS.with(|contracts| contracts.borrow_mut().clear());
+}
+
+/// Sets input, context and state for the next call of a contract and clears the last result.
+pub(crate) fn start_call(input: Vec<u8>, context: Vec<u8>, state: u32) {
+    EXITED.with(|exited| exited.set(false));
+    with_context_mut(|ctx| {
+        ctx.change_input(input.clone());
+        ctx.change_context(context.clone());
+        ctx.clean_output();
+        SyscallExit::fn_impl(ctx, ExitCode::Ok.into_i32());
+        *ctx = take(ctx).with_state(state);
+    });
+}
+
+/// Returns the exit code and output of the last call, the exit code is `None` if the contract
+/// didn't call `exit`.
+pub(crate) fn finish_call() -> (Option<i32>, Vec<u8>) {
+    let exited = EXITED.with(|exited| exited.replace(false));
+    with_context_mut(|ctx| {
+        let exit_code = exited.then(|| ctx.exit_code());
+        let output = ctx.output().clone();
+        ctx.clean_output();
+        (exit_code, output)
+    })
+}
+
+/// Runs the entrypoint of a contract in the current context and returns its exit code and
+/// output, panics revert with their message the same as the panic handler of wasm contracts does.
+pub(crate) fn run_entrypoint<F: FnOnce()>(entrypoint: F) -> (i32, Vec<u8>) {
+    let result = catch_unwind(AssertUnwindSafe(entrypoint));
+    let (exit_code, mut output) = finish_call();
+    let exit_code = match (exit_code, result) {
+        (Some(exit_code), _) => exit_code,
+        (None, Ok(())) => ExitCode::Ok.into_i32(),
+        (None, Err(payload)) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            output.extend(encode_solidity_error_message(&message));
+            ExitCode::Panic.into_i32()
+        }
+    };
+    (exit_code, output)
+}
+
+pub(crate) fn register_native_contract(rwasm_code_hash: B256, main: fn()) {
+    NATIVE_CONTRACTS.with(|contracts| contracts.borrow_mut().insert(rwasm_code_hash, main));
+}
+
+/// Calls a contract deployed by `TestEnv::set_contract` in a new context that shares the state
+/// with the caller, the output becomes return data of the caller like `context_call` does it.
+fn call_native_contract(main: fn(), input: Vec<u8>, context: Vec<u8>, state: u32) -> i32 {
+    let mut caller = CONTEXT.with(|ctx| ctx.take());
+    if caller.depth() >= CALL_STACK_LIMIT {
+        CONTEXT.with(|ctx| ctx.set(caller));
+        return ExitCode::CallDepthOverflow.into_i32();
+    }
+    let callee = Context::new(&[0u8; 0])
+        .with_input(input)
+        .with_context(context)
+        .with_state(state)
+        .with_jzkt(caller.jzkt().clone())
+        .with_depth(caller.depth() + 1);
+    CONTEXT.with(|ctx| ctx.set(callee));
+    let (exit_code, output) = run_entrypoint(main);
+    caller.change_return_data(output);
+    CONTEXT.with(|ctx| ctx.set(caller));
+    exit_code
+}
+
+/// Starts keeping preimages of storage keys for a new `TestEnv`.
+pub(crate) fn enter_test_env() {
+    TEST_ENVS.with(|envs| envs.set(envs.get() + 1));
+}
+
+/// Stops keeping preimages of storage keys and drops them once the last `TestEnv` is gone.
+pub(crate) fn exit_test_env() {
+    let envs = TEST_ENVS.with(|envs| {
+        envs.set(envs.get().saturating_sub(1));
+        envs.get()
+    });
+    if envs == 0 {
+        STORAGE_KEYS.with(|keys| keys.borrow_mut().clear());
+    }
+}
+
+pub(crate) fn remember_storage_key(storage_key: [u8; 32], address: Address, slot: U256) {
+    if TEST_ENVS.with(|envs| envs.get()) == 0 {
+        return;
+    }
+    STORAGE_KEYS.with(|keys| keys.borrow_mut().insert(storage_key, (address, slot)));
+}
+
+pub(crate) fn storage_key_preimage(storage_key: &[u8; 32]) -> Option<(Address, U256)> {
+    STORAGE_KEYS.with(|keys| keys.borrow().get(storage_key).copied())
+}
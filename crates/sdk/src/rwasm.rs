@@ -1,5 +1,11 @@
 use crate::{
     bindings::{
+        _bn254_fr_add,
+        _bn254_fr_batch_inv,
+        _bn254_fr_inner_product,
+        _bn254_fr_inv,
+        _bn254_fr_mul,
+        _bn254_fr_pow,
         _charge_fuel,
         _checkpoint,
         _commit,
@@ -12,6 +18,7 @@ use crate::{
         _exit,
         _forward_output,
         _get_leaf,
+        _groth16_verify,
         _input_size,
         _keccak256,
         _output_size,
@@ -128,6 +135,62 @@ impl SharedAPI for LowLevelSDK {
     fn ecrecover(digest32_ptr: *const u8, sig64_ptr: *const u8, output65_ptr: *mut u8, rec_id: u8) {
         unsafe { _ecrecover(digest32_ptr, sig64_ptr, output65_ptr, rec_id as u32) }
     }
+
+    #[inline(always)]
+    fn bn254_fr_add(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    ) {
+        unsafe { _bn254_fr_add(a32s_ptr, b32s_ptr, f32s_len, output32s_ptr) }
+    }
+
+    #[inline(always)]
+    fn bn254_fr_mul(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    ) {
+        unsafe { _bn254_fr_mul(a32s_ptr, b32s_ptr, f32s_len, output32s_ptr) }
+    }
+
+    #[inline(always)]
+    fn bn254_fr_inv(f32_ptr: *const u8, output32_ptr: *mut u8) {
+        unsafe { _bn254_fr_inv(f32_ptr, output32_ptr) }
+    }
+
+    #[inline(always)]
+    fn bn254_fr_batch_inv(f32s_ptr: *const u8, f32s_len: u32, output32s_ptr: *mut u8) {
+        unsafe { _bn254_fr_batch_inv(f32s_ptr, f32s_len, output32s_ptr) }
+    }
+
+    #[inline(always)]
+    fn bn254_fr_pow(base32_ptr: *const u8, exp32_ptr: *const u8, output32_ptr: *mut u8) {
+        unsafe { _bn254_fr_pow(base32_ptr, exp32_ptr, output32_ptr) }
+    }
+
+    #[inline(always)]
+    fn bn254_fr_inner_product(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32_ptr: *mut u8,
+    ) {
+        unsafe { _bn254_fr_inner_product(a32s_ptr, b32s_ptr, f32s_len, output32_ptr) }
+    }
+
+    #[inline(always)]
+    fn groth16_verify(
+        vk_ptr: *const u8,
+        vk_len: u32,
+        proof_ptr: *const u8,
+        inputs_ptr: *const u8,
+        inputs_len: u32,
+    ) -> i32 {
+        unsafe { _groth16_verify(vk_ptr, vk_len, proof_ptr, inputs_ptr, inputs_len) }
+    }
 }
 
 impl SovereignAPI for LowLevelSDK {
@@ -7,7 +7,37 @@ pub trait SharedAPI {
         fd32_ptr: *const u8,
         output32_ptr: *mut u8,
     );
+    /// Recovers an uncompressed public key (`0x04 ‖ x ‖ y`) from a `r ‖ s` signature, traps if
+    /// the signature doesn't recover to one.
     fn ecrecover(digest32_ptr: *const u8, sig65_ptr: *const u8, output65_ptr: *mut u8, rec_id: u8);
+    fn bn254_fr_add(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    );
+    fn bn254_fr_mul(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32s_ptr: *mut u8,
+    );
+    fn bn254_fr_inv(f32_ptr: *const u8, output32_ptr: *mut u8);
+    fn bn254_fr_batch_inv(f32s_ptr: *const u8, f32s_len: u32, output32s_ptr: *mut u8);
+    fn bn254_fr_pow(base32_ptr: *const u8, exp32_ptr: *const u8, output32_ptr: *mut u8);
+    fn bn254_fr_inner_product(
+        a32s_ptr: *const u8,
+        b32s_ptr: *const u8,
+        f32s_len: u32,
+        output32_ptr: *mut u8,
+    );
+    fn groth16_verify(
+        vk_ptr: *const u8,
+        vk_len: u32,
+        proof_ptr: *const u8,
+        inputs_ptr: *const u8,
+        inputs_len: u32,
+    ) -> i32;
 
     fn read(target: &mut [u8], offset: u32);
     fn input_size() -> u32;
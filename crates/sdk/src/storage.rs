@@ -0,0 +1,700 @@
+//! Typed contract storage with the Solidity storage layout.
+//!
+//! Storage types are handles of storage slots, they don't hold any data and can be declared as
+//! constants, values are read and written through a `StorageBackend`. Slots are computed exactly
+//! like Solidity does, so a contract can share its storage with a Solidity contract or be
+//! migrated from one:
+//! - values smaller than 32 bytes can be packed into one slot with a byte offset
+//! - a value of `StorageMap` with key `k` at slot `p` is stored at `keccak256(h(k) . p)`, where
+//!   `h(k)` is the ABI encoded key for value types and raw bytes for `bytes` and `string`
+//! - `StorageVec` keeps its length at slot `p` and elements from `keccak256(p)`, elements smaller
+//!   than 16 bytes are packed
+//! - `StorageBytes` is stored in slot `p` together with its length if it's shorter than 32 bytes,
+//!   otherwise slot `p` keeps `length * 2 + 1` and data is stored from `keccak256(p)`
+use crate::{AccountManager, LowLevelSDK, SharedAPI};
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+use fluentbase_types::{Address, Bytes, U256};
+use revm_primitives::alloy_primitives::{FixedBytes, Uint};
+
+/// Reads and writes storage slots of a contract.
+pub trait StorageBackend {
+    fn sload(&self, slot: U256) -> U256;
+    fn sstore(&self, slot: U256, value: U256);
+}
+
+/// Storage of a contract account managed by `AccountManager`.
+pub struct AccountStorage<'a, AM: AccountManager> {
+    am: &'a AM,
+    address: Address,
+}
+
+impl<'a, AM: AccountManager> AccountStorage<'a, AM> {
+    pub fn new(am: &'a AM, address: Address) -> Self {
+        Self { am, address }
+    }
+}
+
+impl<'a, AM: AccountManager> StorageBackend for AccountStorage<'a, AM> {
+    fn sload(&self, slot: U256) -> U256 {
+        self.am.storage(self.address, slot, false).0
+    }
+
+    fn sstore(&self, slot: U256, value: U256) {
+        self.am.write_storage(self.address, slot, value);
+    }
+}
+
+fn keccak256(data: &[u8]) -> U256 {
+    let mut hash = [0u8; 32];
+    LowLevelSDK::keccak256(data.as_ptr(), data.len() as u32, hash.as_mut_ptr());
+    U256::from_be_bytes(hash)
+}
+
+/// Value types that take up to 32 bytes of a slot.
+pub trait StoragePrimitive: Sized {
+    /// Number of bytes of the value in a slot.
+    const SIZE: usize;
+
+    /// Converts the value into the lowest `SIZE` bytes of a word.
+    fn to_word(&self) -> U256;
+
+    /// Reads the value from the lowest `SIZE` bytes of a word, higher bytes are zero.
+    fn from_word(word: U256) -> Self;
+}
+
+macro_rules! impl_storage_uint {
+    ($($typ:ty),*) => {
+        $(
+            impl StoragePrimitive for $typ {
+                const SIZE: usize = core::mem::size_of::<$typ>();
+                fn to_word(&self) -> U256 {
+                    U256::from(*self)
+                }
+                fn from_word(word: U256) -> Self {
+                    word.to::<$typ>()
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_uint!(u8, u16, u32, u64, u128);
+
+// signed integers are stored in two's complement truncated to their size
+macro_rules! impl_storage_int {
+    ($($typ:ty => $unsigned:ty),*) => {
+        $(
+            impl StoragePrimitive for $typ {
+                const SIZE: usize = core::mem::size_of::<$typ>();
+                fn to_word(&self) -> U256 {
+                    U256::from(*self as $unsigned)
+                }
+                fn from_word(word: U256) -> Self {
+                    word.to::<$unsigned>() as $typ
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_int!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+impl StoragePrimitive for bool {
+    const SIZE: usize = 1;
+    fn to_word(&self) -> U256 {
+        U256::from(*self as u8)
+    }
+    fn from_word(word: U256) -> Self {
+        word != U256::ZERO
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> StoragePrimitive for Uint<BITS, LIMBS> {
+    const SIZE: usize = {
+        assert!(
+            BITS <= 256 && BITS & 7 == 0,
+            "only byte-sized integers up to 256 bits"
+        );
+        BITS / 8
+    };
+    fn to_word(&self) -> U256 {
+        U256::from_le_slice(self.as_le_slice())
+    }
+    fn from_word(word: U256) -> Self {
+        Self::from_le_slice(&word.as_le_slice()[..Self::SIZE])
+    }
+}
+
+impl StoragePrimitive for Address {
+    const SIZE: usize = 20;
+    fn to_word(&self) -> U256 {
+        U256::from_be_slice(self.as_slice())
+    }
+    fn from_word(word: U256) -> Self {
+        Address::from_word(word.to_be_bytes::<32>().into())
+    }
+}
+
+impl<const N: usize> StoragePrimitive for FixedBytes<N> {
+    const SIZE: usize = {
+        assert!(N <= 32, "fixed bytes can't be longer than 32 bytes");
+        N
+    };
+    fn to_word(&self) -> U256 {
+        U256::from_be_slice(self.as_slice())
+    }
+    fn from_word(word: U256) -> Self {
+        FixedBytes::from_slice(&word.to_be_bytes::<32>()[32 - N..])
+    }
+}
+
+fn size_mask(size: usize) -> U256 {
+    if size >= 32 {
+        U256::MAX
+    } else {
+        (U256::from(1) << (size * 8)) - U256::from(1)
+    }
+}
+
+fn read_primitive<T: StoragePrimitive, S: StorageBackend>(
+    storage: &S,
+    slot: U256,
+    offset: usize,
+) -> T {
+    let word = storage.sload(slot);
+    T::from_word((word >> (offset * 8)) & size_mask(T::SIZE))
+}
+
+fn write_primitive<T: StoragePrimitive, S: StorageBackend>(
+    storage: &S,
+    slot: U256,
+    offset: usize,
+    value: &T,
+) {
+    let mask = size_mask(T::SIZE);
+    let value = (value.to_word() & mask) << (offset * 8);
+    let word = if T::SIZE == 32 {
+        value
+    } else {
+        storage.sload(slot) & !(mask << (offset * 8)) | value
+    };
+    storage.sstore(slot, word);
+}
+
+/// Keys of `StorageMap`.
+pub trait StorageKey {
+    /// Appends `h(k)` of the Solidity layout: ABI encoded value types and raw bytes of `bytes`
+    /// and `string`.
+    fn append_key(&self, out: &mut Vec<u8>);
+
+    /// Slot of the value of the key in a mapping at `slot`.
+    fn storage_slot(&self, slot: U256) -> U256 {
+        let mut data = Vec::with_capacity(64);
+        self.append_key(&mut data);
+        data.extend_from_slice(&slot.to_be_bytes::<32>());
+        keccak256(&data)
+    }
+}
+
+macro_rules! impl_storage_key_uint {
+    ($($typ:ty),*) => {
+        $(
+            impl StorageKey for $typ {
+                fn append_key(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_word().to_be_bytes::<32>());
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_key_uint!(bool, u8, u16, u32, u64, u128, Address);
+
+impl<const BITS: usize, const LIMBS: usize> StorageKey for Uint<BITS, LIMBS> {
+    fn append_key(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_word().to_be_bytes::<32>());
+    }
+}
+
+macro_rules! impl_storage_key_int {
+    ($($typ:ty),*) => {
+        $(
+            impl StorageKey for $typ {
+                fn append_key(&self, out: &mut Vec<u8>) {
+                    // sign extended to 32 bytes
+                    let mut word = [if *self < 0 { 0xff } else { 0 }; 32];
+                    word[16..].copy_from_slice(&(*self as i128).to_be_bytes());
+                    out.extend_from_slice(&word);
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_key_int!(i8, i16, i32, i64, i128);
+
+impl<const N: usize> StorageKey for FixedBytes<N> {
+    fn append_key(&self, out: &mut Vec<u8>) {
+        // `bytesN` are left aligned in ABI words
+        let mut word = [0u8; 32];
+        word[..N].copy_from_slice(self.as_slice());
+        out.extend_from_slice(&word);
+    }
+}
+
+impl StorageKey for Bytes {
+    fn append_key(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self);
+    }
+}
+
+impl StorageKey for String {
+    fn append_key(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl StorageKey for str {
+    fn append_key(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+/// Storage containers that take one slot, they can be values of maps and elements of vectors.
+pub trait StorageSlot {
+    fn at_slot(slot: U256) -> Self;
+}
+
+/// A value in a slot, values smaller than 32 bytes can share a slot with other values.
+pub struct StorageValue<T> {
+    slot: U256,
+    offset: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: StoragePrimitive> StorageValue<T> {
+    pub const fn new(slot: U256) -> Self {
+        Self::packed(slot, 0)
+    }
+
+    /// A value at `offset` bytes from the lowest byte of the slot, like Solidity packs
+    /// consecutive state variables.
+    pub const fn packed(slot: U256, offset: usize) -> Self {
+        assert!(offset + T::SIZE <= 32, "value doesn't fit into the slot");
+        Self {
+            slot,
+            offset,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn slot(&self) -> U256 {
+        self.slot
+    }
+
+    pub fn get<S: StorageBackend>(&self, storage: &S) -> T {
+        read_primitive(storage, self.slot, self.offset)
+    }
+
+    pub fn set<S: StorageBackend>(&self, storage: &S, value: T) {
+        write_primitive(storage, self.slot, self.offset, &value)
+    }
+}
+
+/// Solidity `mapping(K => V)`, values can be primitives or other storage containers.
+pub struct StorageMap<K: ?Sized, V> {
+    slot: U256,
+    _marker: PhantomData<(PhantomData<K>, V)>,
+}
+
+impl<K: StorageKey + ?Sized, V> StorageMap<K, V> {
+    pub const fn new(slot: U256) -> Self {
+        Self {
+            slot,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn slot(&self) -> U256 {
+        self.slot
+    }
+}
+
+impl<K: StorageKey + ?Sized, V: StoragePrimitive> StorageMap<K, V> {
+    pub fn get<S: StorageBackend>(&self, storage: &S, key: &K) -> V {
+        read_primitive(storage, key.storage_slot(self.slot), 0)
+    }
+
+    pub fn set<S: StorageBackend>(&self, storage: &S, key: &K, value: V) {
+        write_primitive(storage, key.storage_slot(self.slot), 0, &value)
+    }
+}
+
+impl<K: StorageKey + ?Sized, V: StorageSlot> StorageMap<K, V> {
+    /// Container stored under the key, e.g. the inner map of a nested mapping.
+    pub fn entry(&self, key: &K) -> V {
+        V::at_slot(key.storage_slot(self.slot))
+    }
+}
+
+impl<K: StorageKey + ?Sized, V> StorageSlot for StorageMap<K, V> {
+    fn at_slot(slot: U256) -> Self {
+        Self::new(slot)
+    }
+}
+
+/// Solidity dynamic storage array `T[]`.
+pub struct StorageVec<T> {
+    slot: U256,
+    _marker: PhantomData<T>,
+}
+
+impl<T> StorageVec<T> {
+    pub const fn new(slot: U256) -> Self {
+        Self {
+            slot,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn slot(&self) -> U256 {
+        self.slot
+    }
+
+    pub fn len<S: StorageBackend>(&self, storage: &S) -> usize {
+        storage.sload(self.slot).to::<usize>()
+    }
+
+    pub fn is_empty<S: StorageBackend>(&self, storage: &S) -> bool {
+        self.len(storage) == 0
+    }
+
+    fn data_slot(&self) -> U256 {
+        keccak256(&self.slot.to_be_bytes::<32>())
+    }
+}
+
+impl<T: StoragePrimitive> StorageVec<T> {
+    // elements that fit into a slot several times are packed
+    const ITEMS_PER_SLOT: usize = if T::SIZE <= 16 { 32 / T::SIZE } else { 1 };
+
+    fn position(&self, index: usize) -> (U256, usize) {
+        let slot = self.data_slot() + U256::from(index / Self::ITEMS_PER_SLOT);
+        (slot, index % Self::ITEMS_PER_SLOT * T::SIZE)
+    }
+
+    pub fn get<S: StorageBackend>(&self, storage: &S, index: usize) -> Option<T> {
+        if index >= self.len(storage) {
+            return None;
+        }
+        let (slot, offset) = self.position(index);
+        Some(read_primitive(storage, slot, offset))
+    }
+
+    /// Panics if the index is out of bounds, like Solidity does.
+    pub fn set<S: StorageBackend>(&self, storage: &S, index: usize, value: T) {
+        assert!(
+            index < self.len(storage),
+            "storage vector index out of bounds"
+        );
+        let (slot, offset) = self.position(index);
+        write_primitive(storage, slot, offset, &value)
+    }
+
+    pub fn push<S: StorageBackend>(&self, storage: &S, value: T) {
+        let length = self.len(storage);
+        let (slot, offset) = self.position(length);
+        write_primitive(storage, slot, offset, &value);
+        storage.sstore(self.slot, U256::from(length + 1));
+    }
+
+    /// Removes the last element and clears its storage.
+    pub fn pop<S: StorageBackend>(&self, storage: &S) -> Option<T> {
+        let length = self.len(storage);
+        if length == 0 {
+            return None;
+        }
+        let (slot, offset) = self.position(length - 1);
+        let value = read_primitive(storage, slot, offset);
+        write_primitive(storage, slot, offset, &T::from_word(U256::ZERO));
+        storage.sstore(self.slot, U256::from(length - 1));
+        Some(value)
+    }
+}
+
+impl<T: StorageSlot> StorageVec<T> {
+    /// Container at the index, each of them takes one slot.
+    pub fn entry<S: StorageBackend>(&self, storage: &S, index: usize) -> Option<T> {
+        if index >= self.len(storage) {
+            return None;
+        }
+        Some(T::at_slot(self.data_slot() + U256::from(index)))
+    }
+
+    /// Increases the length and returns the new element.
+    pub fn grow<S: StorageBackend>(&self, storage: &S) -> T {
+        let length = self.len(storage);
+        storage.sstore(self.slot, U256::from(length + 1));
+        T::at_slot(self.data_slot() + U256::from(length))
+    }
+}
+
+impl<T> StorageSlot for StorageVec<T> {
+    fn at_slot(slot: U256) -> Self {
+        Self::new(slot)
+    }
+}
+
+/// Solidity `bytes` and `string`.
+pub struct StorageBytes {
+    slot: U256,
+}
+
+impl StorageBytes {
+    pub const fn new(slot: U256) -> Self {
+        Self { slot }
+    }
+
+    pub fn slot(&self) -> U256 {
+        self.slot
+    }
+
+    pub fn len<S: StorageBackend>(&self, storage: &S) -> usize {
+        Self::decode_length(storage.sload(self.slot))
+    }
+
+    pub fn is_empty<S: StorageBackend>(&self, storage: &S) -> bool {
+        self.len(storage) == 0
+    }
+
+    fn decode_length(word: U256) -> usize {
+        if word.bit(0) {
+            // long: the slot keeps `length * 2 + 1`
+            (word >> 1usize).to::<usize>()
+        } else {
+            // short: the lowest byte keeps `length * 2`
+            word.byte(0) as usize / 2
+        }
+    }
+
+    fn data_slot(&self) -> U256 {
+        keccak256(&self.slot.to_be_bytes::<32>())
+    }
+
+    pub fn get<S: StorageBackend>(&self, storage: &S) -> Bytes {
+        let word = storage.sload(self.slot);
+        let length = Self::decode_length(word);
+        if !word.bit(0) {
+            return Bytes::copy_from_slice(&word.to_be_bytes::<32>()[..length]);
+        }
+        let data_slot = self.data_slot();
+        let mut result = Vec::with_capacity(length);
+        for i in 0..length.div_ceil(32) {
+            let chunk = storage.sload(data_slot + U256::from(i)).to_be_bytes::<32>();
+            let chunk_length = core::cmp::min(32, length - i * 32);
+            result.extend_from_slice(&chunk[..chunk_length]);
+        }
+        result.into()
+    }
+
+    /// Reads a `string`, panics if it isn't valid UTF-8.
+    pub fn get_string<S: StorageBackend>(&self, storage: &S) -> String {
+        String::from_utf8(self.get(storage).to_vec()).expect("storage string is not valid UTF-8")
+    }
+
+    /// Writes the bytes and clears slots of the previous value that aren't used anymore.
+    pub fn set<S: StorageBackend>(&self, storage: &S, value: &[u8]) {
+        let old_word = storage.sload(self.slot);
+        let old_slots = if old_word.bit(0) {
+            Self::decode_length(old_word).div_ceil(32)
+        } else {
+            0
+        };
+        let data_slot = self.data_slot();
+        let new_slots = if value.len() < 32 {
+            let mut word = [0u8; 32];
+            word[..value.len()].copy_from_slice(value);
+            word[31] = value.len() as u8 * 2;
+            storage.sstore(self.slot, U256::from_be_bytes(word));
+            0
+        } else {
+            storage.sstore(self.slot, U256::from(value.len() * 2 + 1));
+            for (i, chunk) in value.chunks(32).enumerate() {
+                let mut word = [0u8; 32];
+                word[..chunk.len()].copy_from_slice(chunk);
+                storage.sstore(data_slot + U256::from(i), U256::from_be_bytes(word));
+            }
+            value.len().div_ceil(32)
+        };
+        for i in new_slots..old_slots {
+            storage.sstore(data_slot + U256::from(i), U256::ZERO);
+        }
+    }
+
+    pub fn clear<S: StorageBackend>(&self, storage: &S) {
+        self.set(storage, &[])
+    }
+}
+
+impl StorageSlot for StorageBytes {
+    fn at_slot(slot: U256) -> Self {
+        Self::new(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::RefCell;
+    use fluentbase_types::address;
+    use revm_primitives::{hex, keccak256 as expected_keccak256};
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStorage(RefCell<HashMap<U256, U256>>);
+
+    impl StorageBackend for MemoryStorage {
+        fn sload(&self, slot: U256) -> U256 {
+            self.0.borrow().get(&slot).copied().unwrap_or_default()
+        }
+
+        fn sstore(&self, slot: U256, value: U256) {
+            self.0.borrow_mut().insert(slot, value);
+        }
+    }
+
+    fn slot_of(data: &[u8]) -> U256 {
+        U256::from_be_bytes(expected_keccak256(data).0)
+    }
+
+    #[test]
+    fn test_packed_values() {
+        let storage = MemoryStorage::default();
+        // contract { uint128 a; address b; bool c; int8 d; }
+        let a = StorageValue::<u128>::new(U256::ZERO);
+        let b = StorageValue::<Address>::new(U256::from(1));
+        let c = StorageValue::<bool>::packed(U256::from(1), 20);
+        let d = StorageValue::<i8>::packed(U256::from(1), 21);
+        a.set(&storage, 7);
+        b.set(
+            &storage,
+            address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"),
+        );
+        c.set(&storage, true);
+        d.set(&storage, -2);
+        assert_eq!(
+            storage.sload(U256::from(1)),
+            U256::from_be_bytes(hex!(
+                "00000000000000000000fe01f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+            ))
+        );
+        assert_eq!(a.get(&storage), 7);
+        assert_eq!(
+            b.get(&storage),
+            address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+        assert!(c.get(&storage));
+        assert_eq!(d.get(&storage), -2);
+        b.set(&storage, Address::ZERO);
+        assert!(c.get(&storage));
+    }
+
+    #[test]
+    fn test_mapping_slots() {
+        let storage = MemoryStorage::default();
+        let owner = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        let spender = address!("390a4CEdBb65be7511D9E1a35b115376F39DbDF3");
+        // mapping(address => uint256) at slot 0
+        let balances = StorageMap::<Address, U256>::new(U256::ZERO);
+        balances.set(&storage, &owner, U256::from(100));
+        let slot = slot_of(&[owner.into_word().as_slice(), &[0u8; 32]].concat());
+        assert_eq!(storage.sload(slot), U256::from(100));
+        assert_eq!(balances.get(&storage, &owner), U256::from(100));
+        // mapping(address => mapping(address => uint256)) at slot 1
+        let allowances = StorageMap::<Address, StorageMap<Address, U256>>::new(U256::from(1));
+        allowances
+            .entry(&owner)
+            .set(&storage, &spender, U256::from(5));
+        let inner = slot_of(
+            &[
+                owner.into_word().as_slice(),
+                &U256::from(1).to_be_bytes::<32>(),
+            ]
+            .concat(),
+        );
+        let slot = slot_of(&[spender.into_word().as_slice(), &inner.to_be_bytes::<32>()].concat());
+        assert_eq!(storage.sload(slot), U256::from(5));
+        // mapping(string => int64) keys are hashed unpadded
+        let names = StorageMap::<str, i64>::new(U256::from(2));
+        names.set(&storage, "alice", -1);
+        let slot = slot_of(&[b"alice".as_slice(), &U256::from(2).to_be_bytes::<32>()].concat());
+        assert_eq!(storage.sload(slot), U256::from(u64::MAX));
+        assert_eq!(names.get(&storage, "alice"), -1);
+        // mapping(int16 => bool) keys are sign extended
+        let flags = StorageMap::<i16, bool>::new(U256::from(3));
+        flags.set(&storage, &-1, true);
+        let slot = slot_of(&[[0xff; 32].as_slice(), &U256::from(3).to_be_bytes::<32>()].concat());
+        assert_eq!(storage.sload(slot), U256::from(1));
+    }
+
+    #[test]
+    fn test_vec_packing() {
+        let storage = MemoryStorage::default();
+        // uint64[] at slot 5, four elements per slot
+        let values = StorageVec::<u64>::new(U256::from(5));
+        for i in 0..5 {
+            values.push(&storage, i + 1);
+        }
+        assert_eq!(values.len(&storage), 5);
+        let data_slot = slot_of(&U256::from(5).to_be_bytes::<32>());
+        assert_eq!(
+            storage.sload(data_slot),
+            U256::from(4) << 192 | U256::from(3) << 128 | U256::from(2) << 64 | U256::from(1)
+        );
+        assert_eq!(storage.sload(data_slot + U256::from(1)), U256::from(5));
+        assert_eq!(values.get(&storage, 2), Some(3));
+        assert_eq!(values.get(&storage, 5), None);
+        values.set(&storage, 2, 30);
+        assert_eq!(values.get(&storage, 2), Some(30));
+        assert_eq!(values.pop(&storage), Some(5));
+        assert_eq!(storage.sload(data_slot + U256::from(1)), U256::ZERO);
+        assert_eq!(values.len(&storage), 4);
+        // uint256[][] elements are vectors in consecutive slots
+        let nested = StorageVec::<StorageVec<U256>>::new(U256::from(6));
+        nested.grow(&storage).push(&storage, U256::from(9));
+        let inner_slot = slot_of(&U256::from(6).to_be_bytes::<32>());
+        assert_eq!(storage.sload(inner_slot), U256::from(1));
+        assert_eq!(
+            nested.entry(&storage, 0).unwrap().get(&storage, 0),
+            Some(U256::from(9))
+        );
+    }
+
+    #[test]
+    fn test_bytes_layout() {
+        let storage = MemoryStorage::default();
+        let value = StorageBytes::new(U256::from(7));
+        // short values are stored with their length in the slot
+        value.set(&storage, b"hello");
+        assert_eq!(
+            storage.sload(U256::from(7)),
+            U256::from_be_bytes(hex!(
+                "68656c6c6f00000000000000000000000000000000000000000000000000000a"
+            ))
+        );
+        assert_eq!(value.get_string(&storage), "hello");
+        // long values keep `length * 2 + 1` in the slot and data from keccak256(slot)
+        let long = [0xabu8; 40];
+        value.set(&storage, &long);
+        assert_eq!(storage.sload(U256::from(7)), U256::from(81));
+        let data_slot = slot_of(&U256::from(7).to_be_bytes::<32>());
+        assert_eq!(storage.sload(data_slot), U256::from_be_bytes([0xab; 32]));
+        assert_eq!(value.len(&storage), 40);
+        assert_eq!(value.get(&storage), Bytes::copy_from_slice(&long));
+        // data slots of a long value are cleared when it's replaced with a short one
+        value.set(&storage, b"");
+        assert_eq!(storage.sload(data_slot + U256::from(1)), U256::ZERO);
+        assert!(value.is_empty(&storage));
+    }
+}
@@ -0,0 +1,350 @@
+//! Native test environment for contracts.
+//!
+//! `TestEnv` runs `deploy` and `main` of a contract against the std runtime of `LowLevelSDK`,
+//! so contracts can be unit tested without compiling them to wasm:
+//!
+//! ```ignore
+//! let mut env = TestEnv::new().with_address(token).with_caller(owner);
+//! env.set_balance(owner, U256::from(100));
+//! assert!(env.deploy(&[], deploy).is_ok());
+//! env.context.contract_value = U256::from(1);
+//! let result = env.call(&transferCall { to, value }.abi_encode(), main);
+//! assert_eq!(result.exit_code, ExitCode::Ok.into_i32());
+//! assert_eq!(env.storage(token, U256::ZERO), U256::from(1));
+//! ```
+//!
+//! Contracts called by the tested one are deployed with `set_contract`, they run natively as
+//! well. Every call is a transaction: its changes are committed if it succeeds and rolled back
+//! otherwise. State is kept in a thread local, tests running in parallel don't share it, but
+//! only one `TestEnv` should be used by a test at a time.
+use crate::{
+    runtime::{
+        enter_test_env,
+        exit_test_env,
+        register_native_contract,
+        reset_context,
+        run_entrypoint,
+        start_call,
+        storage_key_preimage,
+    },
+    Account,
+    AccountManager,
+    ContractInput,
+    JzktAccountManager,
+    LowLevelSDK,
+};
+use fluentbase_codec::Encoder;
+use fluentbase_types::{
+    Address,
+    Bytes,
+    ExitCode,
+    IJournaledTrie,
+    JournalCheckpoint,
+    JournalEvent,
+    JournalLog,
+    STATE_DEPLOY,
+    STATE_MAIN,
+    U256,
+};
+use std::collections::BTreeMap;
+
+/// Result of a contract call made by `TestEnv`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub exit_code: i32,
+    pub output: Bytes,
+    /// Logs emitted by the call, they're empty if it failed.
+    pub logs: Vec<JournalLog>,
+    /// Storage slots written by the call with their new values, they're empty if it failed.
+    pub storage_changes: BTreeMap<(Address, U256), U256>,
+}
+
+impl TestResult {
+    pub fn is_ok(&self) -> bool {
+        self.exit_code == ExitCode::Ok.into_i32()
+    }
+
+    fn from_exit_code(exit_code: ExitCode) -> Self {
+        Self {
+            exit_code: exit_code.into_i32(),
+            output: Bytes::new(),
+            logs: Vec::new(),
+            storage_changes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Context and state of contract calls made natively in tests.
+pub struct TestEnv {
+    /// Context the contract reads with `ExecutionContext`.
+    pub context: ContractInput,
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        exit_test_env();
+    }
+}
+
+impl TestEnv {
+    /// Creates an environment with an empty state, the state left by previous tests of the
+    /// thread is dropped.
+    pub fn new() -> Self {
+        reset_context();
+        enter_test_env();
+        Self {
+            context: ContractInput::default(),
+        }
+    }
+
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.context.contract_address = address;
+        self
+    }
+
+    /// Sets the caller of the contract and the origin of the transaction.
+    pub fn with_caller(mut self, caller: Address) -> Self {
+        self.context.contract_caller = caller;
+        self.context.tx_caller = caller;
+        self
+    }
+
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.context.contract_value = value;
+        self
+    }
+
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.context.contract_gas_limit = gas_limit;
+        self.context.tx_gas_limit = gas_limit;
+        self
+    }
+
+    pub fn with_block(mut self, number: u64, timestamp: u64) -> Self {
+        self.context.block_number = number;
+        self.context.block_timestamp = timestamp;
+        self
+    }
+
+    pub fn with_static(mut self, is_static: bool) -> Self {
+        self.context.contract_is_static = is_static;
+        self
+    }
+
+    pub fn account(&self, address: Address) -> Account {
+        JzktAccountManager::DEFAULT.account(address).0
+    }
+
+    pub fn set_account(&self, account: &Account) {
+        let am = JzktAccountManager::DEFAULT;
+        am.write_account(account);
+        am.commit();
+    }
+
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        let mut account = self.account(address);
+        account.balance = balance;
+        self.set_account(&account);
+    }
+
+    pub fn storage(&self, address: Address, slot: U256) -> U256 {
+        JzktAccountManager::DEFAULT.storage(address, slot, false).0
+    }
+
+    pub fn set_storage(&self, address: Address, slot: U256, value: U256) {
+        let am = JzktAccountManager::DEFAULT;
+        am.write_storage(address, slot, value);
+        am.commit();
+    }
+
+    /// Deploys a contract that runs `main` natively at `address`, calls of the address made
+    /// with `call_contract` or contract clients execute it.
+    pub fn set_contract(&self, address: Address, main: fn()) {
+        let am = JzktAccountManager::DEFAULT;
+        let mut account = self.account(address);
+        // the code only has the WASM signature, its hash identifies `main`
+        let code = Bytes::from([&[0x00, 0x61, 0x73, 0x6d], address.as_slice()].concat());
+        account.update_bytecode(&am, &code, None, &code, None);
+        am.commit();
+        register_native_contract(account.rwasm_code_hash, main);
+    }
+
+    /// Runs the `deploy` entrypoint of a contract with `input` as constructor arguments.
+    pub fn deploy<F: FnOnce()>(&mut self, input: &[u8], deploy: F) -> TestResult {
+        self.run(STATE_DEPLOY, input, deploy)
+    }
+
+    /// Runs the `main` entrypoint of a contract with `input` as calldata.
+    pub fn call<F: FnOnce()>(&mut self, input: &[u8], main: F) -> TestResult {
+        self.run(STATE_MAIN, input, main)
+    }
+
+    fn run<F: FnOnce()>(&mut self, state: u32, input: &[u8], entrypoint: F) -> TestResult {
+        let am = JzktAccountManager::DEFAULT;
+        let checkpoint = am.checkpoint();
+        if let Err(exit_code) = self.transfer_value(&am) {
+            am.rollback(checkpoint);
+            return TestResult::from_exit_code(exit_code);
+        }
+
+        start_call(input.to_vec(), self.context.encode_to_vec(0), state);
+        let (exit_code, output) = run_entrypoint(entrypoint);
+
+        let jzkt = LowLevelSDK::with_default_jzkt();
+        if exit_code != ExitCode::Ok.into_i32() {
+            am.rollback(checkpoint);
+            return TestResult {
+                exit_code,
+                output: output.into(),
+                logs: Vec::new(),
+                storage_changes: BTreeMap::new(),
+            };
+        }
+        let mut storage_changes = BTreeMap::new();
+        let journal = jzkt.journal();
+        for event in journal
+            .iter()
+            .skip(JournalCheckpoint::from_u64(checkpoint).state())
+        {
+            let JournalEvent::ItemChanged { key, preimage, .. } = event else {
+                continue;
+            };
+            if let Some(storage_key) = storage_key_preimage(key) {
+                storage_changes.insert(storage_key, U256::from_le_bytes(preimage[0]));
+            }
+        }
+        let logs = jzkt.commit().map(|(_, logs)| logs).unwrap_or_default();
+        TestResult {
+            exit_code,
+            output: output.into(),
+            logs,
+            storage_changes,
+        }
+    }
+
+    fn transfer_value(&self, am: &JzktAccountManager) -> Result<(), ExitCode> {
+        let value = self.context.contract_value;
+        if value.is_zero() || self.context.contract_caller == self.context.contract_address {
+            return Ok(());
+        }
+        if self.context.contract_is_static {
+            return Err(ExitCode::WriteProtection);
+        }
+        let mut caller = self.account(self.context.contract_caller);
+        let mut callee = self.account(self.context.contract_address);
+        am.transfer(&mut caller, &mut callee, value)?;
+        am.write_account(&caller);
+        am.write_account(&callee);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountStorage, ContextReader, ExecutionContext, SharedAPI, StorageBackend};
+    use fluentbase_types::{encode_solidity_error_message, B256};
+
+    fn main() {
+        let cr = ExecutionContext::DEFAULT;
+        let input = ExecutionContext::contract_input();
+        if input.is_empty() {
+            panic!("empty input");
+        }
+        let storage = AccountStorage::new(&JzktAccountManager::DEFAULT, cr.contract_address());
+        let counter = storage.sload(U256::ZERO) + U256::from(input[0]);
+        storage.sstore(U256::ZERO, counter);
+        JzktAccountManager::DEFAULT.log(
+            cr.contract_address(),
+            Bytes::copy_from_slice(input),
+            &[B256::from(cr.contract_caller().into_word())],
+        );
+        LowLevelSDK::write(&counter.to_be_bytes::<32>());
+    }
+
+    #[test]
+    fn test_call() {
+        let address = Address::repeat_byte(1);
+        let caller = Address::repeat_byte(2);
+        let mut env = TestEnv::new().with_address(address).with_caller(caller);
+        env.set_storage(address, U256::ZERO, U256::from(10));
+
+        let result = env.call(&[5], main);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.output,
+            Bytes::copy_from_slice(&U256::from(15).to_be_bytes::<32>())
+        );
+        assert_eq!(
+            result.storage_changes,
+            BTreeMap::from([((address, U256::ZERO), U256::from(15))])
+        );
+        assert_eq!(
+            result.logs,
+            vec![JournalLog {
+                address,
+                topics: vec![B256::from(caller.into_word())],
+                data: Bytes::from(vec![5]),
+            }]
+        );
+        assert_eq!(env.storage(address, U256::ZERO), U256::from(15));
+    }
+
+    #[test]
+    fn test_failed_call() {
+        let address = Address::repeat_byte(1);
+        let caller = Address::repeat_byte(2);
+        let mut env = TestEnv::new().with_address(address).with_caller(caller);
+
+        let result = env.call(&[], main);
+        assert_eq!(result.exit_code, ExitCode::Panic.into_i32());
+        assert_eq!(
+            result.output,
+            Bytes::from(encode_solidity_error_message("empty input"))
+        );
+
+        // the value can't be paid, so nothing is called
+        let mut env = env.with_value(U256::from(1));
+        let result = env.call(&[1], main);
+        assert!(!result.is_ok());
+        assert_eq!(env.storage(address, U256::ZERO), U256::ZERO);
+
+        env.set_balance(caller, U256::from(3));
+        let result = env.call(&[1], || {
+            main();
+            LowLevelSDK::exit(ExitCode::Panic.into_i32());
+        });
+        assert_eq!(result.exit_code, ExitCode::Panic.into_i32());
+        assert!(result.storage_changes.is_empty());
+        assert_eq!(env.storage(address, U256::ZERO), U256::ZERO);
+        assert_eq!(env.account(caller).balance, U256::from(3));
+
+        let result = env.call(&[1], main);
+        assert!(result.is_ok());
+        assert_eq!(env.account(caller).balance, U256::from(2));
+        assert_eq!(env.account(address).balance, U256::from(1));
+    }
+
+    #[test]
+    fn test_storage_keys_are_kept_with_test_env() {
+        let address = Address::repeat_byte(1);
+        let slot = U256::from(7);
+        let storage_key = crate::calc_storage_key(&address, slot.as_le_slice().as_ptr());
+
+        let env = TestEnv::new();
+        env.set_storage(address, slot, U256::from(1));
+        assert_eq!(storage_key_preimage(&storage_key), Some((address, slot)));
+        drop(env);
+        assert_eq!(storage_key_preimage(&storage_key), None);
+
+        // keys computed outside of tests aren't kept
+        crate::calc_storage_key(&address, slot.as_le_slice().as_ptr());
+        assert_eq!(storage_key_preimage(&storage_key), None);
+    }
+}
@@ -19,6 +19,16 @@ impl<T: Encoder<T> + Default> CoreInput<T> {
     }
 }
 
+/// Output of a WASM call or create, see [`EvmCreateMethodOutput::with_revert_reason`].
+fn revert_reason(exit_code: i32, output: Bytes) -> Bytes {
+    let exit_code = ExitCode::from(exit_code);
+    if exit_code.is_error() && exit_code != ExitCode::Panic && output.is_empty() {
+        exit_code.encode_revert().into()
+    } else {
+        output
+    }
+}
+
 pub const EVM_CREATE_METHOD_ID: u32 =
     derive_keccak256_id!("_evm_create(bytes,uint256,u64,bool,uint256)");
 
@@ -61,6 +71,16 @@ impl EvmCreateMethodOutput {
         self
     }
 
+    /// Encodes the exit code as `Error(string)` revert data if execution failed without any
+    /// output (traps and errors raised by the core), so callers get a readable revert reason.
+    /// Reverts with `ExitCode::Panic` keep their data, even if it's empty. EVM bytecode and
+    /// precompiles fail with empty data the same way as in Ethereum, so it's applied to WASM
+    /// calls and creates only.
+    pub fn with_revert_reason(mut self) -> Self {
+        self.output = revert_reason(self.exit_code, self.output);
+        self
+    }
+
     pub fn with_gas(mut self, gas: u64, gas_refund: i64) -> Self {
         self.gas = gas;
         self.gas_refund = gas_refund;
@@ -72,6 +92,7 @@ pub const EVM_CALL_METHOD_ID: u32 =
     derive_keccak256_id!(b"_evm_call(address,uint256,bytes,uint64)");
 
 #[derive(Default, Debug, Clone, Codec)]
+#[codec(schema)]
 pub struct EvmCallMethodInput {
     /// Callee is an address that holds bytecode only, it doesn't mean that its also
     /// used as callee address itself. Callee is managed by context reader and can differ.
@@ -83,6 +104,7 @@ pub struct EvmCallMethodInput {
 }
 
 #[derive(Default, Debug, Clone, Codec)]
+#[codec(schema)]
 pub struct EvmCallMethodOutput {
     pub output: Bytes,
     pub exit_code: i32,
@@ -110,6 +132,12 @@ impl EvmCallMethodOutput {
         self.gas_refund = refund;
         self
     }
+
+    /// See [`EvmCreateMethodOutput::with_revert_reason`].
+    pub fn with_revert_reason(mut self) -> Self {
+        self.output = revert_reason(self.exit_code, self.output);
+        self
+    }
 }
 
 pub const EVM_SLOAD_METHOD_ID: u32 = derive_keccak256_id!("_evm_sload(uint256)");
@@ -146,3 +174,93 @@ pub const WASM_CALL_METHOD_ID: u32 =
 
 pub type WasmCallMethodInput = EvmCallMethodInput;
 pub type WasmCallMethodOutput = EvmCallMethodOutput;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluentbase_codec::Schema;
+    use std::{fs, process::Command};
+
+    /// Decodes values encoded by Rust with the generated TypeScript codecs and encodes them back.
+    #[test]
+    #[ignore = "requires Node.js 22.6 or newer to run TypeScript"]
+    fn test_typescript_round_trip() {
+        let input = EvmCallMethodInput {
+            callee: Address::repeat_byte(0xab),
+            value: U256::MAX - U256::from(1),
+            input: Bytes::from_static(&[0x01, 0x02, 0x03]),
+            gas_limit: 100_000,
+            depth: 7,
+        };
+        let output = EvmCallMethodOutput {
+            output: Bytes::from_static(b"Hello, World"),
+            exit_code: ExitCode::OutOfFuel.into_i32(),
+            gas_remaining: u64::MAX,
+            gas_refund: -4800,
+        };
+        let mut schema = Schema::new();
+        schema
+            .add::<EvmCallMethodInput>()
+            .add::<EvmCallMethodOutput>();
+        let script = format!(
+            r#"import assert from "node:assert/strict";
+import {{
+  decodeEvmCallMethodInput,
+  decodeEvmCallMethodOutput,
+  encodeEvmCallMethodInput,
+  encodeEvmCallMethodOutput,
+}} from "./codec.ts";
+
+const bytes = (hex: string): Uint8Array => Uint8Array.from(Buffer.from(hex.slice(2), "hex"));
+
+const input = bytes("{}");
+const decodedInput = decodeEvmCallMethodInput(input);
+assert.deepEqual(decodedInput, {{
+  callee: "{}",
+  value: {}n,
+  input: bytes("{}"),
+  gas_limit: {}n,
+  depth: {},
+}});
+assert.deepEqual(encodeEvmCallMethodInput(decodedInput), input);
+
+const output = bytes("{}");
+const decodedOutput = decodeEvmCallMethodOutput(output);
+assert.deepEqual(decodedOutput, {{
+  output: bytes("{}"),
+  exit_code: {},
+  gas_remaining: {}n,
+  gas_refund: {}n,
+}});
+assert.deepEqual(encodeEvmCallMethodOutput(decodedOutput), output);
+"#,
+            Bytes::from(input.encode_to_vec(0)),
+            Bytes::copy_from_slice(input.callee.as_slice()),
+            input.value,
+            input.input,
+            input.gas_limit,
+            input.depth,
+            Bytes::from(output.encode_to_vec(0)),
+            output.output,
+            output.exit_code,
+            output.gas_remaining,
+            output.gas_refund,
+        );
+        let dir = std::env::temp_dir().join(format!("fluentbase-codec-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("package.json"), r#"{"type":"module"}"#).unwrap();
+        fs::write(dir.join("codec.ts"), schema.to_typescript()).unwrap();
+        fs::write(dir.join("test.ts"), script).unwrap();
+        let result = Command::new("node")
+            .arg("--experimental-strip-types")
+            .arg(dir.join("test.ts"))
+            .output()
+            .expect("node is not installed");
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            result.status.success(),
+            "{}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{LowLevelSDK, SharedAPI};
-use fluentbase_types::{b256, Address, Bytes32, B256, U256};
+use fluentbase_types::{b256, Address, Bytes32, ExitCode, SolidityError, B256, U256};
 use revm_primitives::alloy_primitives::private::alloy_rlp::{
     Encodable,
     EMPTY_LIST_CODE,
@@ -49,6 +49,10 @@ pub fn calc_storage_key(address: &Address, slot32_le_ptr: *const u8) -> [u8; 32]
             storage_key.as_mut_ptr(),
         );
     }
+    #[cfg(feature = "std")]
+    crate::runtime::remember_storage_key(storage_key, *address, unsafe {
+        U256::from_le_slice(&*core::ptr::slice_from_raw_parts(slot32_le_ptr, 32))
+    });
     storage_key
 }
 
@@ -81,6 +85,21 @@ pub fn calc_create2_address(deployer: &Address, salt: &U256, init_code_hash: &B2
     Address::from_word(B256::from(bytes32))
 }
 
+/// Reverts execution with Solidity compatible revert data of the error, it can be
+/// an `ExitCode`, a `SolidityErrorMessage` or a custom error. Exit codes are kept as is, other
+/// errors exit with `ExitCode::Panic`, it's the exit code of EVM `REVERT`.
+#[inline(always)]
+pub fn revert<SDK: SharedAPI>(error: &impl SolidityError) -> ! {
+    revert_with_data::<SDK>(error.exit_code(), &error.encode_revert())
+}
+
+/// Reverts execution with the exit code and already encoded revert data.
+#[inline(always)]
+pub fn revert_with_data<SDK: SharedAPI>(exit_code: ExitCode, revert_data: &[u8]) -> ! {
+    SDK::write(revert_data);
+    SDK::exit(exit_code.into_i32())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,33 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Soft-float lowering for wasm binaries.
+//!
+//! rWASM has no float instructions, so contracts that use `f32`/`f64` (usually pulled in by
+//! third-party crates) can't be compiled. This crate rewrites such modules before the rWASM
+//! translation: floats become integer bit patterns and arithmetic becomes calls to
+//! integer-only IEEE-754 routines that are generated into the module itself. All the results
+//! are rounded to nearest-even and every produced NaN is the positive canonical one, so
+//! execution is deterministic across hosts.
+
+extern crate alloc;
+
+mod library;
+mod lowering;
+
+pub use lowering::lower_floats;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoftFloatError {
+    /// The input is not a valid wasm binary.
+    Parse,
+    /// Float globals can't be imported since the host has no way to provide them.
+    FloatImport,
+    /// The binary uses proposals that can't be lowered (e.g. SIMD or components).
+    Unsupported,
+}
+
+impl From<wasmparser::BinaryReaderError> for SoftFloatError {
+    fn from(_: wasmparser::BinaryReaderError) -> Self {
+        SoftFloatError::Parse
+    }
+}
@@ -0,0 +1,1366 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use fluentbase_wasm_encoder::{op, FuncBuilder, BLOCK_EMPTY, VAL_I32, VAL_I64};
+
+/// Binary layout of an IEEE-754 binary format, all the routines are generated for both widths
+/// from the same code and operate on the raw bits widened into `i64`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) enum Float {
+    F32,
+    F64,
+}
+
+impl Float {
+    pub(crate) fn val_type(self) -> u8 {
+        match self {
+            Float::F32 => VAL_I32,
+            Float::F64 => VAL_I64,
+        }
+    }
+
+    fn man(self) -> i64 {
+        match self {
+            Float::F32 => 23,
+            Float::F64 => 52,
+        }
+    }
+
+    fn exp_max(self) -> i64 {
+        match self {
+            Float::F32 => 0xff,
+            Float::F64 => 0x7ff,
+        }
+    }
+
+    fn bias(self) -> i64 {
+        match self {
+            Float::F32 => 127,
+            Float::F64 => 1023,
+        }
+    }
+
+    fn sign(self) -> i64 {
+        match self {
+            Float::F32 => 1 << 31,
+            Float::F64 => i64::MIN,
+        }
+    }
+
+    fn abs(self) -> i64 {
+        match self {
+            Float::F32 => (1 << 31) - 1,
+            Float::F64 => i64::MAX,
+        }
+    }
+
+    fn frac(self) -> i64 {
+        (1 << self.man()) - 1
+    }
+
+    fn inf(self) -> i64 {
+        self.exp_max() << self.man()
+    }
+
+    /// Positive quiet NaN, every NaN produced by the library is canonical for determinism.
+    fn nan(self) -> i64 {
+        self.inf() | (1 << (self.man() - 1))
+    }
+
+    fn one(self) -> i64 {
+        self.bias() << self.man()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) enum Int {
+    I32,
+    I64,
+}
+
+impl Int {
+    fn val_type(self) -> u8 {
+        match self {
+            Int::I32 => VAL_I32,
+            Int::I64 => VAL_I64,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) enum Rounding {
+    Ceil,
+    Floor,
+    Trunc,
+    Nearest,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) enum Compare {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) enum LibFunc {
+    // internal helpers, always `i64` based
+    ShiftRightJam,
+    RoundPack(Float),
+    AddMags(Float),
+    SubMags(Float),
+    // replacements for wasm float instructions
+    Add(Float),
+    Sub(Float),
+    Mul(Float),
+    Div(Float),
+    Sqrt(Float),
+    Min(Float),
+    Max(Float),
+    Copysign(Float),
+    Round(Float, Rounding),
+    Compare(Float, Compare),
+    Promote,
+    Demote,
+    Convert {
+        from: Int,
+        signed: bool,
+        to: Float,
+    },
+    Truncate {
+        from: Float,
+        to: Int,
+        signed: bool,
+        saturating: bool,
+    },
+}
+
+impl LibFunc {
+    fn deps(self) -> Vec<LibFunc> {
+        match self {
+            LibFunc::ShiftRightJam => vec![],
+            LibFunc::RoundPack(_) => vec![LibFunc::ShiftRightJam],
+            LibFunc::AddMags(fmt) | LibFunc::SubMags(fmt) => {
+                vec![LibFunc::ShiftRightJam, LibFunc::RoundPack(fmt)]
+            }
+            LibFunc::Add(fmt) | LibFunc::Sub(fmt) => {
+                vec![LibFunc::AddMags(fmt), LibFunc::SubMags(fmt)]
+            }
+            LibFunc::Mul(fmt) | LibFunc::Div(fmt) | LibFunc::Sqrt(fmt) => {
+                vec![LibFunc::RoundPack(fmt)]
+            }
+            LibFunc::Demote => vec![LibFunc::RoundPack(Float::F32)],
+            LibFunc::Convert { to, .. } => vec![LibFunc::RoundPack(to)],
+            _ => vec![],
+        }
+    }
+
+    fn signature(self) -> (Vec<u8>, Vec<u8>) {
+        match self {
+            LibFunc::ShiftRightJam => (vec![VAL_I64; 2], vec![VAL_I64]),
+            LibFunc::RoundPack(_) => (vec![VAL_I64; 3], vec![VAL_I64]),
+            LibFunc::AddMags(_) | LibFunc::SubMags(_) => (vec![VAL_I64; 2], vec![VAL_I64]),
+            LibFunc::Add(fmt)
+            | LibFunc::Sub(fmt)
+            | LibFunc::Mul(fmt)
+            | LibFunc::Div(fmt)
+            | LibFunc::Min(fmt)
+            | LibFunc::Max(fmt)
+            | LibFunc::Copysign(fmt) => (vec![fmt.val_type(); 2], vec![fmt.val_type()]),
+            LibFunc::Sqrt(fmt) | LibFunc::Round(fmt, _) => {
+                (vec![fmt.val_type()], vec![fmt.val_type()])
+            }
+            LibFunc::Compare(fmt, _) => (vec![fmt.val_type(); 2], vec![VAL_I32]),
+            LibFunc::Promote => (vec![VAL_I32], vec![VAL_I64]),
+            LibFunc::Demote => (vec![VAL_I64], vec![VAL_I32]),
+            LibFunc::Convert { from, to, .. } => (vec![from.val_type()], vec![to.val_type()]),
+            LibFunc::Truncate { from, to, .. } => (vec![from.val_type()], vec![to.val_type()]),
+        }
+    }
+}
+
+/// The set of soft-float routines requested by a module, functions are appended after all the
+/// module's own functions starting from `func_base`.
+pub(crate) struct Library {
+    func_base: u32,
+    funcs: Vec<LibFunc>,
+    indices: BTreeMap<LibFunc, u32>,
+}
+
+impl Library {
+    pub(crate) fn new(func_base: u32) -> Self {
+        Self {
+            func_base,
+            funcs: Vec::new(),
+            indices: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn request(&mut self, func: LibFunc) -> u32 {
+        if let Some(func_idx) = self.indices.get(&func) {
+            return *func_idx;
+        }
+        let func_idx = self.func_base + self.funcs.len() as u32;
+        self.funcs.push(func);
+        self.indices.insert(func, func_idx);
+        for dep in func.deps() {
+            self.request(dep);
+        }
+        func_idx
+    }
+
+    fn index(&self, func: LibFunc) -> u32 {
+        *self
+            .indices
+            .get(&func)
+            .expect("softfloat: dependency is not requested")
+    }
+
+    pub(crate) fn signatures(&self) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> + '_ {
+        self.funcs.iter().map(|func| func.signature())
+    }
+
+    pub(crate) fn bodies(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.funcs.iter().map(|func| self.generate(*func))
+    }
+
+    fn generate(&self, func: LibFunc) -> Vec<u8> {
+        let params = func.signature().0.len() as u32;
+        let mut b = FuncBuilder::new(params);
+        match func {
+            LibFunc::ShiftRightJam => gen_shift_right_jam(&mut b),
+            LibFunc::RoundPack(fmt) => {
+                gen_round_pack(&mut b, fmt, self.index(LibFunc::ShiftRightJam))
+            }
+            LibFunc::AddMags(fmt) => gen_add_mags(&mut b, fmt, self),
+            LibFunc::SubMags(fmt) => gen_sub_mags(&mut b, fmt, self),
+            LibFunc::Add(fmt) => gen_add(&mut b, fmt, false, self),
+            LibFunc::Sub(fmt) => gen_add(&mut b, fmt, true, self),
+            LibFunc::Mul(fmt) => gen_mul(&mut b, fmt, self.index(LibFunc::RoundPack(fmt))),
+            LibFunc::Div(fmt) => gen_div(&mut b, fmt, self.index(LibFunc::RoundPack(fmt))),
+            LibFunc::Sqrt(fmt) => gen_sqrt(&mut b, fmt, self.index(LibFunc::RoundPack(fmt))),
+            LibFunc::Min(fmt) => gen_min_max(&mut b, fmt, true),
+            LibFunc::Max(fmt) => gen_min_max(&mut b, fmt, false),
+            LibFunc::Copysign(fmt) => gen_copysign(&mut b, fmt),
+            LibFunc::Round(fmt, mode) => gen_round(&mut b, fmt, mode),
+            LibFunc::Compare(fmt, cmp) => gen_compare(&mut b, fmt, cmp),
+            LibFunc::Promote => gen_promote(&mut b),
+            LibFunc::Demote => gen_demote(&mut b, self.index(LibFunc::RoundPack(Float::F32))),
+            LibFunc::Convert { from, signed, to } => {
+                gen_convert(&mut b, from, signed, to, self.index(LibFunc::RoundPack(to)))
+            }
+            LibFunc::Truncate {
+                from,
+                to,
+                signed,
+                saturating,
+            } => gen_truncate(&mut b, from, to, signed, saturating),
+        }
+        b.finish()
+    }
+}
+
+/// Loads a float parameter as raw bits widened into a new `i64` local.
+fn load_bits(b: &mut FuncBuilder, fmt: Float, param: u32) -> u32 {
+    let local = b.local(VAL_I64);
+    b.get(param);
+    if fmt == Float::F32 {
+        b.op(op::I64_EXTEND_I32_U);
+    }
+    b.set(local);
+    local
+}
+
+/// Returns `i64` bits from the top of the stack as a value of the `fmt` type.
+fn ret_bits(b: &mut FuncBuilder, fmt: Float) {
+    if fmt == Float::F32 {
+        b.op(op::I32_WRAP_I64);
+    }
+    b.op(op::RETURN);
+}
+
+fn ret_int(b: &mut FuncBuilder, int: Int) {
+    if int == Int::I32 {
+        b.op(op::I32_WRAP_I64);
+    }
+    b.op(op::RETURN);
+}
+
+/// Emits `if { const; return }` for a condition on the top of the stack.
+fn ret_const_if(b: &mut FuncBuilder, fmt: Float, value: i64) {
+    b.block(op::IF, BLOCK_EMPTY).i64_const(value);
+    ret_bits(b, fmt);
+    b.op(op::END);
+}
+
+/// Emits `if { local; return }` for a condition on the top of the stack.
+fn ret_local_if(b: &mut FuncBuilder, fmt: Float, local: u32) {
+    b.block(op::IF, BLOCK_EMPTY).get(local);
+    ret_bits(b, fmt);
+    b.op(op::END);
+}
+
+fn push_is_nan(b: &mut FuncBuilder, fmt: Float, x: u32) {
+    b.get(x)
+        .i64_const(fmt.abs())
+        .op(op::I64_AND)
+        .i64_const(fmt.inf())
+        .op(op::I64_GT_U);
+}
+
+fn abs_local(b: &mut FuncBuilder, fmt: Float, x: u32) -> u32 {
+    let local = b.local(VAL_I64);
+    b.get(x).i64_const(fmt.abs()).op(op::I64_AND).set(local);
+    local
+}
+
+/// Splits finite non-zero bits into a biased exponent and a significand with the leading one at
+/// bit `man`, subnormals are normalized so the exponent may become negative.
+fn unpack(b: &mut FuncBuilder, fmt: Float, x: u32) -> (u32, u32) {
+    let (e, m, s) = (b.local(VAL_I64), b.local(VAL_I64), b.local(VAL_I64));
+    b.get(x)
+        .i64_const(fmt.man())
+        .op(op::I64_SHR_U)
+        .i64_const(fmt.exp_max())
+        .op(op::I64_AND)
+        .set(e);
+    b.get(x).i64_const(fmt.frac()).op(op::I64_AND).set(m);
+    b.get(e).op(op::I64_EQZ).block(op::IF, BLOCK_EMPTY);
+    b.get(m)
+        .op(op::I64_CLZ)
+        .i64_const(63 - fmt.man())
+        .op(op::I64_SUB)
+        .set(s);
+    b.get(m).get(s).op(op::I64_SHL).set(m);
+    b.i64_const(1).get(s).op(op::I64_SUB).set(e);
+    b.op(op::ELSE);
+    b.get(m).i64_const(1 << fmt.man()).op(op::I64_OR).set(m);
+    b.op(op::END);
+    (e, m)
+}
+
+fn swap(b: &mut FuncBuilder, tmp: u32, x: u32, y: u32) {
+    b.get(x).set(tmp).get(y).set(x).get(tmp).set(y);
+}
+
+/// `shift_right_jam(sig, dist)`, shifts right keeping the lost bits as a sticky lsb.
+fn gen_shift_right_jam(b: &mut FuncBuilder) {
+    let (sig, dist) = (0, 1);
+    b.get(dist)
+        .i64_const(63)
+        .op(op::I64_LT_U)
+        .block(op::IF, VAL_I64);
+    b.get(sig).get(dist).op(op::I64_SHR_U);
+    b.get(sig)
+        .i64_const(0)
+        .get(dist)
+        .op(op::I64_SUB)
+        .op(op::I64_SHL)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .op(op::I64_EXTEND_I32_U)
+        .op(op::I64_OR);
+    b.op(op::ELSE);
+    b.get(sig)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .op(op::I64_EXTEND_I32_U);
+    b.op(op::END);
+}
+
+/// `round_pack(sign, exp, sig)`, rounds to nearest-even a significand with the leading one at
+/// bit 62 and packs it, `exp` is the biased exponent minus one. Handles overflow and subnormals.
+fn gen_round_pack(b: &mut FuncBuilder, fmt: Float, jam: u32) {
+    let (sign, exp, sig) = (0, 1, 2);
+    let round_bits = b.local(VAL_I64);
+    let shift = 62 - fmt.man();
+    let increment = 1i64 << (shift - 1);
+    let max_exp = fmt.exp_max() - 2;
+    b.get(exp)
+        .i64_const(0)
+        .op(op::I64_LT_S)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(sig)
+        .i64_const(0)
+        .get(exp)
+        .op(op::I64_SUB)
+        .call(jam)
+        .set(sig);
+    b.i64_const(0).set(exp);
+    b.op(op::ELSE);
+    b.get(exp).i64_const(max_exp).op(op::I64_GT_S);
+    b.get(exp).i64_const(max_exp).op(op::I64_EQ);
+    b.get(sig)
+        .i64_const(increment)
+        .op(op::I64_ADD)
+        .i64_const(0)
+        .op(op::I64_LT_S)
+        .op(op::I32_AND)
+        .op(op::I32_OR);
+    b.block(op::IF, BLOCK_EMPTY)
+        .get(sign)
+        .i64_const(fmt.inf())
+        .op(op::I64_OR)
+        .op(op::RETURN)
+        .op(op::END);
+    b.op(op::END);
+    b.get(sig)
+        .i64_const((1 << shift) - 1)
+        .op(op::I64_AND)
+        .set(round_bits);
+    b.get(sig)
+        .i64_const(increment)
+        .op(op::I64_ADD)
+        .i64_const(shift)
+        .op(op::I64_SHR_U)
+        .set(sig);
+    // a tie is rounded to even
+    b.get(round_bits)
+        .i64_const(increment)
+        .op(op::I64_EQ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(sig)
+        .i64_const(-2)
+        .op(op::I64_AND)
+        .set(sig)
+        .op(op::END);
+    b.get(sig)
+        .op(op::I64_EQZ)
+        .block(op::IF, BLOCK_EMPTY)
+        .i64_const(0)
+        .set(exp)
+        .op(op::END);
+    // the leading one is added into the exponent field here
+    b.get(sign)
+        .get(exp)
+        .i64_const(fmt.man())
+        .op(op::I64_SHL)
+        .get(sig)
+        .op(op::I64_ADD)
+        .op(op::I64_OR);
+}
+
+/// Adds magnitudes of two finite non-zero numbers with the same sign.
+fn gen_add_mags(b: &mut FuncBuilder, fmt: Float, lib: &Library) {
+    let (x, y) = (0, 1);
+    let (sign, tmp) = (b.local(VAL_I64), b.local(VAL_I64));
+    b.get(x).i64_const(fmt.sign()).op(op::I64_AND).set(sign);
+    let (ex, mx) = unpack(b, fmt, x);
+    let (ey, my) = unpack(b, fmt, y);
+    b.get(ex)
+        .get(ey)
+        .op(op::I64_LT_S)
+        .block(op::IF, BLOCK_EMPTY);
+    swap(b, tmp, ex, ey);
+    swap(b, tmp, mx, my);
+    b.op(op::END);
+    b.get(mx).i64_const(61 - fmt.man()).op(op::I64_SHL).set(mx);
+    b.get(my).i64_const(61 - fmt.man()).op(op::I64_SHL).set(my);
+    b.get(ex).get(ey).op(op::I64_NE).block(op::IF, BLOCK_EMPTY);
+    b.get(my)
+        .get(ex)
+        .get(ey)
+        .op(op::I64_SUB)
+        .call(lib.index(LibFunc::ShiftRightJam))
+        .set(my);
+    b.op(op::END);
+    b.get(mx).get(my).op(op::I64_ADD).set(mx);
+    b.get(mx)
+        .i64_const(1 << 62)
+        .op(op::I64_LT_U)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(mx).i64_const(1).op(op::I64_SHL).set(mx);
+    b.get(ex).i64_const(1).op(op::I64_SUB).set(ex);
+    b.op(op::END);
+    b.get(sign)
+        .get(ex)
+        .get(mx)
+        .call(lib.index(LibFunc::RoundPack(fmt)));
+}
+
+/// Subtracts magnitudes of two finite non-zero numbers with different signs.
+fn gen_sub_mags(b: &mut FuncBuilder, fmt: Float, lib: &Library) {
+    let (x, y) = (0, 1);
+    let (sign, tmp) = (b.local(VAL_I64), b.local(VAL_I64));
+    b.get(x).i64_const(fmt.sign()).op(op::I64_AND).set(sign);
+    let (ex, mx) = unpack(b, fmt, x);
+    let (ey, my) = unpack(b, fmt, y);
+    // exact cancellation gives a positive zero
+    b.get(ex)
+        .get(ey)
+        .op(op::I64_EQ)
+        .get(mx)
+        .get(my)
+        .op(op::I64_EQ)
+        .op(op::I32_AND)
+        .block(op::IF, BLOCK_EMPTY)
+        .i64_const(0)
+        .op(op::RETURN)
+        .op(op::END);
+    b.get(ex).get(ey).op(op::I64_LT_S);
+    b.get(ex)
+        .get(ey)
+        .op(op::I64_EQ)
+        .get(mx)
+        .get(my)
+        .op(op::I64_LT_U)
+        .op(op::I32_AND)
+        .op(op::I32_OR);
+    b.block(op::IF, BLOCK_EMPTY);
+    swap(b, tmp, ex, ey);
+    swap(b, tmp, mx, my);
+    b.get(sign).i64_const(fmt.sign()).op(op::I64_XOR).set(sign);
+    b.op(op::END);
+    b.get(mx).i64_const(62 - fmt.man()).op(op::I64_SHL).set(mx);
+    b.get(my).i64_const(62 - fmt.man()).op(op::I64_SHL).set(my);
+    b.get(ex).get(ey).op(op::I64_NE).block(op::IF, BLOCK_EMPTY);
+    b.get(my)
+        .get(ex)
+        .get(ey)
+        .op(op::I64_SUB)
+        .call(lib.index(LibFunc::ShiftRightJam))
+        .set(my);
+    b.op(op::END);
+    b.get(mx).get(my).op(op::I64_SUB).set(mx);
+    b.get(mx)
+        .op(op::I64_CLZ)
+        .i64_const(1)
+        .op(op::I64_SUB)
+        .set(tmp);
+    b.get(mx).get(tmp).op(op::I64_SHL).set(mx);
+    b.get(ex)
+        .i64_const(1)
+        .op(op::I64_SUB)
+        .get(tmp)
+        .op(op::I64_SUB)
+        .set(ex);
+    b.get(sign)
+        .get(ex)
+        .get(mx)
+        .call(lib.index(LibFunc::RoundPack(fmt)));
+}
+
+fn gen_add(b: &mut FuncBuilder, fmt: Float, negate: bool, lib: &Library) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    if negate {
+        b.get(y).i64_const(fmt.sign()).op(op::I64_XOR).set(y);
+    }
+    push_is_nan(b, fmt, x);
+    push_is_nan(b, fmt, y);
+    b.op(op::I32_OR);
+    ret_const_if(b, fmt, fmt.nan());
+    let abs_x = abs_local(b, fmt, x);
+    let abs_y = abs_local(b, fmt, y);
+    // inf - inf is NaN, otherwise an infinity wins
+    b.get(abs_x)
+        .i64_const(fmt.inf())
+        .op(op::I64_EQ)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(abs_y)
+        .i64_const(fmt.inf())
+        .op(op::I64_EQ)
+        .get(x)
+        .get(y)
+        .op(op::I64_NE)
+        .op(op::I32_AND);
+    ret_const_if(b, fmt, fmt.nan());
+    b.get(x);
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.get(abs_y).i64_const(fmt.inf()).op(op::I64_EQ);
+    ret_local_if(b, fmt, y);
+    // -0 + -0 is -0, any other sum of zeros is +0
+    b.get(abs_x).op(op::I64_EQZ).block(op::IF, BLOCK_EMPTY);
+    b.get(abs_y)
+        .op(op::I64_EQZ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(x)
+        .get(y)
+        .op(op::I64_AND);
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.get(y);
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.get(abs_y).op(op::I64_EQZ);
+    ret_local_if(b, fmt, x);
+    b.get(x)
+        .get(y)
+        .op(op::I64_XOR)
+        .i64_const(fmt.sign())
+        .op(op::I64_AND)
+        .op(op::I64_EQZ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(x)
+        .get(y)
+        .call(lib.index(LibFunc::AddMags(fmt)));
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.get(x).get(y).call(lib.index(LibFunc::SubMags(fmt)));
+    ret_bits(b, fmt);
+}
+
+/// Shared special cases of multiplication and division, returns the sign local.
+fn mul_div_specials(b: &mut FuncBuilder, fmt: Float, x: u32, y: u32, is_div: bool) -> u32 {
+    let sign = b.local(VAL_I64);
+    b.get(x)
+        .get(y)
+        .op(op::I64_XOR)
+        .i64_const(fmt.sign())
+        .op(op::I64_AND)
+        .set(sign);
+    push_is_nan(b, fmt, x);
+    push_is_nan(b, fmt, y);
+    b.op(op::I32_OR);
+    ret_const_if(b, fmt, fmt.nan());
+    let abs_x = abs_local(b, fmt, x);
+    let abs_y = abs_local(b, fmt, y);
+    let signed_inf = |b: &mut FuncBuilder| {
+        b.get(sign).i64_const(fmt.inf()).op(op::I64_OR);
+        ret_bits(b, fmt);
+    };
+    let signed_zero = |b: &mut FuncBuilder| {
+        b.get(sign);
+        ret_bits(b, fmt);
+    };
+    if is_div {
+        // inf/inf and 0/0 are NaN, inf/y and x/0 are inf, x/inf and 0/y are zero
+        b.get(abs_x)
+            .i64_const(fmt.inf())
+            .op(op::I64_EQ)
+            .block(op::IF, BLOCK_EMPTY);
+        b.get(abs_y).i64_const(fmt.inf()).op(op::I64_EQ);
+        ret_const_if(b, fmt, fmt.nan());
+        signed_inf(b);
+        b.op(op::END);
+        b.get(abs_y)
+            .i64_const(fmt.inf())
+            .op(op::I64_EQ)
+            .block(op::IF, BLOCK_EMPTY);
+        signed_zero(b);
+        b.op(op::END);
+        b.get(abs_y).op(op::I64_EQZ).block(op::IF, BLOCK_EMPTY);
+        b.get(abs_x).op(op::I64_EQZ);
+        ret_const_if(b, fmt, fmt.nan());
+        signed_inf(b);
+        b.op(op::END);
+        b.get(abs_x).op(op::I64_EQZ).block(op::IF, BLOCK_EMPTY);
+        signed_zero(b);
+        b.op(op::END);
+    } else {
+        // inf*0 is NaN, inf*y is inf, 0*y is zero
+        for (a, other) in [(abs_x, abs_y), (abs_y, abs_x)] {
+            b.get(a)
+                .i64_const(fmt.inf())
+                .op(op::I64_EQ)
+                .block(op::IF, BLOCK_EMPTY);
+            b.get(other).op(op::I64_EQZ);
+            ret_const_if(b, fmt, fmt.nan());
+            signed_inf(b);
+            b.op(op::END);
+        }
+        b.get(abs_x)
+            .op(op::I64_EQZ)
+            .get(abs_y)
+            .op(op::I64_EQZ)
+            .op(op::I32_OR)
+            .block(op::IF, BLOCK_EMPTY);
+        signed_zero(b);
+        b.op(op::END);
+    }
+    sign
+}
+
+fn gen_mul(b: &mut FuncBuilder, fmt: Float, round_pack: u32) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    let sign = mul_div_specials(b, fmt, x, y, false);
+    let (ex, mx) = unpack(b, fmt, x);
+    let (ey, my) = unpack(b, fmt, y);
+    b.get(mx).i64_const(62 - fmt.man()).op(op::I64_SHL).set(mx);
+    b.get(my).i64_const(63 - fmt.man()).op(op::I64_SHL).set(my);
+    // 64x64 -> 128 multiplication over 32-bit limbs, keeping the high half and a sticky bit
+    let limbs: [u32; 9] = core::array::from_fn(|_| b.local(VAL_I64));
+    let [x0, x1, y0, y1, p00, p01, p10, p11, mid] = limbs;
+    const LOW: i64 = 0xffff_ffff;
+    b.get(mx).i64_const(LOW).op(op::I64_AND).set(x0);
+    b.get(mx).i64_const(32).op(op::I64_SHR_U).set(x1);
+    b.get(my).i64_const(LOW).op(op::I64_AND).set(y0);
+    b.get(my).i64_const(32).op(op::I64_SHR_U).set(y1);
+    b.get(x0).get(y0).op(op::I64_MUL).set(p00);
+    b.get(x0).get(y1).op(op::I64_MUL).set(p01);
+    b.get(x1).get(y0).op(op::I64_MUL).set(p10);
+    b.get(x1).get(y1).op(op::I64_MUL).set(p11);
+    b.get(p00)
+        .i64_const(32)
+        .op(op::I64_SHR_U)
+        .get(p01)
+        .i64_const(LOW)
+        .op(op::I64_AND)
+        .op(op::I64_ADD)
+        .get(p10)
+        .i64_const(LOW)
+        .op(op::I64_AND)
+        .op(op::I64_ADD)
+        .set(mid);
+    b.get(p11)
+        .get(p01)
+        .i64_const(32)
+        .op(op::I64_SHR_U)
+        .op(op::I64_ADD)
+        .get(p10)
+        .i64_const(32)
+        .op(op::I64_SHR_U)
+        .op(op::I64_ADD)
+        .get(mid)
+        .i64_const(32)
+        .op(op::I64_SHR_U)
+        .op(op::I64_ADD)
+        .get(mid)
+        .i64_const(32)
+        .op(op::I64_SHL)
+        .get(p00)
+        .i64_const(LOW)
+        .op(op::I64_AND)
+        .op(op::I64_OR)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .op(op::I64_EXTEND_I32_U)
+        .op(op::I64_OR)
+        .set(mx);
+    b.get(ex)
+        .get(ey)
+        .op(op::I64_ADD)
+        .i64_const(fmt.bias())
+        .op(op::I64_SUB)
+        .set(ex);
+    b.get(mx)
+        .i64_const(1 << 62)
+        .op(op::I64_LT_U)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(mx).i64_const(1).op(op::I64_SHL).set(mx);
+    b.get(ex).i64_const(1).op(op::I64_SUB).set(ex);
+    b.op(op::END);
+    b.get(sign).get(ex).get(mx).call(round_pack);
+    ret_bits(b, fmt);
+}
+
+fn gen_div(b: &mut FuncBuilder, fmt: Float, round_pack: u32) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    let sign = mul_div_specials(b, fmt, x, y, true);
+    let (ex, mx) = unpack(b, fmt, x);
+    let (ey, my) = unpack(b, fmt, y);
+    let (q, i) = (b.local(VAL_I64), b.local(VAL_I64));
+    b.get(ex)
+        .get(ey)
+        .op(op::I64_SUB)
+        .i64_const(fmt.bias() - 1)
+        .op(op::I64_ADD)
+        .set(ex);
+    b.get(mx)
+        .get(my)
+        .op(op::I64_LT_U)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(mx).i64_const(1).op(op::I64_SHL).set(mx);
+    b.get(ex).i64_const(1).op(op::I64_SUB).set(ex);
+    b.op(op::END);
+    // restoring division, 63 quotient bits leave the leading one at bit 62
+    b.i64_const(63).set(i);
+    b.block(op::LOOP, BLOCK_EMPTY);
+    b.get(q).i64_const(1).op(op::I64_SHL).set(q);
+    b.get(mx)
+        .get(my)
+        .op(op::I64_GE_U)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(mx).get(my).op(op::I64_SUB).set(mx);
+    b.get(q).i64_const(1).op(op::I64_OR).set(q);
+    b.op(op::END);
+    b.get(mx).i64_const(1).op(op::I64_SHL).set(mx);
+    b.get(i)
+        .i64_const(1)
+        .op(op::I64_SUB)
+        .tee(i)
+        .op(op::I64_EQZ)
+        .op(op::I32_EQZ)
+        .br_if(0);
+    b.op(op::END);
+    b.get(q)
+        .get(mx)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .op(op::I64_EXTEND_I32_U)
+        .op(op::I64_OR)
+        .set(q);
+    b.get(sign).get(ex).get(q).call(round_pack);
+    ret_bits(b, fmt);
+}
+
+fn gen_sqrt(b: &mut FuncBuilder, fmt: Float, round_pack: u32) {
+    let x = load_bits(b, fmt, 0);
+    push_is_nan(b, fmt, x);
+    ret_const_if(b, fmt, fmt.nan());
+    b.get(x)
+        .i64_const(fmt.abs())
+        .op(op::I64_AND)
+        .op(op::I64_EQZ);
+    ret_local_if(b, fmt, x);
+    b.get(x)
+        .i64_const(fmt.sign())
+        .op(op::I64_AND)
+        .i64_const(0)
+        .op(op::I64_NE);
+    ret_const_if(b, fmt, fmt.nan());
+    b.get(x).i64_const(fmt.inf()).op(op::I64_EQ);
+    ret_local_if(b, fmt, x);
+    let (e, m) = unpack(b, fmt, x);
+    let (top, r, q, t, i) = (
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+    );
+    // make the unbiased exponent even, the significand is then in [1, 4)
+    b.get(e).i64_const(fmt.bias()).op(op::I64_SUB).set(e);
+    b.get(e)
+        .i64_const(1)
+        .op(op::I64_AND)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .block(op::IF, BLOCK_EMPTY);
+    b.get(m).i64_const(1).op(op::I64_SHL).set(m);
+    b.get(e).i64_const(1).op(op::I64_SUB).set(e);
+    b.op(op::END);
+    // digit-by-digit square root producing `man + 3` bits, two guard bits plus a sticky bit
+    b.get(m).i64_const(62 - fmt.man()).op(op::I64_SHL).set(top);
+    b.i64_const(fmt.man() + 3).set(i);
+    b.block(op::LOOP, BLOCK_EMPTY);
+    b.get(r)
+        .i64_const(2)
+        .op(op::I64_SHL)
+        .get(top)
+        .i64_const(62)
+        .op(op::I64_SHR_U)
+        .op(op::I64_OR)
+        .set(r);
+    b.get(top).i64_const(2).op(op::I64_SHL).set(top);
+    b.get(q)
+        .i64_const(2)
+        .op(op::I64_SHL)
+        .i64_const(1)
+        .op(op::I64_OR)
+        .set(t);
+    b.get(q).i64_const(1).op(op::I64_SHL).set(q);
+    b.get(r).get(t).op(op::I64_GE_U).block(op::IF, BLOCK_EMPTY);
+    b.get(r).get(t).op(op::I64_SUB).set(r);
+    b.get(q).i64_const(1).op(op::I64_OR).set(q);
+    b.op(op::END);
+    b.get(i)
+        .i64_const(1)
+        .op(op::I64_SUB)
+        .tee(i)
+        .op(op::I64_EQZ)
+        .op(op::I32_EQZ)
+        .br_if(0);
+    b.op(op::END);
+    b.get(q)
+        .i64_const(60 - fmt.man())
+        .op(op::I64_SHL)
+        .get(r)
+        .i64_const(0)
+        .op(op::I64_NE)
+        .op(op::I64_EXTEND_I32_U)
+        .op(op::I64_OR)
+        .set(q);
+    b.i64_const(0)
+        .get(e)
+        .i64_const(1)
+        .op(op::I64_SHR_S)
+        .i64_const(fmt.bias() - 1)
+        .op(op::I64_ADD)
+        .get(q)
+        .call(round_pack);
+    ret_bits(b, fmt);
+}
+
+/// Maps non-NaN bits into an `i64` that compares like the float, both zeros map to `0`.
+fn order_key(b: &mut FuncBuilder, fmt: Float, x: u32) -> u32 {
+    let key = b.local(VAL_I64);
+    b.get(x)
+        .i64_const(fmt.sign())
+        .op(op::I64_AND)
+        .op(op::I64_EQZ)
+        .block(op::IF, VAL_I64)
+        .get(x)
+        .op(op::ELSE)
+        .i64_const(0)
+        .get(x)
+        .i64_const(fmt.abs())
+        .op(op::I64_AND)
+        .op(op::I64_SUB)
+        .op(op::END)
+        .set(key);
+    key
+}
+
+fn gen_min_max(b: &mut FuncBuilder, fmt: Float, is_min: bool) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    push_is_nan(b, fmt, x);
+    push_is_nan(b, fmt, y);
+    b.op(op::I32_OR);
+    ret_const_if(b, fmt, fmt.nan());
+    let key_x = order_key(b, fmt, x);
+    let key_y = order_key(b, fmt, y);
+    // for equal values merging the sign bits orders -0 below +0
+    b.get(key_x)
+        .get(key_y)
+        .op(op::I64_EQ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(x)
+        .get(y)
+        .op(if is_min { op::I64_OR } else { op::I64_AND });
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.get(x)
+        .get(y)
+        .get(key_x)
+        .get(key_y)
+        .op(if is_min { op::I64_LT_S } else { op::I64_GT_S })
+        .op(op::SELECT);
+    ret_bits(b, fmt);
+}
+
+fn gen_copysign(b: &mut FuncBuilder, fmt: Float) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    b.get(x)
+        .i64_const(fmt.abs())
+        .op(op::I64_AND)
+        .get(y)
+        .i64_const(fmt.sign())
+        .op(op::I64_AND)
+        .op(op::I64_OR);
+    ret_bits(b, fmt);
+}
+
+fn gen_round(b: &mut FuncBuilder, fmt: Float, mode: Rounding) {
+    let x = load_bits(b, fmt, 0);
+    let (e, sign, mask, t) = (
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+        b.local(VAL_I64),
+    );
+    push_is_nan(b, fmt, x);
+    ret_const_if(b, fmt, fmt.nan());
+    b.get(x)
+        .i64_const(fmt.man())
+        .op(op::I64_SHR_U)
+        .i64_const(fmt.exp_max())
+        .op(op::I64_AND)
+        .set(e);
+    // infinities and values without a fractional part are returned as is
+    b.get(e).i64_const(fmt.bias() + fmt.man()).op(op::I64_GE_S);
+    ret_local_if(b, fmt, x);
+    b.get(x).i64_const(fmt.sign()).op(op::I64_AND).set(sign);
+    // |x| < 1 rounds to a signed zero or to a signed one
+    b.get(e)
+        .i64_const(fmt.bias())
+        .op(op::I64_LT_S)
+        .block(op::IF, BLOCK_EMPTY);
+    match mode {
+        Rounding::Trunc => {}
+        Rounding::Floor => {
+            b.get(sign)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .get(x)
+                .i64_const(fmt.abs())
+                .op(op::I64_AND)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .op(op::I32_AND);
+            ret_const_if(b, fmt, fmt.sign() | fmt.one());
+        }
+        Rounding::Ceil => {
+            b.get(sign)
+                .op(op::I64_EQZ)
+                .get(x)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .op(op::I32_AND);
+            ret_const_if(b, fmt, fmt.one());
+        }
+        Rounding::Nearest => {
+            b.get(e)
+                .i64_const(fmt.bias() - 1)
+                .op(op::I64_EQ)
+                .get(x)
+                .i64_const(fmt.frac())
+                .op(op::I64_AND)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .op(op::I32_AND)
+                .block(op::IF, BLOCK_EMPTY)
+                .get(sign)
+                .i64_const(fmt.one())
+                .op(op::I64_OR);
+            ret_bits(b, fmt);
+            b.op(op::END);
+        }
+    }
+    b.get(sign);
+    ret_bits(b, fmt);
+    b.op(op::END);
+    b.i64_const(1)
+        .i64_const(fmt.bias() + fmt.man())
+        .get(e)
+        .op(op::I64_SUB)
+        .op(op::I64_SHL)
+        .i64_const(1)
+        .op(op::I64_SUB)
+        .set(mask);
+    b.get(x).get(mask).op(op::I64_AND).op(op::I64_EQZ);
+    ret_local_if(b, fmt, x);
+    b.get(x)
+        .get(mask)
+        .i64_const(-1)
+        .op(op::I64_XOR)
+        .op(op::I64_AND)
+        .set(t);
+    // rounding the magnitude up may carry into the exponent which is still correct
+    let round_up = |b: &mut FuncBuilder| {
+        b.get(t)
+            .get(mask)
+            .op(op::I64_ADD)
+            .i64_const(1)
+            .op(op::I64_ADD);
+        ret_bits(b, fmt);
+    };
+    match mode {
+        Rounding::Trunc => {}
+        Rounding::Floor => {
+            b.get(sign)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .block(op::IF, BLOCK_EMPTY);
+            round_up(b);
+            b.op(op::END);
+        }
+        Rounding::Ceil => {
+            b.get(sign).op(op::I64_EQZ).block(op::IF, BLOCK_EMPTY);
+            round_up(b);
+            b.op(op::END);
+        }
+        Rounding::Nearest => {
+            let (frac, half) = (b.local(VAL_I64), b.local(VAL_I64));
+            b.get(x).get(mask).op(op::I64_AND).set(frac);
+            b.get(mask)
+                .i64_const(1)
+                .op(op::I64_SHR_U)
+                .i64_const(1)
+                .op(op::I64_ADD)
+                .set(half);
+            b.get(frac).get(half).op(op::I64_GT_U);
+            b.get(frac)
+                .get(half)
+                .op(op::I64_EQ)
+                .get(x)
+                .get(mask)
+                .i64_const(1)
+                .op(op::I64_ADD)
+                .op(op::I64_AND)
+                .i64_const(0)
+                .op(op::I64_NE)
+                .op(op::I32_AND)
+                .op(op::I32_OR)
+                .block(op::IF, BLOCK_EMPTY);
+            round_up(b);
+            b.op(op::END);
+        }
+    }
+    b.get(t);
+    ret_bits(b, fmt);
+}
+
+fn gen_compare(b: &mut FuncBuilder, fmt: Float, cmp: Compare) {
+    let x = load_bits(b, fmt, 0);
+    let y = load_bits(b, fmt, 1);
+    // NaN is unordered, only `ne` holds
+    push_is_nan(b, fmt, x);
+    push_is_nan(b, fmt, y);
+    b.op(op::I32_OR)
+        .block(op::IF, BLOCK_EMPTY)
+        .i32_const((cmp == Compare::Ne) as i32)
+        .op(op::RETURN)
+        .op(op::END);
+    let key_x = order_key(b, fmt, x);
+    let key_y = order_key(b, fmt, y);
+    b.get(key_x).get(key_y).op(match cmp {
+        Compare::Eq => op::I64_EQ,
+        Compare::Ne => op::I64_NE,
+        Compare::Lt => op::I64_LT_S,
+        Compare::Gt => op::I64_GT_S,
+        Compare::Le => op::I64_LE_S,
+        Compare::Ge => op::I64_GE_S,
+    });
+}
+
+fn gen_promote(b: &mut FuncBuilder) {
+    let (from, to) = (Float::F32, Float::F64);
+    let x = load_bits(b, from, 0);
+    let sign = b.local(VAL_I64);
+    push_is_nan(b, from, x);
+    ret_const_if(b, to, to.nan());
+    b.get(x)
+        .i64_const(from.sign())
+        .op(op::I64_AND)
+        .i64_const(32)
+        .op(op::I64_SHL)
+        .set(sign);
+    b.get(x)
+        .i64_const(from.abs())
+        .op(op::I64_AND)
+        .i64_const(from.inf())
+        .op(op::I64_EQ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(sign)
+        .i64_const(to.inf())
+        .op(op::I64_OR);
+    ret_bits(b, to);
+    b.op(op::END);
+    b.get(x)
+        .i64_const(from.abs())
+        .op(op::I64_AND)
+        .op(op::I64_EQZ);
+    ret_local_if(b, to, sign);
+    // every binary32 value, subnormals included, is a normal binary64 value
+    let (e, m) = unpack(b, from, x);
+    b.get(sign)
+        .get(e)
+        .i64_const(to.bias() - from.bias())
+        .op(op::I64_ADD)
+        .i64_const(to.man())
+        .op(op::I64_SHL)
+        .op(op::I64_OR)
+        .get(m)
+        .i64_const(to.man() - from.man())
+        .op(op::I64_SHL)
+        .i64_const(to.frac())
+        .op(op::I64_AND)
+        .op(op::I64_OR);
+    ret_bits(b, to);
+}
+
+fn gen_demote(b: &mut FuncBuilder, round_pack: u32) {
+    let (from, to) = (Float::F64, Float::F32);
+    let x = load_bits(b, from, 0);
+    let sign = b.local(VAL_I64);
+    push_is_nan(b, from, x);
+    ret_const_if(b, to, to.nan());
+    b.get(x)
+        .i64_const(32)
+        .op(op::I64_SHR_U)
+        .i64_const(to.sign())
+        .op(op::I64_AND)
+        .set(sign);
+    b.get(x)
+        .i64_const(from.abs())
+        .op(op::I64_AND)
+        .i64_const(from.inf())
+        .op(op::I64_EQ)
+        .block(op::IF, BLOCK_EMPTY)
+        .get(sign)
+        .i64_const(to.inf())
+        .op(op::I64_OR);
+    ret_bits(b, to);
+    b.op(op::END);
+    b.get(x)
+        .i64_const(from.abs())
+        .op(op::I64_AND)
+        .op(op::I64_EQZ);
+    ret_local_if(b, to, sign);
+    let (e, m) = unpack(b, from, x);
+    b.get(sign)
+        .get(e)
+        .i64_const(from.bias() - to.bias() + 1)
+        .op(op::I64_SUB)
+        .get(m)
+        .i64_const(62 - from.man())
+        .op(op::I64_SHL)
+        .call(round_pack);
+    ret_bits(b, to);
+}
+
+fn gen_convert(b: &mut FuncBuilder, from: Int, signed: bool, to: Float, round_pack: u32) {
+    let (v, sign, s) = (b.local(VAL_I64), b.local(VAL_I64), b.local(VAL_I64));
+    b.get(0);
+    if from == Int::I32 {
+        b.op(if signed {
+            op::I64_EXTEND_I32_S
+        } else {
+            op::I64_EXTEND_I32_U
+        });
+    }
+    b.set(v);
+    if signed {
+        // the magnitude of `i64::MIN` is still correct when treated as unsigned
+        b.get(v)
+            .i64_const(0)
+            .op(op::I64_LT_S)
+            .block(op::IF, BLOCK_EMPTY);
+        b.i64_const(to.sign()).set(sign);
+        b.i64_const(0).get(v).op(op::I64_SUB).set(v);
+        b.op(op::END);
+    }
+    b.get(v).op(op::I64_EQZ);
+    ret_const_if(b, to, 0);
+    b.get(v).op(op::I64_CLZ).set(s);
+    b.get(v).get(s).op(op::I64_SHL).set(v);
+    b.get(sign)
+        .i64_const(62 + to.bias())
+        .get(s)
+        .op(op::I64_SUB)
+        .get(v)
+        .i64_const(1)
+        .op(op::I64_SHR_U)
+        .get(v)
+        .i64_const(1)
+        .op(op::I64_AND)
+        .op(op::I64_OR)
+        .call(round_pack);
+    ret_bits(b, to);
+}
+
+fn gen_truncate(b: &mut FuncBuilder, from: Float, to: Int, signed: bool, saturating: bool) {
+    let x = load_bits(b, from, 0);
+    let (negative, e, mag) = (b.local(VAL_I64), b.local(VAL_I64), b.local(VAL_I64));
+    let width = match to {
+        Int::I32 => 32,
+        Int::I64 => 64,
+    };
+    let (min, max): (i64, i64) = match (signed, to) {
+        (true, Int::I32) => (i32::MIN as i64, i32::MAX as i64),
+        (true, Int::I64) => (i64::MIN, i64::MAX),
+        (false, Int::I32) => (0, u32::MAX as i64),
+        (false, Int::I64) => (0, -1),
+    };
+    // out of range values either saturate or trap with an integer overflow like native code
+    let overflow = |b: &mut FuncBuilder| {
+        if saturating {
+            b.get(negative)
+                .op(op::I64_EQZ)
+                .block(op::IF, VAL_I64)
+                .i64_const(max)
+                .op(op::ELSE)
+                .i64_const(min)
+                .op(op::END);
+            ret_int(b, to);
+        } else {
+            b.i32_const(i32::MIN)
+                .i32_const(-1)
+                .op(op::I32_DIV_S)
+                .op(op::UNREACHABLE);
+        }
+    };
+    push_is_nan(b, from, x);
+    b.block(op::IF, BLOCK_EMPTY);
+    if saturating {
+        b.i64_const(0);
+        ret_int(b, to);
+    } else {
+        b.op(op::UNREACHABLE);
+    }
+    b.op(op::END);
+    b.get(x)
+        .i64_const(from.sign())
+        .op(op::I64_AND)
+        .set(negative);
+    b.get(x)
+        .i64_const(from.man())
+        .op(op::I64_SHR_U)
+        .i64_const(from.exp_max())
+        .op(op::I64_AND)
+        .i64_const(from.bias())
+        .op(op::I64_SUB)
+        .set(e);
+    b.get(e)
+        .i64_const(0)
+        .op(op::I64_LT_S)
+        .block(op::IF, BLOCK_EMPTY)
+        .i64_const(0);
+    ret_int(b, to);
+    b.op(op::END);
+    b.get(e)
+        .i64_const(64)
+        .op(op::I64_GE_S)
+        .block(op::IF, BLOCK_EMPTY);
+    overflow(b);
+    b.op(op::END);
+    b.get(x)
+        .i64_const(from.frac())
+        .op(op::I64_AND)
+        .i64_const(1 << from.man())
+        .op(op::I64_OR)
+        .set(mag);
+    b.get(e)
+        .i64_const(from.man())
+        .op(op::I64_GE_S)
+        .block(op::IF, VAL_I64)
+        .get(mag)
+        .get(e)
+        .i64_const(from.man())
+        .op(op::I64_SUB)
+        .op(op::I64_SHL)
+        .op(op::ELSE)
+        .get(mag)
+        .i64_const(from.man())
+        .get(e)
+        .op(op::I64_SUB)
+        .op(op::I64_SHR_U)
+        .op(op::END)
+        .set(mag);
+    if signed {
+        let limit = 1i64 << (width - 1);
+        b.get(negative)
+            .i64_const(0)
+            .op(op::I64_NE)
+            .block(op::IF, BLOCK_EMPTY);
+        b.get(mag)
+            .i64_const(limit)
+            .op(op::I64_GT_U)
+            .block(op::IF, BLOCK_EMPTY);
+        overflow(b);
+        b.op(op::END);
+        b.i64_const(0).get(mag).op(op::I64_SUB);
+        ret_int(b, to);
+        b.op(op::END);
+        b.get(mag)
+            .i64_const(limit)
+            .op(op::I64_GE_U)
+            .block(op::IF, BLOCK_EMPTY);
+        overflow(b);
+        b.op(op::END);
+    } else {
+        b.get(negative)
+            .i64_const(0)
+            .op(op::I64_NE)
+            .block(op::IF, BLOCK_EMPTY);
+        overflow(b);
+        b.op(op::END);
+        if to == Int::I32 {
+            b.get(mag)
+                .i64_const(max)
+                .op(op::I64_GT_U)
+                .block(op::IF, BLOCK_EMPTY);
+            overflow(b);
+            b.op(op::END);
+        }
+    }
+    b.get(mag);
+    ret_int(b, to);
+}
@@ -0,0 +1,800 @@
+use crate::{
+    library::{Compare, Float, Int, LibFunc, Library, Rounding},
+    SoftFloatError,
+};
+use alloc::vec::Vec;
+use core::ops::Range;
+use fluentbase_wasm_encoder::{op, write_i64, write_section, write_u32, VAL_I32, VAL_I64};
+use wasmparser::{
+    BlockType,
+    FunctionBody,
+    Operator,
+    OperatorsReader,
+    Parser,
+    Payload,
+    Type,
+    TypeRef,
+    ValType,
+};
+
+const TYPE_SECTION: u8 = 1;
+const FUNCTION_SECTION: u8 = 3;
+const GLOBAL_SECTION: u8 = 6;
+const CODE_SECTION: u8 = 10;
+
+enum Section<'a> {
+    Raw(u8, Range<usize>),
+    Type(wasmparser::TypeSectionReader<'a>),
+    Function(wasmparser::FunctionSectionReader<'a>),
+    Global(wasmparser::GlobalSectionReader<'a>),
+    Code(Vec<FunctionBody<'a>>),
+}
+
+struct Lowering<'a> {
+    wasm: &'a [u8],
+    library: Library,
+    changed: bool,
+}
+
+/// Rewrites all `f32`/`f64` instructions and types of the module into integer ones.
+///
+/// Floats are stored as their raw bit patterns in `i32`/`i64` values, loads, stores, constants
+/// and reinterpretations become plain integer operations and the arithmetic is replaced with
+/// calls to IEEE-754 routines that are appended to the module. Only the routines used by the
+/// module are included. Returns the binary unchanged if it doesn't touch floats.
+pub fn lower_floats(wasm: &[u8]) -> Result<Vec<u8>, SoftFloatError> {
+    let mut sections = Vec::new();
+    let mut num_imported_funcs = 0u32;
+    let mut num_defined_funcs = 0u32;
+    let mut num_types = 0u32;
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        match payload {
+            Payload::Version { .. } | Payload::End(_) => {}
+            Payload::TypeSection(reader) => {
+                num_types = reader.count();
+                sections.push(Section::Type(reader));
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader.clone() {
+                    match import?.ty {
+                        TypeRef::Func(_) => num_imported_funcs += 1,
+                        TypeRef::Global(global_type)
+                            if matches!(global_type.content_type, ValType::F32 | ValType::F64) =>
+                        {
+                            return Err(SoftFloatError::FloatImport)
+                        }
+                        _ => {}
+                    }
+                }
+                sections.push(Section::Raw(2, reader.range()));
+            }
+            Payload::FunctionSection(reader) => {
+                num_defined_funcs = reader.count();
+                sections.push(Section::Function(reader));
+            }
+            Payload::GlobalSection(reader) => sections.push(Section::Global(reader)),
+            Payload::CodeSectionStart { .. } => sections.push(Section::Code(Vec::new())),
+            Payload::CodeSectionEntry(body) => match sections.last_mut() {
+                Some(Section::Code(bodies)) => bodies.push(body),
+                _ => unreachable!("softfloat: code entry outside of the code section"),
+            },
+            payload => match payload.as_section() {
+                Some((id, range)) => sections.push(Section::Raw(id, range)),
+                None => return Err(SoftFloatError::Unsupported),
+            },
+        }
+    }
+
+    let mut lowering = Lowering {
+        wasm,
+        library: Library::new(num_imported_funcs + num_defined_funcs),
+        changed: false,
+    };
+    // functions are rewritten first since they decide what routines have to be appended
+    let mut lowered_bodies = Vec::new();
+    for section in sections.iter() {
+        if let Section::Code(bodies) = section {
+            for body in bodies {
+                lowered_bodies.push(lowering.lower_body(body)?);
+            }
+        }
+    }
+    let mut global_section = Vec::new();
+    for section in sections.iter() {
+        if let Section::Global(reader) = section {
+            write_u32(&mut global_section, reader.count());
+            for global in reader.clone() {
+                let global = global?;
+                global_section.push(lowering.val_type(global.ty.content_type)?);
+                global_section.push(global.ty.mutable as u8);
+                lowering.lower_operators(
+                    global.init_expr.get_operators_reader(),
+                    &mut global_section,
+                )?;
+            }
+        }
+    }
+    let mut type_section = Vec::new();
+    for section in sections.iter() {
+        if let Section::Type(reader) = section {
+            for ty in reader.clone() {
+                let Type::Func(func_type) = ty?;
+                type_section.push(0x60);
+                write_u32(&mut type_section, func_type.params().len() as u32);
+                for param in func_type.params() {
+                    type_section.push(lowering.val_type(*param)?);
+                }
+                write_u32(&mut type_section, func_type.results().len() as u32);
+                for result in func_type.results() {
+                    type_section.push(lowering.val_type(*result)?);
+                }
+            }
+        }
+    }
+    if !lowering.changed {
+        return Ok(wasm.to_vec());
+    }
+
+    // routines share signatures, so only distinct ones are appended to the type section
+    let mut lib_types: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    let mut lib_type_indices = Vec::new();
+    for signature in lowering.library.signatures() {
+        let position = match lib_types.iter().position(|ty| *ty == signature) {
+            Some(position) => position,
+            None => {
+                lib_types.push(signature);
+                lib_types.len() - 1
+            }
+        };
+        lib_type_indices.push(num_types + position as u32);
+    }
+    for (params, results) in lib_types.iter() {
+        type_section.push(0x60);
+        write_u32(&mut type_section, params.len() as u32);
+        type_section.extend_from_slice(params);
+        write_u32(&mut type_section, results.len() as u32);
+        type_section.extend_from_slice(results);
+    }
+    let mut type_section_with_count = Vec::new();
+    write_u32(
+        &mut type_section_with_count,
+        num_types + lib_types.len() as u32,
+    );
+    type_section_with_count.extend_from_slice(&type_section);
+
+    let mut output = Vec::with_capacity(wasm.len() * 2);
+    output.extend_from_slice(&wasm[0..8]);
+    for section in sections.iter() {
+        match section {
+            Section::Raw(id, range) => {
+                write_section(&mut output, *id, &wasm[range.clone()]);
+            }
+            Section::Type(_) => {
+                write_section(&mut output, TYPE_SECTION, &type_section_with_count);
+            }
+            Section::Function(reader) => {
+                let mut function_section = Vec::new();
+                write_u32(
+                    &mut function_section,
+                    reader.count() + lib_type_indices.len() as u32,
+                );
+                for type_idx in reader.clone() {
+                    write_u32(&mut function_section, type_idx?);
+                }
+                for type_idx in lib_type_indices.iter() {
+                    write_u32(&mut function_section, *type_idx);
+                }
+                write_section(&mut output, FUNCTION_SECTION, &function_section);
+            }
+            Section::Global(_) => {
+                write_section(&mut output, GLOBAL_SECTION, &global_section);
+            }
+            Section::Code(_) => {
+                let mut code_section = Vec::new();
+                write_u32(
+                    &mut code_section,
+                    (lowered_bodies.len() + lib_type_indices.len()) as u32,
+                );
+                for body in lowered_bodies
+                    .iter()
+                    .cloned()
+                    .chain(lowering.library.bodies())
+                {
+                    write_u32(&mut code_section, body.len() as u32);
+                    code_section.extend_from_slice(&body);
+                }
+                write_section(&mut output, CODE_SECTION, &code_section);
+            }
+        }
+    }
+    Ok(output)
+}
+
+impl<'a> Lowering<'a> {
+    fn val_type(&mut self, val_type: ValType) -> Result<u8, SoftFloatError> {
+        Ok(match val_type {
+            ValType::I32 => VAL_I32,
+            ValType::I64 => VAL_I64,
+            ValType::F32 => {
+                self.changed = true;
+                VAL_I32
+            }
+            ValType::F64 => {
+                self.changed = true;
+                VAL_I64
+            }
+            ValType::FuncRef => 0x70,
+            ValType::ExternRef => 0x6F,
+            ValType::V128 => return Err(SoftFloatError::Unsupported),
+        })
+    }
+
+    fn lower_body(&mut self, body: &FunctionBody<'a>) -> Result<Vec<u8>, SoftFloatError> {
+        let mut output = Vec::new();
+        let mut locals = body.get_locals_reader()?;
+        write_u32(&mut output, locals.get_count());
+        for _ in 0..locals.get_count() {
+            let (count, val_type) = locals.read()?;
+            write_u32(&mut output, count);
+            output.push(self.val_type(val_type)?);
+        }
+        self.lower_operators(body.get_operators_reader()?, &mut output)?;
+        Ok(output)
+    }
+
+    fn lower_operators(
+        &mut self,
+        mut reader: OperatorsReader<'a>,
+        output: &mut Vec<u8>,
+    ) -> Result<(), SoftFloatError> {
+        while !reader.eof() {
+            let start = reader.original_position();
+            let operator = reader.read()?;
+            let original = &self.wasm[start..reader.original_position()];
+            self.lower_operator(operator, original, output)?;
+        }
+        Ok(())
+    }
+
+    fn lower_operator(
+        &mut self,
+        operator: Operator,
+        original: &[u8],
+        output: &mut Vec<u8>,
+    ) -> Result<(), SoftFloatError> {
+        use Float::{F32, F64};
+        let lib_func = match operator {
+            Operator::Block { blockty } | Operator::Loop { blockty } | Operator::If { blockty } => {
+                output.extend_from_slice(original);
+                if let BlockType::Type(val_type) = blockty {
+                    // the block type is encoded as a single byte right after the opcode
+                    let position = output.len() - original.len() + 1;
+                    output[position] = self.val_type(val_type)?;
+                }
+                return Ok(());
+            }
+            Operator::TypedSelect { ty } => {
+                output.extend_from_slice(&[original[0], 1, self.val_type(ty)?]);
+                return Ok(());
+            }
+            Operator::F32Load { .. } => return self.patch(output, original, op::I32_LOAD),
+            Operator::F64Load { .. } => return self.patch(output, original, op::I64_LOAD),
+            Operator::F32Store { .. } => return self.patch(output, original, op::I32_STORE),
+            Operator::F64Store { .. } => return self.patch(output, original, op::I64_STORE),
+            Operator::F32Const { value } => {
+                output.push(op::I32_CONST);
+                write_i64(output, value.bits() as i32 as i64);
+                self.changed = true;
+                return Ok(());
+            }
+            Operator::F64Const { value } => {
+                output.push(op::I64_CONST);
+                write_i64(output, value.bits() as i64);
+                self.changed = true;
+                return Ok(());
+            }
+            Operator::I32ReinterpretF32
+            | Operator::I64ReinterpretF64
+            | Operator::F32ReinterpretI32
+            | Operator::F64ReinterpretI64 => {
+                self.changed = true;
+                return Ok(());
+            }
+            Operator::F32Abs => return self.bit_op(output, F32, 0x7fff_ffff, op::I32_AND),
+            Operator::F32Neg => return self.bit_op(output, F32, i32::MIN as i64, op::I32_XOR),
+            Operator::F64Abs => return self.bit_op(output, F64, i64::MAX, op::I64_AND),
+            Operator::F64Neg => return self.bit_op(output, F64, i64::MIN, op::I64_XOR),
+            Operator::F32Add => LibFunc::Add(F32),
+            Operator::F32Sub => LibFunc::Sub(F32),
+            Operator::F32Mul => LibFunc::Mul(F32),
+            Operator::F32Div => LibFunc::Div(F32),
+            Operator::F32Sqrt => LibFunc::Sqrt(F32),
+            Operator::F32Min => LibFunc::Min(F32),
+            Operator::F32Max => LibFunc::Max(F32),
+            Operator::F32Copysign => LibFunc::Copysign(F32),
+            Operator::F32Ceil => LibFunc::Round(F32, Rounding::Ceil),
+            Operator::F32Floor => LibFunc::Round(F32, Rounding::Floor),
+            Operator::F32Trunc => LibFunc::Round(F32, Rounding::Trunc),
+            Operator::F32Nearest => LibFunc::Round(F32, Rounding::Nearest),
+            Operator::F32Eq => LibFunc::Compare(F32, Compare::Eq),
+            Operator::F32Ne => LibFunc::Compare(F32, Compare::Ne),
+            Operator::F32Lt => LibFunc::Compare(F32, Compare::Lt),
+            Operator::F32Gt => LibFunc::Compare(F32, Compare::Gt),
+            Operator::F32Le => LibFunc::Compare(F32, Compare::Le),
+            Operator::F32Ge => LibFunc::Compare(F32, Compare::Ge),
+            Operator::F64Add => LibFunc::Add(F64),
+            Operator::F64Sub => LibFunc::Sub(F64),
+            Operator::F64Mul => LibFunc::Mul(F64),
+            Operator::F64Div => LibFunc::Div(F64),
+            Operator::F64Sqrt => LibFunc::Sqrt(F64),
+            Operator::F64Min => LibFunc::Min(F64),
+            Operator::F64Max => LibFunc::Max(F64),
+            Operator::F64Copysign => LibFunc::Copysign(F64),
+            Operator::F64Ceil => LibFunc::Round(F64, Rounding::Ceil),
+            Operator::F64Floor => LibFunc::Round(F64, Rounding::Floor),
+            Operator::F64Trunc => LibFunc::Round(F64, Rounding::Trunc),
+            Operator::F64Nearest => LibFunc::Round(F64, Rounding::Nearest),
+            Operator::F64Eq => LibFunc::Compare(F64, Compare::Eq),
+            Operator::F64Ne => LibFunc::Compare(F64, Compare::Ne),
+            Operator::F64Lt => LibFunc::Compare(F64, Compare::Lt),
+            Operator::F64Gt => LibFunc::Compare(F64, Compare::Gt),
+            Operator::F64Le => LibFunc::Compare(F64, Compare::Le),
+            Operator::F64Ge => LibFunc::Compare(F64, Compare::Ge),
+            Operator::F64PromoteF32 => LibFunc::Promote,
+            Operator::F32DemoteF64 => LibFunc::Demote,
+            Operator::F32ConvertI32S => convert(Int::I32, true, F32),
+            Operator::F32ConvertI32U => convert(Int::I32, false, F32),
+            Operator::F32ConvertI64S => convert(Int::I64, true, F32),
+            Operator::F32ConvertI64U => convert(Int::I64, false, F32),
+            Operator::F64ConvertI32S => convert(Int::I32, true, F64),
+            Operator::F64ConvertI32U => convert(Int::I32, false, F64),
+            Operator::F64ConvertI64S => convert(Int::I64, true, F64),
+            Operator::F64ConvertI64U => convert(Int::I64, false, F64),
+            Operator::I32TruncF32S => truncate(F32, Int::I32, true, false),
+            Operator::I32TruncF32U => truncate(F32, Int::I32, false, false),
+            Operator::I32TruncF64S => truncate(F64, Int::I32, true, false),
+            Operator::I32TruncF64U => truncate(F64, Int::I32, false, false),
+            Operator::I64TruncF32S => truncate(F32, Int::I64, true, false),
+            Operator::I64TruncF32U => truncate(F32, Int::I64, false, false),
+            Operator::I64TruncF64S => truncate(F64, Int::I64, true, false),
+            Operator::I64TruncF64U => truncate(F64, Int::I64, false, false),
+            Operator::I32TruncSatF32S => truncate(F32, Int::I32, true, true),
+            Operator::I32TruncSatF32U => truncate(F32, Int::I32, false, true),
+            Operator::I32TruncSatF64S => truncate(F64, Int::I32, true, true),
+            Operator::I32TruncSatF64U => truncate(F64, Int::I32, false, true),
+            Operator::I64TruncSatF32S => truncate(F32, Int::I64, true, true),
+            Operator::I64TruncSatF32U => truncate(F32, Int::I64, false, true),
+            Operator::I64TruncSatF64S => truncate(F64, Int::I64, true, true),
+            Operator::I64TruncSatF64U => truncate(F64, Int::I64, false, true),
+            _ => {
+                output.extend_from_slice(original);
+                return Ok(());
+            }
+        };
+        let func_idx = self.library.request(lib_func);
+        output.push(op::CALL);
+        write_u32(output, func_idx);
+        self.changed = true;
+        Ok(())
+    }
+
+    /// Copies a memory instruction replacing its opcode, the memory argument stays the same.
+    fn patch(
+        &mut self,
+        output: &mut Vec<u8>,
+        original: &[u8],
+        opcode: u8,
+    ) -> Result<(), SoftFloatError> {
+        output.push(opcode);
+        output.extend_from_slice(&original[1..]);
+        self.changed = true;
+        Ok(())
+    }
+
+    /// Emits a sign bit manipulation that doesn't need a library routine.
+    fn bit_op(
+        &mut self,
+        output: &mut Vec<u8>,
+        fmt: Float,
+        mask: i64,
+        opcode: u8,
+    ) -> Result<(), SoftFloatError> {
+        output.push(match fmt {
+            Float::F32 => op::I32_CONST,
+            Float::F64 => op::I64_CONST,
+        });
+        write_i64(output, mask);
+        output.push(opcode);
+        self.changed = true;
+        Ok(())
+    }
+}
+
+fn convert(from: Int, signed: bool, to: Float) -> LibFunc {
+    LibFunc::Convert { from, signed, to }
+}
+
+fn truncate(from: Float, to: Int, signed: bool, saturating: bool) -> LibFunc {
+    LibFunc::Truncate {
+        from,
+        to,
+        signed,
+        saturating,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lower_floats;
+    use wasmi::{core::ValueType, Config, Engine, Instance, Linker, Module, Store, Value};
+
+    const UNARY_F32: &[&str] = &[
+        "f32.sqrt",
+        "f32.ceil",
+        "f32.floor",
+        "f32.trunc",
+        "f32.nearest",
+    ];
+    const UNARY_F64: &[&str] = &[
+        "f64.sqrt",
+        "f64.ceil",
+        "f64.floor",
+        "f64.trunc",
+        "f64.nearest",
+    ];
+    const BINARY_F32: &[&str] = &[
+        "f32.add",
+        "f32.sub",
+        "f32.mul",
+        "f32.div",
+        "f32.min",
+        "f32.max",
+        "f32.copysign",
+    ];
+    const BINARY_F64: &[&str] = &[
+        "f64.add",
+        "f64.sub",
+        "f64.mul",
+        "f64.div",
+        "f64.min",
+        "f64.max",
+        "f64.copysign",
+    ];
+    const COMPARE: &[&str] = &["eq", "ne", "lt", "gt", "le", "ge"];
+    const BIT_OPS: &[&str] = &["abs", "neg", "copysign"];
+
+    struct Runner {
+        store: Store<()>,
+        instance: Instance,
+    }
+
+    impl Runner {
+        fn new(wasm: &[u8], floats: bool) -> Self {
+            let mut config = Config::default();
+            config.floats(floats);
+            let engine = Engine::new(&config);
+            let module = Module::new(&engine, wasm).unwrap();
+            let mut store = Store::new(&engine, ());
+            let instance = Linker::<()>::new(&engine)
+                .instantiate(&mut store, &module)
+                .unwrap()
+                .start(&mut store)
+                .unwrap();
+            Self { store, instance }
+        }
+
+        fn call(&mut self, name: &str, params: &[Value]) -> Option<Value> {
+            let func = self.instance.get_func(&self.store, name).unwrap();
+            let mut results = [Value::I32(0)];
+            let ty = func.ty(&self.store);
+            let results = &mut results[..ty.results().len()];
+            func.call(&mut self.store, params, results).ok()?;
+            results.first().cloned().or(Some(Value::I32(0)))
+        }
+    }
+
+    fn bits(value: &Value) -> u64 {
+        match value {
+            Value::I32(v) => *v as u32 as u64,
+            Value::I64(v) => *v as u64,
+            Value::F32(v) => v.to_bits() as u64,
+            Value::F64(v) => v.to_bits(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn value(ty: ValueType, bits: u64, lowered: bool) -> Value {
+        match (ty, lowered) {
+            (ValueType::I32, _) | (ValueType::F32, true) => Value::I32(bits as i32),
+            (ValueType::I64, _) | (ValueType::F64, true) => Value::I64(bits as i64),
+            (ValueType::F32, false) => Value::F32(f32::from_bits(bits as u32).into()),
+            (ValueType::F64, false) => Value::F64(f64::from_bits(bits).into()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_nan(ty: ValueType, bits: u64) -> bool {
+        match ty {
+            ValueType::F32 => f32::from_bits(bits as u32).is_nan(),
+            ValueType::F64 => f64::from_bits(bits).is_nan(),
+            _ => false,
+        }
+    }
+
+    /// Interesting bit patterns followed by pseudo-random ones.
+    fn samples(ty: ValueType, seed: u64) -> Vec<u64> {
+        let mut result: Vec<u64> = match ty {
+            ValueType::F32 => [
+                0.0f32,
+                -0.0,
+                1.0,
+                -1.0,
+                0.5,
+                -0.5,
+                1.5,
+                2.5,
+                -2.5,
+                0.49999997,
+                3.0,
+                1e-40,
+                -1e-45,
+                f32::MIN_POSITIVE,
+                f32::MAX,
+                f32::MIN,
+                f32::INFINITY,
+                f32::NEG_INFINITY,
+                f32::NAN,
+                2147483648.0,
+                -2147483648.0,
+                4294967296.0,
+                9.223372e18,
+                -9.223372e18,
+                1.8446744e19,
+                16777217.0,
+            ]
+            .iter()
+            .map(|v| v.to_bits() as u64)
+            .collect(),
+            ValueType::F64 => [
+                0.0f64,
+                -0.0,
+                1.0,
+                -1.0,
+                0.5,
+                -0.5,
+                1.5,
+                2.5,
+                -2.5,
+                0.49999999999999994,
+                3.0,
+                1e-310,
+                -5e-324,
+                f64::MIN_POSITIVE,
+                f64::MAX,
+                f64::MIN,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NAN,
+                2147483647.9,
+                2147483648.0,
+                -2147483648.9,
+                -2147483649.0,
+                4294967295.5,
+                4294967296.0,
+                9223372036854775807.0,
+                -9223372036854775808.0,
+                18446744073709551615.0,
+                3.4028235677973366e38,
+                1.401298464324817e-45,
+                7.006492321624085e-46,
+            ]
+            .iter()
+            .map(|v| v.to_bits())
+            .collect(),
+            _ => vec![
+                0,
+                1,
+                u64::MAX,
+                i32::MAX as u64,
+                i32::MIN as u32 as u64,
+                u32::MAX as u64,
+                i64::MAX as u64,
+                i64::MIN as u64,
+                (1 << 24) + 1,
+                (1 << 53) + 1,
+            ],
+        };
+        let mut state = seed | 1;
+        for i in 0..600 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let mut sample = state;
+            // every other sample has a moderate exponent, so that results stay finite
+            if i % 2 == 0 {
+                sample = match ty {
+                    ValueType::F32 => (sample & 0x807f_ffff) | ((120 + (sample >> 40) % 16) << 23),
+                    ValueType::F64 => {
+                        (sample & 0x800f_ffff_ffff_ffff) | ((1016 + (sample >> 20) % 16) << 52)
+                    }
+                    _ => sample >> (sample % 64),
+                };
+            }
+            result.push(sample);
+        }
+        result
+    }
+
+    fn check_ops(ops: &[(String, Vec<ValueType>, ValueType)]) {
+        let mut wat = String::from("(module\n");
+        for (i, (op, params, result)) in ops.iter().enumerate() {
+            let params = params
+                .iter()
+                .map(|ty| format!("{:?}", ty).to_lowercase())
+                .collect::<Vec<_>>();
+            let gets = (0..params.len())
+                .map(|i| format!("local.get {}", i))
+                .collect::<Vec<_>>();
+            wat += &format!(
+                "(func (export \"{}\") (param {}) (result {}) {} {})\n",
+                i,
+                params.join(" "),
+                format!("{:?}", result).to_lowercase(),
+                gets.join(" "),
+                op,
+            );
+        }
+        wat += ")";
+        let wasm = wat::parse_str(&wat).unwrap();
+        let lowered = lower_floats(&wasm).unwrap();
+        let mut native = Runner::new(&wasm, true);
+        let mut soft = Runner::new(&lowered, false);
+        for (i, (op, params, result)) in ops.iter().enumerate() {
+            let a = samples(params[0], i as u64 + 1);
+            let b = samples(*params.last().unwrap(), i as u64 + 1000);
+            let pairs = a
+                .iter()
+                .flat_map(|x| b.iter().take(80).map(move |y| (*x, *y)))
+                .filter(|(_, y)| params.len() == 2 || *y == b[0]);
+            for (x, y) in pairs {
+                let inputs = [x, y];
+                let call = |runner: &mut Runner, lowered: bool| {
+                    let values = params
+                        .iter()
+                        .zip(inputs.iter())
+                        .map(|(ty, bits)| value(*ty, *bits, lowered))
+                        .collect::<Vec<_>>();
+                    runner.call(&i.to_string(), &values).map(|v| bits(&v))
+                };
+                let (expected, actual) = (call(&mut native, false), call(&mut soft, true));
+                match (expected, actual) {
+                    // sign manipulations are bit operations that keep the NaN payload
+                    (Some(expected), Some(actual))
+                        if is_nan(*result, expected)
+                            && !BIT_OPS.iter().any(|b| op.ends_with(b)) =>
+                    {
+                        let canonical = match result {
+                            ValueType::F32 => f32::NAN.to_bits() as u64,
+                            _ => f64::NAN.to_bits(),
+                        };
+                        assert_eq!(actual, canonical, "{} {:x?}", op, &inputs[..params.len()]);
+                    }
+                    _ => assert_eq!(expected, actual, "{} {:x?}", op, &inputs[..params.len()]),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut ops = Vec::new();
+        for (unary, binary, ty) in [
+            (UNARY_F32, BINARY_F32, ValueType::F32),
+            (UNARY_F64, BINARY_F64, ValueType::F64),
+        ] {
+            for op in unary {
+                ops.push((op.to_string(), vec![ty], ty));
+            }
+            for op in binary {
+                ops.push((op.to_string(), vec![ty, ty], ty));
+            }
+            for op in ["abs", "neg"] {
+                ops.push((format!("{:?}.{}", ty, op).to_lowercase(), vec![ty], ty));
+            }
+            for op in COMPARE {
+                let op = format!("{:?}.{}", ty, op).to_lowercase();
+                ops.push((op, vec![ty, ty], ValueType::I32));
+            }
+        }
+        check_ops(&ops);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let (f32, f64) = (ValueType::F32, ValueType::F64);
+        let mut ops = vec![
+            ("f64.promote_f32".to_string(), vec![f32], f64),
+            ("f32.demote_f64".to_string(), vec![f64], f32),
+        ];
+        for (float, float_ty) in [("f32", f32), ("f64", f64)] {
+            for (int, int_ty) in [("i32", ValueType::I32), ("i64", ValueType::I64)] {
+                for sign in ["s", "u"] {
+                    let convert = format!("{}.convert_{}_{}", float, int, sign);
+                    ops.push((convert, vec![int_ty], float_ty));
+                    let trunc = format!("{}.trunc_{}_{}", int, float, sign);
+                    ops.push((trunc, vec![float_ty], int_ty));
+                    let trunc_sat = format!("{}.trunc_sat_{}_{}", int, float, sign);
+                    ops.push((trunc_sat, vec![float_ty], int_ty));
+                }
+            }
+        }
+        check_ops(&ops);
+    }
+
+    #[test]
+    fn test_module_state() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (memory 1)
+              (global $scale (mut f64) (f64.const 0.25))
+              (func $lerp (param $a f32) (param $b f32) (param $t f64) (result f32)
+                (f32.add
+                  (local.get $a)
+                  (f32.mul
+                    (f32.sub (local.get $b) (local.get $a))
+                    (f32.demote_f64 (local.get $t)))))
+              (func (export "main") (param $n i32) (result i64)
+                (local $i i32) (local $acc f64)
+                (loop $next
+                  (f32.store (i32.shl (local.get $i) (i32.const 2))
+                    (call $lerp
+                      (f32.convert_i32_s (local.get $i))
+                      (f32.const 100.5)
+                      (f64.mul (global.get $scale) (f64.convert_i32_u (local.get $i)))))
+                  (br_if $next
+                    (i32.lt_u (local.tee $i (i32.add (local.get $i) (i32.const 1)))
+                      (local.get $n))))
+                (local.set $i (i32.const 0))
+                (block $done
+                  (loop $sum
+                    (local.set $acc
+                      (f64.add (local.get $acc)
+                        (select (result f64)
+                          (f64.promote_f32 (f32.load (i32.shl (local.get $i) (i32.const 2))))
+                          (f64.const -1)
+                          (f32.gt (f32.load (i32.shl (local.get $i) (i32.const 2)))
+                            (f32.const 0)))))
+                    (br_if $done
+                      (i32.ge_u (local.tee $i (i32.add (local.get $i) (i32.const 1)))
+                        (local.get $n)))
+                    (br $sum)))
+                (global.set $scale (f64.sqrt (f64.abs (local.get $acc))))
+                (i64.reinterpret_f64 (f64.nearest (f64.mul (local.get $acc) (global.get $scale))))))
+            "#,
+        )
+        .unwrap();
+        let lowered = lower_floats(&wasm).unwrap();
+        let mut native = Runner::new(&wasm, true);
+        let mut soft = Runner::new(&lowered, false);
+        for n in [1, 2, 7, 31] {
+            let params = [Value::I32(n)];
+            let expected = native.call("main", &params).map(|v| bits(&v));
+            assert_eq!(expected, soft.call("main", &params).map(|v| bits(&v)));
+        }
+    }
+
+    #[test]
+    fn test_module_without_floats_is_unchanged() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (func (export "main") (param i64) (result i64)
+                (i64.add (local.get 0) (i64.const 1))))
+            "#,
+        )
+        .unwrap();
+        assert_eq!(lower_floats(&wasm).unwrap(), wasm);
+    }
+}
@@ -82,6 +82,7 @@ impl JournalCheckpoint {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct JournalLog {
     pub address: Address,
     pub topics: Vec<B256>,
@@ -12,7 +12,18 @@ pub use journal::*;
 mod linker;
 pub use linker::*;
 mod types;
-pub use alloy_primitives::{address, b256, bloom, bytes, fixed_bytes, Address, Bytes, B256, U256};
+pub use alloy_primitives::{
+    address,
+    b256,
+    bloom,
+    bytes,
+    fixed_bytes,
+    Address,
+    Bytes,
+    B256,
+    I256,
+    U256,
+};
 pub use types::*;
 
 pub const KECCAK_EMPTY: B256 =
@@ -30,3 +41,18 @@ pub const NATIVE_TRANSFER_ADDRESS: Address = address!("0000000000000000000000000
 
 pub const STATE_MAIN: u32 = 0;
 pub const STATE_DEPLOY: u32 = 1;
+
+/// Sizes of Groth16 parameters over BN254 (see `fluentbase_sdk::bn254::groth16_verify` for the
+/// layout)
+pub const GROTH16_G1_SIZE: usize = 64;
+pub const GROTH16_G2_SIZE: usize = 128;
+/// Proof is `A (G1) || B (G2) || C (G1)`
+pub const GROTH16_PROOF_SIZE: usize = 2 * GROTH16_G1_SIZE + GROTH16_G2_SIZE;
+/// Verification key without IC points is `alpha (G1) || beta (G2) || gamma (G2) || delta (G2)`,
+/// and it's followed by `n + 1` IC points (G1) where `n` is a number of public inputs
+pub const GROTH16_VK_HEADER_SIZE: usize = GROTH16_G1_SIZE + 3 * GROTH16_G2_SIZE;
+
+/// Returns size of the verification key for the given number of public inputs
+pub const fn groth16_vk_size(num_inputs: usize) -> usize {
+    GROTH16_VK_HEADER_SIZE + (num_inputs + 1) * GROTH16_G1_SIZE
+}
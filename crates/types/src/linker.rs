@@ -9,11 +9,18 @@ macro_rules! import_func {
     };
 }
 
-const SHARED_IMPORT_LINKER: [(&'static str, &'static str, u32, u32); 20] = [
+const SHARED_IMPORT_LINKER: [(&'static str, &'static str, u32, u32); 27] = [
     import_func!("_keccak256", KECCAK256),
     import_func!("_poseidon", KECCAK256),
     import_func!("_poseidon_hash", POSEIDON_HASH),
     import_func!("_ecrecover", ECRECOVER),
+    import_func!("_bn254_fr_add", BN254_FR_ADD),
+    import_func!("_bn254_fr_mul", BN254_FR_MUL),
+    import_func!("_bn254_fr_inv", BN254_FR_INV),
+    import_func!("_bn254_fr_batch_inv", BN254_FR_BATCH_INV),
+    import_func!("_bn254_fr_pow", BN254_FR_POW),
+    import_func!("_bn254_fr_inner_product", BN254_FR_INNER_PRODUCT),
+    import_func!("_groth16_verify", GROTH16_VERIFY),
     import_func!("_exit", EXIT),
     import_func!("_write", WRITE),
     import_func!("_input_size", INPUT_SIZE),
@@ -46,11 +53,18 @@ pub fn create_shared_import_linker<
     F::from(SHARED_IMPORT_LINKER)
 }
 
-const SOVEREIGN_IMPORT_LINKER: [(&'static str, &'static str, u32, u32); 27] = [
+const SOVEREIGN_IMPORT_LINKER: [(&'static str, &'static str, u32, u32); 34] = [
     import_func!("_keccak256", KECCAK256),
     import_func!("_poseidon", KECCAK256),
     import_func!("_poseidon_hash", POSEIDON_HASH),
     import_func!("_ecrecover", ECRECOVER),
+    import_func!("_bn254_fr_add", BN254_FR_ADD),
+    import_func!("_bn254_fr_mul", BN254_FR_MUL),
+    import_func!("_bn254_fr_inv", BN254_FR_INV),
+    import_func!("_bn254_fr_batch_inv", BN254_FR_BATCH_INV),
+    import_func!("_bn254_fr_pow", BN254_FR_POW),
+    import_func!("_bn254_fr_inner_product", BN254_FR_INNER_PRODUCT),
+    import_func!("_groth16_verify", GROTH16_VERIFY),
     import_func!("_exit", EXIT),
     import_func!("_write", WRITE),
     import_func!("_input_size", INPUT_SIZE),
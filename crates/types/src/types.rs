@@ -1,5 +1,5 @@
 use crate::U256;
-use alloc::format;
+use alloc::{format, string::String, vec::Vec};
 use alloy_primitives::hex;
 use core::{fmt, fmt::Formatter};
 #[cfg(feature = "rwasm")]
@@ -12,6 +12,71 @@ use strum_macros::{Display, EnumProperty, FromRepr};
 pub type Bytes32 = [u8; 32];
 pub type Bytes20 = [u8; 20];
 
+/// sig4("Error(string)")
+pub const SOLIDITY_ERROR_SELECTOR: u32 = 0x08c379a0;
+/// sig4("Panic(uint256)")
+pub const SOLIDITY_PANIC_SELECTOR: u32 = 0x4e487b71;
+
+/// An error that can be returned as Solidity compatible revert data.
+///
+/// Revert data is the 4-byte selector of the error signature followed by ABI encoded
+/// parameters, it's what wallets and explorers decode to display revert reasons. Custom errors
+/// can take the selector from `derive_keccak256_id!`, for example
+/// `derive_keccak256_id!("InsufficientBalance(uint256,uint256)")`.
+pub trait SolidityError {
+    /// Selector of the error signature.
+    const SELECTOR: u32;
+
+    /// Writes ABI encoded parameters of the error.
+    fn encode_params(&self, output: &mut Vec<u8>);
+
+    /// Exit code execution halts with, custom errors revert the same way as Solidity does.
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::Panic
+    }
+
+    fn encode_revert(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(4 + 32);
+        output.extend_from_slice(&Self::SELECTOR.to_be_bytes());
+        self.encode_params(&mut output);
+        output
+    }
+}
+
+/// Message of `Error(string)`, the same error that Solidity's `revert("...")` produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidityErrorMessage<'a>(pub &'a str);
+
+impl<'a> SolidityError for SolidityErrorMessage<'a> {
+    const SELECTOR: u32 = SOLIDITY_ERROR_SELECTOR;
+
+    fn encode_params(&self, output: &mut Vec<u8>) {
+        let message = self.0.as_bytes();
+        output.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+        output.extend_from_slice(&U256::from(message.len()).to_be_bytes::<32>());
+        output.extend_from_slice(message);
+        output.resize(output.len() + (32 - message.len() % 32) % 32, 0);
+    }
+}
+
+/// Encodes `Error(string)` revert data with the given message.
+pub fn encode_solidity_error_message(message: &str) -> Vec<u8> {
+    SolidityErrorMessage(message).encode_revert()
+}
+
+/// Decodes the message of `Error(string)` revert data, returns `None` for any other data.
+pub fn decode_solidity_error_message(revert_data: &[u8]) -> Option<String> {
+    let params = revert_data.strip_prefix(&SOLIDITY_ERROR_SELECTOR.to_be_bytes())?;
+    let word = |offset: usize| -> Option<usize> {
+        let word = U256::from_be_slice(params.get(offset..offset.checked_add(32)?)?);
+        usize::try_from(word).ok()
+    };
+    let offset = word(0)?;
+    let length = word(offset)?;
+    let message = params.get(offset.checked_add(32)?..)?.get(..length)?;
+    String::from_utf8(message.to_vec()).ok()
+}
+
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Display, FromRepr)]
 #[repr(i32)]
 pub enum ExitCode {
@@ -53,6 +118,7 @@ pub enum ExitCode {
     InvalidJump = -1032,
     NotActivatedEIP = -1033,
     ImmutableContext = -1034,
+    Bn254Error = -1035,
     // trap error codes
     UnreachableCodeReached = -2006,
     MemoryOutOfBounds = -2007,
@@ -103,6 +169,20 @@ impl ExitCode {
     }
 }
 
+/// Exit codes revert with `Error(string)` that has the name of the exit code as the message.
+impl SolidityError for ExitCode {
+    const SELECTOR: u32 = SOLIDITY_ERROR_SELECTOR;
+
+    fn encode_params(&self, output: &mut Vec<u8>) {
+        let message = format!("{}", self);
+        SolidityErrorMessage(&message).encode_params(output);
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        *self
+    }
+}
+
 #[cfg(feature = "rwasm")]
 impl From<TrapCode> for ExitCode {
     fn from(value: TrapCode) -> Self {
@@ -148,6 +228,13 @@ pub enum SysFuncIdx {
     POSEIDON = 0x0102,
     POSEIDON_HASH = 0x0103,
     ECRECOVER = 0x0104,
+    BN254_FR_ADD = 0x0105,
+    BN254_FR_MUL = 0x0106,
+    BN254_FR_INV = 0x0107,
+    BN254_FR_BATCH_INV = 0x0108,
+    BN254_FR_POW = 0x0109,
+    BN254_FR_INNER_PRODUCT = 0x010A,
+    GROTH16_VERIFY = 0x010B,
 
     // SYS host
     EXIT = 0x0001,
@@ -212,3 +299,91 @@ impl Into<FuncIdx> for SysFuncIdx {
         (self as u32).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_solidity_error_message() {
+        // the revert data of `revert("Not enough Ether provided.")`
+        let expected = hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "000000000000000000000000000000000000000000000000000000000000001a"
+            "4e6f7420656e6f7567682045746865722070726f76696465642e000000000000"
+        );
+        let revert_data = encode_solidity_error_message("Not enough Ether provided.");
+        assert_eq!(revert_data, expected);
+        assert_eq!(
+            decode_solidity_error_message(&revert_data).as_deref(),
+            Some("Not enough Ether provided.")
+        );
+        // messages that fill the last word completely get no padding
+        let message = "x".repeat(64);
+        let revert_data = encode_solidity_error_message(&message);
+        assert_eq!(revert_data.len(), 4 + 32 + 32 + 64);
+        assert_eq!(decode_solidity_error_message(&revert_data), Some(message));
+        assert_eq!(
+            encode_solidity_error_message(""),
+            hex!(
+                "08c379a0"
+                "0000000000000000000000000000000000000000000000000000000000000020"
+                "0000000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_solidity_error_message_rejects_other_data() {
+        let mut panic_data = [0u8; 36];
+        ExitCode::Panic.encode_solidity_panic(&mut panic_data);
+        assert_eq!(decode_solidity_error_message(&panic_data), None);
+        let revert_data = encode_solidity_error_message("truncated");
+        assert_eq!(
+            decode_solidity_error_message(&revert_data[..revert_data.len() - 32]),
+            None
+        );
+        assert_eq!(decode_solidity_error_message(&[]), None);
+    }
+
+    #[test]
+    fn test_exit_code_solidity_error() {
+        let revert_data = ExitCode::InsufficientBalance.encode_revert();
+        assert_eq!(
+            decode_solidity_error_message(&revert_data).as_deref(),
+            Some("InsufficientBalance")
+        );
+        assert_eq!(
+            ExitCode::InsufficientBalance.exit_code(),
+            ExitCode::InsufficientBalance
+        );
+        assert_eq!(SolidityErrorMessage("").exit_code(), ExitCode::Panic);
+    }
+
+    #[test]
+    fn test_custom_solidity_error() {
+        struct InsufficientBalance {
+            available: U256,
+            required: U256,
+        }
+        impl SolidityError for InsufficientBalance {
+            // sig4("InsufficientBalance(uint256,uint256)")
+            const SELECTOR: u32 = 0xcf479181;
+
+            fn encode_params(&self, output: &mut Vec<u8>) {
+                output.extend_from_slice(&self.available.to_be_bytes::<32>());
+                output.extend_from_slice(&self.required.to_be_bytes::<32>());
+            }
+        }
+        let revert_data = InsufficientBalance {
+            available: U256::from(1),
+            required: U256::from(2),
+        }
+        .encode_revert();
+        assert_eq!(revert_data[..4], hex!("cf479181"));
+        assert_eq!(revert_data.len(), 4 + 64);
+        assert_eq!(revert_data[4 + 31], 1);
+        assert_eq!(revert_data[4 + 63], 2);
+    }
+}
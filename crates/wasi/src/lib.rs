@@ -0,0 +1,66 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! WASI preview1 compatibility shim.
+//!
+//! Crates that only build for `wasm32-wasi` import functions from `wasi_snapshot_preview1`
+//! that don't exist in fluentbase. This crate rewrites such modules before the rWASM
+//! translation, every supported WASI import is replaced with a function generated into the
+//! module that forwards the call into fluentbase syscalls:
+//!
+//! - `fd_write` writes stdout into the contract output (`_write`) and stderr into the debug log
+//!   (`_debug_log`)
+//! - `fd_read` reads stdin from the contract input (`_read`)
+//! - `args_get`/`environ_get` and their `*_sizes_get` report no arguments and no variables
+//! - `random_get` fills buffers from a fixed seed, so it's deterministic and NOT random
+//! - `proc_exit` halts with the given exit code (`_exit`)
+//!
+//! Any other WASI import is rejected.
+
+extern crate alloc;
+
+mod module;
+mod shim;
+
+use alloc::string::String;
+use core::fmt;
+pub use module::shim_wasi_imports;
+
+pub const WASI_MODULE: &str = "wasi_snapshot_preview1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasiError {
+    /// The input is not a valid wasm binary.
+    Parse,
+    /// The WASI function has no safe mapping onto fluentbase syscalls.
+    UnsupportedImport(String),
+    /// The WASI function is imported with a signature that doesn't match preview1.
+    SignatureMismatch(String),
+}
+
+impl fmt::Display for WasiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasiError::Parse => write!(f, "failed to parse wasm binary"),
+            WasiError::UnsupportedImport(name) => {
+                write!(
+                    f,
+                    "WASI import `{}::{}` is not supported",
+                    WASI_MODULE, name
+                )
+            }
+            WasiError::SignatureMismatch(name) => {
+                write!(
+                    f,
+                    "WASI import `{}::{}` has a wrong signature",
+                    WASI_MODULE, name
+                )
+            }
+        }
+    }
+}
+
+impl From<wasmparser::BinaryReaderError> for WasiError {
+    fn from(_: wasmparser::BinaryReaderError) -> Self {
+        WasiError::Parse
+    }
+}
@@ -0,0 +1,843 @@
+use crate::{
+    shim::{generate, ShimContext, Syscall, Wasi, RANDOM_SEED},
+    WasiError,
+    WASI_MODULE,
+};
+use alloc::{string::ToString, vec::Vec};
+use core::ops::Range;
+use fluentbase_wasm_encoder::{
+    op,
+    write_func_type,
+    write_i64,
+    write_name,
+    write_section,
+    write_u32,
+    VAL_I32,
+    VAL_I64,
+};
+use wasmparser::{
+    ElementItems,
+    ElementKind,
+    ExternalKind,
+    FunctionBody,
+    Operator,
+    OperatorsReader,
+    Parser,
+    Payload,
+    Type,
+    TypeRef,
+    ValType,
+};
+
+const SYSCALL_MODULE: &str = "fluentbase_v1preview";
+
+const TYPE_SECTION: u8 = 1;
+const IMPORT_SECTION: u8 = 2;
+const FUNCTION_SECTION: u8 = 3;
+const GLOBAL_SECTION: u8 = 6;
+const EXPORT_SECTION: u8 = 7;
+const START_SECTION: u8 = 8;
+const ELEMENT_SECTION: u8 = 9;
+const CODE_SECTION: u8 = 10;
+
+enum Section<'a> {
+    Raw(u8, Range<usize>),
+    Type,
+    Import,
+    Function(wasmparser::FunctionSectionReader<'a>),
+    Global(wasmparser::GlobalSectionReader<'a>),
+    Export(wasmparser::ExportSectionReader<'a>),
+    Start(u32),
+    Element(wasmparser::ElementSectionReader<'a>),
+    Code(Vec<FunctionBody<'a>>),
+}
+
+/// Where an imported function of the original module ends up.
+enum ImportedFunc {
+    /// The import is kept, it's the n-th imported function of the rewritten module.
+    Kept(u32),
+    /// The import is replaced with the n-th generated shim.
+    Shim(u32),
+}
+
+/// Translates function indices of the original module into the rewritten one.
+struct Remap<'a> {
+    wasm: &'a [u8],
+    imported_funcs: Vec<ImportedFunc>,
+    num_imported_funcs: u32,
+    num_defined_funcs: u32,
+}
+
+/// Replaces `wasi_snapshot_preview1` imports of the module with generated functions that call
+/// fluentbase syscalls instead.
+///
+/// Missing syscall imports are appended to the import section, the shims are appended to the
+/// defined functions and all function references (calls, exports, tables, start) are remapped.
+/// Fails if the module imports a WASI function that has no safe mapping. Returns the binary
+/// unchanged if it doesn't import anything from WASI.
+pub fn shim_wasi_imports(wasm: &[u8]) -> Result<Vec<u8>, WasiError> {
+    let mut sections = Vec::new();
+    let mut types: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    let mut imports: Vec<(wasmparser::Import, Range<usize>)> = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::Version { .. } | Payload::End(_) => {}
+            Payload::TypeSection(reader) => {
+                for ty in reader {
+                    let Type::Func(func_type) = ty?;
+                    types.push((
+                        func_type.params().iter().map(|ty| val_type(*ty)).collect(),
+                        func_type.results().iter().map(|ty| val_type(*ty)).collect(),
+                    ));
+                }
+                sections.push(Section::Type);
+            }
+            Payload::ImportSection(reader) => {
+                let end = reader.range().end;
+                for import in reader.into_iter_with_offsets() {
+                    let (offset, import) = import?;
+                    if let Some((_, previous)) = imports.last_mut() {
+                        *previous = previous.start..offset;
+                    }
+                    imports.push((import, offset..end));
+                }
+                sections.push(Section::Import);
+            }
+            Payload::FunctionSection(reader) => sections.push(Section::Function(reader)),
+            Payload::GlobalSection(reader) => sections.push(Section::Global(reader)),
+            Payload::ExportSection(reader) => sections.push(Section::Export(reader)),
+            Payload::StartSection { func, .. } => sections.push(Section::Start(func)),
+            Payload::ElementSection(reader) => sections.push(Section::Element(reader)),
+            Payload::CodeSectionStart { .. } => sections.push(Section::Code(Vec::new())),
+            Payload::CodeSectionEntry(body) => match sections.last_mut() {
+                Some(Section::Code(bodies)) => bodies.push(body),
+                _ => unreachable!("wasi: code entry outside of the code section"),
+            },
+            // function names would point to wrong functions after the renumbering
+            Payload::CustomSection(reader) if reader.name() == "name" => {}
+            payload => match payload.as_section() {
+                Some((id, range)) => sections.push(Section::Raw(id, range)),
+                None => return Err(WasiError::Parse),
+            },
+        }
+    }
+
+    let mut kept_imports = Vec::new();
+    let mut imported_funcs = Vec::new();
+    let mut shims: Vec<(Wasi, u32)> = Vec::new();
+    let mut syscalls = [None; Syscall::ALL.len()];
+    let mut num_kept_funcs = 0u32;
+    let mut num_imported_globals = 0u32;
+    for (import, range) in imports.iter() {
+        if import.module == WASI_MODULE {
+            let name = import.name.to_string();
+            let (wasi, type_idx) = match (Wasi::from_name(import.name), import.ty) {
+                (Some(wasi), TypeRef::Func(type_idx)) => (wasi, type_idx),
+                _ => return Err(WasiError::UnsupportedImport(name)),
+            };
+            let (params, results) = wasi.signature();
+            match types.get(type_idx as usize) {
+                Some(ty) if ty.0 == params && ty.1 == results => {}
+                _ => return Err(WasiError::SignatureMismatch(name)),
+            }
+            imported_funcs.push(ImportedFunc::Shim(shims.len() as u32));
+            shims.push((wasi, type_idx));
+            continue;
+        }
+        match import.ty {
+            TypeRef::Func(type_idx) => {
+                // syscalls that are already imported with the right signature are reused
+                let syscall = Syscall::ALL.iter().position(|syscall| {
+                    let (params, results) = syscall.signature();
+                    import.module == SYSCALL_MODULE
+                        && import.name == syscall.name()
+                        && types.get(type_idx as usize)
+                            == Some(&(params.to_vec(), results.to_vec()))
+                });
+                if let Some(position) = syscall {
+                    syscalls[position] = Some(num_kept_funcs);
+                }
+                imported_funcs.push(ImportedFunc::Kept(num_kept_funcs));
+                num_kept_funcs += 1;
+            }
+            TypeRef::Global(_) => num_imported_globals += 1,
+            _ => {}
+        }
+        kept_imports.push(range.clone());
+    }
+    if shims.is_empty() {
+        return Ok(wasm.to_vec());
+    }
+
+    // import the syscalls that the shims need, together with their types
+    let mut syscall_imports = Vec::new();
+    for (position, syscall) in Syscall::ALL.iter().enumerate() {
+        let needed = shims
+            .iter()
+            .any(|(wasi, _)| wasi.syscalls().contains(syscall));
+        if !needed || syscalls[position].is_some() {
+            continue;
+        }
+        let (params, results) = syscall.signature();
+        let signature = (params.to_vec(), results.to_vec());
+        let type_idx = match types.iter().position(|ty| *ty == signature) {
+            Some(type_idx) => type_idx,
+            None => {
+                types.push(signature);
+                types.len() - 1
+            }
+        };
+        syscalls[position] = Some(num_kept_funcs + syscall_imports.len() as u32);
+        syscall_imports.push((*syscall, type_idx as u32));
+    }
+
+    let mut num_defined_funcs = 0;
+    let mut num_defined_globals = 0;
+    for section in sections.iter() {
+        match section {
+            Section::Function(reader) => num_defined_funcs = reader.count(),
+            Section::Global(reader) => num_defined_globals = reader.count(),
+            _ => {}
+        }
+    }
+    let remap = Remap {
+        wasm,
+        imported_funcs,
+        num_imported_funcs: num_kept_funcs + syscall_imports.len() as u32,
+        num_defined_funcs,
+    };
+
+    // the stdin cursor and the generator state live in globals appended to the module
+    let mut new_globals: Vec<(u8, i64)> = Vec::new();
+    let mut new_global = |val_type: u8, init: i64| {
+        new_globals.push((val_type, init));
+        num_imported_globals + num_defined_globals + new_globals.len() as u32 - 1
+    };
+    let has = |wasi: Wasi| shims.iter().any(|(shim, _)| *shim == wasi);
+    let ctx = ShimContext {
+        syscalls,
+        stdin_offset_global: match has(Wasi::FdRead) {
+            true => new_global(VAL_I32, 0),
+            false => u32::MAX,
+        },
+        random_state_global: match has(Wasi::RandomGet) {
+            true => new_global(VAL_I64, RANDOM_SEED),
+            false => u32::MAX,
+        },
+    };
+
+    let mut output = Vec::with_capacity(wasm.len() + 1024);
+    output.extend_from_slice(&wasm[0..8]);
+    let mut written = Vec::new();
+    for section in sections.iter() {
+        let (id, contents) = match section {
+            Section::Raw(id, range) => (*id, wasm[range.clone()].to_vec()),
+            Section::Type => (TYPE_SECTION, encode_types(&types)),
+            Section::Import => (
+                IMPORT_SECTION,
+                encode_imports(wasm, &kept_imports, &syscall_imports),
+            ),
+            Section::Function(reader) => {
+                let mut contents = Vec::new();
+                write_u32(&mut contents, num_defined_funcs + shims.len() as u32);
+                for type_idx in reader.clone() {
+                    write_u32(&mut contents, type_idx?);
+                }
+                for (_, type_idx) in shims.iter() {
+                    write_u32(&mut contents, *type_idx);
+                }
+                (FUNCTION_SECTION, contents)
+            }
+            Section::Global(reader) => {
+                let mut contents = Vec::new();
+                write_u32(
+                    &mut contents,
+                    num_defined_globals + new_globals.len() as u32,
+                );
+                for global in reader.clone() {
+                    let global = global?;
+                    contents.push(val_type(global.ty.content_type));
+                    contents.push(global.ty.mutable as u8);
+                    remap.operators(global.init_expr.get_operators_reader(), &mut contents)?;
+                }
+                encode_globals(&new_globals, &mut contents);
+                (GLOBAL_SECTION, contents)
+            }
+            Section::Export(reader) => (EXPORT_SECTION, remap.exports(reader)?),
+            Section::Start(func) => {
+                let mut contents = Vec::new();
+                write_u32(&mut contents, remap.func(*func));
+                (START_SECTION, contents)
+            }
+            Section::Element(reader) => (ELEMENT_SECTION, remap.elements(reader)?),
+            Section::Code(bodies) => {
+                let mut contents = Vec::new();
+                write_u32(&mut contents, (bodies.len() + shims.len()) as u32);
+                for body in bodies.iter() {
+                    let body = remap.body(body)?;
+                    write_u32(&mut contents, body.len() as u32);
+                    contents.extend_from_slice(&body);
+                }
+                encode_shims(&shims, &ctx, &mut contents);
+                (CODE_SECTION, contents)
+            }
+        };
+        write_section(&mut output, id, &contents);
+        written.push((id, output.len()));
+    }
+
+    // a module without functions or globals gets new sections at their canonical positions
+    let mut missing = Vec::new();
+    if !written.iter().any(|(id, _)| *id == FUNCTION_SECTION) {
+        let mut function_section = Vec::new();
+        write_u32(&mut function_section, shims.len() as u32);
+        for (_, type_idx) in shims.iter() {
+            write_u32(&mut function_section, *type_idx);
+        }
+        missing.push((FUNCTION_SECTION, function_section));
+    }
+    if !written.iter().any(|(id, _)| *id == GLOBAL_SECTION) && !new_globals.is_empty() {
+        let mut global_section = Vec::new();
+        write_u32(&mut global_section, new_globals.len() as u32);
+        encode_globals(&new_globals, &mut global_section);
+        missing.push((GLOBAL_SECTION, global_section));
+    }
+    if !written.iter().any(|(id, _)| *id == CODE_SECTION) {
+        let mut code_section = Vec::new();
+        write_u32(&mut code_section, shims.len() as u32);
+        encode_shims(&shims, &ctx, &mut code_section);
+        missing.push((CODE_SECTION, code_section));
+    }
+    // inserting from the back keeps the recorded offsets of preceding sections valid
+    for (id, contents) in missing.into_iter().rev() {
+        let position = written
+            .iter()
+            .rev()
+            .find(|(written_id, _)| {
+                *written_id != 0 && section_order(*written_id) < section_order(id)
+            })
+            .map(|(_, end)| *end)
+            .unwrap_or(8);
+        let mut section = Vec::new();
+        write_section(&mut section, id, &contents);
+        output.splice(position..position, section);
+    }
+    Ok(output)
+}
+
+/// Position of a section in the order required by the binary format.
+fn section_order(id: u8) -> usize {
+    const ORDER: [u8; 13] = [1, 2, 3, 4, 5, 13, 6, 7, 8, 9, 12, 10, 11];
+    ORDER.iter().position(|ordered| *ordered == id).unwrap_or(0)
+}
+
+fn val_type(val_type: ValType) -> u8 {
+    match val_type {
+        ValType::I32 => VAL_I32,
+        ValType::I64 => VAL_I64,
+        ValType::F32 => 0x7D,
+        ValType::F64 => 0x7C,
+        ValType::V128 => 0x7B,
+        ValType::FuncRef => 0x70,
+        ValType::ExternRef => 0x6F,
+    }
+}
+
+fn encode_types(types: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut contents = Vec::new();
+    write_u32(&mut contents, types.len() as u32);
+    for (params, results) in types.iter() {
+        write_func_type(&mut contents, params, results);
+    }
+    contents
+}
+
+fn encode_imports(wasm: &[u8], kept: &[Range<usize>], syscalls: &[(Syscall, u32)]) -> Vec<u8> {
+    let mut contents = Vec::new();
+    write_u32(&mut contents, (kept.len() + syscalls.len()) as u32);
+    for range in kept.iter() {
+        contents.extend_from_slice(&wasm[range.clone()]);
+    }
+    for (syscall, type_idx) in syscalls.iter() {
+        write_name(&mut contents, SYSCALL_MODULE);
+        write_name(&mut contents, syscall.name());
+        contents.push(0x00);
+        write_u32(&mut contents, *type_idx);
+    }
+    contents
+}
+
+fn encode_globals(globals: &[(u8, i64)], contents: &mut Vec<u8>) {
+    for (val_type, init) in globals.iter() {
+        contents.push(*val_type);
+        contents.push(1);
+        contents.push(match *val_type {
+            VAL_I32 => op::I32_CONST,
+            _ => op::I64_CONST,
+        });
+        write_i64(contents, *init);
+        contents.push(op::END);
+    }
+}
+
+fn encode_shims(shims: &[(Wasi, u32)], ctx: &ShimContext, contents: &mut Vec<u8>) {
+    for (wasi, _) in shims.iter() {
+        let body = generate(*wasi, ctx);
+        write_u32(contents, body.len() as u32);
+        contents.extend_from_slice(&body);
+    }
+}
+
+impl<'a> Remap<'a> {
+    fn func(&self, func_idx: u32) -> u32 {
+        match self.imported_funcs.get(func_idx as usize) {
+            Some(ImportedFunc::Kept(new_idx)) => *new_idx,
+            Some(ImportedFunc::Shim(shim)) => {
+                self.num_imported_funcs + self.num_defined_funcs + *shim
+            }
+            None => func_idx - self.imported_funcs.len() as u32 + self.num_imported_funcs,
+        }
+    }
+
+    fn operators(
+        &self,
+        mut reader: OperatorsReader<'a>,
+        output: &mut Vec<u8>,
+    ) -> Result<(), WasiError> {
+        while !reader.eof() {
+            let start = reader.original_position();
+            let (opcode, function_index) = match reader.read()? {
+                Operator::Call { function_index } => (op::CALL, function_index),
+                Operator::ReturnCall { function_index } => (op::RETURN_CALL, function_index),
+                Operator::RefFunc { function_index } => (op::REF_FUNC, function_index),
+                _ => {
+                    output.extend_from_slice(&self.wasm[start..reader.original_position()]);
+                    continue;
+                }
+            };
+            output.push(opcode);
+            write_u32(output, self.func(function_index));
+        }
+        Ok(())
+    }
+
+    fn body(&self, body: &FunctionBody<'a>) -> Result<Vec<u8>, WasiError> {
+        let operators = body.get_operators_reader()?;
+        let locals = body.range().start..operators.original_position();
+        let mut output = self.wasm[locals].to_vec();
+        self.operators(operators, &mut output)?;
+        Ok(output)
+    }
+
+    fn exports(&self, reader: &wasmparser::ExportSectionReader<'a>) -> Result<Vec<u8>, WasiError> {
+        let mut contents = Vec::new();
+        write_u32(&mut contents, reader.count());
+        for export in reader.clone() {
+            let export = export?;
+            write_name(&mut contents, export.name);
+            let (kind, index) = match export.kind {
+                ExternalKind::Func => (0x00, self.func(export.index)),
+                ExternalKind::Table => (0x01, export.index),
+                ExternalKind::Memory => (0x02, export.index),
+                ExternalKind::Global => (0x03, export.index),
+                ExternalKind::Tag => (0x04, export.index),
+            };
+            contents.push(kind);
+            write_u32(&mut contents, index);
+        }
+        Ok(contents)
+    }
+
+    fn elements(
+        &self,
+        reader: &wasmparser::ElementSectionReader<'a>,
+    ) -> Result<Vec<u8>, WasiError> {
+        let mut contents = Vec::new();
+        write_u32(&mut contents, reader.count());
+        for element in reader.clone() {
+            let element = element?;
+            // the segment flags: bit 0 is passive/declared, bit 1 is an explicit table index
+            // or declared, bit 2 is expression items
+            let expressions = matches!(element.items, ElementItems::Expressions(_));
+            let mut flags = if expressions { 4 } else { 0 };
+            match &element.kind {
+                ElementKind::Passive => flags |= 1,
+                ElementKind::Declared => flags |= 3,
+                ElementKind::Active { table_index, .. } => {
+                    if *table_index != 0 || (expressions && element.ty != ValType::FuncRef) {
+                        flags |= 2;
+                    }
+                }
+            }
+            write_u32(&mut contents, flags);
+            if let ElementKind::Active {
+                table_index,
+                offset_expr,
+            } = &element.kind
+            {
+                if flags & 2 != 0 {
+                    write_u32(&mut contents, *table_index);
+                }
+                self.operators(offset_expr.get_operators_reader(), &mut contents)?;
+            }
+            if flags & 3 != 0 {
+                contents.push(match expressions {
+                    true => val_type(element.ty),
+                    // the only element kind is `funcref`
+                    false => 0x00,
+                });
+            }
+            match element.items {
+                ElementItems::Functions(items) => {
+                    write_u32(&mut contents, items.count());
+                    for func_idx in items {
+                        write_u32(&mut contents, self.func(func_idx?));
+                    }
+                }
+                ElementItems::Expressions(items) => {
+                    write_u32(&mut contents, items.count());
+                    for expr in items {
+                        self.operators(expr?.get_operators_reader(), &mut contents)?;
+                    }
+                }
+            }
+        }
+        Ok(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use wasmi::{core::Trap, Caller, Engine, Extern, Linker, Module, Store};
+
+    #[derive(Default)]
+    struct Host {
+        input: Vec<u8>,
+        output: Vec<u8>,
+        debug_log: Vec<u8>,
+    }
+
+    fn memory(caller: &Caller<'_, Host>) -> wasmi::Memory {
+        caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .unwrap()
+    }
+
+    fn read_memory(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Vec<u8> {
+        let mut buffer = vec![0u8; len as usize];
+        memory(caller)
+            .read(caller, ptr as usize, &mut buffer)
+            .unwrap();
+        buffer
+    }
+
+    /// Runs the exported `main` of the shimmed module, returns its result or the exit code.
+    fn run(wat: &str, input: &[u8]) -> (Result<i32, i32>, Host) {
+        let wasm = shim_wasi_imports(&wat::parse_str(wat).unwrap()).unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut store = Store::new(
+            &engine,
+            Host {
+                input: input.to_vec(),
+                ..Default::default()
+            },
+        );
+        let mut linker = Linker::<Host>::new(&engine);
+        linker
+            .func_wrap(
+                SYSCALL_MODULE,
+                "_write",
+                |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+                    let data = read_memory(&caller, ptr, len);
+                    caller.data_mut().output.extend(data);
+                },
+            )
+            .unwrap()
+            .func_wrap(
+                SYSCALL_MODULE,
+                "_debug_log",
+                |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+                    let data = read_memory(&caller, ptr, len);
+                    caller.data_mut().debug_log.extend(data);
+                },
+            )
+            .unwrap()
+            .func_wrap(
+                SYSCALL_MODULE,
+                "_read",
+                |mut caller: Caller<'_, Host>, target: i32, offset: i32, len: i32| {
+                    let data = caller.data().input[offset as usize..][..len as usize].to_vec();
+                    memory(&caller)
+                        .write(&mut caller, target as usize, &data)
+                        .unwrap();
+                },
+            )
+            .unwrap()
+            .func_wrap(SYSCALL_MODULE, "_input_size", |caller: Caller<'_, Host>| {
+                caller.data().input.len() as i32
+            })
+            .unwrap()
+            .func_wrap(SYSCALL_MODULE, "_exit", |code: i32| -> Result<(), Trap> {
+                Err(Trap::i32_exit(code))
+            })
+            .unwrap();
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let main = instance.get_typed_func::<(), i32>(&store, "main").unwrap();
+        let result = main
+            .call(&mut store, ())
+            .map_err(|trap| trap.i32_exit_status().unwrap());
+        (result, store.into_data())
+    }
+
+    #[test]
+    fn test_fd_write() {
+        let (result, host) = run(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "\10\00\00\00\06\00\00\00\20\00\00\00\05\00\00\00")
+              (data (i32.const 16) "Hello,")
+              (data (i32.const 32) "world")
+              (func (export "main") (result i32)
+                (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 2) (i32.const 64)))
+                (drop (call $fd_write (i32.const 2) (i32.const 8) (i32.const 1) (i32.const 68)))
+                (i32.add
+                  (i32.add (i32.load (i32.const 64)) (i32.load (i32.const 68)))
+                  (i32.mul
+                    (call $fd_write (i32.const 3) (i32.const 0) (i32.const 1) (i32.const 72))
+                    (i32.const 1000)))))
+            "#,
+            &[],
+        );
+        assert_eq!(result, Ok(8000 + 11 + 5));
+        assert_eq!(host.output, b"Hello,world");
+        assert_eq!(host.debug_log, b"world");
+    }
+
+    #[test]
+    fn test_fd_read() {
+        let (result, host) = run(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "fd_read"
+                (func $fd_read (param i32 i32 i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "\20\00\00\00\04\00\00\00\24\00\00\00\10\00\00\00")
+              (func (export "main") (result i32)
+                (local $total i32)
+                ;; the first read fills both buffers partially, the second one hits EOF
+                (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 2) (i32.const 64)))
+                (local.set $total (i32.load (i32.const 64)))
+                (i32.store (i32.const 4) (local.get $total))
+                (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 68)))
+                (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 2) (i32.const 64)))
+                (i32.add
+                  (i32.mul (local.get $total) (i32.const 100))
+                  (i32.add
+                    (i32.load (i32.const 64))
+                    (call $fd_read (i32.const 1) (i32.const 0) (i32.const 2) (i32.const 64))))))
+            "#,
+            b"fluentbase",
+        );
+        assert_eq!(result, Ok(1000 + 8));
+        assert_eq!(host.output, b"fluentbase");
+    }
+
+    #[test]
+    fn test_args_and_environ() {
+        let (result, _) = run(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "args_sizes_get"
+                (func $args_sizes_get (param i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "args_get"
+                (func $args_get (param i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "environ_sizes_get"
+                (func $environ_sizes_get (param i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "environ_get"
+                (func $environ_get (param i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff\ff")
+              (func (export "main") (result i32)
+                (i32.add
+                  (i32.add
+                    (call $args_sizes_get (i32.const 0) (i32.const 4))
+                    (call $environ_sizes_get (i32.const 8) (i32.const 12)))
+                  (i32.add
+                    (i32.add
+                      (call $args_get (i32.const 32) (i32.const 64))
+                      (call $environ_get (i32.const 32) (i32.const 64)))
+                    (i32.or
+                      (i32.or (i32.load (i32.const 0)) (i32.load (i32.const 4)))
+                      (i32.or (i32.load (i32.const 8)) (i32.load (i32.const 12))))))))
+            "#,
+            &[],
+        );
+        assert_eq!(result, Ok(0));
+    }
+
+    #[test]
+    fn test_random_get_is_deterministic() {
+        let wat = r#"
+            (module
+              (import "wasi_snapshot_preview1" "random_get"
+                (func $random_get (param i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "\20\00\00\00\20\00\00\00")
+              (func (export "main") (result i32)
+                (drop (call $random_get (i32.const 32) (i32.const 16)))
+                (drop (call $random_get (i32.const 48) (i32.const 16)))
+                (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+                (i32.const 0)))
+            "#;
+        let (_, first) = run(wat, &[]);
+        let (_, second) = run(wat, &[]);
+        assert_eq!(first.output.len(), 32);
+        assert_eq!(first.output, second.output);
+        // both calls continue the same sequence instead of restarting it
+        assert_ne!(first.output[..16], first.output[16..]);
+        assert!(first.output.iter().any(|byte| *byte != first.output[0]));
+    }
+
+    #[test]
+    fn test_proc_exit() {
+        let (result, host) = run(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+              (import "fluentbase_v1preview" "_write" (func $write (param i32 i32)))
+              (memory (export "memory") 1)
+              (data (i32.const 0) "ok")
+              (func (export "main") (result i32)
+                (call $write (i32.const 0) (i32.const 2))
+                (call $proc_exit (i32.const 3))
+                (i32.const 0)))
+            "#,
+            &[],
+        );
+        assert_eq!(result, Err(3));
+        assert_eq!(host.output, b"ok");
+    }
+
+    #[test]
+    fn test_function_references_are_remapped() {
+        // defined functions move because of the removed and added imports, calls, the table,
+        // globals with `ref.func`, exports and the start function have to follow them
+        let (result, host) = run(
+            r#"
+            (module
+              (import "fluentbase_v1preview" "_input_size" (func $input_size (result i32)))
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func $fd_write (param i32 i32 i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+              (type $unary (func (param i32) (result i32)))
+              (memory (export "memory") 1)
+              (table 3 funcref)
+              (global $counter (mut i32) (i32.const 0))
+              (global $ref funcref (ref.func $double))
+              (elem (i32.const 0) $double $triple)
+              (elem declare func $inc)
+              (data (i32.const 0) "\10\00\00\00\02\00\00\00")
+              (data (i32.const 16) "hi")
+              (func $double (param i32) (result i32) (i32.mul (local.get 0) (i32.const 2)))
+              (func $triple (param i32) (result i32) (i32.mul (local.get 0) (i32.const 3)))
+              (func $inc (param i32) (result i32) (i32.add (local.get 0) (i32.const 1)))
+              (func $init (global.set $counter (i32.const 7)))
+              (func (export "main") (result i32)
+                (table.set (i32.const 2) (ref.func $inc))
+                (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+                (i32.add
+                  (call_indirect (type $unary) (global.get $counter) (i32.const 0))
+                  (i32.add
+                    (call_indirect (type $unary) (i32.const 100) (i32.const 1))
+                    (i32.add
+                      (call_indirect (type $unary) (call $input_size) (i32.const 2))
+                      (call $inc (i32.const 1000))))))
+              (start $init))
+            "#,
+            b"abcd",
+        );
+        assert_eq!(result, Ok(14 + 300 + 5 + 1001));
+        assert_eq!(host.output, b"hi");
+    }
+
+    #[test]
+    fn test_missing_sections_are_inserted() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "random_get"
+                (func $random_get (param i32 i32) (result i32)))
+              (memory 1)
+              (export "random_get" (func $random_get))
+              (data (i32.const 0) "data"))
+            "#,
+        )
+        .unwrap();
+        let wasm = shim_wasi_imports(&wasm).unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        assert_eq!(module.exports().count(), 1);
+    }
+
+    #[test]
+    fn test_unsupported_imports_are_rejected() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "fd_write"
+                (func (param i32 i32 i32 i32) (result i32)))
+              (import "wasi_snapshot_preview1" "clock_time_get"
+                (func (param i32 i64 i32) (result i32))))
+            "#,
+        )
+        .unwrap();
+        let err = shim_wasi_imports(&wasm).unwrap_err();
+        assert_eq!(
+            err,
+            WasiError::UnsupportedImport("clock_time_get".to_string())
+        );
+        assert_eq!(
+            err.to_string(),
+            "WASI import `wasi_snapshot_preview1::clock_time_get` is not supported"
+        );
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func (param i64))))
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            shim_wasi_imports(&wasm),
+            Err(WasiError::SignatureMismatch("proc_exit".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_module_without_wasi_is_unchanged() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (import "fluentbase_v1preview" "_exit" (func (param i32)))
+              (func (export "main") (result i32) (i32.const 1)))
+            "#,
+        )
+        .unwrap();
+        assert_eq!(shim_wasi_imports(&wasm).unwrap(), wasm);
+    }
+}
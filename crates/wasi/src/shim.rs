@@ -0,0 +1,261 @@
+use alloc::vec::Vec;
+use fluentbase_wasm_encoder::{op, FuncBuilder, BLOCK_EMPTY, VAL_I32};
+
+const ERRNO_SUCCESS: i32 = 0;
+const ERRNO_BADF: i32 = 8;
+
+const FD_STDIN: i32 = 0;
+const FD_STDOUT: i32 = 1;
+
+/// Seed of the `random_get` generator, every execution gets the same sequence.
+pub(crate) const RANDOM_SEED: i64 = 0x5eed_f1ae_7ba5_e000;
+
+/// Supported functions of `wasi_snapshot_preview1`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Wasi {
+    FdWrite,
+    FdRead,
+    ArgsGet,
+    ArgsSizesGet,
+    EnvironGet,
+    EnvironSizesGet,
+    RandomGet,
+    ProcExit,
+}
+
+impl Wasi {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "fd_write" => Wasi::FdWrite,
+            "fd_read" => Wasi::FdRead,
+            "args_get" => Wasi::ArgsGet,
+            "args_sizes_get" => Wasi::ArgsSizesGet,
+            "environ_get" => Wasi::EnvironGet,
+            "environ_sizes_get" => Wasi::EnvironSizesGet,
+            "random_get" => Wasi::RandomGet,
+            "proc_exit" => Wasi::ProcExit,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn signature(self) -> (&'static [u8], &'static [u8]) {
+        match self {
+            Wasi::FdWrite | Wasi::FdRead => (&[VAL_I32; 4], &[VAL_I32]),
+            Wasi::ArgsGet
+            | Wasi::ArgsSizesGet
+            | Wasi::EnvironGet
+            | Wasi::EnvironSizesGet
+            | Wasi::RandomGet => (&[VAL_I32; 2], &[VAL_I32]),
+            Wasi::ProcExit => (&[VAL_I32], &[]),
+        }
+    }
+
+    pub(crate) fn syscalls(self) -> &'static [Syscall] {
+        match self {
+            Wasi::FdWrite => &[Syscall::Write, Syscall::DebugLog],
+            Wasi::FdRead => &[Syscall::Read, Syscall::InputSize],
+            Wasi::ProcExit => &[Syscall::Exit],
+            _ => &[],
+        }
+    }
+}
+
+/// Fluentbase syscalls the shims are built on.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Syscall {
+    Write,
+    DebugLog,
+    Read,
+    InputSize,
+    Exit,
+}
+
+impl Syscall {
+    pub(crate) const ALL: [Syscall; 5] = [
+        Syscall::Write,
+        Syscall::DebugLog,
+        Syscall::Read,
+        Syscall::InputSize,
+        Syscall::Exit,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Syscall::Write => "_write",
+            Syscall::DebugLog => "_debug_log",
+            Syscall::Read => "_read",
+            Syscall::InputSize => "_input_size",
+            Syscall::Exit => "_exit",
+        }
+    }
+
+    pub(crate) fn signature(self) -> (&'static [u8], &'static [u8]) {
+        match self {
+            Syscall::Write | Syscall::DebugLog => (&[VAL_I32; 2], &[]),
+            Syscall::Read => (&[VAL_I32; 3], &[]),
+            Syscall::InputSize => (&[], &[VAL_I32]),
+            Syscall::Exit => (&[VAL_I32], &[]),
+        }
+    }
+}
+
+/// Indices the shims refer to in the rewritten module.
+pub(crate) struct ShimContext {
+    pub(crate) syscalls: [Option<u32>; 5],
+    pub(crate) stdin_offset_global: u32,
+    pub(crate) random_state_global: u32,
+}
+
+impl ShimContext {
+    fn syscall(&self, syscall: Syscall) -> u32 {
+        let position = Syscall::ALL.iter().position(|s| *s == syscall).unwrap();
+        self.syscalls[position].expect("wasi: syscall is not imported")
+    }
+}
+
+pub(crate) fn generate(wasi: Wasi, ctx: &ShimContext) -> Vec<u8> {
+    let mut b = FuncBuilder::new(wasi.signature().0.len() as u32);
+    match wasi {
+        Wasi::FdWrite => gen_fd_write(&mut b, ctx),
+        Wasi::FdRead => gen_fd_read(&mut b, ctx),
+        Wasi::ArgsSizesGet | Wasi::EnvironSizesGet => {
+            // both the count and the buffer size are zero
+            for param in [0, 1] {
+                b.get(param).i32_const(0).mem(op::I32_STORE, 2, 0);
+            }
+            b.i32_const(ERRNO_SUCCESS);
+        }
+        Wasi::ArgsGet | Wasi::EnvironGet => {
+            b.i32_const(ERRNO_SUCCESS);
+        }
+        Wasi::RandomGet => gen_random_get(&mut b, ctx),
+        Wasi::ProcExit => {
+            b.get(0)
+                .op_idx(op::CALL, ctx.syscall(Syscall::Exit))
+                .op(op::UNREACHABLE);
+        }
+    }
+    b.finish()
+}
+
+/// Emits a loop over `iovs_len` iovecs of `(ptr, len)`, calls `body` with locals holding the
+/// buffer pointer and length, then stores the sum of lengths into `result_ptr`.
+fn for_each_iovec(b: &mut FuncBuilder, body: impl Fn(&mut FuncBuilder, u32, u32)) {
+    let (iovs, iovs_len, result_ptr) = (1, 2, 3);
+    let (i, total, addr, ptr, len) = (
+        b.local(VAL_I32),
+        b.local(VAL_I32),
+        b.local(VAL_I32),
+        b.local(VAL_I32),
+        b.local(VAL_I32),
+    );
+    b.block(op::BLOCK, BLOCK_EMPTY).block(op::LOOP, BLOCK_EMPTY);
+    b.get(i).get(iovs_len).op(op::I32_GE_U).op_idx(op::BR_IF, 1);
+    b.get(iovs)
+        .get(i)
+        .i32_const(8)
+        .op(op::I32_MUL)
+        .op(op::I32_ADD)
+        .set(addr);
+    b.get(addr).mem(op::I32_LOAD, 2, 0).set(ptr);
+    b.get(addr).mem(op::I32_LOAD, 2, 4).set(len);
+    body(b, ptr, len);
+    b.get(total).get(len).op(op::I32_ADD).set(total);
+    b.get(i).i32_const(1).op(op::I32_ADD).set(i);
+    b.op_idx(op::BR, 0);
+    b.op(op::END).op(op::END);
+    b.get(result_ptr).get(total).mem(op::I32_STORE, 2, 0);
+    b.i32_const(ERRNO_SUCCESS);
+}
+
+fn gen_fd_write(b: &mut FuncBuilder, ctx: &ShimContext) {
+    let fd = 0;
+    // only stdout and stderr are writable
+    b.get(fd)
+        .i32_const(FD_STDOUT)
+        .op(op::I32_SUB)
+        .i32_const(2)
+        .op(op::I32_GE_U)
+        .block(op::IF, BLOCK_EMPTY)
+        .i32_const(ERRNO_BADF)
+        .op(op::RETURN)
+        .op(op::END);
+    let (write, debug_log) = (ctx.syscall(Syscall::Write), ctx.syscall(Syscall::DebugLog));
+    for_each_iovec(b, |b, ptr, len| {
+        b.get(fd)
+            .i32_const(FD_STDOUT)
+            .op(op::I32_EQ)
+            .block(op::IF, BLOCK_EMPTY)
+            .get(ptr)
+            .get(len)
+            .op_idx(op::CALL, write)
+            .op(op::ELSE)
+            .get(ptr)
+            .get(len)
+            .op_idx(op::CALL, debug_log)
+            .op(op::END);
+    });
+}
+
+fn gen_fd_read(b: &mut FuncBuilder, ctx: &ShimContext) {
+    let fd = 0;
+    b.get(fd)
+        .i32_const(FD_STDIN)
+        .op(op::I32_NE)
+        .block(op::IF, BLOCK_EMPTY)
+        .i32_const(ERRNO_BADF)
+        .op(op::RETURN)
+        .op(op::END);
+    let avail = b.local(VAL_I32);
+    let offset = ctx.stdin_offset_global;
+    let (read, input_size) = (ctx.syscall(Syscall::Read), ctx.syscall(Syscall::InputSize));
+    for_each_iovec(b, |b, ptr, len| {
+        // reads are clamped by the rest of the input, zero bytes read means EOF
+        b.op_idx(op::CALL, input_size)
+            .op_idx(op::GLOBAL_GET, offset)
+            .op(op::I32_SUB)
+            .set(avail);
+        b.get(len)
+            .get(avail)
+            .get(len)
+            .get(avail)
+            .op(op::I32_LT_U)
+            .op(op::SELECT)
+            .set(len);
+        b.get(ptr)
+            .op_idx(op::GLOBAL_GET, offset)
+            .get(len)
+            .op_idx(op::CALL, read);
+        b.op_idx(op::GLOBAL_GET, offset)
+            .get(len)
+            .op(op::I32_ADD)
+            .op_idx(op::GLOBAL_SET, offset);
+    });
+}
+
+fn gen_random_get(b: &mut FuncBuilder, ctx: &ShimContext) {
+    let (buf, buf_len) = (0, 1);
+    let i = b.local(VAL_I32);
+    let state = ctx.random_state_global;
+    b.block(op::BLOCK, BLOCK_EMPTY).block(op::LOOP, BLOCK_EMPTY);
+    b.get(i).get(buf_len).op(op::I32_GE_U).op_idx(op::BR_IF, 1);
+    // 64-bit LCG (Knuth's MMIX constants), the high bits are the best ones
+    b.op_idx(op::GLOBAL_GET, state)
+        .i64_const(6364136223846793005)
+        .op(op::I64_MUL)
+        .i64_const(1442695040888963407)
+        .op(op::I64_ADD)
+        .op_idx(op::GLOBAL_SET, state);
+    b.get(buf)
+        .get(i)
+        .op(op::I32_ADD)
+        .op_idx(op::GLOBAL_GET, state)
+        .i64_const(56)
+        .op(op::I64_SHR_U)
+        .op(op::I32_WRAP_I64)
+        .mem(op::I32_STORE8, 0, 0);
+    b.get(i).i32_const(1).op(op::I32_ADD).set(i);
+    b.op_idx(op::BR, 0);
+    b.op(op::END).op(op::END);
+    b.i32_const(ERRNO_SUCCESS);
+}
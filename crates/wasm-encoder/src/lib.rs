@@ -0,0 +1,215 @@
+#![no_std]
+
+//! Helpers to emit raw wasm binary encoding.
+//!
+//! The soft-float lowering and the WASI shim both rewrite modules before the rWASM translation
+//! and generate new functions into them, this crate has the LEB128 and section writers and the
+//! function body assembler they share.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+pub const VAL_I32: u8 = 0x7F;
+pub const VAL_I64: u8 = 0x7E;
+pub const BLOCK_EMPTY: u8 = 0x40;
+
+pub fn write_u32(out: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+pub fn write_i64(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+pub fn write_name(out: &mut Vec<u8>, name: &str) {
+    write_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+}
+
+pub fn write_section(out: &mut Vec<u8>, id: u8, contents: &[u8]) {
+    out.push(id);
+    write_u32(out, contents.len() as u32);
+    out.extend_from_slice(contents);
+}
+
+pub fn write_func_type(out: &mut Vec<u8>, params: &[u8], results: &[u8]) {
+    out.push(0x60);
+    write_u32(out, params.len() as u32);
+    out.extend_from_slice(params);
+    write_u32(out, results.len() as u32);
+    out.extend_from_slice(results);
+}
+
+/// Opcodes used by the generated functions.
+pub mod op {
+    pub const UNREACHABLE: u8 = 0x00;
+    pub const BLOCK: u8 = 0x02;
+    pub const LOOP: u8 = 0x03;
+    pub const IF: u8 = 0x04;
+    pub const ELSE: u8 = 0x05;
+    pub const END: u8 = 0x0B;
+    pub const BR: u8 = 0x0C;
+    pub const BR_IF: u8 = 0x0D;
+    pub const RETURN: u8 = 0x0F;
+    pub const CALL: u8 = 0x10;
+    pub const RETURN_CALL: u8 = 0x12;
+    pub const SELECT: u8 = 0x1B;
+    pub const LOCAL_GET: u8 = 0x20;
+    pub const LOCAL_SET: u8 = 0x21;
+    pub const LOCAL_TEE: u8 = 0x22;
+    pub const GLOBAL_GET: u8 = 0x23;
+    pub const GLOBAL_SET: u8 = 0x24;
+    pub const I32_LOAD: u8 = 0x28;
+    pub const I64_LOAD: u8 = 0x29;
+    pub const I32_STORE: u8 = 0x36;
+    pub const I64_STORE: u8 = 0x37;
+    pub const I32_STORE8: u8 = 0x3A;
+    pub const I32_CONST: u8 = 0x41;
+    pub const I64_CONST: u8 = 0x42;
+    pub const I32_EQZ: u8 = 0x45;
+    pub const I32_EQ: u8 = 0x46;
+    pub const I32_NE: u8 = 0x47;
+    pub const I32_LT_U: u8 = 0x49;
+    pub const I32_GE_U: u8 = 0x4F;
+    pub const I64_EQZ: u8 = 0x50;
+    pub const I64_EQ: u8 = 0x51;
+    pub const I64_NE: u8 = 0x52;
+    pub const I64_LT_S: u8 = 0x53;
+    pub const I64_LT_U: u8 = 0x54;
+    pub const I64_GT_S: u8 = 0x55;
+    pub const I64_GT_U: u8 = 0x56;
+    pub const I64_LE_S: u8 = 0x57;
+    pub const I64_GE_S: u8 = 0x59;
+    pub const I64_GE_U: u8 = 0x5A;
+    pub const I32_ADD: u8 = 0x6A;
+    pub const I32_SUB: u8 = 0x6B;
+    pub const I32_MUL: u8 = 0x6C;
+    pub const I32_DIV_S: u8 = 0x6D;
+    pub const I32_AND: u8 = 0x71;
+    pub const I32_OR: u8 = 0x72;
+    pub const I32_XOR: u8 = 0x73;
+    pub const I64_CLZ: u8 = 0x79;
+    pub const I64_ADD: u8 = 0x7C;
+    pub const I64_SUB: u8 = 0x7D;
+    pub const I64_MUL: u8 = 0x7E;
+    pub const I64_AND: u8 = 0x83;
+    pub const I64_OR: u8 = 0x84;
+    pub const I64_XOR: u8 = 0x85;
+    pub const I64_SHL: u8 = 0x86;
+    pub const I64_SHR_S: u8 = 0x87;
+    pub const I64_SHR_U: u8 = 0x88;
+    pub const I32_WRAP_I64: u8 = 0xA7;
+    pub const I64_EXTEND_I32_S: u8 = 0xAC;
+    pub const I64_EXTEND_I32_U: u8 = 0xAD;
+    pub const REF_FUNC: u8 = 0xD2;
+}
+
+/// A tiny assembler for function bodies, it tracks declared locals and emits raw opcodes.
+pub struct FuncBuilder {
+    params: u32,
+    locals: Vec<u8>,
+    code: Vec<u8>,
+}
+
+impl FuncBuilder {
+    pub fn new(params: u32) -> Self {
+        Self {
+            params,
+            locals: Vec::new(),
+            code: Vec::new(),
+        }
+    }
+
+    pub fn local(&mut self, val_type: u8) -> u32 {
+        self.locals.push(val_type);
+        self.params + self.locals.len() as u32 - 1
+    }
+
+    pub fn op(&mut self, opcode: u8) -> &mut Self {
+        self.code.push(opcode);
+        self
+    }
+
+    /// Emits an opcode followed by an unsigned LEB128 immediate.
+    pub fn op_idx(&mut self, opcode: u8, idx: u32) -> &mut Self {
+        self.code.push(opcode);
+        write_u32(&mut self.code, idx);
+        self
+    }
+
+    pub fn get(&mut self, local: u32) -> &mut Self {
+        self.op_idx(op::LOCAL_GET, local)
+    }
+
+    pub fn set(&mut self, local: u32) -> &mut Self {
+        self.op_idx(op::LOCAL_SET, local)
+    }
+
+    pub fn tee(&mut self, local: u32) -> &mut Self {
+        self.op_idx(op::LOCAL_TEE, local)
+    }
+
+    pub fn call(&mut self, func_idx: u32) -> &mut Self {
+        self.op_idx(op::CALL, func_idx)
+    }
+
+    pub fn br_if(&mut self, depth: u32) -> &mut Self {
+        self.op_idx(op::BR_IF, depth)
+    }
+
+    pub fn i32_const(&mut self, value: i32) -> &mut Self {
+        self.code.push(op::I32_CONST);
+        write_i64(&mut self.code, value as i64);
+        self
+    }
+
+    pub fn i64_const(&mut self, value: i64) -> &mut Self {
+        self.code.push(op::I64_CONST);
+        write_i64(&mut self.code, value);
+        self
+    }
+
+    /// Emits a memory access with natural alignment of `align_log2` and the given offset.
+    pub fn mem(&mut self, opcode: u8, align_log2: u32, offset: u32) -> &mut Self {
+        self.code.push(opcode);
+        write_u32(&mut self.code, align_log2);
+        write_u32(&mut self.code, offset);
+        self
+    }
+
+    pub fn block(&mut self, opcode: u8, block_type: u8) -> &mut Self {
+        self.code.push(opcode);
+        self.code.push(block_type);
+        self
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.code.push(op::END);
+        let mut body = Vec::with_capacity(self.code.len() + self.locals.len() * 2 + 1);
+        write_u32(&mut body, self.locals.len() as u32);
+        for val_type in self.locals.iter() {
+            write_u32(&mut body, 1);
+            body.push(*val_type);
+        }
+        body.extend_from_slice(&self.code);
+        body
+    }
+}
@@ -2,7 +2,7 @@ use fluentbase_codec::Encoder;
 use fluentbase_core::{Account, JZKT_ACCOUNT_COMPRESSION_FLAGS};
 use fluentbase_runtime::{DefaultEmptyRuntimeDatabase, ExecutionResult, Runtime, RuntimeContext};
 use fluentbase_sdk::{ContractInput, LowLevelSDK};
-use fluentbase_types::{Address, Bytes, IJournaledTrie, STATE_DEPLOY, STATE_MAIN, U256};
+use fluentbase_types::{Address, Bytes, IJournaledTrie, B256, STATE_DEPLOY, STATE_MAIN, U256};
 use hashbrown::HashMap;
 use paste::paste;
 
@@ -133,9 +133,13 @@ impl ContractInputWrapper {
     impl_once_setter!(block_coinbase, Address);
     impl_once_setter!(block_timestamp, u64);
     impl_once_setter!(block_number, u64);
-    impl_once_setter!(block_difficulty, u64);
+    impl_once_setter!(block_difficulty, U256);
+    impl_once_setter!(block_prevrandao, B256);
     impl_once_setter!(block_gas_limit, u64);
     impl_once_setter!(block_base_fee, U256);
+    impl_once_setter!(block_blob_base_fee, U256);
+    impl_once_setter!(tx_hash, B256);
+    impl_once_setter!(tx_type, u8);
     impl_once_setter!(tx_gas_price, U256);
     impl_once_setter!(tx_gas_priority_fee, Option<U256>);
     impl_once_setter!(tx_caller, Address);
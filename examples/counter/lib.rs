@@ -0,0 +1,45 @@
+#![cfg_attr(target_arch = "wasm32", no_std)]
+extern crate alloc;
+extern crate fluentbase_sdk;
+
+use alloy_sol_types::{sol, SolCall, SolValue};
+use fluentbase_sdk::{
+    contract,
+    AccountStorage,
+    ContextReader,
+    ExecutionContext,
+    JzktAccountManager,
+    StorageValue,
+    U256,
+};
+
+const COUNTER: StorageValue<U256> = StorageValue::new(U256::ZERO);
+
+fn storage() -> AccountStorage<'static, JzktAccountManager> {
+    AccountStorage::new(
+        &JzktAccountManager::DEFAULT,
+        ExecutionContext::DEFAULT.contract_address(),
+    )
+}
+
+#[derive(Default)]
+struct Counter;
+
+#[contract]
+impl Counter {
+    // the initial value is passed to the constructor in the init code
+    fn constructor(&mut self, initial: U256) {
+        COUNTER.set(&storage(), initial);
+    }
+
+    #[view]
+    pub fn value(&self) -> U256 {
+        COUNTER.get(&storage())
+    }
+
+    pub fn increment(&mut self, by: U256) -> U256 {
+        let value = COUNTER.get(&storage()) + by;
+        COUNTER.set(&storage(), value);
+        value
+    }
+}
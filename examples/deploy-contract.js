@@ -3,6 +3,26 @@ const fs = require('fs');
 
 const DEPLOYER_PRIVATE_KEY = 'ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80';
 
+// ABI-encoded constructor arguments are passed in the `constructor_args` custom section
+const constructorArgsSection = (constructorArgs) => {
+    const leb128 = (value) => {
+        let bytes = [];
+        do {
+            let byte = value & 0x7f;
+            value >>>= 7;
+            bytes.push(value ? byte | 0x80 : byte);
+        } while (value);
+        return Buffer.from(bytes);
+    };
+    const name = Buffer.from('constructor_args');
+    const payload = Buffer.concat([
+        leb128(name.length),
+        name,
+        Buffer.from(constructorArgs.replace(/^0x/, ''), 'hex'),
+    ]);
+    return Buffer.concat([Buffer.from([0]), leb128(payload.length), payload]).toString('hex');
+};
+
 const main = async () => {
     if (process.argv.length < 3) {
         console.log(`You must specify path to the WASM binary!`);
@@ -32,8 +52,11 @@ const main = async () => {
         process.exit(-1);
     }
 
-    let [binaryPath] = args;
+    let [binaryPath, constructorArgs] = args;
     let wasmBinary = fs.readFileSync(binaryPath).toString('hex');
+    if (constructorArgs) {
+        wasmBinary += constructorArgsSection(constructorArgs);
+    }
     const web3 = new Web3(web3Url);
     let privateKey = process.env.DEPLOYER_PRIVATE_KEY || DEPLOYER_PRIVATE_KEY;
     let account = web3.eth.accounts.privateKeyToAccount('0x' + privateKey);
@@ -1,14 +1,17 @@
-use alloy_sol_types::{sol, SolCall, SolEvent, SolType, SolValue};
+use alloy_sol_types::{sol, SolCall, SolType, SolValue};
 use fluentbase_sdk::{
+    emit,
+    AccountStorage,
     Address,
     Bytes,
     ContextReader,
     ExecutionContext,
+    JzktAccountManager,
     LowLevelSDK,
     SharedAPI,
+    StorageMap,
     U256,
 };
-use hex_literal::hex;
 
 sol! {
     event Transfer(address indexed from, address indexed to, uint256 value);
@@ -26,31 +29,22 @@ sol! {
     function transferFrom(address from, address to, uint256 value) external returns (bool);
 }
 
-const STORAGE_BALANCES: [u8; 32] =
-    hex!("0000000000000000000000000000000000000000000000000000000000000000");
-const STORAGE_ALLOWANCES: [u8; 32] =
-    hex!("0000000000000000000000000000000000000000000000000000000000000001");
+// the same layout as Solidity's `mapping(address => uint256) balances` and
+// `mapping(address => mapping(address => uint256)) allowances`
+const BALANCES: StorageMap<Address, U256> = StorageMap::new(U256::ZERO);
+const ALLOWANCES: StorageMap<Address, StorageMap<Address, U256>> =
+    StorageMap::new(U256::from_limbs([1, 0, 0, 0]));
 
-fn storage_mapping_key(slot: &[u8], value: &[u8]) -> [u8; 32] {
-    let mut raw_storage_key: [u8; 64] = [0; 64];
-    raw_storage_key[0..32].copy_from_slice(slot);
-    raw_storage_key[32..64].copy_from_slice(value);
-    let mut storage_key: [u8; 32] = [0; 32];
-    LowLevelSDK::keccak256(
-        raw_storage_key.as_ptr(),
-        raw_storage_key.len() as u32,
-        storage_key.as_mut_ptr(),
-    );
-    storage_key
+fn storage(ctx: &ExecutionContext) -> AccountStorage<'static, JzktAccountManager> {
+    AccountStorage::new(&JzktAccountManager::DEFAULT, ctx.contract_address())
 }
 
 pub fn deploy() {
-    let mut ctx = ExecutionContext::default();
+    let ctx = ExecutionContext::default();
     let owner_address = ctx.contract_caller();
     let owner_balance: U256 = U256::from_str_radix("1000000000000000000000000", 10).unwrap();
     // mint balance to owner
-    let storage_key = storage_mapping_key(&STORAGE_BALANCES, owner_address.abi_encode().as_slice());
-    // LowLevelSDK::evm_sstore(&storage_key, owner_balance.as_le_slice())
+    BALANCES.set(&storage(&ctx), &owner_address, owner_balance);
 }
 
 struct ERC20<'a>(&'a mut ExecutionContext);
@@ -73,12 +67,11 @@ impl<'a> ERC20<'a> {
     }
 
     fn balance_of(&self, address: Address) -> U256 {
-        let mut balance = U256::from(0);
-        let storage_key = storage_mapping_key(&STORAGE_BALANCES, address.abi_encode().as_slice());
-        unsafe {
-            // LowLevelSDK::evm_sload(&storage_key, balance.as_le_slice_mut());
-        }
-        balance
+        BALANCES.get(&storage(self.0), &address)
+    }
+
+    fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        ALLOWANCES.entry(&owner).get(&storage(self.0), &spender)
     }
 
     fn transfer(&mut self, to: Address, value: U256) -> U256 {
@@ -90,40 +83,18 @@ impl<'a> ERC20<'a> {
         } else if to.is_zero() {
             panic!("invalid receiver");
         }
+        let storage = storage(self.0);
         // update from balance
-        {
-            let mut from_balance = U256::from(0);
-            let from_balance_key =
-                storage_mapping_key(&STORAGE_BALANCES, from.abi_encode().as_slice());
-            // unsafe {
-            // LowLevelSDK::evm_sload(&from_balance_key, from_balance.as_le_slice_mut());
-            // }
-            if from_balance < value {
-                panic!("insufficient balance");
-            }
-            let from_balance = from_balance - value;
-            // LowLevelSDK::evm_sstore(&from_balance_key, from_balance.as_le_slice());
+        let from_balance = BALANCES.get(&storage, &from);
+        if from_balance < value {
+            panic!("insufficient balance");
         }
+        BALANCES.set(&storage, &from, from_balance - value);
         // update to balance
-        {
-            let mut to_balance = U256::from(0);
-            let to_balance_key = storage_mapping_key(&STORAGE_BALANCES, to.abi_encode().as_slice());
-            unsafe {
-                // LowLevelSDK::evm_sload(&to_balance_key, to_balance.as_le_slice_mut());
-            }
-            let to_balance = to_balance + value;
-            // LowLevelSDK::evm_sstore(&to_balance_key, to_balance.as_le_slice());
-        }
+        let to_balance = BALANCES.get(&storage, &to);
+        BALANCES.set(&storage, &to, to_balance + value);
         // emit event
-        let transfer_event = Transfer {
-            from: from.clone(),
-            to,
-            value,
-        };
-        // self.0.emit_log(
-        //     transfer_event.encode_topics().iter().map(|v| v.0).collect(),
-        //     transfer_event.encode_data(),
-        // );
+        emit!(Transfer { from, to, value });
         U256::from(1)
     }
 }